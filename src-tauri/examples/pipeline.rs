@@ -0,0 +1,113 @@
+//! Record or decode audio and transcribe it without the app UI, printing
+//! the time spent in each stage.
+//!
+//! ```sh
+//! cargo run --release --example pipeline -- --seconds 5
+//! cargo run --release --example pipeline -- --file memo.m4a --runs 3
+//! ```
+//!
+//! Uses the model in `~/.cyrano/models` and the settings in
+//! `~/.cyrano/settings.json`, like the app.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+use cyrano_lib::harness;
+
+const USAGE: &str = "Usage: pipeline [--seconds N | --file PATH] [--runs N]
+
+  --seconds N  Record N seconds from the dictation microphone (default 5)
+  --file PATH  Transcribe an audio file instead of recording
+  --runs N     Transcribe the audio N times (default 1)";
+
+enum Source {
+    Microphone(Duration),
+    File(PathBuf),
+}
+
+struct Options {
+    source: Source,
+    runs: u32,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        source: Source::Microphone(Duration::from_secs(5)),
+        runs: 1,
+    };
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("Missing value for {arg}"));
+        match arg.as_str() {
+            "--seconds" => {
+                let seconds: f64 = value()?.parse().map_err(|e| format!("--seconds: {e}"))?;
+                options.source = Source::Microphone(Duration::from_secs_f64(seconds));
+            }
+            "--file" => options.source = Source::File(PathBuf::from(value()?)),
+            "--runs" => options.runs = value()?.parse().map_err(|e| format!("--runs: {e}"))?,
+            _ => return Err(format!("Unknown argument {arg}")),
+        }
+    }
+    Ok(options)
+}
+
+fn run(options: Options) -> Result<(), harness::CyranoError> {
+    let started_at = Instant::now();
+    let samples = match &options.source {
+        Source::Microphone(duration) => {
+            eprintln!("Recording for {:.1}s...", duration.as_secs_f64());
+            harness::record(*duration)?
+        }
+        Source::File(path) => harness::load_audio_file(path)?,
+    };
+    println!(
+        "audio: {:.2}s of audio in {}ms",
+        samples.len() as f64 / 16_000.0,
+        started_at.elapsed().as_millis()
+    );
+
+    let started_at = Instant::now();
+    harness::load_model()?;
+    println!(
+        "model: {} loaded in {}ms",
+        harness::model_name().unwrap_or_default(),
+        started_at.elapsed().as_millis()
+    );
+
+    for run in 1..=options.runs {
+        let started_at = Instant::now();
+        let transcription = harness::transcribe(&samples)?;
+        println!(
+            "run {run}: {}ms, language {}",
+            started_at.elapsed().as_millis(),
+            transcription.language.as_deref().unwrap_or("unknown")
+        );
+        if run == 1 {
+            println!("{}", transcription.text);
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    if std::env::args().any(|arg| arg == "--help" || arg == "-h") {
+        println!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run(options) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("[{}] {e}", e.code());
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! Run saved dictations through the pipeline again and compare the time
+//! spent in each stage with the original session.
+//!
+//! ```sh
+//! cargo run --release --features replay --example replay -- \
+//!     ~/.cyrano/recordings/recording-20261016-134553.json --runs 3
+//! ```
+//!
+//! Dictations are saved with their session trace when `save_recordings` is
+//! enabled. Uses the model and settings in `~/.cyrano`, like the app: replay
+//! on the same model and decode options to compare releases.
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use cyrano_lib::harness;
+
+const USAGE: &str = "Usage: replay TRACE... [--runs N]
+
+  TRACE     Session trace (recording-*.json) or its recording (recording-*.wav)
+  --runs N  Replay each session N times, reporting the fastest run (default 1)";
+
+struct Options {
+    traces: Vec<PathBuf>,
+    runs: u32,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        traces: Vec::new(),
+        runs: 1,
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--runs" => {
+                let value = args.next().ok_or("Missing value for --runs")?;
+                options.runs = value.parse().map_err(|e| format!("--runs: {e}"))?;
+            }
+            _ if arg.starts_with("--") => return Err(format!("Unknown argument {arg}")),
+            _ => options.traces.push(PathBuf::from(arg)),
+        }
+    }
+    if options.traces.is_empty() {
+        return Err("No session trace given".to_string());
+    }
+    options.runs = options.runs.max(1);
+    Ok(options)
+}
+
+/// Change from `before` to `after`, in percent.
+fn change(before: u64, after: u64) -> String {
+    if before == 0 {
+        return "n/a".to_string();
+    }
+    format!("{:+.0}%", (after as f64 / before as f64 - 1.0) * 100.0)
+}
+
+fn replay(path: &Path, runs: u32) -> Result<(), harness::CyranoError> {
+    let (trace, samples) = harness::load_session(path)?;
+    println!(
+        "{}: {:.2}s of audio, model {}",
+        path.display(),
+        samples.len() as f64 / 16_000.0,
+        trace.model.as_deref().unwrap_or("unknown")
+    );
+    if harness::settings_differ_from(&trace) {
+        println!("  warning: model or decode options differ from the session");
+    }
+
+    let mut fastest: Option<harness::Replay> = None;
+    for _ in 0..runs {
+        let replay = harness::replay(trace.clone(), &samples)?;
+        if fastest
+            .as_ref()
+            .is_none_or(|best| replay.transcribe_ms < best.transcribe_ms)
+        {
+            fastest = Some(replay);
+        }
+    }
+    let Some(replay) = fastest else {
+        return Ok(());
+    };
+
+    for (stage, before, after) in [
+        ("prepare", trace.prepare_ms, replay.prepare_ms),
+        ("transcribe", trace.transcribe_ms, replay.transcribe_ms),
+        (
+            "post-process",
+            trace.post_process_ms,
+            replay.post_process_ms,
+        ),
+    ] {
+        println!(
+            "  {stage:<12} {before:>6}ms -> {after:>6}ms ({})",
+            change(before, after)
+        );
+    }
+    if replay.text == trace.text {
+        println!("  text: unchanged");
+    } else {
+        println!(
+            "  text changed:\n    - {}\n    + {}",
+            trace.text, replay.text
+        );
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    if std::env::args().any(|arg| arg == "--help" || arg == "-h") {
+        println!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if let Err(e) = harness::load_model() {
+        eprintln!("[{}] {e}", e.code());
+        return ExitCode::FAILURE;
+    }
+    let mut status = ExitCode::SUCCESS;
+    for path in &options.traces {
+        if let Err(e) = replay(path, options.runs) {
+            eprintln!("{}: [{}] {e}", path.display(), e.code());
+            status = ExitCode::FAILURE;
+        }
+    }
+    status
+}
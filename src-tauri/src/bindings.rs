@@ -2,8 +2,9 @@ use tauri_specta::{collect_commands, Builder};
 
 pub fn generate_bindings() -> Builder<tauri::Wry> {
     use crate::commands::{
-        notifications, preferences, quick_pane, recording, recording_overlay, recovery,
-        transcription,
+        capabilities, diagnostics, events, history, notifications, permissions, plugins,
+        preferences, quick_pane, recording, recording_overlay, recovery, settings, storage, sync,
+        transcription, vocabulary, voice_memos,
     };
 
     Builder::<tauri::Wry>::new().commands(collect_commands![
@@ -21,13 +22,33 @@ pub fn generate_bindings() -> Builder<tauri::Wry> {
         quick_pane::update_quick_pane_shortcut,
         recording::get_default_recording_shortcut,
         recording::update_recording_shortcut,
+        recording::get_default_redo_shortcut,
+        recording::update_redo_shortcut,
+        recording::update_clipboard_audio_shortcut,
+        recording::update_recording_shortcut_variants,
+        recording::redo_last_dictation,
+        recording::output_pending_dictation,
+        recording::start_meeting_transcription,
         recording::start_recording,
         recording::stop_recording,
+        recording::get_recording_state,
+        recording::get_last_transcription,
+        recording::get_active_session,
+        recording::export_last_recording_wav,
+        recording::get_interrupted_session,
+        recording::get_recoverable_recording,
+        recording::recover_last_recording,
+        recording::discard_recoverable_recording,
+        recording::test_microphone,
         recording::check_microphone_permission,
         recording::request_microphone_permission,
         recording::check_accessibility_permission,
         recording::request_accessibility_permission,
         recording::open_accessibility_settings,
+        permissions::get_permissions_report,
+        permissions::check_permission,
+        permissions::request_permission,
+        permissions::open_permission_settings,
         recording_overlay::show_recording_overlay,
         recording_overlay::report_recording_overlay_rendered,
         recording_overlay::dismiss_recording_overlay,
@@ -37,8 +58,56 @@ pub fn generate_bindings() -> Builder<tauri::Wry> {
         transcription::check_model_status,
         transcription::get_model_directory,
         transcription::open_model_directory,
+        transcription::list_available_models,
+        transcription::list_installed_models,
+        transcription::set_active_model,
+        transcription::set_transcription_language,
+        transcription::update_decode_options,
+        transcription::set_model_keep_alive,
         transcription::cancel_transcription,
         transcription::copy_to_clipboard,
+        transcription::transcribe_file,
+        transcription::transcribe_clipboard_audio,
+        transcription::retranscribe_last,
+        transcription::start_quickstart,
+        diagnostics::run_diagnostics,
+        diagnostics::get_startup_report,
+        diagnostics::is_safe_mode,
+        diagnostics::inject_test_signal,
+        diagnostics::describe_error,
+        diagnostics::get_log_level,
+        diagnostics::set_log_level,
+        diagnostics::reset_log_level,
+        capabilities::get_capabilities,
+        events::subscribe_events,
+        storage::repair_data_directories,
+        storage::cleanup_storage,
+        settings::get_settings,
+        settings::update_settings,
+        settings::update_api_key,
+        settings::get_output_mode,
+        settings::set_output_mode,
+        settings::get_post_processors,
+        settings::set_post_processors,
+        history::list_history,
+        history::get_history_audio_path,
+        history::delete_history_entry,
+        history::export_action_items_markdown,
+        history::add_action_items_to_reminders,
+        history::output_history_entry,
+        history::retranscribe_history,
+        history::set_history_sync_folder,
+        history::sync_history,
+        sync::configure_encrypted_sync,
+        sync::unlock_encrypted_sync,
+        sync::lock_encrypted_sync,
+        sync::run_encrypted_sync,
+        vocabulary::export_vocabulary,
+        vocabulary::import_vocabulary,
+        voice_memos::list_importable_memos,
+        voice_memos::transcribe_memo,
+        plugins::list_plugins,
+        plugins::set_plugin_approval,
     ])
 }
 
@@ -0,0 +1,15 @@
+//! Tauri commands for feature availability.
+//!
+//! Thin command handlers that delegate to capabilities_service.
+
+use tauri::AppHandle;
+
+use crate::services::capabilities_service::{self, Capabilities};
+
+/// Get which optional features are currently available, with the reason
+/// for each unavailable one.
+#[tauri::command]
+#[specta::specta]
+pub fn get_capabilities(app: AppHandle) -> Capabilities {
+    capabilities_service::get_capabilities(&app)
+}
@@ -0,0 +1,91 @@
+//! Tauri commands for diagnostics.
+//!
+//! Thin command handlers that delegate to diagnostics_service.
+
+use tauri::AppHandle;
+
+use crate::domain::{self, CyranoError, ErrorDescription};
+use crate::infrastructure::audio::test_signal::TestSignal;
+use crate::services::diagnostics_service::{self, DiagnosticsReport, StartupReport};
+use crate::services::log_level_service::{self, LogLevel, LogLevels};
+use crate::services::{dictation_service, safe_mode_service};
+
+/// Run all diagnostic checks and return the report.
+///
+/// Includes permission status, model availability, and sandbox/hardened
+/// runtime restrictions along with the fallback used for each.
+#[tauri::command]
+#[specta::specta]
+pub fn run_diagnostics(app: AppHandle) -> DiagnosticsReport {
+    log::info!("run_diagnostics command called");
+    diagnostics_service::run_diagnostics(&app)
+}
+
+/// Get the results of the self-test that ran at startup.
+///
+/// # Returns
+/// * `Some(StartupReport)` with the checks and whether the app is degraded
+/// * `None` if the self-test has not completed yet
+#[tauri::command]
+#[specta::specta]
+pub fn get_startup_report() -> Option<StartupReport> {
+    diagnostics_service::get_startup_report()
+}
+
+/// Check whether the app started in safe mode (`--safe-mode` or Shift held
+/// at launch), in which case only the diagnostics should be shown.
+#[tauri::command]
+#[specta::specta]
+pub fn is_safe_mode() -> bool {
+    safe_mode_service::is_safe_mode()
+}
+
+/// Transcribe and output a synthetic signal (tone, chirp or spoken text) as
+/// if it had just been recorded, bypassing the microphone.
+///
+/// # Returns
+/// * `Ok(())` once the transcription is started; its result comes with the
+///   usual transcription events
+/// * `Err(CyranoError::RecordingFailed)` if a dictation is in progress or the
+///   signal is invalid
+#[tauri::command]
+#[specta::specta]
+pub fn inject_test_signal(app: AppHandle, signal: TestSignal) -> Result<(), CyranoError> {
+    log::info!("inject_test_signal command called");
+    dictation_service::inject_test_signal(&app, &signal)
+}
+
+/// Describe an error code (e.g. `CYR-001`) with remediation steps.
+///
+/// # Returns
+/// * `Some(ErrorDescription)` for a known code
+/// * `None` if the code is unknown
+#[tauri::command]
+#[specta::specta]
+pub fn describe_error(code: String) -> Option<ErrorDescription> {
+    domain::describe_error_code(&code)
+}
+
+/// Get the current log level and per-module overrides.
+#[tauri::command]
+#[specta::specta]
+pub fn get_log_level() -> LogLevels {
+    log_level_service::get_log_levels()
+}
+
+/// Change the log level at runtime, for every module or only `module`.
+///
+/// `module` is `audio`, `whisper`, or a log target prefix such as
+/// `cyrano_lib::services::dictation_service`.
+#[tauri::command]
+#[specta::specta]
+pub fn set_log_level(level: LogLevel, module: Option<String>) {
+    log_level_service::set_log_level(level, module.as_deref());
+}
+
+/// Restore the default log level and drop per-module overrides.
+#[tauri::command]
+#[specta::specta]
+pub fn reset_log_level() {
+    log_level_service::reset_log_levels();
+}
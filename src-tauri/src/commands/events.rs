@@ -0,0 +1,15 @@
+//! Tauri commands for event routing.
+//!
+//! Thin command handlers that delegate to notifier_service.
+
+use tauri::WebviewWindow;
+
+use crate::services::notifier_service::{self, EventChannel};
+
+/// Subscribe the calling window to `channels`, replacing its previous
+/// subscriptions. Backend events of other channels are no longer sent to it.
+#[tauri::command]
+#[specta::specta]
+pub fn subscribe_events(window: WebviewWindow, channels: Vec<EventChannel>) {
+    notifier_service::subscribe(window.label(), &channels);
+}
@@ -0,0 +1,137 @@
+//! Tauri commands for the transcription history.
+//!
+//! Thin command handlers that delegate to history_service.
+
+use tauri::{AppHandle, Manager};
+
+use crate::domain::CyranoError;
+use crate::services::action_item_service;
+use crate::services::dictation_service;
+use crate::services::history_service::{self, HistoryEntry};
+use crate::services::history_sync_service::{self, HistorySyncReport};
+use crate::services::output_service::OutputMode;
+use crate::services::retranscription_service::{self, HistoryRange};
+
+/// List all history entries, newest first.
+#[tauri::command]
+#[specta::specta]
+pub fn list_history() -> Result<Vec<HistoryEntry>, CyranoError> {
+    history_service::list_entries()
+}
+
+/// Get the path of the FLAC audio attached to a history entry, for playback.
+///
+/// The file is allowed in the asset protocol scope, so the frontend plays
+/// it from `convertFileSrc(path)` instead of receiving the whole file over
+/// IPC.
+///
+/// # Returns
+/// * `Ok(String)` with the path of the FLAC file
+/// * `Err(CyranoError::HistoryEntryNotFound)` if the entry has no audio
+/// * `Err(CyranoError::StorageFailed)` if the file could not be allowed
+#[tauri::command]
+#[specta::specta]
+pub fn get_history_audio_path(app: AppHandle, id: String) -> Result<String, CyranoError> {
+    let path = history_service::audio_file(&id)?;
+    app.asset_protocol_scope()
+        .allow_file(&path)
+        .map_err(|e| CyranoError::StorageFailed {
+            reason: format!("Failed to allow playback of {}: {e}", path.display()),
+        })?;
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// Delete a history entry and its audio attachment.
+#[tauri::command]
+#[specta::specta]
+pub fn delete_history_entry(id: String) -> Result<(), CyranoError> {
+    log::info!("delete_history_entry command called for {id}");
+    history_service::delete_entry(&id)
+}
+
+/// Export the action items of a history entry as a Markdown checklist.
+#[tauri::command]
+#[specta::specta]
+pub fn export_action_items_markdown(id: String) -> Result<String, CyranoError> {
+    action_item_service::export_markdown(&id)
+}
+
+/// Add the action items of a history entry to Reminders (macOS).
+///
+/// # Returns
+/// * `Ok(u32)` with the number of reminders created
+/// * `Err(CyranoError::ReminderExportFailed)` if Reminders could not be used
+#[tauri::command]
+#[specta::specta]
+pub fn add_action_items_to_reminders(id: String) -> Result<u32, CyranoError> {
+    log::info!("add_action_items_to_reminders command called for {id}");
+    action_item_service::export_to_reminders(&id)
+}
+
+/// Copy or paste the text of a history entry again, through the same output
+/// as a new dictation.
+///
+/// Runs in the background; listen for clipboard-copied and clipboard-failed
+/// events. When pasting or typing, Cyrano is hidden so the text goes to the
+/// previous app.
+///
+/// # Returns
+/// * `Ok(())` once the output is started
+/// * `Err(CyranoError::HistoryEntryNotFound)` if the entry does not exist
+#[tauri::command]
+#[specta::specta]
+pub fn output_history_entry(
+    app: AppHandle,
+    id: String,
+    mode: OutputMode,
+) -> Result<(), CyranoError> {
+    log::info!("output_history_entry command called for {id} ({mode:?})");
+    dictation_service::output_history_entry(&app, &id, mode)
+}
+
+/// Re-transcribe the stored audio of history entries with another model.
+///
+/// Runs in the background; listen for history-retranscription-progress and
+/// history-retranscription-complete events.
+///
+/// # Arguments
+/// * `range` - Creation time bounds of the entries to process
+/// * `model` - File name of a model in `~/.cyrano/models` (e.g. `ggml-small.bin`)
+///
+/// # Returns
+/// * `Ok(u32)` with the number of entries queued
+/// * `Err(CyranoError::ModelNotFound)` if the model does not exist
+/// * `Err(CyranoError::TranscriptionFailed)` if a job is already running
+#[tauri::command]
+#[specta::specta]
+pub fn retranscribe_history(
+    app: AppHandle,
+    range: HistoryRange,
+    model: String,
+) -> Result<u32, CyranoError> {
+    log::info!("retranscribe_history command called with model {model}");
+    retranscription_service::retranscribe_history(&app, range, &model)
+}
+
+/// Enable history sync through a synced folder, or disable it with None.
+///
+/// # Returns
+/// * `Ok(Some(HistorySyncReport))` after the first sync when enabling
+/// * `Ok(None)` when disabling
+/// * `Err(CyranoError::StorageFailed)` if the folder is unusable
+#[tauri::command]
+#[specta::specta]
+pub fn set_history_sync_folder(
+    folder: Option<String>,
+) -> Result<Option<HistorySyncReport>, CyranoError> {
+    log::info!("set_history_sync_folder command called");
+    history_sync_service::set_sync_folder(folder)
+}
+
+/// Merge history changes from other devices and compact this device's log.
+#[tauri::command]
+#[specta::specta]
+pub fn sync_history() -> Result<HistorySyncReport, CyranoError> {
+    log::info!("sync_history command called");
+    history_sync_service::sync_history()
+}
@@ -3,10 +3,21 @@
 //! Each submodule contains related commands and their helper functions.
 //! Import specific commands via their submodule (e.g., `commands::preferences::greet`).
 
+pub mod capabilities;
+pub mod diagnostics;
+pub mod events;
+pub mod history;
 pub mod notifications;
+pub mod permissions;
+pub mod plugins;
 pub mod preferences;
 pub mod quick_pane;
 pub mod recording;
 pub mod recording_overlay;
 pub mod recovery;
+pub mod settings;
+pub mod storage;
+pub mod sync;
 pub mod transcription;
+pub mod vocabulary;
+pub mod voice_memos;
@@ -0,0 +1,43 @@
+//! Tauri commands for the macOS permissions the app may need.
+//!
+//! Thin command handlers that delegate to permission_service.
+
+use crate::domain::{CyranoError, PermissionStatus};
+use crate::services::permission_service::{self, Permission, PermissionsReport};
+
+/// Get the status of the Microphone, Accessibility and Input Monitoring
+/// permissions, with a readiness score for onboarding.
+#[tauri::command]
+#[specta::specta]
+pub fn get_permissions_report() -> PermissionsReport {
+    permission_service::permissions_report()
+}
+
+/// Check the current status of a permission.
+#[tauri::command]
+#[specta::specta]
+pub fn check_permission(permission: Permission) -> PermissionStatus {
+    permission_service::check_permission(permission)
+}
+
+/// Request a permission, showing the system prompt if the user was never
+/// asked.
+///
+/// # Returns
+/// * `Ok(true)` if the permission is granted
+/// * `Ok(false)` if Accessibility or Input Monitoring is not granted
+/// * `Err(CyranoError::MicAccessDenied)` if the microphone is denied
+#[tauri::command]
+#[specta::specta]
+pub fn request_permission(permission: Permission) -> Result<bool, CyranoError> {
+    log::info!("request_permission command called for {permission:?}");
+    permission_service::request_permission(permission)
+}
+
+/// Open the System Settings pane where a permission is granted.
+#[tauri::command]
+#[specta::specta]
+pub fn open_permission_settings(permission: Permission) -> Result<(), CyranoError> {
+    log::info!("open_permission_settings command called for {permission:?}");
+    permission_service::open_permission_settings(permission)
+}
@@ -0,0 +1,34 @@
+//! Tauri commands for post-processing plugins.
+//!
+//! Thin command handlers that delegate to plugin_service.
+
+use crate::domain::CyranoError;
+use crate::services::plugin_service::{self, PluginInfo};
+
+/// List plugins installed in `~/.cyrano/plugins` with their approval status.
+///
+/// # Returns
+/// * `Ok(Vec<PluginInfo>)` sorted by run order
+/// * `Err(CyranoError::StorageFailed)` if a plugin file cannot be read
+#[tauri::command]
+#[specta::specta]
+pub fn list_plugins() -> Result<Vec<PluginInfo>, CyranoError> {
+    log::info!("list_plugins command called");
+    plugin_service::list_plugins()
+}
+
+/// Allow or deny the current version of a plugin.
+///
+/// # Arguments
+/// * `name` - Plugin file name
+/// * `allowed` - Whether the plugin may process transcriptions
+///
+/// # Returns
+/// * `Ok(())` once the decision is saved
+/// * `Err(CyranoError::StorageFailed)` if the plugin is not installed
+#[tauri::command]
+#[specta::specta]
+pub fn set_plugin_approval(name: String, allowed: bool) -> Result<(), CyranoError> {
+    log::info!("set_plugin_approval command called");
+    plugin_service::set_plugin_approval(&name, allowed)
+}
@@ -5,11 +5,19 @@
 
 use tauri::AppHandle;
 
-use crate::domain::{CyranoError, PermissionStatus};
+use crate::domain::{CyranoError, PermissionStatus, RecordingState};
 use crate::services::accessibility_service;
+use crate::services::meeting_assistant_service;
+use crate::services::microphone_test_service::{self, MicrophoneTestResult};
+use crate::services::output_service::OutputMode;
 use crate::services::permission_service;
-use crate::services::recording_service::{self, RecordingStoppedPayload};
-use crate::services::shortcut_service::{self, DEFAULT_RECORDING_SHORTCUT};
+use crate::services::recording_export_service;
+use crate::services::recording_recovery_service::{self, InterruptedSession, RecoverableRecording};
+use crate::services::recording_service::{self, ActiveSession, RecordingStoppedPayload};
+use crate::services::recording_state::{self, LastTranscription};
+use crate::services::settings_service::{DictationOverrides, ShortcutVariant};
+use crate::services::shortcut_service::{self, DEFAULT_RECORDING_SHORTCUT, DEFAULT_REDO_SHORTCUT};
+use crate::services::{dictation_service, redo_service, settings_service, transcription_service};
 
 /// Returns the default recording shortcut constant for frontend use.
 #[tauri::command]
@@ -53,6 +61,154 @@ pub fn update_recording_shortcut(
     Ok(())
 }
 
+/// Returns the redo shortcut suggested to the user, for frontend use.
+///
+/// It is not registered until the user sets it with `update_redo_shortcut`.
+#[tauri::command]
+#[specta::specta]
+pub fn get_default_redo_shortcut() -> String {
+    DEFAULT_REDO_SHORTCUT.to_string()
+}
+
+/// Updates and persists the global shortcut that redoes the last dictation.
+/// Pass None to remove the shortcut.
+///
+/// # Returns
+/// * `Ok(())` if the shortcut was registered and saved
+/// * `Err(CyranoError)` if registration or saving failed
+#[tauri::command]
+#[specta::specta]
+pub fn update_redo_shortcut(app: AppHandle, shortcut: Option<String>) -> Result<(), CyranoError> {
+    #[cfg(desktop)]
+    match shortcut.as_deref() {
+        Some(new_shortcut) => {
+            log::info!("Updating redo shortcut to: {new_shortcut}");
+            shortcut_service::register_redo_shortcut(&app, new_shortcut)?;
+        }
+        None => {
+            log::info!("Removing redo shortcut");
+            shortcut_service::unregister_redo_shortcut(&app);
+        }
+    }
+
+    #[cfg(not(desktop))]
+    {
+        let _ = &app;
+        log::warn!("Global shortcuts not supported on this platform");
+    }
+
+    settings_service::update_settings(|settings| settings.redo_shortcut = shortcut)?;
+    Ok(())
+}
+
+/// Updates and persists the global shortcut that transcribes the audio on
+/// the clipboard. Pass None to remove the shortcut.
+///
+/// # Returns
+/// * `Ok(())` if the shortcut was registered and saved
+/// * `Err(CyranoError)` if registration or saving failed
+#[tauri::command]
+#[specta::specta]
+pub fn update_clipboard_audio_shortcut(
+    app: AppHandle,
+    shortcut: Option<String>,
+) -> Result<(), CyranoError> {
+    #[cfg(desktop)]
+    match shortcut.as_deref() {
+        Some(new_shortcut) => {
+            log::info!("Updating clipboard audio shortcut to: {new_shortcut}");
+            shortcut_service::register_clipboard_audio_shortcut(&app, new_shortcut)?;
+        }
+        None => {
+            log::info!("Removing clipboard audio shortcut");
+            shortcut_service::unregister_clipboard_audio_shortcut(&app);
+        }
+    }
+
+    #[cfg(not(desktop))]
+    {
+        let _ = &app;
+        log::warn!("Global shortcuts not supported on this platform");
+    }
+
+    settings_service::update_settings(|settings| settings.clipboard_audio_shortcut = shortcut)?;
+    Ok(())
+}
+
+/// Updates and persists the variants of the recording shortcut: the
+/// recording shortcut plus a modifier, starting dictations with different
+/// behavior (e.g. translated to English, or output verbatim).
+///
+/// # Returns
+/// * `Ok(())` if the variants were registered and saved
+/// * `Err(CyranoError)` if registration or saving failed
+#[tauri::command]
+#[specta::specta]
+pub fn update_recording_shortcut_variants(
+    app: AppHandle,
+    variants: Vec<ShortcutVariant>,
+) -> Result<(), CyranoError> {
+    log::info!("Updating {} recording shortcut variant(s)", variants.len());
+
+    // Saved first, so variants that fail to register are still kept
+    settings_service::update_settings(|settings| {
+        settings.recording_shortcut_variants = variants.clone()
+    })?;
+
+    #[cfg(desktop)]
+    shortcut_service::register_recording_variants(&app, &variants)?;
+
+    #[cfg(not(desktop))]
+    {
+        let _ = &app;
+        log::warn!("Global shortcuts not supported on this platform");
+    }
+
+    Ok(())
+}
+
+/// Starts transcribing the meeting announced by meeting-detected.
+///
+/// # Returns
+/// * `Err(CyranoError::RecordingFailed)` if no meeting is in progress or
+///   system audio cannot be captured
+#[tauri::command]
+#[specta::specta]
+pub fn start_meeting_transcription() -> Result<(), CyranoError> {
+    log::info!("start_meeting_transcription command called");
+    meeting_assistant_service::start_meeting_transcription()
+}
+
+/// Deletes the text inserted by the last dictation and starts recording again.
+///
+/// Only effective within a few seconds of the dictation completing.
+///
+/// # Returns
+/// * `Ok(true)` if the redo was performed
+/// * `Ok(false)` if there was nothing to redo
+/// * `Err(CyranoError::RecordingFailed)` if the inserted text could not be deleted
+#[tauri::command]
+#[specta::specta]
+pub fn redo_last_dictation(app: AppHandle) -> Result<bool, CyranoError> {
+    log::info!("redo_last_dictation command called");
+    redo_service::redo_last_dictation(&app)
+}
+
+/// Outputs the dictation waiting for an output mode (`ask_each_time`) in the
+/// mode the user picked.
+///
+/// Runs in the background; listen for clipboard-copied and clipboard-failed
+/// events.
+///
+/// # Returns
+/// `false` if no dictation was waiting, or `mode` is `ask_each_time`.
+#[tauri::command]
+#[specta::specta]
+pub fn output_pending_dictation(app: AppHandle, mode: OutputMode) -> bool {
+    log::info!("output_pending_dictation command called with {mode:?}");
+    dictation_service::output_pending_dictation(&app, mode)
+}
+
 /// Starts audio recording from the microphone.
 ///
 /// # Arguments
@@ -66,7 +222,9 @@ pub fn update_recording_shortcut(
 #[specta::specta]
 pub fn start_recording(app: AppHandle) -> Result<(), CyranoError> {
     log::info!("start_recording command called");
-    recording_service::start_recording(&app)
+    recording_service::start_recording(&app)?;
+    transcription_service::preload_model(&DictationOverrides::default());
+    Ok(())
 }
 
 /// Stops audio recording and returns the recording information.
@@ -84,6 +242,112 @@ pub fn stop_recording(app: AppHandle) -> Result<RecordingStoppedPayload, CyranoE
     recording_service::stop_recording(&app)
 }
 
+/// Gets the current recording workflow state, for windows that missed the
+/// recording-state-changed events.
+#[tauri::command]
+#[specta::specta]
+pub fn get_recording_state() -> RecordingState {
+    recording_state::get_recording_state()
+}
+
+/// Gets the result of the last dictation since the app started.
+///
+/// # Returns
+/// * `Some(LastTranscription)` with the text that was output
+/// * `None` if no dictation completed yet
+#[tauri::command]
+#[specta::specta]
+pub fn get_last_transcription() -> Option<LastTranscription> {
+    recording_state::get_last_transcription()
+}
+
+/// Gets the dictation in progress, so a window created or reloaded
+/// mid-session can restore its state.
+///
+/// # Returns
+/// * `Some(ActiveSession)` while recording or transcribing
+/// * `None` otherwise
+#[tauri::command]
+#[specta::specta]
+pub fn get_active_session() -> Option<ActiveSession> {
+    recording_service::active_session()
+}
+
+/// Saves the audio of the last dictation as a WAV file in `~/.cyrano/recordings`.
+///
+/// # Returns
+/// * `Ok(String)` with the path of the saved file
+/// * `Err(CyranoError::RecordingFailed)` if nothing was recorded yet
+/// * `Err(CyranoError::StorageFailed)` if the file could not be written
+#[tauri::command]
+#[specta::specta]
+pub fn export_last_recording_wav() -> Result<String, CyranoError> {
+    recording_export_service::export_last_recording()
+}
+
+/// Gets the dictation interrupted by the last quit, so the main window can
+/// offer to recover it.
+///
+/// # Returns
+/// * `Some(InterruptedSession)` until the recording is recovered or discarded
+/// * `None` if the last session ended normally
+#[tauri::command]
+#[specta::specta]
+pub fn get_interrupted_session() -> Option<InterruptedSession> {
+    recording_recovery_service::get_interrupted_session()
+}
+
+/// Gets the recording interrupted by the last crash, if any.
+///
+/// # Returns
+/// * `Some(RecoverableRecording)` with the duration of the saved audio
+/// * `None` if there is nothing to recover
+#[tauri::command]
+#[specta::specta]
+pub fn get_recoverable_recording() -> Option<RecoverableRecording> {
+    recording_recovery_service::get_recoverable_recording()
+}
+
+/// Transcribes the recording interrupted by the last crash, then deletes it.
+///
+/// Progress and result are reported with the file-transcription events.
+///
+/// # Returns
+/// * `Ok(())` once the recording is queued for transcription
+/// * `Err(CyranoError::RecordingFailed)` if there is nothing to recover
+/// * `Err(CyranoError::StorageFailed)` if the recording could not be read
+#[tauri::command]
+#[specta::specta]
+pub fn recover_last_recording(app: AppHandle) -> Result<(), CyranoError> {
+    log::info!("recover_last_recording command called");
+    recording_recovery_service::recover_last_recording(&app)
+}
+
+/// Deletes the recording interrupted by the last crash without transcribing it.
+#[tauri::command]
+#[specta::specta]
+pub fn discard_recoverable_recording() -> Result<(), CyranoError> {
+    recording_recovery_service::discard_recoverable_recording()
+}
+
+/// Records from the microphone for about 2 seconds and reports its levels,
+/// so the settings UI can check that it produces signal.
+///
+/// # Returns
+/// * `Ok(MicrophoneTestResult)` with the device name and peak/RMS levels
+/// * `Err(CyranoError::MicAccessDenied)` if permission is denied
+/// * `Err(CyranoError::RecordingFailed)` while recording, or if capture failed
+#[tauri::command]
+#[specta::specta]
+pub async fn test_microphone() -> Result<MicrophoneTestResult, CyranoError> {
+    log::info!("test_microphone command called");
+    tauri::async_runtime::spawn_blocking(microphone_test_service::test_microphone)
+        .await
+        .map_err(|e| CyranoError::RecordingFailed {
+            reason: format!("Microphone test did not complete: {e}"),
+        })?
+}
+
 /// Checks the current microphone permission status.
 ///
 /// # Returns
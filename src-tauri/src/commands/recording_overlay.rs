@@ -3,13 +3,25 @@
 //! The recording overlay is a floating panel (NSPanel on macOS, standard window elsewhere)
 //! that displays the current recording state. It appears when the user triggers recording
 //! via the global shortcut and provides visual feedback for the recording workflow.
+//!
+//! With the `dot` overlay style, a second, 12px panel showing only a colored dot
+//! below the menu bar is used instead, for users who find the panel intrusive
+//! (e.g. during screen shares). Both windows load the same page.
+//!
+//! With the `cursor` overlay placement, the window appears just below the text
+//! cursor of the focused app (or the mouse pointer), clamped to the screen edges.
 
 use std::sync::{Mutex, OnceLock};
 use std::time::Instant;
-use tauri::{AppHandle, Emitter, Manager, WebviewUrl};
+use tauri::{AppHandle, Manager, WebviewUrl};
 
 use crate::domain::RecordingState;
-use crate::services::{recording_service, recording_state};
+use crate::infrastructure::accessibility;
+use crate::services::notifier_service::{self, EventChannel};
+use crate::services::settings_service::{self, OverlayPlacement, OverlayStyle};
+use crate::services::{
+    dictation_service, monitor_layout_service, recording_service, recording_state,
+};
 
 // ============================================================================
 // Constants
@@ -22,6 +34,21 @@ const RECORDING_OVERLAY_LABEL: &str = "recording-overlay";
 const RECORDING_OVERLAY_WIDTH: f64 = 200.0;
 const RECORDING_OVERLAY_HEIGHT: f64 = 80.0;
 
+/// Window label for the dot overlay
+const RECORDING_DOT_LABEL: &str = "recording-overlay-dot";
+
+/// Dot overlay diameter
+const RECORDING_DOT_SIZE: f64 = 12.0;
+
+/// Gap between the dot overlay and the menu bar
+const RECORDING_DOT_MARGIN: f64 = 8.0;
+
+/// Gap between the text cursor (or mouse pointer) and the overlay
+const CURSOR_GAP: f64 = 8.0;
+
+/// Both overlay windows, hidden together on dismiss.
+const OVERLAY_LABELS: [&str; 2] = [RECORDING_OVERLAY_LABEL, RECORDING_DOT_LABEL];
+
 static LAST_SHOW_INSTANT: OnceLock<Mutex<Option<Instant>>> = OnceLock::new();
 
 fn last_show_instant() -> &'static Mutex<Option<Instant>> {
@@ -53,38 +80,54 @@ tauri_panel! {
 // Window Initialization
 // ============================================================================
 
-/// Creates the recording overlay window at app startup.
+/// Creates the recording overlay windows (panel and dot) at app startup.
 /// Must be called from the main thread (e.g., in setup()).
-/// The window starts hidden and is shown via show_recording_overlay command.
+/// The windows start hidden and are shown via show_recording_overlay command.
 pub fn init_recording_overlay(app: &AppHandle) -> Result<(), String> {
-    #[cfg(target_os = "macos")]
-    {
-        init_recording_overlay_macos(app)
+    for label in OVERLAY_LABELS {
+        notifier_service::subscribe(
+            label,
+            &[
+                EventChannel::Recording,
+                EventChannel::Transcription,
+                EventChannel::Clipboard,
+            ],
+        );
+
+        #[cfg(target_os = "macos")]
+        init_recording_overlay_macos(app, label)?;
+
+        #[cfg(not(target_os = "macos"))]
+        init_recording_overlay_standard(app, label)?;
     }
+    Ok(())
+}
 
-    #[cfg(not(target_os = "macos"))]
-    {
-        init_recording_overlay_standard(app)
+/// Logical size of an overlay window.
+fn overlay_size(label: &str) -> (f64, f64) {
+    if label == RECORDING_DOT_LABEL {
+        (RECORDING_DOT_SIZE, RECORDING_DOT_SIZE)
+    } else {
+        (RECORDING_OVERLAY_WIDTH, RECORDING_OVERLAY_HEIGHT)
     }
 }
 
-/// Creates the recording overlay as an NSPanel on macOS (hidden).
+/// Creates a recording overlay window as an NSPanel on macOS (hidden).
 #[cfg(target_os = "macos")]
-fn init_recording_overlay_macos(app: &AppHandle) -> Result<(), String> {
+fn init_recording_overlay_macos(app: &AppHandle, label: &str) -> Result<(), String> {
     use tauri::{LogicalSize, Size};
 
-    log::debug!("Creating recording overlay as NSPanel (macOS)");
+    log::debug!("Creating {label} as NSPanel (macOS)");
 
-    let panel = PanelBuilder::<_, RecordingOverlayPanel>::new(app, RECORDING_OVERLAY_LABEL)
+    let (width, height) = overlay_size(label);
+    let panel = PanelBuilder::<_, RecordingOverlayPanel>::new(app, label)
         .url(WebviewUrl::App("recording-overlay.html".into()))
         .title("Recording")
-        .size(Size::Logical(LogicalSize::new(
-            RECORDING_OVERLAY_WIDTH,
-            RECORDING_OVERLAY_HEIGHT,
-        )))
+        .size(Size::Logical(LogicalSize::new(width, height)))
         .level(PanelLevel::Status) // Status level to appear above fullscreen apps
         .transparent(true)
-        .has_shadow(true)
+        // A square shadow would show around the round dot
+        .has_shadow(label != RECORDING_DOT_LABEL)
         .collection_behavior(
             CollectionBehavior::new()
                 .full_screen_auxiliary()
@@ -101,39 +144,36 @@ fn init_recording_overlay_macos(app: &AppHandle) -> Result<(), String> {
                 .center()
         })
         .build()
-        .map_err(|e| format!("Failed to create recording overlay panel: {e}"))?;
+        .map_err(|e| format!("Failed to create {label} panel: {e}"))?;
 
     // Start hidden - will be shown via show_recording_overlay command
     panel.hide();
-    log::info!("Recording overlay NSPanel created (hidden)");
+    log::info!("{label} NSPanel created (hidden)");
     Ok(())
 }
 
-/// Creates the recording overlay as a standard Tauri window (hidden) on non-macOS platforms.
+/// Creates a recording overlay as a standard Tauri window (hidden) on non-macOS platforms.
 #[cfg(not(target_os = "macos"))]
-fn init_recording_overlay_standard(app: &AppHandle) -> Result<(), String> {
+fn init_recording_overlay_standard(app: &AppHandle, label: &str) -> Result<(), String> {
     use tauri::webview::WebviewWindowBuilder;
 
-    log::debug!("Creating recording overlay as standard window");
-
-    WebviewWindowBuilder::new(
-        app,
-        RECORDING_OVERLAY_LABEL,
-        WebviewUrl::App("recording-overlay.html".into()),
-    )
-    .title("Recording")
-    .inner_size(RECORDING_OVERLAY_WIDTH, RECORDING_OVERLAY_HEIGHT)
-    .always_on_top(true)
-    .skip_taskbar(true)
-    .decorations(false)
-    .transparent(true)
-    .visible(false) // Start hidden
-    .resizable(false)
-    .center()
-    .build()
-    .map_err(|e| format!("Failed to create recording overlay window: {e}"))?;
-
-    log::info!("Recording overlay window created (hidden)");
+    log::debug!("Creating {label} as standard window");
+
+    let (width, height) = overlay_size(label);
+    WebviewWindowBuilder::new(app, label, WebviewUrl::App("recording-overlay.html".into()))
+        .title("Recording")
+        .inner_size(width, height)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .decorations(false)
+        .transparent(true)
+        .visible(false) // Start hidden
+        .resizable(false)
+        .center()
+        .build()
+        .map_err(|e| format!("Failed to create {label} window: {e}"))?;
+
+    log::info!("{label} window created (hidden)");
     Ok(())
 }
 
@@ -142,28 +182,16 @@ fn init_recording_overlay_standard(app: &AppHandle) -> Result<(), String> {
 // ============================================================================
 
 /// Gets the monitor containing the given cursor position, falling back to primary monitor.
+/// Uses the cached monitor layout, so it does not enumerate monitors on each show.
 fn get_monitor_for_cursor(
     app: &AppHandle,
     cursor_pos: tauri::PhysicalPosition<f64>,
 ) -> Option<tauri::Monitor> {
-    match app.monitor_from_point(cursor_pos.x, cursor_pos.y) {
-        Ok(Some(m)) => Some(m),
-        Ok(None) => {
-            log::warn!("No monitor found at cursor position, trying primary monitor");
-            app.primary_monitor().ok().flatten()
-        }
-        Err(e) => {
-            log::warn!("Failed to get monitor from point: {e}");
-            app.primary_monitor().ok().flatten()
-        }
-    }
+    monitor_layout_service::monitor_at(app, cursor_pos.x, cursor_pos.y)
 }
 
-/// Calculates the position to center a window on the monitor containing the cursor.
-/// Falls back to primary monitor if cursor monitor cannot be determined.
-fn get_centered_position_on_cursor_monitor(
-    app: &AppHandle,
-) -> Option<tauri::PhysicalPosition<i32>> {
+/// Gets the monitor containing the cursor.
+fn get_cursor_monitor(app: &AppHandle) -> Option<tauri::Monitor> {
     // Get cursor position
     let cursor_pos = match app.cursor_position() {
         Ok(pos) => pos,
@@ -175,8 +203,16 @@ fn get_centered_position_on_cursor_monitor(
 
     log::debug!("Cursor position: ({}, {})", cursor_pos.x, cursor_pos.y);
 
+    get_monitor_for_cursor(app, cursor_pos)
+}
+
+/// Calculates the position to center a window on the monitor containing the cursor.
+/// Falls back to primary monitor if cursor monitor cannot be determined.
+fn get_centered_position_on_cursor_monitor(
+    app: &AppHandle,
+) -> Option<tauri::PhysicalPosition<i32>> {
     // Get the monitor containing the cursor
-    let monitor = get_monitor_for_cursor(app, cursor_pos)?;
+    let monitor = get_cursor_monitor(app)?;
 
     let monitor_pos = monitor.position();
     let monitor_size = monitor.size();
@@ -204,10 +240,150 @@ fn get_centered_position_on_cursor_monitor(
     Some(tauri::PhysicalPosition::new(x, y))
 }
 
-/// Positions the recording overlay window centered on the monitor containing the cursor.
-fn position_recording_overlay_on_cursor_monitor(app: &AppHandle) {
-    if let Some(position) = get_centered_position_on_cursor_monitor(app) {
-        if let Some(window) = app.get_webview_window(RECORDING_OVERLAY_LABEL) {
+/// Calculates the position of the dot overlay: centered just below the menu bar of
+/// the monitor containing the cursor.
+fn get_dot_position_on_cursor_monitor(app: &AppHandle) -> Option<tauri::PhysicalPosition<i32>> {
+    let monitor = get_cursor_monitor(app)?;
+    // The work area excludes the menu bar and the Dock
+    let work_area = monitor.work_area();
+    let scale_factor = monitor.scale_factor();
+
+    let scaled_size = (RECORDING_DOT_SIZE * scale_factor) as i32;
+    let x = work_area.position.x + (work_area.size.width as i32 - scaled_size) / 2;
+    let y = work_area.position.y + (RECORDING_DOT_MARGIN * scale_factor) as i32;
+
+    log::debug!("Calculated dot position: ({x}, {y})");
+
+    Some(tauri::PhysicalPosition::new(x, y))
+}
+
+/// A rectangle in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PixelRect {
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+}
+
+/// Calculates the position of a `width`x`height` window horizontally centered on
+/// `anchor` and `gap` below it (above it when there is no room below), clamped to
+/// stay inside `bounds`.
+fn position_near_anchor(
+    anchor: PixelRect,
+    width: i32,
+    height: i32,
+    gap: i32,
+    bounds: PixelRect,
+) -> (i32, i32) {
+    let x = anchor.x + (anchor.width - width) / 2;
+    let below = anchor.y + anchor.height + gap;
+    let y = if below + height <= bounds.y + bounds.height {
+        below
+    } else {
+        anchor.y - gap - height
+    };
+
+    let max_x = (bounds.x + bounds.width - width).max(bounds.x);
+    let max_y = (bounds.y + bounds.height - height).max(bounds.y);
+    (x.clamp(bounds.x, max_x), y.clamp(bounds.y, max_y))
+}
+
+/// Gets the text cursor of the focused app in physical pixels, with the monitor
+/// containing it.
+fn get_caret_anchor(app: &AppHandle) -> Option<(PixelRect, tauri::Monitor)> {
+    let caret = accessibility::focused_caret_bounds()?;
+    log::debug!("Text cursor at ({}, {}) points", caret.x, caret.y);
+
+    // Monitor positions are physical pixels, the caret is in points
+    let monitors = monitor_layout_service::layout(app).monitors;
+    monitors.into_iter().find_map(|monitor| {
+        let scale_factor = monitor.scale_factor();
+        let position = *monitor.position();
+        let size = *monitor.size();
+        let left = position.x as f64 / scale_factor;
+        let top = position.y as f64 / scale_factor;
+        let inside = caret.x >= left
+            && caret.x < left + size.width as f64 / scale_factor
+            && caret.y >= top
+            && caret.y < top + size.height as f64 / scale_factor;
+
+        inside.then(|| {
+            let anchor = PixelRect {
+                x: position.x + ((caret.x - left) * scale_factor) as i32,
+                y: position.y + ((caret.y - top) * scale_factor) as i32,
+                width: (caret.width * scale_factor) as i32,
+                height: (caret.height * scale_factor) as i32,
+            };
+            (anchor, monitor)
+        })
+    })
+}
+
+/// Gets the mouse pointer in physical pixels, with the monitor containing it.
+fn get_mouse_anchor(app: &AppHandle) -> Option<(PixelRect, tauri::Monitor)> {
+    let cursor_pos = match app.cursor_position() {
+        Ok(pos) => pos,
+        Err(e) => {
+            log::warn!("Failed to get cursor position: {e}");
+            return None;
+        }
+    };
+    let monitor = get_monitor_for_cursor(app, cursor_pos)?;
+    let anchor = PixelRect {
+        x: cursor_pos.x as i32,
+        y: cursor_pos.y as i32,
+        width: 0,
+        height: 0,
+    };
+    Some((anchor, monitor))
+}
+
+/// Calculates the position of an overlay window just below the text cursor of the
+/// focused app, or the mouse pointer when the app does not report its text cursor,
+/// kept inside the monitor's work area.
+fn get_position_near_text_cursor(
+    app: &AppHandle,
+    label: &str,
+) -> Option<tauri::PhysicalPosition<i32>> {
+    let (anchor, monitor) = get_caret_anchor(app).or_else(|| {
+        log::debug!("No text cursor reported, placing overlay at the mouse pointer");
+        get_mouse_anchor(app)
+    })?;
+
+    let scale_factor = monitor.scale_factor();
+    let work_area = monitor.work_area();
+    let bounds = PixelRect {
+        x: work_area.position.x,
+        y: work_area.position.y,
+        width: work_area.size.width as i32,
+        height: work_area.size.height as i32,
+    };
+    let (width, height) = overlay_size(label);
+    let (x, y) = position_near_anchor(
+        anchor,
+        (width * scale_factor) as i32,
+        (height * scale_factor) as i32,
+        (CURSOR_GAP * scale_factor) as i32,
+        bounds,
+    );
+
+    log::debug!("Calculated position near text cursor: ({x}, {y})");
+
+    Some(tauri::PhysicalPosition::new(x, y))
+}
+
+/// Positions an overlay window according to the `overlay_placement` setting.
+fn position_recording_overlay(app: &AppHandle, label: &str) {
+    let position = match settings_service::get_settings().overlay_placement {
+        OverlayPlacement::Cursor => get_position_near_text_cursor(app, label),
+        OverlayPlacement::Center if label == RECORDING_DOT_LABEL => {
+            get_dot_position_on_cursor_monitor(app)
+        }
+        OverlayPlacement::Center => get_centered_position_on_cursor_monitor(app),
+    };
+    if let Some(position) = position {
+        if let Some(window) = app.get_webview_window(label) {
             if let Err(e) = window.set_position(position) {
                 log::warn!("Failed to set window position: {e}");
             }
@@ -219,23 +395,38 @@ fn position_recording_overlay_on_cursor_monitor(app: &AppHandle) {
 // Window Visibility
 // ============================================================================
 
-/// Returns whether the recording overlay is currently visible.
-fn is_recording_overlay_visible(app: &AppHandle) -> bool {
+/// Label of the overlay window selected by the `overlay_style` setting.
+fn active_overlay_label() -> &'static str {
+    match settings_service::get_settings().overlay_style {
+        OverlayStyle::Panel => RECORDING_OVERLAY_LABEL,
+        OverlayStyle::Dot => RECORDING_DOT_LABEL,
+    }
+}
+
+/// Returns whether an overlay window is currently visible.
+fn is_overlay_window_visible(app: &AppHandle, label: &str) -> bool {
     #[cfg(target_os = "macos")]
     {
-        app.get_webview_panel(RECORDING_OVERLAY_LABEL)
+        app.get_webview_panel(label)
             .map(|panel| panel.is_visible())
             .unwrap_or(false)
     }
 
     #[cfg(not(target_os = "macos"))]
     {
-        app.get_webview_window(RECORDING_OVERLAY_LABEL)
+        app.get_webview_window(label)
             .and_then(|window| window.is_visible().ok())
             .unwrap_or(false)
     }
 }
 
+/// Returns whether the recording overlay (panel or dot) is currently visible.
+fn is_recording_overlay_visible(app: &AppHandle) -> bool {
+    OVERLAY_LABELS
+        .iter()
+        .any(|label| is_overlay_window_visible(app, label))
+}
+
 /// Payload emitted when the recording overlay is shown.
 #[derive(Clone, serde::Serialize)]
 pub struct RecordingOverlayShownPayload {
@@ -260,36 +451,36 @@ pub fn show_recording_overlay(app: AppHandle) -> Result<(), String> {
         *guard = Some(start);
     }
 
-    position_recording_overlay_on_cursor_monitor(&app);
+    let label = active_overlay_label();
+    position_recording_overlay(&app, label);
 
     #[cfg(target_os = "macos")]
     {
         let panel = app
-            .get_webview_panel(RECORDING_OVERLAY_LABEL)
+            .get_webview_panel(label)
             .map_err(|e| format!("Recording overlay panel not found: {e:?}"))?;
         panel.show();
-        log::debug!("Recording overlay panel shown (macOS)");
+        log::debug!("Recording overlay panel {label} shown (macOS)");
     }
 
     #[cfg(not(target_os = "macos"))]
     {
-        let window = app
-            .get_webview_window(RECORDING_OVERLAY_LABEL)
-            .ok_or_else(|| {
-                "Recording overlay window not found - was init_recording_overlay called at startup?"
-                    .to_string()
-            })?;
+        let window = app.get_webview_window(label).ok_or_else(|| {
+            "Recording overlay window not found - was init_recording_overlay called at startup?"
+                .to_string()
+        })?;
         window
             .show()
             .map_err(|e| format!("Failed to show window: {e}"))?;
-        log::debug!("Recording overlay window shown");
+        log::debug!("Recording overlay window {label} shown");
     }
 
     let elapsed_ms = start.elapsed().as_millis() as u64;
     log::info!("Recording overlay show call completed in {elapsed_ms}ms");
 
     // Emit event for frontend to update state
-    if let Err(e) = app.emit(
+    if let Err(e) = notifier_service::emit(
+        &app,
         "recording-overlay-shown",
         RecordingOverlayShownPayload {
             show_call_ms: elapsed_ms,
@@ -300,7 +491,8 @@ pub fn show_recording_overlay(app: AppHandle) -> Result<(), String> {
 
     // Update state for listeners
     recording_state::set_recording_state(RecordingState::Recording);
-    if let Err(e) = app.emit(
+    if let Err(e) = notifier_service::emit(
+        &app,
         "recording-state-changed",
         RecordingStateChangedPayload {
             state: RecordingState::Recording,
@@ -337,45 +529,61 @@ pub fn report_recording_overlay_rendered(_app: AppHandle) -> Result<(), String>
     Ok(())
 }
 
-/// Dismisses the recording overlay window.
+/// Hides an overlay window. Returns false if it was already hidden.
 /// On macOS, resigns key window status before hiding to avoid activating main window.
-#[tauri::command]
-#[specta::specta]
-pub fn dismiss_recording_overlay(app: AppHandle) -> Result<(), String> {
+fn hide_overlay_window(app: &AppHandle, label: &str) -> Result<bool, String> {
     #[cfg(target_os = "macos")]
     {
-        if let Ok(panel) = app.get_webview_panel(RECORDING_OVERLAY_LABEL) {
+        if let Ok(panel) = app.get_webview_panel(label) {
             // Guard: resign_key_window triggers blur event which could call dismiss again
             if !panel.is_visible() {
-                return Ok(());
+                return Ok(false);
             }
-            log::info!("Dismissing recording overlay window");
+            log::info!("Dismissing recording overlay window {label}");
             // Resign key window BEFORE hiding to prevent macOS from
             // activating our main window (which would cause space switching)
             panel.resign_key_window();
             panel.hide();
-            log::debug!("Recording overlay panel dismissed (macOS)");
+            log::debug!("Recording overlay panel {label} dismissed (macOS)");
+            return Ok(true);
         }
     }
 
     #[cfg(not(target_os = "macos"))]
     {
-        if let Some(window) = app.get_webview_window(RECORDING_OVERLAY_LABEL) {
+        if let Some(window) = app.get_webview_window(label) {
             let is_visible = window.is_visible().unwrap_or(false);
             if !is_visible {
-                log::debug!("Recording overlay already hidden, skipping");
-                return Ok(());
+                return Ok(false);
             }
-            log::info!("Dismissing recording overlay window");
+            log::info!("Dismissing recording overlay window {label}");
             window
                 .hide()
                 .map_err(|e| format!("Failed to hide window: {e}"))?;
-            log::debug!("Recording overlay window hidden");
+            log::debug!("Recording overlay window {label} hidden");
+            return Ok(true);
         }
     }
 
+    Ok(false)
+}
+
+/// Dismisses the recording overlay window (panel or dot).
+#[tauri::command]
+#[specta::specta]
+pub fn dismiss_recording_overlay(app: AppHandle) -> Result<(), String> {
+    let mut was_visible = false;
+    for label in OVERLAY_LABELS {
+        was_visible |= hide_overlay_window(&app, label)?;
+    }
+    if !was_visible {
+        log::debug!("Recording overlay already hidden, skipping");
+        return Ok(());
+    }
+
     recording_state::set_recording_state(RecordingState::Idle);
-    if let Err(e) = app.emit(
+    if let Err(e) = notifier_service::emit(
+        &app,
         "recording-state-changed",
         RecordingStateChangedPayload {
             state: RecordingState::Idle,
@@ -384,7 +592,7 @@ pub fn dismiss_recording_overlay(app: AppHandle) -> Result<(), String> {
         log::error!("Failed to emit recording-state-changed event: {e}");
     }
 
-    if let Err(e) = app.emit("recording-overlay-dismissed", ()) {
+    if let Err(e) = notifier_service::emit(&app, "recording-overlay-dismissed", ()) {
         log::error!("Failed to emit recording-overlay-dismissed event: {e}");
     }
 
@@ -415,10 +623,11 @@ pub fn cancel_recording(app: AppHandle) -> Result<(), String> {
     dismiss_recording_overlay(app.clone())?;
 
     let cleared_samples = recording_service::cancel_recording();
+    dictation_service::clear_queued_recording();
     log::info!("Cancelled recording, discarded {cleared_samples} audio samples");
 
     // Emit recording-cancelled event for state management
-    if let Err(e) = app.emit("recording-cancelled", ()) {
+    if let Err(e) = notifier_service::emit(&app, "recording-cancelled", ()) {
         log::error!("Failed to emit recording-cancelled event: {e}");
     }
 
@@ -469,6 +678,63 @@ mod tests {
         assert!(RECORDING_OVERLAY_HEIGHT <= 200.0);
     }
 
+    #[test]
+    fn test_dot_overlay_is_a_separate_small_window() {
+        assert_ne!(RECORDING_DOT_LABEL, RECORDING_OVERLAY_LABEL);
+        assert_eq!(overlay_size(RECORDING_DOT_LABEL), (12.0, 12.0));
+        assert_eq!(
+            overlay_size(RECORDING_OVERLAY_LABEL),
+            (RECORDING_OVERLAY_WIDTH, RECORDING_OVERLAY_HEIGHT)
+        );
+    }
+
+    const SCREEN: PixelRect = PixelRect {
+        x: 0,
+        y: 50,
+        width: 1000,
+        height: 700,
+    };
+
+    fn caret(x: i32, y: i32) -> PixelRect {
+        PixelRect {
+            x,
+            y,
+            width: 2,
+            height: 20,
+        }
+    }
+
+    #[test]
+    fn test_overlay_goes_below_the_caret() {
+        assert_eq!(
+            position_near_anchor(caret(500, 300), 200, 80, 8, SCREEN),
+            (401, 328)
+        );
+    }
+
+    #[test]
+    fn test_overlay_is_clamped_to_the_screen_edges() {
+        // Near the left and right edges
+        assert_eq!(
+            position_near_anchor(caret(10, 300), 200, 80, 8, SCREEN).0,
+            0
+        );
+        assert_eq!(
+            position_near_anchor(caret(990, 300), 200, 80, 8, SCREEN).0,
+            800
+        );
+        // No room below: above the caret
+        assert_eq!(
+            position_near_anchor(caret(500, 700), 200, 80, 8, SCREEN).1,
+            612
+        );
+        // Under the menu bar
+        assert_eq!(
+            position_near_anchor(caret(500, 0), 200, 80, 8, SCREEN).1,
+            50
+        );
+    }
+
     #[test]
     fn test_recording_overlay_shown_payload_serializes() {
         let payload = RecordingOverlayShownPayload { show_call_ms: 42 };
@@ -0,0 +1,116 @@
+//! Backend settings commands.
+//!
+//! Thin command handlers that delegate to settings_service.
+
+use tauri::AppHandle;
+
+use crate::domain::CyranoError;
+use crate::services::output_service::{self, OutputMode};
+use crate::services::settings_service::{self, ApiKeyService, Settings, SettingsState};
+use crate::services::text_processing_service::{self, PostProcessorKind};
+
+/// Returns the current backend settings and the keys locked by managed preferences.
+#[tauri::command]
+#[specta::specta]
+pub fn get_settings() -> SettingsState {
+    settings_service::get_settings_state()
+}
+
+/// Replaces and persists the backend settings.
+///
+/// The redo and clipboard audio shortcuts and the recording shortcut
+/// variants are kept as-is: they must be changed through
+/// `update_redo_shortcut`, `update_clipboard_audio_shortcut` and
+/// `update_recording_shortcut_variants` so that the global shortcuts are
+/// re-registered. The API keys, never sent to the frontend, are kept too
+/// and changed through `update_api_key`.
+///
+/// # Returns
+/// * `Ok(SettingsState)` with the saved settings
+/// * `Err(CyranoError::SettingManaged)` if a managed setting was changed
+/// * `Err(CyranoError::StorageFailed)` if the settings could not be written
+#[tauri::command]
+#[specta::specta]
+pub fn update_settings(settings: Settings) -> Result<SettingsState, CyranoError> {
+    log::info!("update_settings command called");
+    settings_service::update_settings(|current| {
+        let redo_shortcut = current.redo_shortcut.take();
+        let recording_shortcut_variants = std::mem::take(&mut current.recording_shortcut_variants);
+        let clipboard_audio_shortcut = current.clipboard_audio_shortcut.take();
+        let cloud_transcription_api_key = current.cloud_transcription_api_key.take();
+        let summary_api_key = current.summary_api_key.take();
+        *current = Settings {
+            redo_shortcut,
+            recording_shortcut_variants,
+            clipboard_audio_shortcut,
+            cloud_transcription_api_key,
+            summary_api_key,
+            ..settings
+        };
+    })?;
+    Ok(settings_service::get_settings_state())
+}
+
+/// Sets or, with None or an empty key, clears an API key.
+///
+/// The key is written to the settings file and never returned:
+/// `SettingsState` only tells whether it is set.
+///
+/// # Returns
+/// * `Ok(SettingsState)` with the saved settings
+/// * `Err(CyranoError::SettingManaged)` if the key is managed
+/// * `Err(CyranoError::StorageFailed)` if the settings could not be written
+#[tauri::command]
+#[specta::specta]
+pub fn update_api_key(
+    service: ApiKeyService,
+    api_key: Option<String>,
+) -> Result<SettingsState, CyranoError> {
+    log::info!("update_api_key command called for {service:?}");
+    let api_key = api_key.filter(|key| !key.trim().is_empty());
+    settings_service::update_settings(|settings| match service {
+        ApiKeyService::CloudTranscription => settings.cloud_transcription_api_key = api_key,
+        ApiKeyService::Summary => settings.summary_api_key = api_key,
+    })?;
+    Ok(settings_service::get_settings_state())
+}
+
+/// Returns the active output mode.
+#[tauri::command]
+#[specta::specta]
+pub fn get_output_mode() -> OutputMode {
+    settings_service::get_settings().output_mode
+}
+
+/// Sets and persists the output mode. Emits output-mode-changed.
+///
+/// # Returns
+/// * `Ok(())` on success
+/// * `Err(CyranoError::SettingManaged)` if the output mode is managed
+/// * `Err(CyranoError::StorageFailed)` if the settings could not be written
+#[tauri::command]
+#[specta::specta]
+pub fn set_output_mode(app: AppHandle, mode: OutputMode) -> Result<(), CyranoError> {
+    log::info!("set_output_mode command called with {mode:?}");
+    output_service::set_output_mode(&app, mode)
+}
+
+/// Returns the post-processors applied to dictations, in order.
+#[tauri::command]
+#[specta::specta]
+pub fn get_post_processors() -> Vec<PostProcessorKind> {
+    settings_service::get_settings().post_processors
+}
+
+/// Sets and persists the post-processors applied to dictations, in order.
+///
+/// # Returns
+/// * `Ok(())` on success
+/// * `Err(CyranoError::SettingManaged)` if the post-processors are managed
+/// * `Err(CyranoError::StorageFailed)` if the settings could not be written
+#[tauri::command]
+#[specta::specta]
+pub fn set_post_processors(processors: Vec<PostProcessorKind>) -> Result<(), CyranoError> {
+    log::info!("set_post_processors command called");
+    text_processing_service::set_post_processors(processors)
+}
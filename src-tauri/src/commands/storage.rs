@@ -0,0 +1,36 @@
+//! Tauri commands for the `~/.cyrano` data directory.
+//!
+//! Thin command handlers that delegate to data_directory_service and
+//! storage_cleanup_service.
+
+use crate::domain::CyranoError;
+use crate::services::data_directory_service::{self, DataDirectoryReport};
+use crate::services::storage_cleanup_service::{self, CleanupReport};
+
+/// Recreate missing data directories, migrate legacy files, and re-check access.
+///
+/// # Returns
+/// * `Ok(DataDirectoryReport)` describing each directory after repair
+/// * `Err(CyranoError::StorageFailed)` if the data root cannot be created
+#[tauri::command]
+#[specta::specta]
+pub fn repair_data_directories() -> Result<DataDirectoryReport, CyranoError> {
+    log::info!("repair_data_directories command called");
+    data_directory_service::bootstrap()
+}
+
+/// Find orphaned temporary files, debug dumps, old logs and partial model
+/// downloads, and delete them if `delete` is true.
+///
+/// Call with `delete` false to show the reclaimable space, then again with
+/// `delete` true once the user confirms.
+///
+/// # Returns
+/// * `Ok(CleanupReport)` listing the files and the space freed
+/// * `Err(CyranoError::StorageFailed)` if the data root cannot be resolved
+#[tauri::command]
+#[specta::specta]
+pub fn cleanup_storage(delete: bool) -> Result<CleanupReport, CyranoError> {
+    log::info!("cleanup_storage command called");
+    storage_cleanup_service::cleanup_storage(delete)
+}
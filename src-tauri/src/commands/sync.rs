@@ -0,0 +1,58 @@
+//! Tauri commands for end-to-end encrypted sync.
+//!
+//! Thin command handlers that delegate to encrypted_sync_service.
+
+use crate::domain::CyranoError;
+use crate::services::encrypted_sync_service::{self, EncryptedSyncReport};
+
+/// Enable encrypted sync with a WebDAV or relay server, or disable it with None.
+///
+/// # Arguments
+/// * `url` - HTTPS URL of an existing WebDAV collection or relay endpoint
+/// * `username` - Username for HTTP basic auth, if the server requires one
+///
+/// # Returns
+/// * `Ok(())` once the configuration is saved
+/// * `Err(CyranoError::SyncFailed)` if the URL is not HTTPS
+#[tauri::command]
+#[specta::specta]
+pub fn configure_encrypted_sync(
+    url: Option<String>,
+    username: Option<String>,
+) -> Result<(), CyranoError> {
+    log::info!("configure_encrypted_sync command called");
+    encrypted_sync_service::configure(url, username)
+}
+
+/// Unlock encrypted sync for this session and run a first sync.
+///
+/// The passphrase and password are kept in memory only.
+///
+/// # Returns
+/// * `Ok(EncryptedSyncReport)` describing the first sync
+/// * `Err(CyranoError::SyncFailed)` if the passphrase is wrong or the server is unreachable
+#[tauri::command]
+#[specta::specta]
+pub fn unlock_encrypted_sync(
+    passphrase: String,
+    password: Option<String>,
+) -> Result<EncryptedSyncReport, CyranoError> {
+    log::info!("unlock_encrypted_sync command called");
+    encrypted_sync_service::unlock(passphrase, password)
+}
+
+/// Forget the encrypted sync secrets for this session.
+#[tauri::command]
+#[specta::specta]
+pub fn lock_encrypted_sync() {
+    log::info!("lock_encrypted_sync command called");
+    encrypted_sync_service::lock();
+}
+
+/// Sync settings and history with the encrypted sync server.
+#[tauri::command]
+#[specta::specta]
+pub fn run_encrypted_sync() -> Result<EncryptedSyncReport, CyranoError> {
+    log::info!("run_encrypted_sync command called");
+    encrypted_sync_service::sync_now()
+}
@@ -3,8 +3,15 @@
 //! Thin command handlers that delegate to transcription_service and output_service.
 
 use crate::domain::CyranoError;
-use crate::services::transcription_service::ModelStatus;
-use crate::services::{output_service, transcription_service};
+use crate::infrastructure::whisper::DecodeOptions;
+use crate::services::model_catalog_service::{self, CatalogModel};
+use crate::services::settings_service::ModelKeepAlive;
+use crate::services::transcription_service::{InstalledModel, ModelStatus};
+use crate::services::{
+    dictation_service, file_transcription_service, output_service, quickstart_service,
+    retranscription_service, transcription_service,
+};
+use std::path::PathBuf;
 use tauri::AppHandle;
 
 /// Check the current model status.
@@ -49,10 +56,91 @@ pub fn open_model_directory() -> Result<(), CyranoError> {
     Ok(())
 }
 
-/// Request cancellation of ongoing transcription.
+/// List the models that can be downloaded, from smallest to largest, with
+/// their size, languages and expected speed, for a model picker.
 ///
-/// This sets a flag that will abort transcription before it starts or
-/// at the earliest opportunity. Note that once whisper processing begins,
+/// # Returns
+/// * `Ok(Vec<CatalogModel>)` with installed models marked
+/// * `Err(CyranoError::ModelNotFound)` if the models directory cannot be resolved
+#[tauri::command]
+#[specta::specta]
+pub fn list_available_models() -> Result<Vec<CatalogModel>, CyranoError> {
+    log::info!("list_available_models command called");
+    model_catalog_service::list_available_models()
+}
+
+/// List the model files in the models directory with what each one is
+/// (variant, size, quantization, memory needed), read from its header.
+///
+/// # Returns
+/// * `Ok(Vec<InstalledModel>)` sorted by file name
+/// * `Err(CyranoError::ModelNotFound)` if the models directory cannot be read
+#[tauri::command]
+#[specta::specta]
+pub fn list_installed_models() -> Result<Vec<InstalledModel>, CyranoError> {
+    log::info!("list_installed_models command called");
+    transcription_service::list_installed_models()
+}
+
+/// Select the model used for transcription. A loaded model is swapped for
+/// the new one in the background.
+///
+/// # Arguments
+/// * `name` - File name of a model in `~/.cyrano/models` (e.g. `ggml-small.bin`)
+///
+/// # Returns
+/// * `Ok(())` if the model is now active
+/// * `Err(CyranoError::ModelNotFound)` if no such model is installed
+#[tauri::command]
+#[specta::specta]
+pub fn set_active_model(name: String) -> Result<(), CyranoError> {
+    log::info!("set_active_model command called with {name}");
+    transcription_service::set_active_model(&name)
+}
+
+/// Set the language spoken in dictations, or `None` to detect it.
+///
+/// # Returns
+/// * `Ok(())` if the language was saved
+/// * `Err(CyranoError::TranscriptionFailed)` if Whisper does not know the language
+/// * `Err(CyranoError::StorageFailed)` if the settings could not be written
+#[tauri::command]
+#[specta::specta]
+pub fn set_transcription_language(language: Option<String>) -> Result<(), CyranoError> {
+    log::info!("set_transcription_language command called");
+    transcription_service::set_transcription_language(language.as_deref())
+}
+
+/// Set how Whisper decodes dictations (greedy or beam search, temperature
+/// fallback). Out of range values are clamped.
+///
+/// # Returns
+/// * `Ok(DecodeOptions)` with the options as saved
+/// * `Err(CyranoError::StorageFailed)` if the settings could not be written
+#[tauri::command]
+#[specta::specta]
+pub fn update_decode_options(options: DecodeOptions) -> Result<DecodeOptions, CyranoError> {
+    log::info!("update_decode_options command called");
+    transcription_service::set_decode_options(options)
+}
+
+/// Set how long the model stays loaded after a transcription: a number of
+/// minutes, until quit, or unloaded right after each transcription.
+///
+/// # Returns
+/// * `Ok(())` if the setting was saved
+/// * `Err(CyranoError::StorageFailed)` if the settings could not be written
+#[tauri::command]
+#[specta::specta]
+pub fn set_model_keep_alive(keep_alive: ModelKeepAlive) -> Result<(), CyranoError> {
+    log::info!("set_model_keep_alive command called");
+    transcription_service::set_model_keep_alive(keep_alive)
+}
+
+/// Request cancellation of the dictation being transcribed.
+///
+/// This cancels the dictation's token, which aborts transcription before it
+/// starts or at the earliest opportunity. Background jobs are not affected. Note that once whisper processing begins,
 /// it will complete before checking the flag.
 ///
 /// # Returns
@@ -62,6 +150,7 @@ pub fn open_model_directory() -> Result<(), CyranoError> {
 pub fn cancel_transcription() {
     log::info!("cancel_transcription command called");
     transcription_service::request_cancellation();
+    dictation_service::clear_queued_recording();
 }
 
 /// Copy text to the system clipboard.
@@ -83,6 +172,84 @@ pub fn copy_to_clipboard(text: String, app: AppHandle) -> Result<(), CyranoError
     output_service::copy_to_clipboard(&text, &app)
 }
 
+/// Queue an audio file for transcription.
+///
+/// The result is saved to history and reported through a notification and
+/// a file-transcription-complete (or file-transcription-failed) event.
+///
+/// # Arguments
+/// * `path` - Absolute path of a WAV, FLAC, M4A, MP3, AAC, AIFF or CAF file
+///
+/// # Returns
+/// * `Ok(())` once the file is queued
+/// * `Err(CyranoError::UnsupportedAudioFile)` if the format is not supported
+#[tauri::command]
+#[specta::specta]
+pub fn transcribe_file(path: String, app: AppHandle) -> Result<(), CyranoError> {
+    log::info!("transcribe_file command called");
+    let path = PathBuf::from(path);
+    if !file_transcription_service::is_supported_file(&path) {
+        return Err(CyranoError::UnsupportedAudioFile {
+            path: path.display().to_string(),
+            reason: "unsupported format".to_string(),
+        });
+    }
+    file_transcription_service::enqueue_files(&app, vec![path]);
+    Ok(())
+}
+
+/// Transcribes the audio file, audio data or path to an audio file on the
+/// clipboard. Progress and results are reported like `transcribe_file`.
+///
+/// # Returns
+/// * `Ok(u32)` with the number of files queued
+/// * `Err(CyranoError::NoClipboardAudio)` if the clipboard holds no audio
+#[tauri::command]
+#[specta::specta]
+pub fn transcribe_clipboard_audio(app: AppHandle) -> Result<u32, CyranoError> {
+    log::info!("transcribe_clipboard_audio command called");
+    file_transcription_service::transcribe_clipboard_audio(&app)
+}
+
+/// Get started in one click: download the tiny English model, verify it,
+/// make it the active model and transcribe a spoken sample sentence.
+///
+/// Runs in the background; listen for quickstart-progress, then
+/// quickstart-complete or quickstart-failed events.
+///
+/// # Returns
+/// * `Ok(())` once the quickstart is started
+/// * `Err(CyranoError::ModelDownloadFailed)` if it is already running
+#[tauri::command]
+#[specta::specta]
+pub fn start_quickstart(app: AppHandle) -> Result<(), CyranoError> {
+    log::info!("start_quickstart command called");
+    quickstart_service::start_quickstart(&app)
+}
+
+/// Transcribe the last dictation again with another model, without speaking
+/// again. Needs `keep_last_recording`; the result becomes the last
+/// transcription.
+///
+/// # Arguments
+/// * `model` - File name of a model in `~/.cyrano/models` (e.g. `ggml-small.bin`)
+///
+/// # Returns
+/// * `Ok(String)` with the new text
+/// * `Err(CyranoError::ModelNotFound)` if the model does not exist
+/// * `Err(CyranoError::TranscriptionFailed)` if no recording was kept or the
+///   transcription failed
+#[tauri::command]
+#[specta::specta]
+pub async fn retranscribe_last(model: String) -> Result<String, CyranoError> {
+    log::info!("retranscribe_last command called with model {model}");
+    tauri::async_runtime::spawn_blocking(move || retranscription_service::retranscribe_last(&model))
+        .await
+        .map_err(|e| CyranoError::TranscriptionFailed {
+            reason: format!("Re-transcription did not complete: {e}"),
+        })?
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,35 @@
+//! Tauri commands for custom vocabulary and replacement rules.
+//!
+//! Thin command handlers that delegate to vocabulary_service.
+
+use std::path::Path;
+
+use crate::domain::CyranoError;
+use crate::services::vocabulary_service::{self, ImportMode, VocabularyImportReport};
+
+/// Export the vocabulary and replacement rules to a .json or .csv file.
+///
+/// # Returns
+/// * `Ok(())` once the file is written
+/// * `Err(CyranoError::StorageFailed)` if the extension is unsupported or the write fails
+#[tauri::command]
+#[specta::specta]
+pub fn export_vocabulary(path: String) -> Result<(), CyranoError> {
+    log::info!("export_vocabulary command called");
+    vocabulary_service::export_vocabulary(Path::new(&path))
+}
+
+/// Import vocabulary and replacement rules from a .json or .csv file.
+///
+/// # Returns
+/// * `Ok(VocabularyImportReport)` describing what changed
+/// * `Err(CyranoError::StorageFailed)` if the file cannot be read or parsed
+#[tauri::command]
+#[specta::specta]
+pub fn import_vocabulary(
+    path: String,
+    mode: ImportMode,
+) -> Result<VocabularyImportReport, CyranoError> {
+    log::info!("import_vocabulary command called");
+    vocabulary_service::import_vocabulary(Path::new(&path), mode)
+}
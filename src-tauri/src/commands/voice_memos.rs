@@ -0,0 +1,37 @@
+//! Tauri commands for importing voice memos.
+//!
+//! Thin command handlers that delegate to voice_memo_service.
+
+use std::path::PathBuf;
+
+use tauri::AppHandle;
+
+use crate::domain::CyranoError;
+use crate::services::voice_memo_service::{self, ImportableMemo};
+
+/// List the voice memos that have not been transcribed yet, newest first.
+///
+/// # Returns
+/// * `Ok(Vec<ImportableMemo>)`, empty if no memo folder is available
+/// * `Err(CyranoError::StorageFailed)` if the memo folder cannot be read
+#[tauri::command]
+#[specta::specta]
+pub fn list_importable_memos() -> Result<Vec<ImportableMemo>, CyranoError> {
+    log::info!("list_importable_memos command called");
+    voice_memo_service::list_importable_memos()
+}
+
+/// Transcribe a voice memo returned by `list_importable_memos`.
+///
+/// Progress and results are reported through the file-transcription-*
+/// events, and the memo is no longer listed afterwards.
+///
+/// # Returns
+/// * `Ok(())` if the memo was queued
+/// * `Err(CyranoError::UnsupportedAudioFile)` if `path` is not a memo
+#[tauri::command]
+#[specta::specta]
+pub fn transcribe_memo(path: String, app: AppHandle) -> Result<(), CyranoError> {
+    log::info!("transcribe_memo command called");
+    voice_memo_service::transcribe_memo(&app, &PathBuf::from(path))
+}
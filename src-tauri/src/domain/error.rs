@@ -1,4 +1,8 @@
 //! Application error types.
+//!
+//! Every `CyranoError` variant has a stable user-facing code (`CYR-001` style)
+//! that is included in event payloads and logs. Codes are never reused or
+//! renumbered; `describe_error_code` maps a code to remediation steps.
 
 use serde::Serialize;
 use specta::Type;
@@ -34,6 +38,272 @@ pub enum CyranoError {
     /// Failed to open system settings.
     #[error("Failed to open settings: {reason}")]
     OpenSettingsFailed { reason: String },
+
+    /// A file system operation on the data directory failed.
+    #[error("Storage operation failed: {reason}")]
+    StorageFailed { reason: String },
+
+    /// A global shortcut could not be registered.
+    #[error("Failed to register shortcut {shortcut}: {reason}")]
+    ShortcutRegistrationFailed { shortcut: String, reason: String },
+
+    /// The requested history entry (or its audio) does not exist.
+    #[error("History entry not found: {id}")]
+    HistoryEntryNotFound { id: String },
+
+    /// Encrypted sync with the remote server failed.
+    #[error("Sync failed: {reason}")]
+    SyncFailed { reason: String },
+
+    /// The setting is locked by the organization's managed preferences.
+    #[error("Setting {key} is managed by your organization")]
+    SettingManaged { key: String },
+
+    /// An audio file could not be read or decoded for transcription.
+    #[error("Cannot read audio file {path}: {reason}")]
+    UnsupportedAudioFile { path: String, reason: String },
+
+    /// The clipboard holds no audio file, audio data or path to an audio file.
+    #[error("No audio found on the clipboard")]
+    NoClipboardAudio,
+
+    /// Summarizing a transcription failed.
+    #[error("Summary failed: {reason}")]
+    SummaryFailed { reason: String },
+
+    /// Adding action items to Reminders failed.
+    #[error("Could not add reminders: {reason}")]
+    ReminderExportFailed { reason: String },
+
+    /// The microphone delivers no audio, typically because another app holds it exclusively.
+    #[error("Microphone is in use by another app")]
+    MicInUse,
+
+    /// The Whisper model file is damaged or incomplete (e.g. a truncated download).
+    #[error("Model file is corrupted ({reason}): {path}")]
+    ModelCorrupted { path: String, reason: String },
+
+    /// Downloading a Whisper model failed.
+    #[error("Model download failed: {reason}")]
+    ModelDownloadFailed { reason: String },
+}
+
+impl CyranoError {
+    /// Stable user-facing error code, e.g. `CYR-001`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::MicAccessDenied => "CYR-001",
+            Self::ModelNotFound { .. } => "CYR-002",
+            Self::ModelLoadFailed { .. } => "CYR-003",
+            Self::TranscriptionFailed { .. } => "CYR-004",
+            Self::RecordingFailed { .. } => "CYR-005",
+            Self::ClipboardFailed { .. } => "CYR-006",
+            Self::OpenSettingsFailed { .. } => "CYR-007",
+            Self::StorageFailed { .. } => "CYR-008",
+            Self::ShortcutRegistrationFailed { .. } => "CYR-009",
+            Self::HistoryEntryNotFound { .. } => "CYR-010",
+            Self::SyncFailed { .. } => "CYR-011",
+            Self::SettingManaged { .. } => "CYR-012",
+            Self::UnsupportedAudioFile { .. } => "CYR-013",
+            Self::NoClipboardAudio => "CYR-014",
+            Self::SummaryFailed { .. } => "CYR-015",
+            Self::ReminderExportFailed { .. } => "CYR-016",
+            Self::MicInUse => "CYR-017",
+            Self::ModelCorrupted { .. } => "CYR-018",
+            Self::ModelDownloadFailed { .. } => "CYR-019",
+        }
+    }
+}
+
+/// User-facing description of an error code.
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct ErrorDescription {
+    /// The error code, e.g. `CYR-001`
+    pub code: String,
+    /// Short human-readable title
+    pub title: String,
+    /// Steps the user can take to resolve the error, in order
+    pub remediation: Vec<String>,
+}
+
+/// Title and remediation steps for each error code.
+const ERROR_CATALOG: &[(&str, &str, &[&str])] = &[
+    (
+        "CYR-001",
+        "Microphone access denied",
+        &[
+            "Open System Settings > Privacy & Security > Microphone.",
+            "Enable Cyrano in the list of apps.",
+            "Restart recording.",
+        ],
+    ),
+    (
+        "CYR-002",
+        "Speech model not found",
+        &[
+            "Download a Whisper model (.bin file).",
+            "Place it in ~/.cyrano/models.",
+            "Run the storage repair from settings if the folder is missing.",
+        ],
+    ),
+    (
+        "CYR-003",
+        "Speech model failed to load",
+        &[
+            "Check that the model file is complete and not corrupted.",
+            "Close memory-heavy apps or switch to a smaller model.",
+            "Re-download the model if the problem persists.",
+        ],
+    ),
+    (
+        "CYR-004",
+        "Transcription failed",
+        &[
+            "Try recording again.",
+            "Make sure the recording contains audible speech.",
+            "Run diagnostics to check the model and microphone.",
+        ],
+    ),
+    (
+        "CYR-005",
+        "Recording failed",
+        &[
+            "Check that a microphone is connected and selected as input.",
+            "Close other apps that may be using the microphone.",
+            "Try recording again.",
+        ],
+    ),
+    (
+        "CYR-006",
+        "Clipboard unavailable",
+        &[
+            "Try again; another app may be holding the clipboard.",
+            "Copy the text from the transcription history instead.",
+        ],
+    ),
+    (
+        "CYR-007",
+        "Could not open System Settings",
+        &["Open System Settings manually and navigate to Privacy & Security."],
+    ),
+    (
+        "CYR-008",
+        "Storage error",
+        &[
+            "Check that your disk is not full.",
+            "Check that ~/.cyrano is writable.",
+            "Run the storage repair from settings.",
+        ],
+    ),
+    (
+        "CYR-009",
+        "Shortcut unavailable",
+        &[
+            "Another app may already use this shortcut.",
+            "Choose a different shortcut in settings.",
+        ],
+    ),
+    (
+        "CYR-010",
+        "History entry not found",
+        &[
+            "Refresh the history list; the entry may have been deleted.",
+            "Audio is only kept when saving audio with history is enabled.",
+        ],
+    ),
+    (
+        "CYR-011",
+        "Encrypted sync failed",
+        &[
+            "Check your network connection and the sync server address.",
+            "Use the same passphrase as on your other devices.",
+            "Check the server username and password.",
+        ],
+    ),
+    (
+        "CYR-012",
+        "Setting managed by your organization",
+        &[
+            "This setting is locked by a configuration profile or a managed settings file.",
+            "Contact your IT administrator to change it.",
+        ],
+    ),
+    (
+        "CYR-013",
+        "Unsupported audio file",
+        &[
+            "Use a WAV, FLAC, M4A, MP3, AIFF or CAF file.",
+            "Check that the file plays in another app; it may be damaged or protected.",
+        ],
+    ),
+    (
+        "CYR-014",
+        "No audio on the clipboard",
+        &[
+            "Copy an audio file in Finder or a recording in Voice Memos, then try again.",
+            "You can also copy the full path of an audio file as text.",
+        ],
+    ),
+    (
+        "CYR-015",
+        "Summary failed",
+        &[
+            "Check that the summary server is running and reachable at the configured address.",
+            "Check the model name and API key in settings.",
+            "The full transcription is kept; only the summary is missing.",
+        ],
+    ),
+    (
+        "CYR-016",
+        "Could not add reminders",
+        &[
+            "Allow Cyrano to control Reminders in System Settings > Privacy & Security > Automation.",
+            "Export the action items as Markdown instead.",
+        ],
+    ),
+    (
+        "CYR-017",
+        "Microphone in use by another app",
+        &[
+            "Quit or pause apps that may be using the microphone, such as video calls or recorders.",
+            "Check that the microphone is not muted and that Cyrano is allowed to use it.",
+            "Try recording again.",
+        ],
+    ),
+    (
+        "CYR-018",
+        "Model file corrupted",
+        &[
+            "Delete the model file from ~/.cyrano/models.",
+            "Download the model again, keeping Cyrano open until the download completes.",
+            "If it happens again, check that the disk has enough free space.",
+        ],
+    ),
+    (
+        "CYR-019",
+        "Model download failed",
+        &[
+            "Check your internet connection and try again.",
+            "Check that the disk has enough free space for the model.",
+            "Download the model from your browser and move it to ~/.cyrano/models.",
+        ],
+    ),
+];
+
+/// Look up the description and remediation steps for an error code.
+///
+/// # Returns
+/// * `Some(ErrorDescription)` for a known code (case-insensitive)
+/// * `None` if the code is unknown
+pub fn describe_error_code(code: &str) -> Option<ErrorDescription> {
+    ERROR_CATALOG
+        .iter()
+        .find(|(known, _, _)| known.eq_ignore_ascii_case(code.trim()))
+        .map(|(known, title, steps)| ErrorDescription {
+            code: (*known).to_string(),
+            title: (*title).to_string(),
+            remediation: steps.iter().map(|s| (*s).to_string()).collect(),
+        })
 }
 
 #[cfg(test)]
@@ -97,6 +367,180 @@ mod tests {
         assert_eq!(err.to_string(), "Failed to open settings: command failed");
     }
 
+    #[test]
+    fn test_storage_failed_message() {
+        let err = CyranoError::StorageFailed {
+            reason: "read-only volume".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Storage operation failed: read-only volume"
+        );
+    }
+
+    #[test]
+    fn test_shortcut_registration_failed_message() {
+        let err = CyranoError::ShortcutRegistrationFailed {
+            shortcut: "Alt+Space".to_string(),
+            reason: "already in use".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Failed to register shortcut Alt+Space: already in use"
+        );
+    }
+
+    #[test]
+    fn test_history_entry_not_found_message() {
+        let err = CyranoError::HistoryEntryNotFound {
+            id: "1700000000000".to_string(),
+        };
+        assert_eq!(err.to_string(), "History entry not found: 1700000000000");
+    }
+
+    #[test]
+    fn test_sync_failed_message() {
+        let err = CyranoError::SyncFailed {
+            reason: "wrong passphrase".to_string(),
+        };
+        assert_eq!(err.to_string(), "Sync failed: wrong passphrase");
+    }
+
+    #[test]
+    fn test_setting_managed_message() {
+        let err = CyranoError::SettingManaged {
+            key: "history_audio".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Setting history_audio is managed by your organization"
+        );
+    }
+
+    #[test]
+    fn test_unsupported_audio_file_message() {
+        let err = CyranoError::UnsupportedAudioFile {
+            path: "/tmp/a.ogg".to_string(),
+            reason: "unsupported format".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Cannot read audio file /tmp/a.ogg: unsupported format"
+        );
+    }
+
+    #[test]
+    fn test_no_clipboard_audio_message() {
+        let err = CyranoError::NoClipboardAudio;
+        assert_eq!(err.to_string(), "No audio found on the clipboard");
+    }
+
+    #[test]
+    fn test_summary_failed_display() {
+        let err = CyranoError::SummaryFailed {
+            reason: "timeout".to_string(),
+        };
+        assert_eq!(err.to_string(), "Summary failed: timeout");
+    }
+
+    #[test]
+    fn test_reminder_export_failed_display() {
+        let err = CyranoError::ReminderExportFailed {
+            reason: "denied".to_string(),
+        };
+        assert_eq!(err.to_string(), "Could not add reminders: denied");
+    }
+
+    #[test]
+    fn test_mic_in_use_display() {
+        let err = CyranoError::MicInUse;
+        assert_eq!(err.to_string(), "Microphone is in use by another app");
+    }
+
+    #[test]
+    fn test_model_corrupted_error_message() {
+        let err = CyranoError::ModelCorrupted {
+            path: "/models/ggml-base.bin".to_string(),
+            reason: "checksum mismatch".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Model file is corrupted (checksum mismatch): /models/ggml-base.bin"
+        );
+    }
+
+    #[test]
+    fn test_model_download_failed_message() {
+        let err = CyranoError::ModelDownloadFailed {
+            reason: "connection reset".to_string(),
+        };
+        assert_eq!(err.to_string(), "Model download failed: connection reset");
+    }
+
+    fn all_variants() -> Vec<CyranoError> {
+        let reason = || "reason".to_string();
+        vec![
+            CyranoError::MicAccessDenied,
+            CyranoError::ModelNotFound { path: reason() },
+            CyranoError::ModelLoadFailed { reason: reason() },
+            CyranoError::TranscriptionFailed { reason: reason() },
+            CyranoError::RecordingFailed { reason: reason() },
+            CyranoError::ClipboardFailed { reason: reason() },
+            CyranoError::OpenSettingsFailed { reason: reason() },
+            CyranoError::StorageFailed { reason: reason() },
+            CyranoError::ShortcutRegistrationFailed {
+                shortcut: reason(),
+                reason: reason(),
+            },
+            CyranoError::HistoryEntryNotFound { id: reason() },
+            CyranoError::SyncFailed { reason: reason() },
+            CyranoError::SettingManaged { key: reason() },
+            CyranoError::UnsupportedAudioFile {
+                path: String::new(),
+                reason: String::new(),
+            },
+            CyranoError::NoClipboardAudio,
+            CyranoError::SummaryFailed { reason: reason() },
+            CyranoError::ReminderExportFailed { reason: reason() },
+            CyranoError::MicInUse,
+            CyranoError::ModelCorrupted {
+                path: reason(),
+                reason: reason(),
+            },
+            CyranoError::ModelDownloadFailed { reason: reason() },
+        ]
+    }
+
+    #[test]
+    fn test_error_codes_are_unique() {
+        let mut codes: Vec<&str> = all_variants().iter().map(CyranoError::code).collect();
+        codes.sort_unstable();
+        let count = codes.len();
+        codes.dedup();
+        assert_eq!(codes.len(), count, "Error codes must be unique");
+    }
+
+    #[test]
+    fn test_every_error_code_is_described() {
+        for err in all_variants() {
+            let description = describe_error_code(err.code())
+                .unwrap_or_else(|| panic!("Missing description for {}", err.code()));
+            assert!(!description.remediation.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_describe_error_code_is_case_insensitive() {
+        let description = describe_error_code(" cyr-001 ").expect("Should find code");
+        assert_eq!(description.code, "CYR-001");
+        assert_eq!(description.title, "Microphone access denied");
+    }
+
+    #[test]
+    fn test_describe_unknown_error_code() {
+        assert!(describe_error_code("CYR-999").is_none());
+    }
+
     #[test]
     fn test_error_serialization() {
         let err = CyranoError::MicAccessDenied;
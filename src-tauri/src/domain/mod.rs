@@ -9,5 +9,5 @@
 mod error;
 mod state;
 
-pub use error::CyranoError;
+pub use error::{describe_error_code, CyranoError, ErrorDescription};
 pub use state::{PermissionStatus, RecordingState};
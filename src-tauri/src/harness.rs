@@ -0,0 +1,140 @@
+//! Service layer entry points for the `pipeline` example.
+//!
+//! Records or decodes audio and transcribes it through the same services as
+//! a dictation, without the Tauri app, so the Rust pipeline can be profiled
+//! and regressions bisected on their own. Run it with
+//! `cargo run --release --example pipeline -- --help`.
+//!
+//! With the `replay` feature, the `replay` example runs saved dictations
+//! (`save_recordings`) through the pipeline again and compares the timings
+//! with those of the original session.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub use crate::domain::CyranoError;
+pub use crate::infrastructure::whisper::Transcription;
+#[cfg(feature = "replay")]
+pub use crate::services::recording_export_service::SessionTrace;
+
+use crate::services::{
+    file_transcription_service, recording_service, settings_service, transcription_service,
+};
+use crate::traits::audio_capture::AudioCapture;
+
+/// Record from the dictation input device for `duration`, as 16kHz mono
+/// samples.
+pub fn record(duration: Duration) -> Result<Vec<f32>, CyranoError> {
+    let mut capture = recording_service::input_adapter(
+        &settings_service::get_settings(),
+        recording_service::bluetooth_default_input().as_deref(),
+    );
+    capture.start_capture()?;
+
+    let started_at = Instant::now();
+    let mut samples = Vec::new();
+    while started_at.elapsed() < duration {
+        samples.extend(capture.take_samples());
+        thread::sleep(Duration::from_millis(10));
+    }
+    samples.extend(capture.stop_capture()?);
+    Ok(samples)
+}
+
+/// Decode an audio file into 16kHz mono samples.
+pub fn load_audio_file(path: &std::path::Path) -> Result<Vec<f32>, CyranoError> {
+    file_transcription_service::load_audio_file(path)
+}
+
+/// Load the Whisper model from `~/.cyrano/models` if it is not loaded yet.
+pub fn load_model() -> Result<(), CyranoError> {
+    transcription_service::ensure_model_loaded()
+}
+
+/// File name of the model used for transcription.
+pub fn model_name() -> Option<String> {
+    transcription_service::current_model_name()
+}
+
+/// Prepare and transcribe samples like a dictation, with the user's
+/// settings (language, vocabulary, normalization).
+pub fn transcribe(samples: &[f32]) -> Result<Transcription, CyranoError> {
+    let prepared = transcription_service::prepare_samples(samples);
+    transcription_service::transcribe_with_language(&prepared)
+}
+
+/// A replayed dictation, timed like its session trace.
+#[cfg(feature = "replay")]
+pub struct Replay {
+    /// The trace of the original session
+    pub trace: SessionTrace,
+    /// Time spent normalizing the recording, in milliseconds
+    pub prepare_ms: u64,
+    /// Time spent transcribing, in milliseconds
+    pub transcribe_ms: u64,
+    /// Time spent sanitizing and post-processing the text, in milliseconds
+    pub post_process_ms: u64,
+    /// Text after sanitization and post-processing
+    pub text: String,
+}
+
+/// Load a saved dictation and its session trace.
+#[cfg(feature = "replay")]
+pub fn load_session(path: &std::path::Path) -> Result<(SessionTrace, Vec<f32>), CyranoError> {
+    crate::services::recording_export_service::load_trace(path)
+}
+
+/// Run a saved dictation through the pipeline again, with the overrides of
+/// the original session and the current settings.
+///
+/// Plugins are not run: they are external programs whose timing says
+/// nothing about Cyrano.
+#[cfg(feature = "replay")]
+pub fn replay(trace: SessionTrace, samples: &[f32]) -> Result<Replay, CyranoError> {
+    use std::sync::Arc;
+
+    use crate::services::text_processing_service;
+    use crate::services::transcription_service::CancellationToken;
+    use crate::utils::text_sanitize;
+
+    let started_at = Instant::now();
+    let prepared = transcription_service::prepare_samples(samples);
+    let prepare_ms = started_at.elapsed().as_millis() as u64;
+
+    let started_at = Instant::now();
+    let transcription = transcription_service::transcribe_streaming(
+        &prepared,
+        Arc::new(|_: &crate::infrastructure::whisper::Segment| {}),
+        &trace.overrides,
+        &CancellationToken::new(),
+    )?;
+    let transcribe_ms = started_at.elapsed().as_millis() as u64;
+
+    let started_at = Instant::now();
+    let text = text_sanitize::sanitize_output(&transcription.text);
+    let text = if trace.overrides.verbatim {
+        text
+    } else {
+        text_processing_service::process_dictation(&text)
+    };
+    let post_process_ms = started_at.elapsed().as_millis() as u64;
+
+    Ok(Replay {
+        trace,
+        prepare_ms,
+        transcribe_ms,
+        post_process_ms,
+        text,
+    })
+}
+
+/// Check whether the model, engine or decode options of the current
+/// settings differ from those of a session, making the replay not
+/// comparable with it.
+#[cfg(feature = "replay")]
+pub fn settings_differ_from(trace: &SessionTrace) -> bool {
+    let settings = settings_service::get_settings();
+    settings.decode_options != trace.decode_options
+        || settings.transcription_engine != trace.engine
+        || model_name() != trace.model
+}
@@ -0,0 +1,234 @@
+//! macOS caret bounds, surrounding text and text insertion using the
+//! Accessibility API (AXUIElement).
+
+use std::ffi::c_void;
+use std::ptr;
+
+use core_foundation::base::{CFRange, CFType, CFTypeRef, TCFType};
+use core_foundation::string::{CFString, CFStringRef};
+use core_graphics::geometry::{CGPoint, CGRect, CGSize};
+
+use super::ScreenRect;
+
+type AXUIElementRef = CFTypeRef;
+type AXError = i32;
+
+const K_AX_ERROR_SUCCESS: AXError = 0;
+
+/// `kAXValueCGRectType`
+const K_AX_VALUE_CG_RECT_TYPE: u32 = 3;
+
+/// `kAXValueCFRangeType`
+const K_AX_VALUE_CF_RANGE_TYPE: u32 = 4;
+
+#[link(name = "ApplicationServices", kind = "framework")]
+extern "C" {
+    fn AXUIElementCreateSystemWide() -> AXUIElementRef;
+    fn AXUIElementCopyAttributeValue(
+        element: AXUIElementRef,
+        attribute: CFStringRef,
+        value: *mut CFTypeRef,
+    ) -> AXError;
+    fn AXUIElementSetAttributeValue(
+        element: AXUIElementRef,
+        attribute: CFStringRef,
+        value: CFTypeRef,
+    ) -> AXError;
+    fn AXUIElementIsAttributeSettable(
+        element: AXUIElementRef,
+        attribute: CFStringRef,
+        settable: *mut bool,
+    ) -> AXError;
+    fn AXUIElementCopyParameterizedAttributeValue(
+        element: AXUIElementRef,
+        parameterized_attribute: CFStringRef,
+        parameter: CFTypeRef,
+        result: *mut CFTypeRef,
+    ) -> AXError;
+    fn AXValueCreate(value_type: u32, value_ptr: *const c_void) -> CFTypeRef;
+    fn AXValueGetValue(value: CFTypeRef, value_type: u32, value_ptr: *mut c_void) -> bool;
+}
+
+/// Get the bounds of the caret (or selection) in the focused text field.
+///
+/// # Returns
+/// * `Some(ScreenRect)` in points, top-left origin
+/// * `None` without Accessibility permission, when no text field has focus,
+///   or when the app does not report text bounds (many Electron apps)
+pub fn focused_caret_bounds() -> Option<ScreenRect> {
+    // SAFETY: every value returned by a Copy/Create function is owned by a
+    // CFType wrapper that releases it, and AXValueGetValue writes a CGRect
+    // into a CGRect.
+    unsafe {
+        let system = CFType::wrap_under_create_rule(AXUIElementCreateSystemWide());
+        let focused = copy_attribute(system.as_CFTypeRef(), "AXFocusedUIElement")?;
+        let range = copy_attribute(focused.as_CFTypeRef(), "AXSelectedTextRange")?;
+
+        let attribute = CFString::from_static_string("AXBoundsForRange");
+        let mut bounds: CFTypeRef = ptr::null();
+        let error = AXUIElementCopyParameterizedAttributeValue(
+            focused.as_CFTypeRef(),
+            attribute.as_concrete_TypeRef(),
+            range.as_CFTypeRef(),
+            &mut bounds,
+        );
+        if error != K_AX_ERROR_SUCCESS || bounds.is_null() {
+            return None;
+        }
+        let bounds = CFType::wrap_under_create_rule(bounds);
+
+        let mut rect = CGRect::new(&CGPoint::new(0.0, 0.0), &CGSize::new(0.0, 0.0));
+        if !AXValueGetValue(
+            bounds.as_CFTypeRef(),
+            K_AX_VALUE_CG_RECT_TYPE,
+            &mut rect as *mut CGRect as *mut c_void,
+        ) {
+            return None;
+        }
+
+        // Some apps answer with an empty rectangle at the origin
+        let rect = ScreenRect {
+            x: rect.origin.x,
+            y: rect.origin.y,
+            width: rect.size.width,
+            height: rect.size.height,
+        };
+        (rect.height > 0.0 && (rect.x != 0.0 || rect.y != 0.0)).then_some(rect)
+    }
+}
+
+/// Get up to `max_units` UTF-16 code units of text before the caret in the
+/// focused text field.
+///
+/// # Returns
+/// * `Some(String)` with the text, empty at the start of the field
+/// * `None` without Accessibility permission, when no text field has focus,
+///   or when the app does not expose its text (secure fields, many Electron
+///   apps)
+pub fn text_before_caret(max_units: usize) -> Option<String> {
+    // SAFETY: every value returned by a Copy/Create function is owned by a
+    // CFType wrapper that releases it, and AXValueGetValue/AXValueCreate
+    // read and write a CFRange as a CFRange.
+    unsafe {
+        let system = CFType::wrap_under_create_rule(AXUIElementCreateSystemWide());
+        let focused = copy_attribute(system.as_CFTypeRef(), "AXFocusedUIElement")?;
+        let selection = copy_attribute(focused.as_CFTypeRef(), "AXSelectedTextRange")?;
+
+        let mut caret = CFRange::init(0, 0);
+        if !AXValueGetValue(
+            selection.as_CFTypeRef(),
+            K_AX_VALUE_CF_RANGE_TYPE,
+            &mut caret as *mut CFRange as *mut c_void,
+        ) {
+            return None;
+        }
+        let length = caret.location.min(max_units as isize);
+        if length <= 0 {
+            return Some(String::new());
+        }
+
+        let range = CFRange::init(caret.location - length, length);
+        let range = CFType::wrap_under_create_rule(AXValueCreate(
+            K_AX_VALUE_CF_RANGE_TYPE,
+            &range as *const CFRange as *const c_void,
+        ));
+        let attribute = CFString::from_static_string("AXStringForRange");
+        let mut text: CFTypeRef = ptr::null();
+        let error = AXUIElementCopyParameterizedAttributeValue(
+            focused.as_CFTypeRef(),
+            attribute.as_concrete_TypeRef(),
+            range.as_CFTypeRef(),
+            &mut text,
+        );
+        if error != K_AX_ERROR_SUCCESS || text.is_null() {
+            return None;
+        }
+        CFType::wrap_under_create_rule(text)
+            .downcast::<CFString>()
+            .map(|text| text.to_string())
+    }
+}
+
+/// Insert text at the caret of the focused text field, replacing the
+/// selection, without simulating keystrokes.
+///
+/// Some apps accept the change without applying it, so the insertion is
+/// only reported once the text before the caret reads back as `text`.
+///
+/// # Returns
+/// * `true` if the text was inserted
+/// * `false` without Accessibility permission, when no text field has
+///   focus, or when the app does not support setting its selected text; the
+///   field is then unchanged, unless the app applied the text without
+///   exposing it
+pub fn insert_text_at_caret(text: &str) -> bool {
+    // The insertion can only be checked in fields that expose their text
+    if text.is_empty() || text_before_caret(1).is_none() {
+        return false;
+    }
+
+    // SAFETY: every value returned by a Copy/Create function is owned by a
+    // CFType wrapper that releases it, and AXUIElementIsAttributeSettable
+    // writes a boolean into a bool.
+    let applied = unsafe {
+        let system = CFType::wrap_under_create_rule(AXUIElementCreateSystemWide());
+        let Some(focused) = copy_attribute(system.as_CFTypeRef(), "AXFocusedUIElement") else {
+            return false;
+        };
+        let attribute = CFString::from_static_string("AXSelectedText");
+        let mut settable = false;
+        let error = AXUIElementIsAttributeSettable(
+            focused.as_CFTypeRef(),
+            attribute.as_concrete_TypeRef(),
+            &mut settable,
+        );
+        if error != K_AX_ERROR_SUCCESS || !settable {
+            return false;
+        }
+        let value = CFString::new(text);
+        AXUIElementSetAttributeValue(
+            focused.as_CFTypeRef(),
+            attribute.as_concrete_TypeRef(),
+            value.as_CFTypeRef(),
+        ) == K_AX_ERROR_SUCCESS
+    };
+
+    applied && text_before_caret(text.encode_utf16().count()).is_some_and(|before| before == text)
+}
+
+/// Copy an attribute of an accessibility element.
+///
+/// # Safety
+/// `element` must be a valid AXUIElementRef.
+unsafe fn copy_attribute(element: AXUIElementRef, attribute: &'static str) -> Option<CFType> {
+    let attribute = CFString::from_static_string(attribute);
+    let mut value: CFTypeRef = ptr::null();
+    let error = AXUIElementCopyAttributeValue(element, attribute.as_concrete_TypeRef(), &mut value);
+    (error == K_AX_ERROR_SUCCESS && !value.is_null()).then(|| CFType::wrap_under_create_rule(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_focused_caret_bounds_does_not_panic() {
+        // Depends on the permission and the focused app (none in headless CI)
+        if let Some(rect) = focused_caret_bounds() {
+            assert!(rect.height > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_text_before_caret_is_bounded() {
+        // Depends on the permission and the focused app (none in headless CI)
+        if let Some(text) = text_before_caret(100) {
+            assert!(text.encode_utf16().count() <= 100);
+        }
+    }
+
+    #[test]
+    fn test_inserting_nothing_is_not_an_insertion() {
+        assert!(!insert_text_at_caret(""));
+    }
+}
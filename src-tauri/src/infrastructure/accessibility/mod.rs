@@ -0,0 +1,42 @@
+//! Focused text element queries.
+//!
+//! Reads where the text cursor (caret) of the focused text field is on
+//! screen through the macOS Accessibility API, so feedback can be shown
+//! where the user is typing, and the text before it, so dictations fit the
+//! sentence being typed. Text can also be inserted at the caret directly.
+//! Requires the Accessibility permission; apps that do not expose their
+//! text fields report nothing.
+
+#[cfg(target_os = "macos")]
+pub mod macos_caret;
+
+#[cfg(target_os = "macos")]
+pub use macos_caret::{focused_caret_bounds, insert_text_at_caret, text_before_caret};
+
+/// Caret bounds are only available on macOS.
+#[cfg(not(target_os = "macos"))]
+pub fn focused_caret_bounds() -> Option<ScreenRect> {
+    None
+}
+
+/// Text around the caret is only available on macOS.
+#[cfg(not(target_os = "macos"))]
+pub fn text_before_caret(_max_units: usize) -> Option<String> {
+    None
+}
+
+/// Inserting text through the Accessibility API is only available on macOS.
+#[cfg(not(target_os = "macos"))]
+pub fn insert_text_at_caret(_text: &str) -> bool {
+    false
+}
+
+/// A rectangle on screen in points, with the origin at the top-left corner
+/// of the main display.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScreenRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
@@ -0,0 +1,207 @@
+//! Reduction of multi-channel input frames to mono.
+//!
+//! Mono and stereo microphones are averaged. Audio interfaces with more than
+//! two inputs usually have a single microphone plugged in, and averaging
+//! would mix the silent channels in (an 8-channel interface would record the
+//! voice 18 dB lower), so a single channel is recorded instead: the one set
+//! in `input_channel`, or else the loudest one.
+//!
+//! The mixer runs in the audio callback: it allocates only when created.
+
+/// Smoothing of the channel levels per callback buffer. At ~10ms per buffer,
+/// the levels follow the last few hundred milliseconds.
+const LEVEL_DECAY: f32 = 0.95;
+
+/// How much louder another channel must get before recording switches to it
+/// (6 dB), so recording does not jump between channels on every word.
+const SWITCH_RATIO: f32 = 4.0;
+
+/// Channel selection strategy.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Selection {
+    /// Average all channels (mono and stereo inputs)
+    Average,
+    /// Record one channel, by index
+    Fixed(usize),
+    /// Record the channel with the highest recent level
+    Loudest,
+}
+
+/// Converts interleaved frames to mono samples.
+pub struct ChannelMixer {
+    channels: usize,
+    selection: Selection,
+    /// Smoothed mean square level of each channel (`Loudest` only)
+    levels: Vec<f32>,
+    /// Channel recorded by `Loudest`
+    loudest: usize,
+}
+
+impl ChannelMixer {
+    /// Create a mixer for `channels` interleaved channels.
+    ///
+    /// `source_channel` is the channel to record on inputs with more than two
+    /// channels, numbered from 1. If None or out of range, the loudest
+    /// channel is recorded.
+    pub fn new(channels: usize, source_channel: Option<u16>) -> Self {
+        let channels = channels.max(1);
+        let selection = if channels <= 2 {
+            Selection::Average
+        } else {
+            match source_channel {
+                Some(channel) if (1..=channels).contains(&(channel as usize)) => {
+                    Selection::Fixed(channel as usize - 1)
+                }
+                Some(channel) => {
+                    log::warn!(
+                        "Input channel {channel} does not exist on a {channels}-channel input, using the loudest channel"
+                    );
+                    Selection::Loudest
+                }
+                None => Selection::Loudest,
+            }
+        };
+        if channels > 2 {
+            log::info!("Multi-channel input ({channels} channels), recording {selection:?}");
+        }
+        let levels = match selection {
+            Selection::Loudest => vec![0.0; channels],
+            _ => Vec::new(),
+        };
+        Self {
+            channels,
+            selection,
+            levels,
+            loudest: 0,
+        }
+    }
+
+    /// Mono samples of a buffer of interleaved frames, with `to_f32`
+    /// converting each sample to the [-1, 1] range.
+    pub fn mix<'a, S: Copy>(
+        &'a mut self,
+        data: &'a [S],
+        to_f32: impl Fn(S) -> f32 + Copy + 'a,
+    ) -> impl Iterator<Item = f32> + 'a {
+        if self.selection == Selection::Loudest {
+            self.update_loudest(data, to_f32);
+        }
+        let selection = match self.selection {
+            Selection::Loudest => Selection::Fixed(self.loudest),
+            selection => selection,
+        };
+        data.chunks(self.channels)
+            .map(move |frame| match selection {
+                Selection::Fixed(channel) => frame.get(channel).map_or(0.0, |&s| to_f32(s)),
+                _ => frame.iter().map(|&s| to_f32(s)).sum::<f32>() / frame.len() as f32,
+            })
+    }
+
+    /// Update the channel levels with a buffer and switch to a channel that
+    /// got clearly louder than the recorded one.
+    fn update_loudest<S: Copy>(&mut self, data: &[S], to_f32: impl Fn(S) -> f32) {
+        let frames = data.len() / self.channels;
+        if frames == 0 {
+            return;
+        }
+        for (channel, level) in self.levels.iter_mut().enumerate() {
+            let mean_square = data
+                .iter()
+                .skip(channel)
+                .step_by(self.channels)
+                .map(|&s| to_f32(s).powi(2))
+                .sum::<f32>()
+                / frames as f32;
+            *level = *level * LEVEL_DECAY + mean_square * (1.0 - LEVEL_DECAY);
+        }
+
+        let (channel, level) = self
+            .levels
+            .iter()
+            .copied()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap_or((0, 0.0));
+        if channel != self.loudest && level > self.levels[self.loudest] * SWITCH_RATIO {
+            self.loudest = channel;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Interleave `frames` frames of `channels` channels, with `signal` on
+    /// channel `active` and silence elsewhere.
+    fn interleaved(channels: usize, active: usize, frames: usize, signal: f32) -> Vec<f32> {
+        (0..frames * channels)
+            .map(|i| {
+                if i % channels == active {
+                    signal * (-1f32).powi((i / channels) as i32)
+                } else {
+                    0.0
+                }
+            })
+            .collect()
+    }
+
+    fn mix(mixer: &mut ChannelMixer, data: &[f32]) -> Vec<f32> {
+        mixer.mix(data, |s| s).collect()
+    }
+
+    #[test]
+    fn test_averages_stereo() {
+        let mut mixer = ChannelMixer::new(2, Some(2));
+        assert_eq!(mix(&mut mixer, &[0.2, 0.4, -0.5, 0.1]), [0.3, -0.2]);
+        let mut mixer = ChannelMixer::new(1, None);
+        assert_eq!(mix(&mut mixer, &[0.2, 0.4]), [0.2, 0.4]);
+    }
+
+    #[test]
+    fn test_records_configured_channel() {
+        let mut mixer = ChannelMixer::new(8, Some(3));
+        let data = interleaved(8, 2, 4, 0.5);
+        assert_eq!(mix(&mut mixer, &data), [0.5, -0.5, 0.5, -0.5]);
+
+        let samples: Vec<f32> = mixer
+            .mix(&[0i16, 0, 16_384, 0], |s| s as f32 / 32768.0)
+            .collect();
+        assert_eq!(samples, [0.5]);
+    }
+
+    #[test]
+    fn test_records_loudest_channel_of_eight() {
+        let mut mixer = ChannelMixer::new(8, None);
+        let data = interleaved(8, 5, 480, 0.3);
+        let samples = mix(&mut mixer, &data);
+        // Full level, not diluted by the seven silent channels
+        assert!(samples.iter().all(|s| s.abs() == 0.3));
+    }
+
+    #[test]
+    fn test_loudest_channel_needs_a_clear_margin_to_switch() {
+        let mut mixer = ChannelMixer::new(4, None);
+        for _ in 0..50 {
+            mix(&mut mixer, &interleaved(4, 1, 480, 0.3));
+        }
+        assert_eq!(mixer.loudest, 1);
+
+        // Slightly louder for a moment: not enough to switch
+        mix(&mut mixer, &interleaved(4, 3, 480, 0.4));
+        assert_eq!(mixer.loudest, 1);
+
+        // The speaker moved to another microphone
+        for _ in 0..50 {
+            mix(&mut mixer, &interleaved(4, 3, 480, 0.3));
+        }
+        assert_eq!(mixer.loudest, 3);
+    }
+
+    #[test]
+    fn test_out_of_range_channel_falls_back_to_loudest() {
+        let mut mixer = ChannelMixer::new(4, Some(9));
+        let samples = mix(&mut mixer, &interleaved(4, 2, 4, 0.5));
+        assert_eq!(samples, [0.5, -0.5, 0.5, -0.5]);
+    }
+}
@@ -1,76 +1,182 @@
 //! cpal audio constants, adapter, and error conversions.
 //!
 //! Provides a concrete AudioCapture implementation backed by cpal.
+//!
+//! The audio callback runs on a real-time thread, so it only reduces
+//! frames to mono (see `channel_mixer`) and pushes them into a lock-free single-producer single-consumer
+//! ring buffer: no locks, no allocations. Resampling to 16kHz and
+//! accumulation happen on the capture thread when it drains the ring.
 
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
 
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use rtrb::{Consumer, Producer, RingBuffer};
 
 use crate::domain::CyranoError;
-use crate::infrastructure::audio::resampler::LinearResampler;
+use crate::infrastructure::audio::channel_mixer::ChannelMixer;
+use crate::infrastructure::audio::resampler::{Resampler, ResamplerQuality};
 use crate::traits::audio_capture::AudioCapture;
 
 /// Target sample rate for Whisper compatibility (16kHz)
 pub const TARGET_SAMPLE_RATE: u32 = 16_000;
 
+/// Seconds of device audio the ring buffer holds between two drains.
+const RING_BUFFER_SECONDS: usize = 2;
+
+/// A running input stream with the capture-thread end of its ring buffer.
+struct ActiveStream {
+    stream: cpal::Stream,
+    consumer: Consumer<f32>,
+    /// Converts the device rate to 16kHz
+    resampler: Resampler,
+}
+
 /// cpal-backed audio capture adapter.
 pub struct CpalAdapter {
-    buffer: Arc<Mutex<Vec<f32>>>,
-    stream: Option<cpal::Stream>,
+    active: Option<ActiveStream>,
+    /// 16kHz samples drained from the ring buffer and not taken yet
+    samples: Vec<f32>,
     is_capturing: bool,
+    /// Set by the stream error callback when the device disappears
+    device_lost: Arc<AtomicBool>,
+    /// Device samples dropped by the callback because the ring buffer was full
+    overruns: Arc<AtomicUsize>,
+    device_name: Option<String>,
+    /// Input device to use instead of the default one, by name
+    preferred_device: Option<String>,
+    /// Channel recorded on inputs with more than two channels, from 1
+    input_channel: Option<u16>,
+    resampler_quality: ResamplerQuality,
 }
 
 impl CpalAdapter {
-    /// Create a new adapter with an empty buffer.
-    pub fn new() -> Self {
+    /// Create a new adapter with an empty buffer, converting device audio to
+    /// 16kHz with the given resampler.
+    pub fn new(resampler_quality: ResamplerQuality) -> Self {
         Self {
-            buffer: Arc::new(Mutex::new(Vec::new())),
-            stream: None,
+            active: None,
+            samples: Vec::new(),
             is_capturing: false,
+            device_lost: Arc::new(AtomicBool::new(false)),
+            overruns: Arc::new(AtomicUsize::new(0)),
+            device_name: None,
+            preferred_device: None,
+            input_channel: None,
+            resampler_quality,
+        }
+    }
+
+    /// Record from the named input device instead of the default one. The
+    /// default device is used if the named one is not found.
+    pub fn with_input_device(mut self, name: String) -> Self {
+        self.preferred_device = Some(name);
+        self
+    }
+
+    /// Record the given channel (numbered from 1) of inputs with more than
+    /// two channels. If None, the loudest channel is recorded.
+    pub fn with_input_channel(mut self, channel: Option<u16>) -> Self {
+        self.input_channel = channel;
+        self
+    }
+
+    /// The preferred input device if it is connected, or else the default one.
+    fn input_device(&self, host: &cpal::Host) -> Option<cpal::Device> {
+        let preferred = self.preferred_device.as_deref().and_then(|name| {
+            host.input_devices()
+                .ok()?
+                .find(|device| device.name().is_ok_and(|device_name| device_name == name))
+        });
+        if preferred.is_none() {
+            if let Some(name) = &self.preferred_device {
+                log::warn!("Input device {name} not found, using the default input");
+            }
+        }
+        preferred.or_else(|| host.default_input_device())
+    }
+
+    /// Open the input device (the default one unless another is preferred)
+    /// and start streaming into a new ring buffer.
+    fn open_input_device(&mut self) -> Result<(), CyranoError> {
+        let host = cpal::default_host();
+        let device = self
+            .input_device(&host)
+            .ok_or(CyranoError::MicAccessDenied)?;
+
+        let configs = get_input_configs(&device)?;
+
+        self.device_lost.store(false, Ordering::SeqCst);
+        let mut result = Err(CyranoError::RecordingFailed {
+            reason: "No supported audio configurations found".to_string(),
+        });
+        let mut sample_rate = TARGET_SAMPLE_RATE;
+        for config in configs {
+            sample_rate = config.sample_rate().0;
+            result = Self::build_stream(
+                &device,
+                config,
+                self.input_channel,
+                self.device_lost.clone(),
+                self.overruns.clone(),
+            );
+            match &result {
+                Ok(_) => {
+                    log::info!("Capturing at {sample_rate}Hz");
+                    break;
+                }
+                // The device may refuse to switch rates, e.g. while another app uses it
+                Err(e) => log::warn!("Failed to open input at {sample_rate}Hz: {e}"),
+            }
         }
+        let (stream, consumer) = result?;
+        stream.play().map_err(CyranoError::from)?;
+
+        self.active = Some(ActiveStream {
+            stream,
+            consumer,
+            resampler: Resampler::new(sample_rate, TARGET_SAMPLE_RATE, self.resampler_quality),
+        });
+        self.device_name = device.name().ok();
+        Ok(())
     }
 
     fn build_stream(
         device: &cpal::Device,
         config: cpal::SupportedStreamConfig,
-        buffer: Arc<Mutex<Vec<f32>>>,
-    ) -> Result<cpal::Stream, CyranoError> {
+        input_channel: Option<u16>,
+        device_lost: Arc<AtomicBool>,
+        overruns: Arc<AtomicUsize>,
+    ) -> Result<(cpal::Stream, Consumer<f32>), CyranoError> {
         let device_sample_rate = config.sample_rate().0;
-        let channels = config.channels() as usize;
+        let mut mixer = ChannelMixer::new(config.channels() as usize, input_channel);
         let sample_format = config.sample_format();
 
-        let resampler = LinearResampler::new(device_sample_rate, TARGET_SAMPLE_RATE);
+        let (mut producer, consumer) =
+            RingBuffer::new(device_sample_rate as usize * RING_BUFFER_SECONDS);
 
-        let err_callback = |err| log::error!("Audio stream error: {err}");
+        let err_callback = move |err| match err {
+            cpal::StreamError::DeviceNotAvailable => {
+                log::warn!("Audio input device disconnected");
+                device_lost.store(true, Ordering::SeqCst);
+            }
+            _ => log::error!("Audio stream error: {err}"),
+        };
 
         let stream = match sample_format {
             cpal::SampleFormat::F32 => {
-                let mut resampler = resampler;
-                let buffer_clone = buffer.clone();
                 let data_callback = move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                    if let Ok(mut buf) = buffer_clone.lock() {
-                        for frame in data.chunks(channels) {
-                            let sample = frame.iter().sum::<f32>() / frame.len() as f32;
-                            resampler.push_sample(sample, &mut buf);
-                        }
-                    }
+                    let samples = mixer.mix(data, |s| s);
+                    push_samples(&mut producer, samples, &overruns);
                 };
                 device
                     .build_input_stream(&config.into(), data_callback, err_callback, None)
                     .map_err(CyranoError::from)?
             }
             cpal::SampleFormat::I16 => {
-                let mut resampler = resampler;
-                let buffer_clone = buffer.clone();
                 let data_callback = move |data: &[i16], _: &cpal::InputCallbackInfo| {
-                    if let Ok(mut buf) = buffer_clone.lock() {
-                        for frame in data.chunks(channels) {
-                            let sample = frame.iter().map(|&s| s as f32).sum::<f32>()
-                                / frame.len() as f32
-                                / 32768.0;
-                            resampler.push_sample(sample, &mut buf);
-                        }
-                    }
+                    let samples = mixer.mix(data, |s| s as f32 / 32768.0);
+                    push_samples(&mut producer, samples, &overruns);
                 };
                 device
                     .build_input_stream(&config.into(), data_callback, err_callback, None)
@@ -83,7 +189,63 @@ impl CpalAdapter {
             }
         };
 
-        Ok(stream)
+        Ok((stream, consumer))
+    }
+
+    /// Resample the samples waiting in the ring buffer into `self.samples`.
+    fn drain(&mut self) {
+        if let Some(active) = self.active.as_mut() {
+            drain_ring(
+                &mut active.consumer,
+                &mut active.resampler,
+                &mut self.samples,
+            );
+        }
+
+        let dropped = self.overruns.swap(0, Ordering::Relaxed);
+        if dropped > 0 {
+            log::warn!("Audio capture fell behind, dropped {dropped} device samples");
+        }
+    }
+
+    /// Stop the stream and keep everything it captured, including the
+    /// samples the resampler still holds back.
+    fn close_stream(&mut self) {
+        self.drain();
+        if let Some(ActiveStream {
+            stream,
+            mut consumer,
+            mut resampler,
+        }) = self.active.take()
+        {
+            // Stop the callback first so nothing is pushed after the last drain
+            drop(stream);
+            drain_ring(&mut consumer, &mut resampler, &mut self.samples);
+            resampler.flush(&mut self.samples);
+        }
+    }
+}
+
+/// Push downmixed samples from the audio callback without blocking. Samples
+/// that do not fit are dropped and counted in `overruns`.
+pub(super) fn push_samples(
+    producer: &mut Producer<f32>,
+    samples: impl Iterator<Item = f32>,
+    overruns: &AtomicUsize,
+) {
+    for sample in samples {
+        if producer.push(sample).is_err() {
+            overruns.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Move the samples waiting in the ring buffer through the resampler.
+fn drain_ring(consumer: &mut Consumer<f32>, resampler: &mut Resampler, out: &mut Vec<f32>) {
+    if let Ok(chunk) = consumer.read_chunk(consumer.slots()) {
+        for sample in chunk {
+            resampler.push_sample(sample, out);
+        }
     }
 }
 
@@ -93,41 +255,53 @@ impl AudioCapture for CpalAdapter {
             return Ok(());
         }
 
-        let host = cpal::default_host();
-        let device = host
-            .default_input_device()
-            .ok_or(CyranoError::MicAccessDenied)?;
-
-        let config = get_input_config(&device)?;
-
-        let stream = Self::build_stream(&device, config, self.buffer.clone())?;
-        stream.play().map_err(CyranoError::from)?;
-
-        self.stream = Some(stream);
+        self.open_input_device()?;
         self.is_capturing = true;
         Ok(())
     }
 
     fn stop_capture(&mut self) -> Result<Vec<f32>, CyranoError> {
-        self.stream = None;
+        self.close_stream();
         self.is_capturing = false;
-
-        let mut buffer = self
-            .buffer
-            .lock()
-            .map_err(|e| CyranoError::RecordingFailed {
-                reason: format!("Failed to lock audio buffer: {e}"),
-            })?;
-
-        Ok(std::mem::take(&mut *buffer))
+        Ok(std::mem::take(&mut self.samples))
     }
 
     fn is_capturing(&self) -> bool {
         self.is_capturing
     }
+
+    fn take_samples(&mut self) -> Vec<f32> {
+        self.drain();
+        std::mem::take(&mut self.samples)
+    }
+
+    fn device_name(&self) -> Option<String> {
+        self.device_name.clone()
+    }
+
+    fn device_lost(&self) -> bool {
+        self.device_lost.load(Ordering::SeqCst)
+    }
+
+    fn switch_to_default_device(&mut self) -> Result<String, CyranoError> {
+        // Close the dead stream first; the samples it captured are kept
+        self.close_stream();
+        if let Err(e) = self.open_input_device() {
+            // Still without a device: keep reporting it so the caller retries
+            self.device_lost.store(true, Ordering::SeqCst);
+            return Err(e);
+        }
+        Ok(self.device_name.clone().unwrap_or_default())
+    }
 }
 
-fn get_input_config(device: &cpal::Device) -> Result<cpal::SupportedStreamConfig, CyranoError> {
+/// Sample formats the capture callbacks convert, in order of preference.
+const CAPTURE_FORMATS: [cpal::SampleFormat; 2] = [cpal::SampleFormat::F32, cpal::SampleFormat::I16];
+
+/// List the configurations to try for a device, best first.
+fn get_input_configs(
+    device: &cpal::Device,
+) -> Result<Vec<cpal::SupportedStreamConfig>, CyranoError> {
     let supported_configs: Vec<_> = device
         .supported_input_configs()
         .map_err(|e| match e {
@@ -144,14 +318,38 @@ fn get_input_config(device: &cpal::Device) -> Result<cpal::SupportedStreamConfig
         });
     }
 
+    Ok(rank_input_configs(&supported_configs))
+}
+
+/// Order candidate configurations: a native 16kHz configuration first, as
+/// it needs no resampling, then the highest sample rate of the preferred
+/// format.
+fn rank_input_configs(
+    supported_configs: &[cpal::SupportedStreamConfigRange],
+) -> Vec<cpal::SupportedStreamConfig> {
+    let mut configs = Vec::with_capacity(2);
+
+    let native = CAPTURE_FORMATS.iter().find_map(|&format| {
+        supported_configs
+            .iter()
+            .filter(|config| config.sample_format() == format)
+            .find_map(|config| (*config).try_with_sample_rate(cpal::SampleRate(TARGET_SAMPLE_RATE)))
+    });
+    configs.extend(native);
+
     // Prefer F32 format; otherwise use the first available format.
-    for config in &supported_configs {
-        if config.sample_format() == cpal::SampleFormat::F32 {
-            return Ok((*config).with_max_sample_rate());
+    let fallback = supported_configs
+        .iter()
+        .find(|config| config.sample_format() == cpal::SampleFormat::F32)
+        .or(supported_configs.first())
+        .map(|config| (*config).with_max_sample_rate());
+    if let Some(fallback) = fallback {
+        if !configs.contains(&fallback) {
+            configs.push(fallback);
         }
     }
 
-    Ok(supported_configs[0].with_max_sample_rate())
+    configs
 }
 
 // Error conversions from cpal errors to CyranoError
@@ -215,6 +413,94 @@ mod tests {
         assert!(matches!(cyrano_err, CyranoError::MicAccessDenied));
     }
 
+    fn range(format: cpal::SampleFormat, min: u32, max: u32) -> cpal::SupportedStreamConfigRange {
+        cpal::SupportedStreamConfigRange::new(
+            1,
+            cpal::SampleRate(min),
+            cpal::SampleRate(max),
+            cpal::SupportedBufferSize::Unknown,
+            format,
+        )
+    }
+
+    #[test]
+    fn test_rank_prefers_native_16k() {
+        let configs = rank_input_configs(&[
+            range(cpal::SampleFormat::F32, 48_000, 48_000),
+            range(cpal::SampleFormat::F32, 8_000, 24_000),
+        ]);
+        let rates: Vec<u32> = configs.iter().map(|c| c.sample_rate().0).collect();
+        assert_eq!(rates, vec![16_000, 48_000]);
+    }
+
+    #[test]
+    fn test_rank_accepts_native_16k_in_i16() {
+        let configs = rank_input_configs(&[
+            range(cpal::SampleFormat::F32, 44_100, 48_000),
+            range(cpal::SampleFormat::I16, 16_000, 16_000),
+        ]);
+        assert_eq!(configs[0].sample_rate().0, 16_000);
+        assert_eq!(configs[0].sample_format(), cpal::SampleFormat::I16);
+        assert_eq!(configs[1].sample_rate().0, 48_000);
+        assert_eq!(configs[1].sample_format(), cpal::SampleFormat::F32);
+    }
+
+    #[test]
+    fn test_rank_falls_back_to_max_rate() {
+        let configs = rank_input_configs(&[
+            range(cpal::SampleFormat::I16, 44_100, 44_100),
+            range(cpal::SampleFormat::F32, 44_100, 96_000),
+        ]);
+        assert_eq!(configs.len(), 1);
+        assert_eq!(configs[0].sample_rate().0, 96_000);
+        assert_eq!(configs[0].sample_format(), cpal::SampleFormat::F32);
+    }
+
+    #[test]
+    fn test_rank_ignores_unsupported_formats_for_native_rate() {
+        let configs = rank_input_configs(&[range(cpal::SampleFormat::U8, 16_000, 16_000)]);
+        // Only the fallback remains, as before native rates were preferred
+        assert_eq!(configs.len(), 1);
+        assert_eq!(configs[0].sample_format(), cpal::SampleFormat::U8);
+    }
+
+    #[test]
+    fn test_rank_does_not_repeat_a_16k_only_device() {
+        let configs = rank_input_configs(&[range(cpal::SampleFormat::F32, 16_000, 16_000)]);
+        assert_eq!(configs.len(), 1);
+    }
+
+    #[test]
+    fn test_ring_buffer_drains_in_order_through_resampler() {
+        let (mut producer, mut consumer) = RingBuffer::new(8);
+        let overruns = AtomicUsize::new(0);
+        let mut resampler = Resampler::new(
+            TARGET_SAMPLE_RATE,
+            TARGET_SAMPLE_RATE,
+            ResamplerQuality::default(),
+        );
+        let mut out = Vec::new();
+
+        push_samples(&mut producer, [0.1, 0.2, 0.3].into_iter(), &overruns);
+        drain_ring(&mut consumer, &mut resampler, &mut out);
+        push_samples(&mut producer, [0.4].into_iter(), &overruns);
+        drain_ring(&mut consumer, &mut resampler, &mut out);
+
+        assert_eq!(out, vec![0.1, 0.2, 0.3, 0.4]);
+        assert_eq!(overruns.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn test_full_ring_buffer_drops_and_counts_samples() {
+        let (mut producer, consumer) = RingBuffer::new(4);
+        let overruns = AtomicUsize::new(0);
+
+        push_samples(&mut producer, std::iter::repeat_n(0.5, 6), &overruns);
+
+        assert_eq!(consumer.slots(), 4);
+        assert_eq!(overruns.load(Ordering::Relaxed), 2);
+    }
+
     #[test]
     fn test_target_sample_rate() {
         assert_eq!(TARGET_SAMPLE_RATE, 16_000);
@@ -0,0 +1,422 @@
+//! FLAC encoding and decoding for stored recordings.
+//!
+//! Audio attached to history entries is stored as 16-bit mono FLAC: lossless,
+//! so it can be re-transcribed later, and roughly half the size of WAV.
+//!
+//! The encoder implements a deliberately small subset of the format:
+//! fixed-size blocks, fixed linear predictors (orders 0-4) and a single Rice
+//! partition per subframe. Decoding uses `claxon` and also accepts
+//! multi-channel files, which are mixed down to mono for import.
+
+use std::io::Cursor;
+
+/// Samples per FLAC block (frame).
+const BLOCK_SIZE: usize = 4096;
+
+/// Bit depth of the encoded samples.
+const BITS_PER_SAMPLE: u32 = 16;
+
+/// Largest Rice parameter expressible with the 4-bit parameter encoding
+/// (15 is reserved as the escape code).
+const MAX_RICE_PARAMETER: u32 = 14;
+
+/// Highest fixed predictor order defined by the format.
+const MAX_FIXED_ORDER: usize = 4;
+
+/// Encode mono `f32` samples in [-1.0, 1.0] as a 16-bit FLAC stream.
+///
+/// Samples outside the range are clamped.
+pub fn encode_flac(samples: &[f32], sample_rate: u32) -> Vec<u8> {
+    let pcm: Vec<i32> = samples.iter().map(|&s| to_pcm16(s)).collect();
+
+    let mut writer = BitWriter::new();
+    writer.write_bytes(b"fLaC");
+    write_stream_info(&mut writer, pcm.len() as u64, sample_rate);
+
+    let mut output = writer.into_bytes();
+    for (frame_number, block) in pcm.chunks(BLOCK_SIZE).enumerate() {
+        output.extend_from_slice(&encode_frame(frame_number as u32, block));
+    }
+    output
+}
+
+/// Decode a FLAC stream into mono `f32` samples, averaging channels.
+///
+/// # Returns
+/// * `Ok((samples, sample_rate))` on success
+/// * `Err(String)` if the stream is invalid
+pub fn decode_flac(bytes: &[u8]) -> Result<(Vec<f32>, u32), String> {
+    let mut reader = claxon::FlacReader::new(Cursor::new(bytes))
+        .map_err(|e| format!("Invalid FLAC stream: {e}"))?;
+
+    let info = reader.streaminfo();
+    let channels = info.channels as usize;
+    let scale = ((1i64 << (info.bits_per_sample - 1)) - 1) as f32;
+    let interleaved = reader
+        .samples()
+        .map(|sample| sample.map(|s| s as f32 / scale))
+        .collect::<Result<Vec<f32>, _>>()
+        .map_err(|e| format!("Failed to decode FLAC samples: {e}"))?;
+
+    let samples = if channels == 1 {
+        interleaved
+    } else {
+        interleaved
+            .chunks_exact(channels)
+            .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+            .collect()
+    };
+
+    Ok((samples, info.sample_rate))
+}
+
+/// Convert a float sample to a 16-bit PCM value.
+fn to_pcm16(sample: f32) -> i32 {
+    (sample.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i32
+}
+
+/// Write the STREAMINFO metadata block (the only metadata block we emit).
+fn write_stream_info(writer: &mut BitWriter, total_samples: u64, sample_rate: u32) {
+    // Last-metadata-block flag set, block type 0 (STREAMINFO), 34 bytes long
+    writer.write(1, 1);
+    writer.write(0, 7);
+    writer.write(34, 24);
+
+    writer.write(BLOCK_SIZE as u64, 16); // Minimum block size
+    writer.write(BLOCK_SIZE as u64, 16); // Maximum block size
+    writer.write(0, 24); // Minimum frame size (unknown)
+    writer.write(0, 24); // Maximum frame size (unknown)
+    writer.write(u64::from(sample_rate), 20);
+    writer.write(0, 3); // Channels - 1
+    writer.write(u64::from(BITS_PER_SAMPLE - 1), 5);
+    writer.write(total_samples >> 32, 4);
+    writer.write(total_samples & 0xFFFF_FFFF, 32);
+    writer.write_bytes(&[0; 16]); // MD5 signature (not computed)
+}
+
+/// Encode one block as a complete frame, including both CRCs.
+fn encode_frame(frame_number: u32, block: &[i32]) -> Vec<u8> {
+    let mut writer = BitWriter::new();
+
+    // Sync code, reserved bit, fixed-blocksize strategy
+    writer.write(0xFFF8, 16);
+    // Block size stored as 16-bit (n - 1) after the header; rate from STREAMINFO
+    writer.write(0b0111, 4);
+    writer.write(0b0000, 4);
+    // Mono, 16 bits per sample, reserved bit
+    writer.write(0b0000, 4);
+    writer.write(0b100, 3);
+    writer.write(0, 1);
+    write_utf8_number(&mut writer, frame_number);
+    writer.write(block.len() as u64 - 1, 16);
+
+    let header_crc = crc8(writer.bytes());
+    writer.write(u64::from(header_crc), 8);
+
+    write_subframe(&mut writer, block);
+    writer.align();
+
+    let frame_crc = crc16(writer.bytes());
+    writer.write(u64::from(frame_crc), 16);
+
+    writer.into_bytes()
+}
+
+/// Write the frame number using FLAC's UTF-8-like variable length coding.
+fn write_utf8_number(writer: &mut BitWriter, number: u32) {
+    if number < 0x80 {
+        writer.write(u64::from(number), 8);
+        return;
+    }
+
+    let byte_count: u32 = match number {
+        0..=0x7FF => 2,
+        0x800..=0xFFFF => 3,
+        0x1_0000..=0x1F_FFFF => 4,
+        0x20_0000..=0x3FF_FFFF => 5,
+        _ => 6,
+    };
+    let prefix = (0xFFu32 << (8 - byte_count)) & 0xFF;
+    writer.write(u64::from(prefix | (number >> (6 * (byte_count - 1)))), 8);
+    for i in (0..byte_count - 1).rev() {
+        writer.write(u64::from(0x80 | ((number >> (6 * i)) & 0x3F)), 8);
+    }
+}
+
+/// Write the cheapest subframe encoding for a block.
+fn write_subframe(writer: &mut BitWriter, block: &[i32]) {
+    if block.iter().all(|&s| s == block[0]) {
+        // CONSTANT subframe
+        writer.write(0, 8);
+        write_signed(writer, block[0], BITS_PER_SAMPLE);
+        return;
+    }
+
+    let verbatim_bits = block.len() as u64 * u64::from(BITS_PER_SAMPLE);
+    let best = (0..=MAX_FIXED_ORDER.min(block.len() - 1))
+        .map(|order| {
+            let residuals = fixed_residuals(block, order);
+            let (parameter, residual_bits) = best_rice_parameter(&residuals);
+            let bits = order as u64 * u64::from(BITS_PER_SAMPLE) + 10 + residual_bits;
+            (order, residuals, parameter, bits)
+        })
+        .min_by_key(|(_, _, _, bits)| *bits);
+
+    match best {
+        Some((order, residuals, parameter, bits)) if bits < verbatim_bits => {
+            // FIXED subframe: type 001xxx where xxx is the predictor order
+            writer.write(0, 1);
+            writer.write(0b001000 | order as u64, 6);
+            writer.write(0, 1);
+            for &sample in &block[..order] {
+                write_signed(writer, sample, BITS_PER_SAMPLE);
+            }
+            // Rice coding with 4-bit parameter, partition order 0
+            writer.write(0b00, 2);
+            writer.write(0, 4);
+            writer.write(u64::from(parameter), 4);
+            for &residual in &residuals {
+                write_rice(writer, residual, parameter);
+            }
+        }
+        _ => {
+            // VERBATIM subframe
+            writer.write(0b0000_0010, 8);
+            for &sample in block {
+                write_signed(writer, sample, BITS_PER_SAMPLE);
+            }
+        }
+    }
+}
+
+/// Compute the residuals of the fixed predictor of the given order.
+fn fixed_residuals(block: &[i32], order: usize) -> Vec<i32> {
+    (order..block.len())
+        .map(|i| {
+            let x = |back: usize| i64::from(block[i - back]);
+            let prediction = match order {
+                0 => 0,
+                1 => x(1),
+                2 => 2 * x(1) - x(2),
+                3 => 3 * x(1) - 3 * x(2) + x(3),
+                _ => 4 * x(1) - 6 * x(2) + 4 * x(3) - x(4),
+            };
+            (x(0) - prediction) as i32
+        })
+        .collect()
+}
+
+/// Map a signed residual to an unsigned value (0, -1, 1, -2, ... → 0, 1, 2, 3, ...).
+fn zigzag(value: i32) -> u32 {
+    ((value << 1) ^ (value >> 31)) as u32
+}
+
+/// Find the Rice parameter that encodes the residuals in the fewest bits.
+///
+/// # Returns
+/// `(parameter, total_bits)` for the residual payload
+fn best_rice_parameter(residuals: &[i32]) -> (u32, u64) {
+    (0..=MAX_RICE_PARAMETER)
+        .map(|parameter| {
+            let bits = residuals
+                .iter()
+                .map(|&r| u64::from(zigzag(r) >> parameter) + 1 + u64::from(parameter))
+                .sum();
+            (parameter, bits)
+        })
+        .min_by_key(|&(_, bits)| bits)
+        .unwrap_or((0, 0))
+}
+
+/// Write one Rice-coded residual: unary quotient, then `parameter` low bits.
+fn write_rice(writer: &mut BitWriter, residual: i32, parameter: u32) {
+    let value = zigzag(residual);
+    let mut quotient = value >> parameter;
+    while quotient >= 32 {
+        writer.write(0, 32);
+        quotient -= 32;
+    }
+    writer.write(1, quotient + 1);
+    if parameter > 0 {
+        writer.write(u64::from(value), parameter);
+    }
+}
+
+/// Write a signed value as a two's complement field of `bits` bits.
+fn write_signed(writer: &mut BitWriter, value: i32, bits: u32) {
+    writer.write(u64::from(value as u32), bits);
+}
+
+/// CRC-8 (polynomial 0x07) over the frame header.
+fn crc8(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |mut crc, &byte| {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+/// CRC-16 (polynomial 0x8005) over the whole frame.
+fn crc16(data: &[u8]) -> u16 {
+    data.iter().fold(0u16, |mut crc, &byte| {
+        crc ^= u16::from(byte) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x8005
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+/// MSB-first bit writer.
+struct BitWriter {
+    bytes: Vec<u8>,
+    accumulator: u64,
+    pending_bits: u32,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        Self {
+            bytes: Vec::new(),
+            accumulator: 0,
+            pending_bits: 0,
+        }
+    }
+
+    /// Write the low `count` bits of `value` (at most 32 bits per call).
+    fn write(&mut self, value: u64, count: u32) {
+        debug_assert!(count <= 32);
+        let mask = (1u64 << count) - 1;
+        self.accumulator = (self.accumulator << count) | (value & mask);
+        self.pending_bits += count;
+        while self.pending_bits >= 8 {
+            self.pending_bits -= 8;
+            self.bytes
+                .push((self.accumulator >> self.pending_bits) as u8);
+        }
+        self.accumulator &= (1u64 << self.pending_bits) - 1;
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.write(u64::from(byte), 8);
+        }
+    }
+
+    /// Pad with zero bits up to the next byte boundary.
+    fn align(&mut self) {
+        if self.pending_bits > 0 {
+            self.write(0, 8 - self.pending_bits);
+        }
+    }
+
+    /// Bytes written so far. Only complete when the writer is byte-aligned.
+    fn bytes(&self) -> &[u8] {
+        debug_assert_eq!(self.pending_bits, 0);
+        &self.bytes
+    }
+
+    fn into_bytes(mut self) -> Vec<u8> {
+        self.align();
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Generate a test tone with a little noise-like variation.
+    fn tone(len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| {
+                let t = i as f32 / 16_000.0;
+                0.5 * (2.0 * std::f32::consts::PI * 440.0 * t).sin()
+                    + 0.05 * ((i * 7919 % 101) as f32 / 101.0 - 0.5)
+            })
+            .collect()
+    }
+
+    fn assert_round_trip(samples: &[f32]) {
+        let encoded = encode_flac(samples, 16_000);
+        let (decoded, sample_rate) = decode_flac(&encoded).expect("Should decode");
+
+        assert_eq!(sample_rate, 16_000);
+        assert_eq!(decoded.len(), samples.len());
+        for (original, restored) in samples.iter().zip(&decoded) {
+            let expected = to_pcm16(*original) as f32 / i16::MAX as f32;
+            assert_eq!(*restored, expected);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_tone() {
+        assert_round_trip(&tone(BLOCK_SIZE * 3 + 123));
+    }
+
+    #[test]
+    fn test_round_trip_silence_uses_constant_subframes() {
+        let samples = vec![0.0; BLOCK_SIZE * 2];
+        assert_round_trip(&samples);
+        // Header + STREAMINFO + two tiny frames
+        assert!(encode_flac(&samples, 16_000).len() < 100);
+    }
+
+    #[test]
+    fn test_round_trip_full_scale_noise() {
+        // Alternating extremes defeat every predictor and force VERBATIM subframes
+        let samples: Vec<f32> = (0..1000)
+            .map(|i| if i % 2 == 0 { 1.0 } else { -1.0 })
+            .collect();
+        assert_round_trip(&samples);
+    }
+
+    #[test]
+    fn test_round_trip_tiny_inputs() {
+        assert_round_trip(&[]);
+        assert_round_trip(&[0.25]);
+        assert_round_trip(&[0.25, -0.25]);
+    }
+
+    #[test]
+    fn test_round_trip_many_frames() {
+        // More than 128 frames exercises multi-byte frame numbers
+        assert_round_trip(&tone(BLOCK_SIZE * 130));
+    }
+
+    #[test]
+    fn test_tone_compresses_below_pcm_size() {
+        let samples = tone(16_000);
+        let encoded = encode_flac(&samples, 16_000);
+        assert!(encoded.len() < samples.len() * 2);
+    }
+
+    #[test]
+    fn test_samples_are_clamped() {
+        assert_eq!(to_pcm16(2.0), i16::MAX as i32);
+        assert_eq!(to_pcm16(-2.0), -(i16::MAX as i32));
+    }
+
+    #[test]
+    fn test_decode_rejects_garbage() {
+        assert!(decode_flac(b"not a flac file").is_err());
+    }
+
+    #[test]
+    fn test_zigzag_mapping() {
+        assert_eq!(zigzag(0), 0);
+        assert_eq!(zigzag(-1), 1);
+        assert_eq!(zigzag(1), 2);
+        assert_eq!(zigzag(-2), 3);
+    }
+}
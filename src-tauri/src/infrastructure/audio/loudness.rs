@@ -0,0 +1,150 @@
+//! Loudness normalization for recorded speech.
+//!
+//! Quiet USB and headset microphones often deliver speech around -40 dBFS,
+//! where Whisper misses words. The recording is boosted towards a target
+//! speech level measured on voiced frames only, so long pauses do not
+//! inflate the gain. The gain is capped, and limited so that almost no
+//! sample clips; the few that would are clamped.
+
+/// Frame length used to measure the speech level (30ms at 16kHz).
+const FRAME_SAMPLES: usize = 480;
+
+/// Frames quieter than this (in dBFS) are treated as silence when measuring.
+const SILENCE_DB: f32 = -60.0;
+
+/// Target RMS level of voiced frames, in dBFS.
+const TARGET_DB: f32 = -20.0;
+
+/// Largest gain applied (+30 dB), so pure noise is never blown up.
+const MAX_GAIN: f32 = 31.6;
+
+/// Fraction of samples allowed to reach the clipping limit after gain.
+const CLIP_FRACTION: f32 = 0.001;
+
+/// Level that the loudest (non-outlier) sample may reach after gain.
+const PEAK_LIMIT: f32 = 0.95;
+
+fn db_to_amplitude(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// RMS level of the voiced frames, or None if the recording is silent.
+fn speech_rms(samples: &[f32]) -> Option<f32> {
+    let silence = db_to_amplitude(SILENCE_DB);
+    let (sum, count) = samples
+        .chunks(FRAME_SAMPLES)
+        .map(|frame| frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32)
+        .filter(|mean_square| mean_square.sqrt() >= silence)
+        .fold((0.0f64, 0usize), |(sum, count), mean_square| {
+            (sum + f64::from(mean_square), count + 1)
+        });
+    (count > 0).then(|| (sum / count as f64).sqrt() as f32)
+}
+
+/// Absolute sample value exceeded by only `CLIP_FRACTION` of the samples.
+fn peak_level(samples: &[f32]) -> f32 {
+    let mut magnitudes: Vec<f32> = samples.iter().map(|s| s.abs()).collect();
+    let index = ((magnitudes.len() as f32 * (1.0 - CLIP_FRACTION)) as usize)
+        .min(magnitudes.len().saturating_sub(1));
+    let (_, peak, _) = magnitudes.select_nth_unstable_by(index, f32::total_cmp);
+    *peak
+}
+
+/// Gain that brings the speech level to the target, within the limits.
+///
+/// Never attenuates: loud recordings are left untouched.
+pub fn normalization_gain(samples: &[f32]) -> f32 {
+    let Some(rms) = speech_rms(samples) else {
+        return 1.0;
+    };
+    let peak = peak_level(samples);
+
+    let mut gain = (db_to_amplitude(TARGET_DB) / rms).min(MAX_GAIN);
+    if peak > 0.0 {
+        gain = gain.min(PEAK_LIMIT / peak);
+    }
+    gain.max(1.0)
+}
+
+/// Normalize the loudness of mono samples in place.
+///
+/// # Returns
+/// The gain that was applied (1.0 if the audio was left unchanged)
+pub fn normalize_loudness(samples: &mut [f32]) -> f32 {
+    let gain = normalization_gain(samples);
+    if gain > 1.0 {
+        for sample in samples.iter_mut() {
+            *sample = (*sample * gain).clamp(-1.0, 1.0);
+        }
+    }
+    gain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(count: usize, amplitude: f32) -> Vec<f32> {
+        (0..count)
+            .map(|i| amplitude * (2.0 * std::f32::consts::PI * 200.0 * i as f32 / 16_000.0).sin())
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn test_quiet_speech_is_boosted_to_target() {
+        let mut samples = tone(16_000, 0.01);
+        let gain = normalize_loudness(&mut samples);
+        assert!(gain > 1.0);
+        let level_db = 20.0 * rms(&samples).log10();
+        assert!((level_db - TARGET_DB).abs() < 0.5, "got {level_db} dBFS");
+    }
+
+    #[test]
+    fn test_pauses_do_not_inflate_gain() {
+        // Whole frames, so no frame mixes speech and silence
+        let speech = tone(FRAME_SAMPLES * 33, 0.01);
+        let mut with_pause = speech.clone();
+        with_pause.extend(vec![0.0; 64_000]);
+        assert!((normalization_gain(&speech) - normalization_gain(&with_pause)).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_loud_audio_is_not_attenuated() {
+        let mut samples = tone(16_000, 0.8);
+        let original = samples.clone();
+        assert_eq!(normalize_loudness(&mut samples), 1.0);
+        assert_eq!(samples, original);
+    }
+
+    #[test]
+    fn test_silence_is_left_unchanged() {
+        let mut samples = vec![0.0; 16_000];
+        assert_eq!(normalize_loudness(&mut samples), 1.0);
+        assert!(samples.iter().all(|&s| s == 0.0));
+
+        assert_eq!(normalize_loudness(&mut []), 1.0);
+    }
+
+    #[test]
+    fn test_gain_is_capped() {
+        // -55 dBFS noise would need +35 dB to reach the target
+        let samples = tone(16_000, db_to_amplitude(-52.0));
+        assert!((normalization_gain(&samples) - MAX_GAIN).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_peaks_limit_the_gain() {
+        // Quiet speech with a loud transient covering more than CLIP_FRACTION
+        let mut samples = tone(16_000, 0.01);
+        for sample in samples.iter_mut().take(100) {
+            *sample = 0.5;
+        }
+        let gain = normalize_loudness(&mut samples);
+        assert!((gain - PEAK_LIMIT / 0.5).abs() < 0.01, "got {gain}");
+        assert!(samples.iter().all(|s| s.abs() <= 1.0));
+    }
+}
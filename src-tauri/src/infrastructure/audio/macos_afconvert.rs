@@ -0,0 +1,46 @@
+//! Conversion of compressed audio files with the system `afconvert` tool.
+//!
+//! Core Audio reads every format macOS can play (AAC/M4A, MP3, AIFF, CAF,
+//! Apple Lossless). Converting to 16kHz mono WAV through `afconvert` gives
+//! us all of them without bundling decoders.
+
+use std::path::Path;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Location of the converter on every macOS install.
+const AFCONVERT: &str = "/usr/bin/afconvert";
+
+/// Convert an audio file to 16-bit 16kHz mono WAV.
+///
+/// # Returns
+/// * `Ok(Vec<u8>)` with the WAV file contents
+/// * `Err(String)` if Core Audio cannot read the file
+pub fn convert_to_wav(path: &Path) -> Result<Vec<u8>, String> {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let output =
+        std::env::temp_dir().join(format!("cyrano-import-{}-{nanos}.wav", std::process::id()));
+
+    let result = Command::new(AFCONVERT)
+        .args(["-f", "WAVE", "-d", "LEI16@16000", "-c", "1"])
+        .arg(path)
+        .arg(&output)
+        .output();
+
+    let converted = match result {
+        Ok(out) if out.status.success() => {
+            std::fs::read(&output).map_err(|e| format!("Failed to read converted audio: {e}"))
+        }
+        Ok(out) => Err(format!(
+            "afconvert failed: {}",
+            String::from_utf8_lossy(&out.stderr).trim()
+        )),
+        Err(e) => Err(format!("Failed to run afconvert: {e}")),
+    };
+
+    let _ = std::fs::remove_file(&output);
+    converted
+}
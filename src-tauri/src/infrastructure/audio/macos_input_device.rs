@@ -0,0 +1,186 @@
+//! Input device transport detection with Core Audio.
+//!
+//! Recording from a Bluetooth headset switches it from the high-quality A2DP
+//! profile to the hands-free profile, which degrades both the recording and
+//! whatever the user is listening to. Core Audio reports each device's
+//! transport, so Bluetooth inputs can be detected and the built-in
+//! microphone found instead.
+
+use std::mem;
+use std::ptr::{null, null_mut};
+
+use coreaudio_sys::{
+    kAudioDevicePropertyStreams, kAudioDevicePropertyTransportType,
+    kAudioDeviceTransportTypeBluetooth, kAudioDeviceTransportTypeBluetoothLE,
+    kAudioDeviceTransportTypeBuiltIn, kAudioHardwareNoError,
+    kAudioHardwarePropertyDefaultInputDevice, kAudioHardwarePropertyDevices,
+    kAudioObjectPropertyElementMaster, kAudioObjectPropertyName, kAudioObjectPropertyScopeGlobal,
+    kAudioObjectPropertyScopeInput, kAudioObjectSystemObject, AudioDeviceID,
+    AudioObjectGetPropertyData, AudioObjectGetPropertyDataSize, AudioObjectID,
+    AudioObjectPropertyAddress, AudioObjectPropertyScope, AudioObjectPropertySelector, CFStringRef,
+};
+use objc2::rc::Retained;
+use objc2_foundation::NSString;
+
+fn address(
+    selector: AudioObjectPropertySelector,
+    scope: AudioObjectPropertyScope,
+) -> AudioObjectPropertyAddress {
+    AudioObjectPropertyAddress {
+        mSelector: selector,
+        mScope: scope,
+        mElement: kAudioObjectPropertyElementMaster,
+    }
+}
+
+/// Read a `u32` property of a Core Audio object.
+fn read_u32_property(object: AudioObjectID, selector: AudioObjectPropertySelector) -> Option<u32> {
+    let address = address(selector, kAudioObjectPropertyScopeGlobal);
+    let mut value: u32 = 0;
+    let mut size = mem::size_of::<u32>() as u32;
+
+    // SAFETY: the address and the output buffer outlive the call, and `size`
+    // matches the buffer.
+    let status = unsafe {
+        AudioObjectGetPropertyData(
+            object,
+            &address,
+            0,
+            null(),
+            &mut size,
+            &mut value as *mut u32 as *mut _,
+        )
+    };
+    (status == kAudioHardwareNoError as i32).then_some(value)
+}
+
+/// Size in bytes of a property's value.
+fn property_size(object: AudioObjectID, address: &AudioObjectPropertyAddress) -> Option<u32> {
+    let mut size = 0u32;
+    // SAFETY: the address and `size` outlive the call.
+    let status = unsafe { AudioObjectGetPropertyDataSize(object, address, 0, null(), &mut size) };
+    (status == kAudioHardwareNoError as i32).then_some(size)
+}
+
+/// Name of a device, as shown in System Settings.
+fn device_name(device: AudioDeviceID) -> Option<String> {
+    let address = address(kAudioObjectPropertyName, kAudioObjectPropertyScopeGlobal);
+    let mut name: CFStringRef = null_mut();
+    let mut size = mem::size_of::<CFStringRef>() as u32;
+
+    // SAFETY: the address and the output pointer outlive the call, and
+    // `size` matches the pointer.
+    let status = unsafe {
+        AudioObjectGetPropertyData(
+            device,
+            &address,
+            0,
+            null(),
+            &mut size,
+            &mut name as *mut CFStringRef as *mut _,
+        )
+    };
+    if status != kAudioHardwareNoError as i32 || name.is_null() {
+        return None;
+    }
+
+    // SAFETY: Core Audio returns a +1 CFString, toll-free bridged to
+    // NSString; `Retained` releases it when dropped.
+    let name = unsafe { Retained::from_raw(name as *mut NSString) }?;
+    Some(name.to_string())
+}
+
+/// All audio devices known to Core Audio.
+fn all_devices() -> Vec<AudioDeviceID> {
+    let address = address(
+        kAudioHardwarePropertyDevices,
+        kAudioObjectPropertyScopeGlobal,
+    );
+    let Some(mut size) = property_size(kAudioObjectSystemObject, &address) else {
+        return Vec::new();
+    };
+    let mut devices: Vec<AudioDeviceID> = vec![0; size as usize / mem::size_of::<AudioDeviceID>()];
+
+    // SAFETY: the buffer holds `size` bytes, and the address and buffer
+    // outlive the call.
+    let status = unsafe {
+        AudioObjectGetPropertyData(
+            kAudioObjectSystemObject,
+            &address,
+            0,
+            null(),
+            &mut size,
+            devices.as_mut_ptr() as *mut _,
+        )
+    };
+    if status != kAudioHardwareNoError as i32 {
+        return Vec::new();
+    }
+    devices.truncate(size as usize / mem::size_of::<AudioDeviceID>());
+    devices
+}
+
+/// Whether a device can record.
+fn has_input_streams(device: AudioDeviceID) -> bool {
+    let address = address(kAudioDevicePropertyStreams, kAudioObjectPropertyScopeInput);
+    property_size(device, &address).is_some_and(|size| size > 0)
+}
+
+fn is_bluetooth(device: AudioDeviceID) -> bool {
+    read_u32_property(device, kAudioDevicePropertyTransportType).is_some_and(|transport| {
+        transport == kAudioDeviceTransportTypeBluetooth
+            || transport == kAudioDeviceTransportTypeBluetoothLE
+    })
+}
+
+/// Name of the default input device if it is a Bluetooth device.
+///
+/// # Returns
+/// * `Some(String)` with the device name for a Bluetooth input
+/// * `None` for other inputs, or if there is no input device
+pub fn bluetooth_default_input() -> Option<String> {
+    let device = read_u32_property(
+        kAudioObjectSystemObject,
+        kAudioHardwarePropertyDefaultInputDevice,
+    )
+    .filter(|device| *device != 0)?;
+    if !is_bluetooth(device) {
+        return None;
+    }
+    Some(device_name(device).unwrap_or_else(|| "Bluetooth headset".to_string()))
+}
+
+/// Name of the default input device.
+pub fn default_input_name() -> Option<String> {
+    read_u32_property(
+        kAudioObjectSystemObject,
+        kAudioHardwarePropertyDefaultInputDevice,
+    )
+    .filter(|device| *device != 0)
+    .and_then(device_name)
+}
+
+/// Name of the built-in microphone, if the Mac has one.
+pub fn built_in_input_name() -> Option<String> {
+    all_devices()
+        .into_iter()
+        .filter(|device| {
+            read_u32_property(*device, kAudioDevicePropertyTransportType)
+                == Some(kAudioDeviceTransportTypeBuiltIn)
+        })
+        .find(|device| has_input_streams(*device))
+        .and_then(device_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_device_queries_do_not_panic() {
+        // Headless CI machines have no input device
+        let _ = bluetooth_default_input();
+        let _ = built_in_input_name();
+        let _ = default_input_name();
+    }
+}
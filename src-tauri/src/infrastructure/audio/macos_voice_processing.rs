@@ -0,0 +1,384 @@
+//! Capture through the macOS voice processing I/O unit.
+//!
+//! The voice processing unit (the one FaceTime uses) cancels the echo of
+//! audio played through the speakers and applies automatic gain control, so
+//! dictating while music or a video plays gives Whisper much cleaner audio.
+//! It always records from the default input, and macOS lowers the volume of
+//! other audio while it runs.
+
+use std::ffi::c_void;
+use std::mem;
+use std::ptr::null_mut;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use coreaudio_sys::{
+    kAUVoiceIOProperty_VoiceProcessingEnableAGC, kAudioFormatFlagIsFloat, kAudioFormatFlagIsPacked,
+    kAudioFormatLinearPCM, kAudioOutputUnitProperty_EnableIO,
+    kAudioOutputUnitProperty_SetInputCallback, kAudioUnitManufacturer_Apple,
+    kAudioUnitProperty_StreamFormat, kAudioUnitScope_Global, kAudioUnitScope_Input,
+    kAudioUnitScope_Output, kAudioUnitSubType_VoiceProcessingIO, kAudioUnitType_Output,
+    AURenderCallbackStruct, AudioBuffer, AudioBufferList, AudioComponentDescription,
+    AudioComponentFindNext, AudioComponentInstanceDispose, AudioComponentInstanceNew,
+    AudioOutputUnitStart, AudioOutputUnitStop, AudioStreamBasicDescription, AudioTimeStamp,
+    AudioUnit, AudioUnitInitialize, AudioUnitRender, AudioUnitRenderActionFlags,
+    AudioUnitSetProperty, AudioUnitUninitialize, OSStatus,
+};
+use rtrb::{Consumer, Producer, RingBuffer};
+
+use crate::domain::CyranoError;
+use crate::infrastructure::audio::cpal_adapter::{push_samples, TARGET_SAMPLE_RATE};
+use crate::infrastructure::audio::macos_input_device;
+use crate::traits::audio_capture::AudioCapture;
+
+/// Element (bus) of an I/O unit connected to the input device.
+const INPUT_ELEMENT: u32 = 1;
+
+/// Element (bus) of an I/O unit connected to the output device.
+const OUTPUT_ELEMENT: u32 = 0;
+
+/// Largest number of frames rendered by one input callback.
+const MAX_FRAMES_PER_CALLBACK: usize = 8192;
+
+/// Seconds of audio the ring buffer holds between two drains.
+const RING_BUFFER_SECONDS: usize = 2;
+
+/// State of the input callback, owned by the running unit.
+struct CallbackState {
+    unit: AudioUnit,
+    producer: Producer<f32>,
+    /// Preallocated so the callback never allocates
+    buffer: Vec<f32>,
+    overruns: Arc<AtomicUsize>,
+}
+
+/// A started voice processing unit with the capture-thread end of its ring
+/// buffer.
+struct RunningUnit {
+    unit: AudioUnit,
+    /// Leaked `Box` handed to the input callback, freed once the unit is disposed
+    state: *mut CallbackState,
+    consumer: Consumer<f32>,
+}
+
+/// Audio capture adapter using macOS voice processing (echo cancellation
+/// and automatic gain control) on the default input. The unit converts
+/// audio to 16kHz mono itself.
+#[derive(Default)]
+pub struct VoiceProcessingAdapter {
+    running: Option<RunningUnit>,
+    samples: Vec<f32>,
+    is_capturing: bool,
+    /// Samples dropped by the callback because the ring buffer was full
+    overruns: Arc<AtomicUsize>,
+    device_name: Option<String>,
+}
+
+impl VoiceProcessingAdapter {
+    /// Create a new adapter with an empty buffer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create, configure and start the voice processing unit.
+    fn open_unit(&mut self) -> Result<(), CyranoError> {
+        let description = AudioComponentDescription {
+            componentType: kAudioUnitType_Output,
+            componentSubType: kAudioUnitSubType_VoiceProcessingIO,
+            componentManufacturer: kAudioUnitManufacturer_Apple,
+            componentFlags: 0,
+            componentFlagsMask: 0,
+        };
+        // SAFETY: the description outlives the call.
+        let component = unsafe { AudioComponentFindNext(null_mut(), &description) };
+        if component.is_null() {
+            return Err(failed("Voice processing is not available".to_string()));
+        }
+        let mut unit: AudioUnit = null_mut();
+        // SAFETY: `component` was just found and `unit` receives the instance.
+        check(
+            unsafe { AudioComponentInstanceNew(component, &mut unit) },
+            "create the voice processing unit",
+        )?;
+
+        let (producer, consumer) =
+            RingBuffer::new(TARGET_SAMPLE_RATE as usize * RING_BUFFER_SECONDS);
+        let state = Box::into_raw(Box::new(CallbackState {
+            unit,
+            producer,
+            buffer: vec![0.0; MAX_FRAMES_PER_CALLBACK],
+            overruns: self.overruns.clone(),
+        }));
+        // SAFETY: `unit` is a fresh instance and `state` stays valid until
+        // `dispose` frees it.
+        if let Err(e) = unsafe { configure_and_start(unit, state) } {
+            // SAFETY: the unit is not running, so the callback no longer
+            // uses `state`.
+            unsafe { dispose(unit, state) };
+            return Err(e);
+        }
+
+        self.running = Some(RunningUnit {
+            unit,
+            state,
+            consumer,
+        });
+        self.device_name = macos_input_device::default_input_name()
+            .map(|name| format!("{name} (voice processing)"));
+        log::info!("Capturing with voice processing at {TARGET_SAMPLE_RATE}Hz");
+        Ok(())
+    }
+
+    /// Move the samples waiting in the ring buffer to `samples`.
+    fn drain(&mut self) {
+        if let Some(running) = &mut self.running {
+            drain_consumer(&mut running.consumer, &mut self.samples);
+        }
+        let overruns = self.overruns.swap(0, Ordering::Relaxed);
+        if overruns > 0 {
+            log::warn!("Dropped {overruns} input samples: the capture thread fell behind");
+        }
+    }
+
+    /// Stop and dispose of the unit, keeping everything it captured.
+    fn close_unit(&mut self) {
+        if let Some(running) = self.running.take() {
+            // SAFETY: the unit was started by `open_unit` and is disposed once.
+            unsafe { dispose(running.unit, running.state) };
+            let mut consumer = running.consumer;
+            drain_consumer(&mut consumer, &mut self.samples);
+        }
+    }
+}
+
+impl Drop for VoiceProcessingAdapter {
+    fn drop(&mut self) {
+        self.close_unit();
+    }
+}
+
+impl AudioCapture for VoiceProcessingAdapter {
+    fn start_capture(&mut self) -> Result<(), CyranoError> {
+        if self.is_capturing {
+            return Ok(());
+        }
+
+        self.open_unit()?;
+        self.is_capturing = true;
+        Ok(())
+    }
+
+    fn stop_capture(&mut self) -> Result<Vec<f32>, CyranoError> {
+        self.close_unit();
+        self.is_capturing = false;
+        Ok(std::mem::take(&mut self.samples))
+    }
+
+    fn is_capturing(&self) -> bool {
+        self.is_capturing
+    }
+
+    fn take_samples(&mut self) -> Vec<f32> {
+        self.drain();
+        std::mem::take(&mut self.samples)
+    }
+
+    fn device_name(&self) -> Option<String> {
+        self.device_name.clone()
+    }
+
+    fn device_lost(&self) -> bool {
+        // The unit follows the default input by itself
+        false
+    }
+
+    fn switch_to_default_device(&mut self) -> Result<String, CyranoError> {
+        self.close_unit();
+        self.open_unit()?;
+        Ok(self.device_name.clone().unwrap_or_default())
+    }
+}
+
+fn drain_consumer(consumer: &mut Consumer<f32>, out: &mut Vec<f32>) {
+    if let Ok(chunk) = consumer.read_chunk(consumer.slots()) {
+        out.extend(chunk);
+    }
+}
+
+/// Enable input only, ask for mono 16kHz float samples, turn on automatic
+/// gain control, and start the unit.
+///
+/// # Safety
+/// `unit` must be a voice processing unit that is not initialized, and
+/// `state` must stay valid while the unit runs.
+unsafe fn configure_and_start(
+    unit: AudioUnit,
+    state: *mut CallbackState,
+) -> Result<(), CyranoError> {
+    check(
+        set_property(
+            unit,
+            kAudioOutputUnitProperty_EnableIO,
+            kAudioUnitScope_Input,
+            INPUT_ELEMENT,
+            &1u32,
+        ),
+        "enable voice processing input",
+    )?;
+    check(
+        set_property(
+            unit,
+            kAudioOutputUnitProperty_EnableIO,
+            kAudioUnitScope_Output,
+            OUTPUT_ELEMENT,
+            &0u32,
+        ),
+        "disable voice processing output",
+    )?;
+
+    // Format of the samples the unit hands out, after voice processing
+    let format = AudioStreamBasicDescription {
+        mSampleRate: f64::from(TARGET_SAMPLE_RATE),
+        mFormatID: kAudioFormatLinearPCM,
+        mFormatFlags: kAudioFormatFlagIsFloat | kAudioFormatFlagIsPacked,
+        mBytesPerPacket: 4,
+        mFramesPerPacket: 1,
+        mBytesPerFrame: 4,
+        mChannelsPerFrame: 1,
+        mBitsPerChannel: 32,
+        mReserved: 0,
+    };
+    check(
+        set_property(
+            unit,
+            kAudioUnitProperty_StreamFormat,
+            kAudioUnitScope_Output,
+            INPUT_ELEMENT,
+            &format,
+        ),
+        "set the voice processing format",
+    )?;
+
+    // Gain control is a bonus: echo cancellation works without it
+    let status = set_property(
+        unit,
+        kAUVoiceIOProperty_VoiceProcessingEnableAGC,
+        kAudioUnitScope_Global,
+        INPUT_ELEMENT,
+        &1u32,
+    );
+    if status != 0 {
+        log::warn!("Failed to enable automatic gain control (OSStatus {status})");
+    }
+
+    let callback = AURenderCallbackStruct {
+        inputProc: Some(input_callback),
+        inputProcRefCon: state.cast(),
+    };
+    check(
+        set_property(
+            unit,
+            kAudioOutputUnitProperty_SetInputCallback,
+            kAudioUnitScope_Global,
+            OUTPUT_ELEMENT,
+            &callback,
+        ),
+        "set the voice processing callback",
+    )?;
+
+    check(
+        AudioUnitInitialize(unit),
+        "initialize the voice processing unit",
+    )?;
+    check(
+        AudioOutputUnitStart(unit),
+        "start the voice processing unit",
+    )
+}
+
+/// Stop and free a unit and its callback state.
+///
+/// # Safety
+/// `unit` and `state` must come from `open_unit`, and must not be used again.
+unsafe fn dispose(unit: AudioUnit, state: *mut CallbackState) {
+    // Errors are ignored: the unit is going away either way
+    AudioOutputUnitStop(unit);
+    AudioUnitUninitialize(unit);
+    AudioComponentInstanceDispose(unit);
+    drop(Box::from_raw(state));
+}
+
+/// Called by Core Audio when input is available: render it into the
+/// preallocated buffer and push it to the ring buffer.
+unsafe extern "C" fn input_callback(
+    ref_con: *mut c_void,
+    action_flags: *mut AudioUnitRenderActionFlags,
+    time_stamp: *const AudioTimeStamp,
+    bus_number: u32,
+    frame_count: u32,
+    _data: *mut AudioBufferList,
+) -> OSStatus {
+    // SAFETY: `ref_con` is the `CallbackState` registered with the callback,
+    // which outlives the running unit and is only used here.
+    let state = &mut *ref_con.cast::<CallbackState>();
+    let frames = (frame_count as usize).min(state.buffer.len());
+    let mut buffers = AudioBufferList {
+        mNumberBuffers: 1,
+        mBuffers: [AudioBuffer {
+            mNumberChannels: 1,
+            mDataByteSize: (frames * mem::size_of::<f32>()) as u32,
+            mData: state.buffer.as_mut_ptr().cast(),
+        }],
+    };
+    let status = AudioUnitRender(
+        state.unit,
+        action_flags,
+        time_stamp,
+        bus_number,
+        frames as u32,
+        &mut buffers,
+    );
+    if status != 0 {
+        return status;
+    }
+    push_samples(
+        &mut state.producer,
+        state.buffer[..frames].iter().copied(),
+        &state.overruns,
+    );
+    0
+}
+
+/// Set a property of an audio unit.
+///
+/// # Safety
+/// `unit` must be a valid audio unit, and `T` the type of the property.
+unsafe fn set_property<T>(
+    unit: AudioUnit,
+    property: u32,
+    scope: u32,
+    element: u32,
+    value: &T,
+) -> OSStatus {
+    AudioUnitSetProperty(
+        unit,
+        property,
+        scope,
+        element,
+        (value as *const T).cast(),
+        mem::size_of::<T>() as u32,
+    )
+}
+
+/// Turn a failed `OSStatus` into an error.
+fn check(status: OSStatus, action: &str) -> Result<(), CyranoError> {
+    if status == 0 {
+        Ok(())
+    } else {
+        Err(failed(format!("Failed to {action} (OSStatus {status})")))
+    }
+}
+
+fn failed(reason: String) -> CyranoError {
+    CyranoError::RecordingFailed { reason }
+}
@@ -1,6 +1,26 @@
 //! Audio capture infrastructure.
 //!
-//! This module contains adapters for audio capture.
+//! This module contains adapters for audio capture, the reduction of
+//! multi-channel input to mono, the FLAC codec used for stored recordings,
+//! decoders for imported audio files, loudness normalization, recording
+//! quality analysis, voice activity detection, detection of microphone use
+//! by other apps and of Bluetooth inputs, capture with macOS voice
+//! processing, and synthetic test signals.
 
+pub mod channel_mixer;
 pub mod cpal_adapter;
+pub mod flac;
+pub mod loudness;
+#[cfg(target_os = "macos")]
+pub mod macos_afconvert;
+#[cfg(target_os = "macos")]
+pub mod macos_input_activity;
+#[cfg(target_os = "macos")]
+pub mod macos_input_device;
+#[cfg(target_os = "macos")]
+pub mod macos_voice_processing;
+pub mod quality;
 pub mod resampler;
+pub mod test_signal;
+pub mod vad;
+pub mod wav;
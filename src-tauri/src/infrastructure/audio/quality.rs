@@ -0,0 +1,206 @@
+//! Recording quality analysis.
+//!
+//! Measures what most often explains a bad transcription: background noise
+//! close to the speech level, clipping from a too high input gain, and
+//! recordings that are mostly silence or very quiet. The analysis runs on
+//! the recording as captured, before loudness normalization.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Frame length used to measure levels (20ms at 16kHz).
+const FRAME_SAMPLES: usize = 320;
+
+/// Level given to digital silence, in dBFS.
+const FLOOR_DB: f32 = -100.0;
+
+/// Frames quieter than this (in dBFS) count as silence.
+const SILENCE_DB: f32 = -50.0;
+
+/// Samples at least this loud count as clipped.
+const CLIP_LEVEL: f32 = 0.99;
+
+/// Percentiles of the frame levels taken as the noise floor and the speech
+/// level.
+const NOISE_PERCENTILE: f32 = 0.1;
+const SPEECH_PERCENTILE: f32 = 0.9;
+
+/// Thresholds beyond which a measure is reported as an issue.
+const MAX_CLIPPING_RATIO: f32 = 0.001;
+const MIN_SNR_DB: f32 = 15.0;
+const MAX_SILENCE_RATIO: f32 = 0.8;
+const QUIET_SPEECH_DB: f32 = -40.0;
+
+/// Quality measures of a recording.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Type)]
+pub struct RecordingQuality {
+    /// Estimated signal-to-noise ratio in dB: the level of the loudest
+    /// frames (speech) above the quietest ones (background)
+    pub snr_db: f32,
+    /// Fraction of samples at full scale
+    pub clipping_ratio: f32,
+    /// Fraction of the recording quieter than -50 dBFS
+    pub silence_ratio: f32,
+    /// Most common frame level in dBFS: the speech level when the recording
+    /// is mostly speech, the background level otherwise
+    pub dominant_level_db: f32,
+}
+
+impl RecordingQuality {
+    /// Problems likely to hurt the transcription, as short descriptions.
+    pub fn issues(&self) -> Vec<&'static str> {
+        let mut issues = Vec::new();
+        if self.clipping_ratio > MAX_CLIPPING_RATIO {
+            issues.push("clipping (input gain too high)");
+        }
+        if self.snr_db < MIN_SNR_DB {
+            issues.push("background noise close to the speech level");
+        }
+        if self.silence_ratio > MAX_SILENCE_RATIO {
+            issues.push("mostly silence");
+        } else if self.dominant_level_db < QUIET_SPEECH_DB {
+            issues.push("very quiet speech");
+        }
+        issues
+    }
+}
+
+/// Analyze a 16kHz recording.
+///
+/// # Returns
+/// * `Some(RecordingQuality)` with the measures of the recording
+/// * `None` if it is shorter than one frame
+pub fn analyze(samples: &[f32]) -> Option<RecordingQuality> {
+    if samples.len() < FRAME_SAMPLES {
+        return None;
+    }
+
+    let mut levels: Vec<f32> = samples.chunks(FRAME_SAMPLES).map(frame_db).collect();
+    let clipped = samples.iter().filter(|s| s.abs() >= CLIP_LEVEL).count();
+    let silent = levels.iter().filter(|db| **db < SILENCE_DB).count();
+    let dominant_level_db = dominant_level(&levels);
+
+    levels.sort_by(f32::total_cmp);
+    Some(RecordingQuality {
+        snr_db: percentile(&levels, SPEECH_PERCENTILE) - percentile(&levels, NOISE_PERCENTILE),
+        clipping_ratio: clipped as f32 / samples.len() as f32,
+        silence_ratio: silent as f32 / levels.len() as f32,
+        dominant_level_db,
+    })
+}
+
+/// Speech level of a 16kHz recording in dBFS: the level exceeded by only
+/// the loudest frames, so pauses do not lower it and clicks do not raise it.
+///
+/// # Returns
+/// * `Some(f32)` with the level
+/// * `None` if the recording is shorter than one frame
+pub fn speech_level_db(samples: &[f32]) -> Option<f32> {
+    if samples.len() < FRAME_SAMPLES {
+        return None;
+    }
+    let mut levels: Vec<f32> = samples.chunks(FRAME_SAMPLES).map(frame_db).collect();
+    levels.sort_by(f32::total_cmp);
+    Some(percentile(&levels, SPEECH_PERCENTILE))
+}
+
+/// Value at percentile `p` of sorted, non-empty `levels`.
+fn percentile(levels: &[f32], p: f32) -> f32 {
+    levels[((levels.len() - 1) as f32 * p).round() as usize]
+}
+
+/// RMS level of a frame in dBFS.
+fn frame_db(frame: &[f32]) -> f32 {
+    let mean_square = frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32;
+    if mean_square > 0.0 {
+        (10.0 * mean_square.log10()).max(FLOOR_DB)
+    } else {
+        FLOOR_DB
+    }
+}
+
+/// Center of the 1 dB range holding the most frame levels.
+fn dominant_level(levels: &[f32]) -> f32 {
+    let mut bins = [0u32; -FLOOR_DB as usize];
+    for db in levels {
+        let bin = (-db).clamp(0.0, -FLOOR_DB - 1.0) as usize;
+        bins[bin] += 1;
+    }
+    let bin = bins
+        .iter()
+        .enumerate()
+        .max_by_key(|(_, count)| **count)
+        .map(|(bin, _)| bin)
+        .unwrap_or(0);
+    -(bin as f32) - 0.5
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 440Hz tone of the given amplitude.
+    fn tone(amplitude: f32, samples: usize) -> Vec<f32> {
+        (0..samples)
+            .map(|i| amplitude * (i as f32 * 440.0 * std::f32::consts::TAU / 16_000.0).sin())
+            .collect()
+    }
+
+    /// Deterministic white noise of the given amplitude.
+    fn noise(amplitude: f32, samples: usize) -> Vec<f32> {
+        let mut state = 12_345u32;
+        (0..samples)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                amplitude * ((state >> 16) as f32 / 32_768.0 - 1.0)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_clean_speech_with_pauses() {
+        let mut samples = tone(0.3, 24_000);
+        samples.extend(noise(0.001, 8_000));
+        let quality = analyze(&samples).unwrap();
+
+        assert!(quality.snr_db > 40.0, "{quality:?}");
+        assert_eq!(quality.clipping_ratio, 0.0);
+        assert!((quality.silence_ratio - 0.25).abs() < 0.01, "{quality:?}");
+        assert!(
+            (quality.dominant_level_db + 13.5).abs() < 1.0,
+            "{quality:?}"
+        );
+        assert!(quality.issues().is_empty());
+    }
+
+    #[test]
+    fn test_reports_clipping_noise_and_silence() {
+        let clipped: Vec<f32> = tone(1.2, 16_000)
+            .into_iter()
+            .map(|s| s.clamp(-1.0, 1.0))
+            .collect();
+        let quality = analyze(&clipped).unwrap();
+        assert!(quality.clipping_ratio > 0.1, "{quality:?}");
+        assert!(quality.issues().contains(&"clipping (input gain too high)"));
+
+        let issues = analyze(&noise(0.2, 16_000)).unwrap().issues();
+        assert_eq!(issues, ["background noise close to the speech level"]);
+
+        let issues = analyze(&[0.0; 16_000]).unwrap().issues();
+        assert!(issues.contains(&"mostly silence"));
+
+        assert_eq!(analyze(&[0.5; 100]), None);
+    }
+
+    #[test]
+    fn test_speech_level_ignores_pauses() {
+        let mut samples = tone(0.3, 8_000);
+        samples.extend(noise(0.001, 24_000));
+        let level = speech_level_db(&samples).unwrap();
+        assert!((level + 13.5).abs() < 1.0, "{level}");
+
+        let level = speech_level_db(&noise(0.001, 16_000)).unwrap();
+        assert!(level < -60.0, "{level}");
+        assert_eq!(speech_level_db(&[0.5; 100]), None);
+    }
+}
@@ -1,7 +1,78 @@
-//! Simple linear resampler for streaming audio.
+//! Streaming resamplers for captured and imported audio.
 //!
 //! Converts input samples from an arbitrary input sample rate to a target
-//! sample rate using linear interpolation. Designed for low-latency streaming.
+//! sample rate, one sample at a time. Two algorithms are available:
+//! linear interpolation, which is cheap but lets content above the target
+//! Nyquist frequency alias back into the speech band, and a windowed-sinc
+//! filter, which low-passes before decimating. Both are designed for
+//! low-latency streaming.
+
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Kernel table entries per input sample, interpolated linearly.
+const KERNEL_OVERSAMPLING: usize = 256;
+
+/// Cutoff as a fraction of the lower Nyquist frequency, leaving room for
+/// the transition band so that it does not alias.
+const CUTOFF_ROLLOFF: f64 = 0.92;
+
+/// Resampling algorithm, trading CPU time for accuracy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ResamplerQuality {
+    /// Linear interpolation, with audible aliasing from 44.1/48kHz sources.
+    Fast,
+    /// Windowed sinc with 16 zero crossings per side.
+    #[default]
+    Balanced,
+    /// Windowed sinc with 32 zero crossings per side.
+    High,
+}
+
+/// Streaming resampler using the algorithm selected by `ResamplerQuality`.
+pub enum Resampler {
+    /// Input already at the output rate
+    Passthrough,
+    Linear(LinearResampler),
+    Sinc(SincResampler),
+}
+
+impl Resampler {
+    /// Create a resampler that converts from `input_rate` to `output_rate`.
+    ///
+    /// Samples at equal rates are passed through unchanged.
+    pub fn new(input_rate: u32, output_rate: u32, quality: ResamplerQuality) -> Self {
+        if input_rate == output_rate {
+            return Self::Passthrough;
+        }
+        match quality {
+            ResamplerQuality::Fast => Self::Linear(LinearResampler::new(input_rate, output_rate)),
+            ResamplerQuality::Balanced => {
+                Self::Sinc(SincResampler::new(input_rate, output_rate, 16))
+            }
+            ResamplerQuality::High => Self::Sinc(SincResampler::new(input_rate, output_rate, 32)),
+        }
+    }
+
+    /// Push a single mono sample and append any generated output samples to `out`.
+    pub fn push_sample(&mut self, sample: f32, out: &mut Vec<f32>) {
+        match self {
+            Self::Passthrough => out.push(sample),
+            Self::Linear(resampler) => resampler.push_sample(sample, out),
+            Self::Sinc(resampler) => resampler.push_sample(sample, out),
+        }
+    }
+
+    /// Append the output samples still held back at the end of the input.
+    pub fn flush(&mut self, out: &mut Vec<f32>) {
+        if let Self::Sinc(resampler) = self {
+            resampler.flush(out);
+        }
+    }
+}
 
 /// Streaming linear resampler state.
 pub struct LinearResampler {
@@ -52,10 +123,161 @@ impl LinearResampler {
     }
 }
 
+/// Streaming windowed-sinc resampler state.
+///
+/// Each output sample is the input convolved with a Blackman-windowed sinc
+/// low-pass kernel centered on the output position. Output is delayed by
+/// half the kernel width (about 1ms from 48kHz to 16kHz) while the samples
+/// after each output position arrive; `flush` emits the remainder.
+pub struct SincResampler {
+    input_rate: u64,
+    output_rate: u64,
+    /// Kernel half-width in input samples
+    half_taps: i64,
+    /// Kernel values for distances 0..=half_taps, `KERNEL_OVERSAMPLING` per sample
+    kernel: Vec<f32>,
+    /// Input samples from `first_index` onwards, zero-padded before index 0
+    history: VecDeque<f32>,
+    first_index: i64,
+    /// Number of input samples pushed
+    input_count: i64,
+    /// Number of output samples produced
+    output_count: u64,
+}
+
+impl SincResampler {
+    /// Create a resampler from `input_rate` to `output_rate` whose kernel
+    /// spans `zero_crossings` zero crossings on each side.
+    pub fn new(input_rate: u32, output_rate: u32, zero_crossings: u32) -> Self {
+        // Normalized to the input Nyquist frequency
+        let cutoff = (f64::from(output_rate) / f64::from(input_rate)).min(1.0) * CUTOFF_ROLLOFF;
+        let half_taps = (f64::from(zero_crossings) / cutoff).ceil() as i64;
+
+        let table_len = half_taps as usize * KERNEL_OVERSAMPLING + 1;
+        let kernel = (0..table_len)
+            .map(|i| {
+                let distance = i as f64 / KERNEL_OVERSAMPLING as f64;
+                (cutoff * sinc(cutoff * distance) * blackman(distance / half_taps as f64)) as f32
+            })
+            .collect();
+
+        let mut history = VecDeque::with_capacity(2 * half_taps as usize + 1);
+        history.extend(std::iter::repeat_n(0.0, half_taps as usize));
+
+        Self {
+            input_rate: u64::from(input_rate),
+            output_rate: u64::from(output_rate),
+            half_taps,
+            kernel,
+            history,
+            first_index: -half_taps,
+            input_count: 0,
+            output_count: 0,
+        }
+    }
+
+    /// Push a single mono sample and append any generated output samples to `out`.
+    pub fn push_sample(&mut self, sample: f32, out: &mut Vec<f32>) {
+        self.history.push_back(sample);
+        self.input_count += 1;
+        let newest = self.input_count - 1;
+
+        // An output needs every input within half_taps after its position
+        while self.next_base() + self.half_taps <= newest {
+            out.push(self.output_at(self.output_count));
+            self.output_count += 1;
+        }
+
+        let oldest_needed = self.next_base() - self.half_taps + 1;
+        while self.first_index < oldest_needed && !self.history.is_empty() {
+            self.history.pop_front();
+            self.first_index += 1;
+        }
+    }
+
+    /// Append the outputs up to the last input sample, treating the input
+    /// as silent after it.
+    pub fn flush(&mut self, out: &mut Vec<f32>) {
+        let last_index = self.input_count - 1;
+        while self.next_base() <= last_index {
+            self.push_sample(0.0, out);
+        }
+    }
+
+    /// Input sample at or before the next output position.
+    fn next_base(&self) -> i64 {
+        (self.output_count * self.input_rate / self.output_rate) as i64
+    }
+
+    /// Compute output sample `index`. Positions are computed exactly from
+    /// the sample count, so they do not drift over long recordings.
+    fn output_at(&self, index: u64) -> f32 {
+        let numerator = index * self.input_rate;
+        let base = (numerator / self.output_rate) as i64;
+        let position =
+            base as f64 + (numerator % self.output_rate) as f64 / self.output_rate as f64;
+        (base - self.half_taps + 1..=base + self.half_taps)
+            .map(|index| {
+                let sample = self.history[(index - self.first_index) as usize];
+                sample * self.kernel_at((position - index as f64).abs())
+            })
+            .sum()
+    }
+
+    /// Kernel value at `distance` input samples from the center.
+    fn kernel_at(&self, distance: f64) -> f32 {
+        let scaled = distance * KERNEL_OVERSAMPLING as f64;
+        let index = scaled as usize;
+        if index + 1 >= self.kernel.len() {
+            return 0.0;
+        }
+        let frac = (scaled - index as f64) as f32;
+        self.kernel[index] + (self.kernel[index + 1] - self.kernel[index]) * frac
+    }
+}
+
+/// Normalized sinc: sin(πx) / (πx).
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-9 {
+        1.0
+    } else {
+        let px = std::f64::consts::PI * x;
+        px.sin() / px
+    }
+}
+
+/// Blackman window over [-1, 1].
+fn blackman(x: f64) -> f64 {
+    if x.abs() >= 1.0 {
+        return 0.0;
+    }
+    let px = std::f64::consts::PI * x;
+    0.42 + 0.5 * px.cos() + 0.08 * (2.0 * px).cos()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn resample(resampler: &mut Resampler, input: &[f32]) -> Vec<f32> {
+        let mut out = Vec::new();
+        for &sample in input {
+            resampler.push_sample(sample, &mut out);
+        }
+        resampler.flush(&mut out);
+        out
+    }
+
+    fn tone(frequency: f32, sample_rate: u32, count: usize) -> Vec<f32> {
+        (0..count)
+            .map(|i| (2.0 * std::f32::consts::PI * frequency * i as f32 / sample_rate as f32).sin())
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
     #[test]
     fn test_resampler_48k_to_16k_length() {
         let mut resampler = LinearResampler::new(48_000, 16_000);
@@ -79,4 +301,73 @@ mod tests {
         // Expect ~16k samples
         assert!(out.len() >= 15_900 && out.len() <= 16_100);
     }
+
+    #[test]
+    fn test_sinc_output_length() {
+        for input_rate in [44_100, 48_000, 8_000] {
+            let input = vec![0.1; input_rate as usize];
+            let out = resample(
+                &mut Resampler::new(input_rate, 16_000, ResamplerQuality::Balanced),
+                &input,
+            );
+            // One output per 16kHz position up to the last input sample
+            let expected = (input.len() * 16_000).div_ceil(input_rate as usize);
+            assert_eq!(out.len(), expected, "from {input_rate}Hz");
+            let middle = &out[100..out.len() - 100];
+            assert!(middle.iter().all(|s| (s - 0.1).abs() < 0.001));
+        }
+    }
+
+    #[test]
+    fn test_sinc_preserves_speech_band_tone() {
+        let input = tone(1_000.0, 48_000, 48_000);
+        let out = resample(
+            &mut Resampler::new(48_000, 16_000, ResamplerQuality::Balanced),
+            &input,
+        );
+        let expected = tone(1_000.0, 16_000, out.len());
+        // Skip the edges, where the kernel overlaps the zero padding
+        let error = out[100..out.len() - 100]
+            .iter()
+            .zip(&expected[100..])
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0f32, f32::max);
+        assert!(error < 0.01, "max error {error}");
+    }
+
+    #[test]
+    fn test_sinc_rejects_tones_that_would_alias() {
+        // 12kHz is above the 8kHz output Nyquist and would fold to 4kHz
+        let input = tone(12_000.0, 48_000, 48_000);
+        let linear = resample(
+            &mut Resampler::new(48_000, 16_000, ResamplerQuality::Fast),
+            &input,
+        );
+        let sinc = resample(
+            &mut Resampler::new(48_000, 16_000, ResamplerQuality::High),
+            &input,
+        );
+        assert!(rms(&linear) > 0.3, "linear rms {}", rms(&linear));
+        assert!(
+            rms(&sinc[200..sinc.len() - 200]) < 0.01,
+            "sinc rms {}",
+            rms(&sinc)
+        );
+    }
+
+    #[test]
+    fn test_equal_rates_pass_through() {
+        let input = tone(440.0, 16_000, 1_000);
+        let out = resample(
+            &mut Resampler::new(16_000, 16_000, ResamplerQuality::High),
+            &input,
+        );
+        assert_eq!(out, input);
+    }
+
+    #[test]
+    fn test_quality_serializes_snake_case() {
+        let json = serde_json::to_string(&ResamplerQuality::Balanced).unwrap();
+        assert_eq!(json, "\"balanced\"");
+    }
 }
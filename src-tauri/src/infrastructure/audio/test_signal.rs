@@ -0,0 +1,173 @@
+//! Synthetic test signals for the transcription pipeline.
+//!
+//! Tones and chirps check that audio flows through the pipeline (a tone
+//! should transcribe to nothing); synthesized speech checks that a known
+//! sentence comes out as text. Speech is rendered by the macOS `say` tool,
+//! so no recording needs to be bundled.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::infrastructure::audio::cpal_adapter::TARGET_SAMPLE_RATE;
+
+/// Longest synthetic signal, in milliseconds.
+const MAX_DURATION_MS: u32 = 60_000;
+
+/// Amplitude of tones and chirps (-12 dBFS), loud enough to never count as
+/// silence without clipping.
+const AMPLITUDE: f32 = 0.25;
+
+/// A synthetic signal injected as if recorded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum TestSignal {
+    /// Sine wave at a fixed frequency.
+    Tone { frequency_hz: f32, duration_ms: u32 },
+    /// Sine wave sweeping linearly from one frequency to another.
+    Chirp {
+        start_hz: f32,
+        end_hz: f32,
+        duration_ms: u32,
+    },
+    /// The text, spoken by the system voice (macOS only).
+    Speech { text: String },
+}
+
+/// Render a test signal as 16kHz mono samples.
+///
+/// # Returns
+/// * `Ok(Vec<f32>)` with the samples
+/// * `Err(String)` if the signal is invalid or speech cannot be synthesized
+pub fn render(signal: &TestSignal) -> Result<Vec<f32>, String> {
+    match signal {
+        TestSignal::Tone {
+            frequency_hz,
+            duration_ms,
+        } => sweep(*frequency_hz, *frequency_hz, *duration_ms),
+        TestSignal::Chirp {
+            start_hz,
+            end_hz,
+            duration_ms,
+        } => sweep(*start_hz, *end_hz, *duration_ms),
+        TestSignal::Speech { text } if text.trim().is_empty() => {
+            Err("No text to speak".to_string())
+        }
+        TestSignal::Speech { text } => speak(text),
+    }
+}
+
+/// Sine wave sweeping linearly from `start_hz` to `end_hz`.
+fn sweep(start_hz: f32, end_hz: f32, duration_ms: u32) -> Result<Vec<f32>, String> {
+    let nyquist = TARGET_SAMPLE_RATE as f32 / 2.0;
+    for frequency in [start_hz, end_hz] {
+        if !(frequency > 0.0 && frequency < nyquist) {
+            return Err(format!("Frequency must be between 0 and {nyquist}Hz"));
+        }
+    }
+    if duration_ms == 0 || duration_ms > MAX_DURATION_MS {
+        return Err(format!(
+            "Duration must be between 1 and {MAX_DURATION_MS}ms"
+        ));
+    }
+
+    let count = (TARGET_SAMPLE_RATE as u64 * u64::from(duration_ms) / 1000) as usize;
+    let rate = TARGET_SAMPLE_RATE as f32;
+    let duration = count as f32 / rate;
+    // Phase of a linear chirp: 2π (f0 t + (f1 - f0) t² / 2T)
+    Ok((0..count)
+        .map(|i| {
+            let t = i as f32 / rate;
+            let cycles = start_hz * t + (end_hz - start_hz) * t * t / (2.0 * duration);
+            AMPLITUDE * (std::f32::consts::TAU * cycles).sin()
+        })
+        .collect())
+}
+
+#[cfg(target_os = "macos")]
+fn speak(text: &str) -> Result<Vec<f32>, String> {
+    use std::process::Command;
+
+    use crate::infrastructure::audio::wav;
+
+    let output =
+        std::env::temp_dir().join(format!("cyrano-test-signal-{}.wav", std::process::id()));
+    let result = Command::new("/usr/bin/say")
+        .arg("-o")
+        .arg(&output)
+        .arg(format!("--data-format=LEF32@{TARGET_SAMPLE_RATE}"))
+        .arg("--")
+        .arg(text)
+        .output();
+
+    let spoken = match result {
+        Ok(out) if out.status.success() => std::fs::read(&output)
+            .map_err(|e| format!("Failed to read synthesized speech: {e}"))
+            .and_then(|bytes| wav::decode_wav(&bytes)),
+        Ok(out) => Err(format!(
+            "say failed: {}",
+            String::from_utf8_lossy(&out.stderr).trim()
+        )),
+        Err(e) => Err(format!("Failed to run say: {e}")),
+    };
+    let _ = std::fs::remove_file(&output);
+
+    match spoken? {
+        (samples, rate) if rate == TARGET_SAMPLE_RATE => Ok(samples),
+        (_, rate) => Err(format!("Synthesized speech at {rate}Hz instead of 16kHz")),
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn speak(_text: &str) -> Result<Vec<f32>, String> {
+    Err("Speech synthesis is only available on macOS".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tone_has_duration_and_level() {
+        let samples = render(&TestSignal::Tone {
+            frequency_hz: 440.0,
+            duration_ms: 500,
+        })
+        .unwrap();
+        assert_eq!(samples.len(), 8_000);
+        let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        assert!((peak - AMPLITUDE).abs() < 0.01, "{peak}");
+    }
+
+    #[test]
+    fn test_chirp_sweeps_up() {
+        let samples = render(&TestSignal::Chirp {
+            start_hz: 100.0,
+            end_hz: 4_000.0,
+            duration_ms: 1_000,
+        })
+        .unwrap();
+        let crossings = |part: &[f32]| {
+            part.windows(2)
+                .filter(|w| w[0] < 0.0 && w[1] >= 0.0)
+                .count()
+        };
+        // Fewer cycles in the first 100ms (near 100Hz) than in the last (near 4kHz)
+        assert!(crossings(&samples[..1_600]) < crossings(&samples[14_400..]));
+    }
+
+    #[test]
+    fn test_rejects_invalid_signals() {
+        let tone = |frequency_hz, duration_ms| TestSignal::Tone {
+            frequency_hz,
+            duration_ms,
+        };
+        assert!(render(&tone(9_000.0, 500)).is_err());
+        assert!(render(&tone(0.0, 500)).is_err());
+        assert!(render(&tone(440.0, 0)).is_err());
+        assert!(render(&tone(440.0, MAX_DURATION_MS + 1)).is_err());
+        assert!(render(&TestSignal::Speech {
+            text: " ".to_string()
+        })
+        .is_err());
+    }
+}
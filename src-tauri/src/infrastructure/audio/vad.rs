@@ -0,0 +1,208 @@
+//! Energy-based voice activity detection for streaming audio.
+//!
+//! Classifies fixed-size frames of 16kHz mono audio as speech or silence by
+//! comparing their level with both an absolute threshold and an adaptive
+//! noise floor, so a steady fan or air conditioner does not count as speech.
+//! Designed to run on the capture thread with negligible cost.
+
+/// Frame length in samples (30ms at 16kHz).
+const FRAME_SAMPLES: usize = 480;
+
+/// Frame duration in milliseconds.
+const FRAME_MS: u32 = 30;
+
+/// How far above the noise floor a frame must be to count as speech, in dB.
+const SPEECH_MARGIN_DB: f32 = 10.0;
+
+/// Consecutive speech frames needed before speech is considered started,
+/// so clicks and keyboard taps are ignored (90ms).
+const SPEECH_ONSET_FRAMES: u32 = 3;
+
+/// Weight of a louder silent frame in the noise floor estimate.
+const NOISE_FLOOR_ADAPTATION: f32 = 0.05;
+
+/// Weight of a speech frame in the noise floor estimate: low enough for
+/// speech to stay above the floor, so that only a hum lasting a few seconds
+/// raises it.
+const SPEECH_FLOOR_ADAPTATION: f32 = 0.01;
+
+/// Level reported for digital silence, in dBFS.
+const MIN_LEVEL_DB: f32 = -100.0;
+
+/// Streaming voice activity detector.
+pub struct VoiceActivityDetector {
+    threshold_db: f32,
+    noise_floor_db: f32,
+    pending: Vec<f32>,
+    speech_run: u32,
+    heard_speech: bool,
+    trailing_silence_frames: u32,
+}
+
+impl VoiceActivityDetector {
+    /// Create a detector that ignores frames quieter than `threshold_db` dBFS.
+    ///
+    /// The noise floor starts at the threshold rather than at the first
+    /// frame, so a recording that starts with speech still detects it.
+    pub fn new(threshold_db: f32) -> Self {
+        Self {
+            threshold_db,
+            noise_floor_db: threshold_db,
+            pending: Vec::with_capacity(FRAME_SAMPLES),
+            speech_run: 0,
+            heard_speech: false,
+            trailing_silence_frames: 0,
+        }
+    }
+
+    /// Feed new 16kHz mono samples. Incomplete frames are kept for the next call.
+    pub fn push_samples(&mut self, samples: &[f32]) {
+        for &sample in samples {
+            self.pending.push(sample);
+            if self.pending.len() == FRAME_SAMPLES {
+                let level = frame_level_db(&self.pending);
+                self.pending.clear();
+                self.process_frame(level);
+            }
+        }
+    }
+
+    /// Whether speech was detected since the detector was created.
+    pub fn heard_speech(&self) -> bool {
+        self.heard_speech
+    }
+
+    /// Milliseconds of silence since speech last ended, or 0 if no speech
+    /// was detected yet.
+    pub fn trailing_silence_ms(&self) -> u32 {
+        if self.heard_speech {
+            self.trailing_silence_frames.saturating_mul(FRAME_MS)
+        } else {
+            0
+        }
+    }
+
+    fn process_frame(&mut self, level_db: f32) {
+        let floor = self.noise_floor_db;
+        let is_speech = level_db > self.threshold_db && level_db > floor + SPEECH_MARGIN_DB;
+
+        if is_speech {
+            self.speech_run += 1;
+            if self.speech_run >= SPEECH_ONSET_FRAMES {
+                self.heard_speech = true;
+                self.trailing_silence_frames = 0;
+            } else {
+                self.trailing_silence_frames += 1;
+            }
+            if level_db > floor {
+                self.noise_floor_db = floor + (level_db - floor) * SPEECH_FLOOR_ADAPTATION;
+            }
+        } else {
+            self.speech_run = 0;
+            self.trailing_silence_frames += 1;
+            // Follow quieter frames at once, louder ones slowly
+            self.noise_floor_db = if level_db < floor {
+                level_db
+            } else {
+                floor + (level_db - floor) * NOISE_FLOOR_ADAPTATION
+            };
+        }
+    }
+}
+
+/// RMS level of a frame in dBFS.
+fn frame_level_db(frame: &[f32]) -> f32 {
+    let mean_square = frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32;
+    if mean_square <= 0.0 {
+        return MIN_LEVEL_DB;
+    }
+    (10.0 * mean_square.log10()).max(MIN_LEVEL_DB)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 16_000.0;
+
+    fn tone(ms: u32, amplitude: f32) -> Vec<f32> {
+        let count = (SAMPLE_RATE * ms as f32 / 1000.0) as usize;
+        (0..count)
+            .map(|i| {
+                amplitude * (2.0 * std::f32::consts::PI * 220.0 * i as f32 / SAMPLE_RATE).sin()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_frame_level_db() {
+        assert_eq!(frame_level_db(&[0.0; FRAME_SAMPLES]), MIN_LEVEL_DB);
+        let full_scale = frame_level_db(&[1.0; FRAME_SAMPLES]);
+        assert!(full_scale.abs() < 0.01, "got {full_scale}");
+    }
+
+    #[test]
+    fn test_silence_only_never_reports_speech() {
+        let mut vad = VoiceActivityDetector::new(-40.0);
+        vad.push_samples(&tone(2000, 0.0));
+        assert!(!vad.heard_speech());
+        assert_eq!(vad.trailing_silence_ms(), 0);
+    }
+
+    #[test]
+    fn test_counts_silence_after_speech() {
+        let mut vad = VoiceActivityDetector::new(-40.0);
+        vad.push_samples(&tone(300, 0.0005));
+        vad.push_samples(&tone(600, 0.3));
+        assert!(vad.heard_speech());
+        assert_eq!(vad.trailing_silence_ms(), 0);
+
+        vad.push_samples(&tone(900, 0.0005));
+        assert_eq!(vad.trailing_silence_ms(), 900);
+    }
+
+    #[test]
+    fn test_speech_resets_trailing_silence() {
+        let mut vad = VoiceActivityDetector::new(-40.0);
+        vad.push_samples(&tone(300, 0.3));
+        vad.push_samples(&tone(600, 0.0));
+        vad.push_samples(&tone(300, 0.3));
+        assert_eq!(vad.trailing_silence_ms(), 0);
+    }
+
+    #[test]
+    fn test_short_click_is_not_speech() {
+        let mut vad = VoiceActivityDetector::new(-40.0);
+        vad.push_samples(&tone(300, 0.0));
+        vad.push_samples(&tone(30, 0.5));
+        vad.push_samples(&tone(300, 0.0));
+        assert!(!vad.heard_speech());
+    }
+
+    #[test]
+    fn test_steady_noise_below_threshold_is_silence() {
+        let mut vad = VoiceActivityDetector::new(-20.0);
+        // About -23 dBFS: above a quiet room, below the configured threshold
+        vad.push_samples(&tone(1000, 0.1));
+        assert!(!vad.heard_speech());
+    }
+
+    #[test]
+    fn test_steady_noise_sets_noise_floor() {
+        let mut vad = VoiceActivityDetector::new(-60.0);
+        // A constant hum above the threshold ends up as the floor instead of speech
+        vad.push_samples(&tone(5000, 0.05));
+        assert!(vad.trailing_silence_ms() > 0);
+        vad.push_samples(&tone(300, 0.5));
+        assert_eq!(vad.trailing_silence_ms(), 0);
+    }
+
+    #[test]
+    fn test_detects_speech_at_the_start() {
+        let mut vad = VoiceActivityDetector::new(-40.0);
+        vad.push_samples(&tone(600, 0.3));
+        assert!(vad.heard_speech());
+        vad.push_samples(&tone(300, 0.0));
+        assert_eq!(vad.trailing_silence_ms(), 300);
+    }
+}
@@ -0,0 +1,236 @@
+//! WAV decoding for imported audio files, and encoding of saved recordings.
+//!
+//! Supports the PCM encodings produced by common recorders and by
+//! `afconvert`: 8/16/24/32-bit integer and 32-bit float, any channel count
+//! (mixed down to mono). `WAVE_FORMAT_EXTENSIBLE` headers are accepted when
+//! their sub-format is PCM or float.
+
+/// Integer PCM format tag.
+const FORMAT_PCM: u16 = 1;
+
+/// IEEE float format tag.
+const FORMAT_FLOAT: u16 = 3;
+
+/// Extensible format tag; the actual format is in the sub-format GUID.
+const FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+/// Sample layout read from the `fmt ` chunk.
+struct WavFormat {
+    format: u16,
+    channels: usize,
+    sample_rate: u32,
+    bits_per_sample: u16,
+}
+
+/// Decode a WAV file into mono `f32` samples, averaging channels.
+///
+/// # Returns
+/// * `Ok((samples, sample_rate))` on success
+/// * `Err(String)` if the file is not a supported WAV file
+pub fn decode_wav(bytes: &[u8]) -> Result<(Vec<f32>, u32), String> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err("Not a WAV file".to_string());
+    }
+
+    let mut format: Option<WavFormat> = None;
+    let mut offset = 12;
+    while offset + 8 <= bytes.len() {
+        let id = &bytes[offset..offset + 4];
+        let size = u32::from_le_bytes([
+            bytes[offset + 4],
+            bytes[offset + 5],
+            bytes[offset + 6],
+            bytes[offset + 7],
+        ]) as usize;
+        let body_start = offset + 8;
+        // Writers that never finalize the header leave the data size too large
+        let body_end = body_start.saturating_add(size).min(bytes.len());
+        let body = &bytes[body_start..body_end];
+
+        match id {
+            b"fmt " => format = Some(parse_format(body)?),
+            b"data" => {
+                let format = format.ok_or("WAV data chunk before fmt chunk")?;
+                return decode_samples(body, &format).map(|s| (s, format.sample_rate));
+            }
+            _ => {}
+        }
+
+        // Chunks are padded to an even size
+        offset = body_end + (size & 1);
+    }
+
+    Err("WAV file has no data chunk".to_string())
+}
+
+/// Encode mono `f32` samples as a 16-bit PCM WAV file.
+pub fn encode_wav(samples: &[f32], sample_rate: u32) -> Vec<u8> {
+    let data_len = (samples.len() * 2) as u32;
+    let mut bytes = Vec::with_capacity(44 + samples.len() * 2);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVE");
+    bytes.extend_from_slice(b"fmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&FORMAT_PCM.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&sample_rate.to_le_bytes());
+    // Byte rate and block align of one 16-bit channel
+    bytes.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+    bytes.extend_from_slice(&2u16.to_le_bytes());
+    bytes.extend_from_slice(&16u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    for &sample in samples {
+        let value = (sample * 32768.0).round().clamp(-32768.0, 32767.0) as i16;
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    bytes
+}
+
+fn parse_format(body: &[u8]) -> Result<WavFormat, String> {
+    if body.len() < 16 {
+        return Err("WAV fmt chunk is too short".to_string());
+    }
+    let read_u16 = |at: usize| u16::from_le_bytes([body[at], body[at + 1]]);
+
+    let mut format = read_u16(0);
+    if format == FORMAT_EXTENSIBLE {
+        // The sub-format GUID starts with the actual format tag
+        if body.len() < 26 {
+            return Err("WAV extensible fmt chunk is too short".to_string());
+        }
+        format = read_u16(24);
+    }
+
+    let format = WavFormat {
+        format,
+        channels: read_u16(2) as usize,
+        sample_rate: u32::from_le_bytes([body[4], body[5], body[6], body[7]]),
+        bits_per_sample: read_u16(14),
+    };
+
+    let supported = match format.format {
+        FORMAT_PCM => matches!(format.bits_per_sample, 8 | 16 | 24 | 32),
+        FORMAT_FLOAT => format.bits_per_sample == 32,
+        _ => false,
+    };
+    if !supported {
+        return Err(format!(
+            "Unsupported WAV encoding (format {}, {} bits)",
+            format.format, format.bits_per_sample
+        ));
+    }
+    if format.channels == 0 || format.sample_rate == 0 {
+        return Err("WAV file declares no channels or sample rate".to_string());
+    }
+    Ok(format)
+}
+
+fn decode_samples(data: &[u8], format: &WavFormat) -> Result<Vec<f32>, String> {
+    let sample_bytes = usize::from(format.bits_per_sample / 8);
+    let frame_bytes = sample_bytes * format.channels;
+
+    let decode = |s: &[u8]| -> f32 {
+        match (format.format, sample_bytes) {
+            (FORMAT_FLOAT, _) => f32::from_le_bytes([s[0], s[1], s[2], s[3]]),
+            // 8-bit PCM is unsigned
+            (_, 1) => (f32::from(s[0]) - 128.0) / 128.0,
+            (_, 2) => f32::from(i16::from_le_bytes([s[0], s[1]])) / 32768.0,
+            (_, 3) => (i32::from_le_bytes([0, s[0], s[1], s[2]]) >> 8) as f32 / 8_388_608.0,
+            _ => i32::from_le_bytes([s[0], s[1], s[2], s[3]]) as f32 / 2_147_483_648.0,
+        }
+    };
+
+    Ok(data
+        .chunks_exact(frame_bytes)
+        .map(|frame| {
+            frame.chunks_exact(sample_bytes).map(decode).sum::<f32>() / format.channels as f32
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wav(format: u16, channels: u16, sample_rate: u32, bits: u16, data: &[u8]) -> Vec<u8> {
+        let block_align = channels * bits / 8;
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(b"WAVE");
+        bytes.extend_from_slice(b"fmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&format.to_le_bytes());
+        bytes.extend_from_slice(&channels.to_le_bytes());
+        bytes.extend_from_slice(&sample_rate.to_le_bytes());
+        bytes.extend_from_slice(&(sample_rate * u32::from(block_align)).to_le_bytes());
+        bytes.extend_from_slice(&block_align.to_le_bytes());
+        bytes.extend_from_slice(&bits.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    #[test]
+    fn test_decodes_16_bit_mono() {
+        let data: Vec<u8> = [0i16, 16384, -32768]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        let (samples, rate) = decode_wav(&wav(FORMAT_PCM, 1, 16_000, 16, &data)).unwrap();
+        assert_eq!(rate, 16_000);
+        assert_eq!(samples, vec![0.0, 0.5, -1.0]);
+    }
+
+    #[test]
+    fn test_mixes_stereo_to_mono() {
+        let data: Vec<u8> = [16384i16, 0, -16384, -16384]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        let (samples, rate) = decode_wav(&wav(FORMAT_PCM, 2, 44_100, 16, &data)).unwrap();
+        assert_eq!(rate, 44_100);
+        assert_eq!(samples, vec![0.25, -0.5]);
+    }
+
+    #[test]
+    fn test_decodes_24_bit_and_float() {
+        let (samples, _) = decode_wav(&wav(FORMAT_PCM, 1, 8_000, 24, &[0, 0, 0xC0])).unwrap();
+        assert_eq!(samples, vec![-0.5]);
+
+        let (samples, _) =
+            decode_wav(&wav(FORMAT_FLOAT, 1, 48_000, 32, &0.25f32.to_le_bytes())).unwrap();
+        assert_eq!(samples, vec![0.25]);
+    }
+
+    #[test]
+    fn test_skips_unknown_chunks() {
+        let mut bytes = wav(FORMAT_PCM, 1, 16_000, 8, &[128, 255]);
+        // Insert an odd-sized LIST chunk (with padding) between fmt and data
+        let list = [b"LIST".as_slice(), &3u32.to_le_bytes(), b"abc", &[0]].concat();
+        bytes.splice(36..36, list);
+        let (samples, _) = decode_wav(&bytes).unwrap();
+        assert_eq!(samples, vec![0.0, 127.0 / 128.0]);
+    }
+
+    #[test]
+    fn test_encoded_wav_decodes_to_same_samples() {
+        let samples = vec![0.0, 0.5, -1.0, 0.25];
+        let (decoded, rate) = decode_wav(&encode_wav(&samples, 16_000)).unwrap();
+        assert_eq!(rate, 16_000);
+        assert_eq!(decoded, samples);
+
+        // Out-of-range samples are clipped
+        let (decoded, _) = decode_wav(&encode_wav(&[1.5, -2.0], 16_000)).unwrap();
+        assert_eq!(decoded, vec![32767.0 / 32768.0, -1.0]);
+    }
+
+    #[test]
+    fn test_rejects_invalid_files() {
+        assert!(decode_wav(b"not a wav file").is_err());
+        assert!(decode_wav(&wav(2, 1, 16_000, 4, &[0])).is_err());
+    }
+}
@@ -0,0 +1,82 @@
+//! macOS display change notifications using NSNotificationCenter.
+//!
+//! AppKit posts `NSApplicationDidChangeScreenParametersNotification` on the
+//! main thread when a screen is added or removed, or when its frame, visible
+//! frame (menu bar, Dock) or scale changes.
+
+use std::sync::OnceLock;
+
+use objc2::rc::Retained;
+use objc2::runtime::{AnyObject, NSObject};
+use objc2::{define_class, msg_send, sel, MainThreadMarker, MainThreadOnly};
+use objc2_app_kit::NSApplicationDidChangeScreenParametersNotification;
+use objc2_foundation::{NSNotification, NSNotificationCenter};
+
+/// Callback run on each display change.
+type ChangeHandler = Box<dyn Fn() + Send + Sync>;
+
+static HANDLER: OnceLock<ChangeHandler> = OnceLock::new();
+
+define_class!(
+    // SAFETY:
+    // - NSObject has no subclassing requirements.
+    // - DisplayObserver does not implement Drop.
+    #[unsafe(super(NSObject))]
+    #[thread_kind = MainThreadOnly]
+    #[name = "CyranoDisplayObserver"]
+    struct DisplayObserver;
+
+    impl DisplayObserver {
+        #[unsafe(method(screenParametersChanged:))]
+        fn screen_parameters_changed(&self, _notification: &NSNotification) {
+            log::info!("Display configuration changed");
+            if let Some(handler) = HANDLER.get() {
+                handler();
+            }
+        }
+    }
+);
+
+impl DisplayObserver {
+    fn new(mtm: MainThreadMarker) -> Retained<Self> {
+        let this = Self::alloc(mtm).set_ivars(());
+        // SAFETY: NSObject's init has no preconditions.
+        unsafe { msg_send![super(this), init] }
+    }
+}
+
+/// Call `handler` each time the display configuration changes.
+///
+/// MUST be called on the main thread (e.g. from the Tauri setup hook). The
+/// handler runs on the main thread and should return quickly.
+///
+/// # Returns
+/// `false` if an observer was already registered
+pub fn observe_display_changes(
+    mtm: MainThreadMarker,
+    handler: impl Fn() + Send + Sync + 'static,
+) -> bool {
+    if HANDLER.set(Box::new(handler)).is_err() {
+        log::warn!("Display observer already registered");
+        return false;
+    }
+
+    let observer = DisplayObserver::new(mtm);
+    let object: &AnyObject = &observer;
+    let center = NSNotificationCenter::defaultCenter();
+    // SAFETY: the observer implements the selector with the expected
+    // signature, and is never deallocated (see below).
+    unsafe {
+        center.addObserver_selector_name_object(
+            object,
+            sel!(screenParametersChanged:),
+            Some(NSApplicationDidChangeScreenParametersNotification),
+            None,
+        );
+    }
+    // The notification center does not retain its observers; keep it for
+    // the lifetime of the app.
+    std::mem::forget(observer);
+    log::info!("Observing display configuration changes");
+    true
+}
@@ -0,0 +1,11 @@
+//! Display configuration change notifications.
+//!
+//! Platform-specific observers telling when monitors are connected,
+//! disconnected, rearranged or change resolution, so cached monitor geometry
+//! can be refreshed.
+
+#[cfg(target_os = "macos")]
+pub mod macos_display;
+
+#[cfg(target_os = "macos")]
+pub use macos_display::observe_display_changes;
@@ -2,19 +2,53 @@
 //!
 //! This module provides low-level keyboard event simulation for macOS,
 //! specifically for simulating Cmd+V paste operations to insert text
-//! at the current cursor position in any application.
+//! at the current cursor position in any application, or typing the text
+//! itself without going through the clipboard. It also watches for Cmd+V
+//! pressed or posted by other apps.
 
-use core_graphics::event::{CGEvent, CGEventFlags, CGEventTapLocation, CGKeyCode};
+use core_foundation::runloop::{kCFRunLoopDefaultMode, CFRunLoop};
+use core_graphics::event::{
+    CGEvent, CGEventFlags, CGEventTap, CGEventTapLocation, CGEventTapOptions, CGEventTapPlacement,
+    CGEventType, CGKeyCode, EventField,
+};
 use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
 use std::thread;
 use std::time::Duration;
 
 /// Virtual keycode for the V key on macOS.
 const K_VK_V: CGKeyCode = 0x09;
 
+/// Virtual keycode for the Delete (backspace) key on macOS.
+const K_VK_DELETE: CGKeyCode = 0x33;
+
 /// Delay between keydown and keyup events for reliability.
 const KEY_EVENT_DELAY_MS: u64 = 10;
 
+/// Most UTF-16 code units carried by one typing event: apps drop the rest.
+const MAX_UNITS_PER_EVENT: usize = 20;
+
+/// Delay between typing events, so slow apps keep up.
+const TYPE_EVENT_DELAY_MS: u64 = 2;
+
+/// `kCGEventSourceStateCombinedSessionState`: hardware and posted events.
+const COMBINED_SESSION_STATE: i32 = 0;
+
+// Link to ApplicationServices framework for event source state
+#[link(name = "ApplicationServices", kind = "framework")]
+extern "C" {
+    /// Modifier flags currently held, for an event source state.
+    fn CGEventSourceFlagsState(state_id: i32) -> u64;
+}
+
+#[link(name = "Carbon", kind = "framework")]
+extern "C" {
+    /// Whether an app turned on secure keyboard entry (password fields,
+    /// secure terminals).
+    fn IsSecureEventInputEnabled() -> u8;
+}
+
 /// Simulate a Cmd+V paste keystroke.
 ///
 /// This function simulates pressing Cmd+V by:
@@ -65,6 +99,176 @@ pub fn simulate_paste() -> Result<(), std::io::Error> {
     Ok(())
 }
 
+/// Simulate pressing Delete (backspace) `count` times.
+///
+/// Used to remove text that was previously inserted at the cursor.
+/// Each character is deleted with its own keydown/keyup pair so that
+/// target applications process every keystroke.
+///
+/// # Returns
+/// * `Ok(())` if all keystrokes were posted
+/// * `Err(std::io::Error)` if event creation failed
+pub fn simulate_backspaces(count: usize) -> Result<(), std::io::Error> {
+    log::debug!("Simulating {count} backspace keystrokes");
+
+    let source = CGEventSource::new(CGEventSourceStateID::HIDSystemState).map_err(|()| {
+        std::io::Error::other("Failed to create CGEventSource for keyboard simulation")
+    })?;
+
+    for _ in 0..count {
+        let down = CGEvent::new_keyboard_event(source.clone(), K_VK_DELETE, true)
+            .map_err(|()| std::io::Error::other("Failed to create Delete keydown event"))?;
+        let up = CGEvent::new_keyboard_event(source.clone(), K_VK_DELETE, false)
+            .map_err(|()| std::io::Error::other("Failed to create Delete keyup event"))?;
+
+        // Clear inherited modifiers so held shortcut keys don't turn this into Cmd+Delete
+        down.set_flags(CGEventFlags::CGEventFlagNull);
+        up.set_flags(CGEventFlags::CGEventFlagNull);
+
+        down.post(CGEventTapLocation::HID);
+        up.post(CGEventTapLocation::HID);
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    Ok(())
+}
+
+/// Split text into UTF-16 chunks of at most `max_units` code units,
+/// without splitting a character.
+fn utf16_chunks(text: &str, max_units: usize) -> Vec<Vec<u16>> {
+    let mut chunks = Vec::new();
+    let mut chunk: Vec<u16> = Vec::with_capacity(max_units);
+    let mut units = [0u16; 2];
+    for c in text.chars() {
+        let encoded = c.encode_utf16(&mut units);
+        if chunk.len() + encoded.len() > max_units && !chunk.is_empty() {
+            chunks.push(std::mem::take(&mut chunk));
+        }
+        chunk.extend_from_slice(encoded);
+    }
+    if !chunk.is_empty() {
+        chunks.push(chunk);
+    }
+    chunks
+}
+
+/// Type text at the cursor as keyboard events carrying the characters
+/// (`CGEventKeyboardSetUnicodeString`), leaving the clipboard untouched.
+///
+/// # Returns
+/// * `Ok(())` if all events were posted
+/// * `Err(std::io::Error)` if event creation failed
+pub fn type_text(text: &str) -> Result<(), std::io::Error> {
+    log::debug!("Typing {} chars", text.chars().count());
+
+    let source = CGEventSource::new(CGEventSourceStateID::HIDSystemState).map_err(|()| {
+        std::io::Error::other("Failed to create CGEventSource for keyboard simulation")
+    })?;
+
+    for chunk in utf16_chunks(text, MAX_UNITS_PER_EVENT) {
+        // The keycode is ignored: the event types the string it carries
+        let down = CGEvent::new_keyboard_event(source.clone(), 0, true)
+            .map_err(|()| std::io::Error::other("Failed to create typing keydown event"))?;
+        let up = CGEvent::new_keyboard_event(source.clone(), 0, false)
+            .map_err(|()| std::io::Error::other("Failed to create typing keyup event"))?;
+
+        // Held shortcut keys must not turn the text into shortcuts
+        down.set_flags(CGEventFlags::CGEventFlagNull);
+        up.set_flags(CGEventFlags::CGEventFlagNull);
+        down.set_string_from_utf16_unchecked(&chunk);
+        up.set_string_from_utf16_unchecked(&chunk);
+
+        down.post(CGEventTapLocation::HID);
+        up.post(CGEventTapLocation::HID);
+        thread::sleep(Duration::from_millis(TYPE_EVENT_DELAY_MS));
+    }
+
+    Ok(())
+}
+
+/// Watch for Cmd+V started by `watch_paste_shortcut`.
+#[derive(Debug, Clone)]
+pub struct PasteShortcutWatch {
+    seen: Arc<AtomicBool>,
+}
+
+impl PasteShortcutWatch {
+    /// Whether a Cmd+V keydown was seen since the watch started.
+    pub fn pasted(&self) -> bool {
+        self.seen.load(Ordering::SeqCst)
+    }
+}
+
+/// Check whether a keydown is Cmd+V.
+fn is_paste_shortcut(keycode: i64, flags: CGEventFlags) -> bool {
+    keycode == i64::from(K_VK_V) && flags.contains(CGEventFlags::CGEventFlagCommand)
+}
+
+/// Watch for Cmd+V keydowns in the login session for the given duration.
+///
+/// Sees both hardware keystrokes and events posted by other processes, so
+/// it notices auto-paste tools as well as the user pasting by hand. Other
+/// keys are ignored. The watch runs on its own thread and stops by itself.
+///
+/// # Returns
+/// * `Some(PasteShortcutWatch)` once the watch is listening
+/// * `None` if the event tap could not be created (missing permission)
+pub fn watch_paste_shortcut(duration: Duration) -> Option<PasteShortcutWatch> {
+    let seen = Arc::new(AtomicBool::new(false));
+    let (ready_tx, ready_rx) = mpsc::channel();
+
+    let flag = Arc::clone(&seen);
+    thread::spawn(move || {
+        let tap = CGEventTap::new(
+            CGEventTapLocation::Session,
+            CGEventTapPlacement::HeadInsertEventTap,
+            CGEventTapOptions::ListenOnly,
+            vec![CGEventType::KeyDown],
+            move |_, _, event| {
+                let keycode = event.get_integer_value_field(EventField::KEYBOARD_EVENT_KEYCODE);
+                if is_paste_shortcut(keycode, event.get_flags()) {
+                    flag.store(true, Ordering::SeqCst);
+                }
+                None
+            },
+        );
+        let Ok(tap) = tap else {
+            let _ = ready_tx.send(false);
+            return;
+        };
+        let Ok(source) = tap.mach_port.create_runloop_source(0) else {
+            let _ = ready_tx.send(false);
+            return;
+        };
+
+        // SAFETY: Reading an immutable CoreFoundation constant
+        let mode = unsafe { kCFRunLoopDefaultMode };
+        CFRunLoop::get_current().add_source(&source, mode);
+        tap.enable();
+        let _ = ready_tx.send(true);
+        CFRunLoop::run_in_mode(mode, duration, false);
+    });
+
+    match ready_rx.recv() {
+        Ok(true) => Some(PasteShortcutWatch { seen }),
+        _ => None,
+    }
+}
+
+/// Check whether secure keyboard entry is on, as it is while a password
+/// field has focus.
+pub fn secure_input_enabled() -> bool {
+    // SAFETY: Pure query with no arguments
+    unsafe { IsSecureEventInputEnabled() != 0 }
+}
+
+/// Check whether a Shift key is held down right now.
+pub fn shift_key_down() -> bool {
+    // SAFETY: Pure query with no pointer arguments
+    let flags = unsafe { CGEventSourceFlagsState(COMBINED_SESSION_STATE) };
+    CGEventFlags::from_bits_truncate(flags).contains(CGEventFlags::CGEventFlagShift)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -98,4 +302,43 @@ mod tests {
         // Virtual keycode for V on macOS is 0x09
         assert_eq!(K_VK_V, 0x09);
     }
+
+    #[test]
+    fn test_is_paste_shortcut() {
+        let v = i64::from(K_VK_V);
+        assert!(is_paste_shortcut(v, CGEventFlags::CGEventFlagCommand));
+        assert!(is_paste_shortcut(
+            v,
+            CGEventFlags::CGEventFlagCommand | CGEventFlags::CGEventFlagShift
+        ));
+        assert!(!is_paste_shortcut(v, CGEventFlags::CGEventFlagNull));
+        assert!(!is_paste_shortcut(
+            i64::from(K_VK_DELETE),
+            CGEventFlags::CGEventFlagCommand
+        ));
+    }
+
+    #[test]
+    fn test_utf16_chunks_keep_characters_whole() {
+        assert!(utf16_chunks("", 20).is_empty());
+        let chunks = utf16_chunks("hello world", 4);
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(String::from_utf16(&chunks.concat()).unwrap(), "hello world");
+
+        // A surrogate pair is never split across events
+        let chunks = utf16_chunks("ab😀", 3);
+        assert_eq!(
+            chunks,
+            vec![
+                "ab".encode_utf16().collect::<Vec<_>>(),
+                "😀".encode_utf16().collect()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_virtual_keycode_delete_is_correct() {
+        // Virtual keycode for Delete (backspace) on macOS is 0x33
+        assert_eq!(K_VK_DELETE, 0x33);
+    }
 }
@@ -1,10 +1,16 @@
 //! Keyboard simulation infrastructure.
 //!
 //! Provides low-level keyboard event simulation for macOS.
-//! Currently supports paste simulation (Cmd+V) for cursor insertion.
+//! Supports paste simulation (Cmd+V) and typing for cursor insertion and
+//! backspace simulation for removing previously inserted text, plus a Cmd+V
+//! watch used to notice pastes from other apps, the state of the Shift
+//! key at launch and whether secure keyboard entry is on.
 
 #[cfg(target_os = "macos")]
 pub mod macos_keyboard;
 
 #[cfg(target_os = "macos")]
-pub use macos_keyboard::simulate_paste;
+pub use macos_keyboard::{
+    secure_input_enabled, shift_key_down, simulate_backspaces, simulate_paste, type_text,
+    watch_paste_shortcut, PasteShortcutWatch,
+};
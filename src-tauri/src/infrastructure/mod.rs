@@ -2,11 +2,33 @@
 //!
 //! This module contains adapters for external systems:
 //! - Audio capture (cpal)
-//! - Speech-to-text (whisper-rs)
-//! - macOS accessibility APIs
+//! - Speech-to-text (whisper-rs, Apple Speech, or an OpenAI-compatible
+//!   transcription API)
+//! - macOS accessibility APIs (permission, focused text caret)
+//! - Display change notifications (NSNotificationCenter)
 //! - Keyboard simulation (CGEvent)
+//! - App Sandbox / hardened runtime detection
+//! - Running application queries (NSWorkspace)
+//! - Managed preferences (configuration profiles)
+//! - Encrypted sync (ChaCha20-Poly1305, WebDAV / relay over HTTPS)
+//! - Services menu provider (NSApplication)
+//! - Pasteboard files and audio data (NSPasteboard)
+//! - Summarization (OpenAI-compatible chat completions API)
+//! - Reminders (AppleScript)
 
+pub mod accessibility;
 pub mod audio;
+pub mod display;
 pub mod keyboard;
+pub mod pasteboard;
 pub mod permissions;
+pub mod preferences;
+pub mod reminders;
+pub mod remote_transcriber;
+pub mod sandbox;
+pub mod speech;
+pub mod summarizer;
+pub mod sync;
+pub mod system_services;
 pub mod whisper;
+pub mod workspace;
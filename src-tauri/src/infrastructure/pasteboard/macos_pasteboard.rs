@@ -0,0 +1,77 @@
+//! macOS pasteboard access using NSPasteboard.
+
+use std::path::PathBuf;
+
+use objc2_app_kit::{NSPasteboard, NSPasteboardTypeFileURL};
+use objc2_foundation::{NSString, NSURL};
+
+/// Audio UTIs apps put on the pasteboard, with the extension of the format.
+const AUDIO_TYPES: &[(&str, &str)] = &[
+    ("com.apple.m4a-audio", "m4a"),
+    ("public.mpeg-4-audio", "m4a"),
+    ("public.mp3", "mp3"),
+    ("com.microsoft.waveform-audio", "wav"),
+    ("public.aiff-audio", "aiff"),
+    ("public.aifc-audio", "aiff"),
+    ("com.apple.coreaudio-format", "caf"),
+    ("org.xiph.flac", "flac"),
+];
+
+/// Audio content found on the pasteboard.
+#[derive(Debug)]
+pub enum PasteboardAudio {
+    /// Files copied in Finder (or any app that copies file URLs)
+    Files(Vec<PathBuf>),
+    /// Raw audio data, with the file extension matching its format
+    Data {
+        bytes: Vec<u8>,
+        extension: &'static str,
+    },
+}
+
+/// Read the file paths from a pasteboard.
+pub(crate) fn file_paths(pasteboard: &NSPasteboard) -> Vec<PathBuf> {
+    // SAFETY: the pasteboard is retained by the caller and
+    // NSPasteboardTypeFileURL is a constant string.
+    unsafe {
+        let Some(items) = pasteboard.pasteboardItems() else {
+            return Vec::new();
+        };
+        items
+            .iter()
+            .filter_map(|item| {
+                let url = item.stringForType(NSPasteboardTypeFileURL)?;
+                let path = NSURL::URLWithString(&url)?.path()?;
+                Some(PathBuf::from(path.to_string()))
+            })
+            .collect()
+    }
+}
+
+/// Read copied files or audio data from the general pasteboard.
+///
+/// Files take precedence over data, since apps that copy a file often also
+/// put its icon or contents on the pasteboard.
+///
+/// # Returns
+/// * `Some(PasteboardAudio)` if files or audio data were copied
+/// * `None` otherwise (the caller may still look for a path in the text)
+pub fn read_audio() -> Option<PasteboardAudio> {
+    // SAFETY: NSPasteboard is thread-safe for reads and the returned objects
+    // are retained for the duration of the call.
+    unsafe {
+        let pasteboard = NSPasteboard::generalPasteboard();
+        let files = file_paths(&pasteboard);
+        if !files.is_empty() {
+            return Some(PasteboardAudio::Files(files));
+        }
+
+        AUDIO_TYPES.iter().find_map(|&(uti, extension)| {
+            let data = pasteboard.dataForType(&NSString::from_str(uti))?;
+            Some(PasteboardAudio::Data {
+                bytes: data.to_vec(),
+                extension,
+            })
+        })
+    }
+}
@@ -0,0 +1,10 @@
+//! Pasteboard (clipboard) access beyond plain text.
+//!
+//! Platform-specific readers for files and audio data copied from other
+//! apps, such as a recording copied in Voice Memos or a file in Finder.
+
+#[cfg(target_os = "macos")]
+pub mod macos_pasteboard;
+
+#[cfg(target_os = "macos")]
+pub use macos_pasteboard::{read_audio, PasteboardAudio};
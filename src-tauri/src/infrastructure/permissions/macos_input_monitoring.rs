@@ -0,0 +1,57 @@
+//! macOS Input Monitoring permission infrastructure.
+//!
+//! Global keyboard shortcuts and event taps only receive key events once the
+//! user allows the app under Privacy & Security > Input Monitoring.
+
+use crate::domain::PermissionStatus;
+
+/// `kIOHIDRequestTypeListenEvent`: access to keyboard events.
+const IOHID_REQUEST_TYPE_LISTEN_EVENT: u32 = 1;
+
+/// `kIOHIDAccessTypeGranted`
+const IOHID_ACCESS_TYPE_GRANTED: u32 = 0;
+
+/// `kIOHIDAccessTypeDenied`
+const IOHID_ACCESS_TYPE_DENIED: u32 = 1;
+
+#[link(name = "IOKit", kind = "framework")]
+extern "C" {
+    /// Check access to a type of HID events, without prompting.
+    fn IOHIDCheckAccess(request_type: u32) -> u32;
+
+    /// Request access to a type of HID events, prompting the first time.
+    fn IOHIDRequestAccess(request_type: u32) -> bool;
+}
+
+/// Check whether the app may listen to keyboard events.
+///
+/// # Returns
+/// * `PermissionStatus::Granted` if Input Monitoring is allowed
+/// * `PermissionStatus::Denied` if the user turned it off
+/// * `PermissionStatus::NotDetermined` if the user was never asked
+pub fn check_input_monitoring() -> PermissionStatus {
+    // SAFETY: IOHIDCheckAccess only reads the permission database
+    match unsafe { IOHIDCheckAccess(IOHID_REQUEST_TYPE_LISTEN_EVENT) } {
+        IOHID_ACCESS_TYPE_GRANTED => PermissionStatus::Granted,
+        IOHID_ACCESS_TYPE_DENIED => PermissionStatus::Denied,
+        _ => PermissionStatus::NotDetermined,
+    }
+}
+
+/// Ask the user to allow the app to listen to keyboard events.
+///
+/// Shows the system prompt the first time; afterwards only reports whether
+/// access is granted.
+pub fn request_input_monitoring() -> bool {
+    // SAFETY: IOHIDRequestAccess takes a request type and returns a boolean
+    unsafe { IOHIDRequestAccess(IOHID_REQUEST_TYPE_LISTEN_EVENT) }
+}
+
+/// Open the Input Monitoring pane of System Settings.
+pub fn open_input_monitoring_preferences() -> std::io::Result<()> {
+    std::process::Command::new("open")
+        .arg("x-apple.systempreferences:com.apple.preference.security?Privacy_ListenEvent")
+        .spawn()?
+        .wait()?;
+    Ok(())
+}
@@ -1,7 +1,10 @@
 //! Permission-related infrastructure adapters.
 //!
 //! Platform-specific implementations for checking and requesting
-//! system permissions (accessibility, microphone, etc.).
+//! system permissions (accessibility, input monitoring, microphone, etc.).
 
 #[cfg(target_os = "macos")]
 pub mod macos_accessibility;
+
+#[cfg(target_os = "macos")]
+pub mod macos_input_monitoring;
@@ -0,0 +1,119 @@
+//! macOS managed preferences infrastructure.
+//!
+//! Configuration profiles installed by MDM write to
+//! `/Library/Managed Preferences`, which `CFPreferences` reports as forced.
+//! Only forced values are read here: plain user defaults are not managed.
+
+use core_foundation::array::CFArray;
+use core_foundation::base::{Boolean, CFType, CFTypeRef, TCFType};
+use core_foundation::boolean::CFBoolean;
+use core_foundation::dictionary::CFDictionary;
+use core_foundation::number::CFNumber;
+use core_foundation::string::{CFString, CFStringRef};
+use serde_json::{Map, Value};
+
+// Link to CoreFoundation framework for the preferences APIs
+#[link(name = "CoreFoundation", kind = "framework")]
+extern "C" {
+    /// Read a preference value for an application (create rule).
+    fn CFPreferencesCopyAppValue(key: CFStringRef, application_id: CFStringRef) -> CFTypeRef;
+
+    /// Whether a preference value is forced by a configuration profile.
+    fn CFPreferencesAppValueIsForced(key: CFStringRef, application_id: CFStringRef) -> Boolean;
+}
+
+/// Convert a property list value to JSON.
+///
+/// Dates and data blobs have no settings counterpart and are skipped.
+fn to_json(value: &CFType) -> Option<Value> {
+    if let Some(boolean) = value.downcast::<CFBoolean>() {
+        return Some(Value::Bool(boolean.into()));
+    }
+    if let Some(number) = value.downcast::<CFNumber>() {
+        return number.to_i64().map(Value::from).or_else(|| {
+            number
+                .to_f64()
+                .and_then(serde_json::Number::from_f64)
+                .map(Value::Number)
+        });
+    }
+    if let Some(string) = value.downcast::<CFString>() {
+        return Some(Value::String(string.to_string()));
+    }
+    if let Some(array) = value.downcast::<CFArray>() {
+        let items = array
+            .iter()
+            .filter_map(|item| {
+                // SAFETY: array items are valid CF objects retained by the array
+                let item = unsafe { CFType::wrap_under_get_rule(*item as CFTypeRef) };
+                to_json(&item)
+            })
+            .collect();
+        return Some(Value::Array(items));
+    }
+    if let Some(dictionary) = value.downcast::<CFDictionary>() {
+        let (keys, values) = dictionary.get_keys_and_values();
+        let mut object = Map::new();
+        for (key, value) in keys.into_iter().zip(values) {
+            // SAFETY: keys and values are valid CF objects retained by the dictionary
+            let (key, value) = unsafe {
+                (
+                    CFType::wrap_under_get_rule(key as CFTypeRef),
+                    CFType::wrap_under_get_rule(value as CFTypeRef),
+                )
+            };
+            if let (Some(key), Some(value)) = (key.downcast::<CFString>(), to_json(&value)) {
+                object.insert(key.to_string(), value);
+            }
+        }
+        return Some(Value::Object(object));
+    }
+    None
+}
+
+/// Read the values of `keys` forced by configuration profiles for `app_id`.
+///
+/// # Arguments
+/// * `app_id` - Preference domain, i.e. the app's bundle identifier
+/// * `keys` - Setting keys to look up
+///
+/// # Returns
+/// The forced values by key; keys that are not forced are omitted.
+pub fn forced_values(app_id: &str, keys: &[String]) -> Map<String, Value> {
+    let app_id = CFString::new(app_id);
+    let mut values = Map::new();
+
+    for key in keys {
+        let cf_key = CFString::new(key);
+
+        // SAFETY: both arguments are valid CFStrings for the duration of the calls
+        let forced = unsafe {
+            CFPreferencesAppValueIsForced(
+                cf_key.as_concrete_TypeRef(),
+                app_id.as_concrete_TypeRef(),
+            ) != 0
+        };
+        if !forced {
+            continue;
+        }
+
+        // SAFETY: same as above; the returned value follows the create rule
+        let raw = unsafe {
+            CFPreferencesCopyAppValue(cf_key.as_concrete_TypeRef(), app_id.as_concrete_TypeRef())
+        };
+        if raw.is_null() {
+            continue;
+        }
+        // SAFETY: raw is a non-null CF object we own
+        let value = unsafe { CFType::wrap_under_create_rule(raw) };
+
+        match to_json(&value) {
+            Some(json) => {
+                values.insert(key.clone(), json);
+            }
+            None => log::warn!("Ignoring managed preference {key}: unsupported value type"),
+        }
+    }
+
+    values
+}
@@ -0,0 +1,10 @@
+//! Managed (MDM-enforced) preference access.
+//!
+//! Platform-specific implementations for reading settings forced by
+//! configuration profiles.
+
+#[cfg(target_os = "macos")]
+pub mod macos_preferences;
+
+#[cfg(target_os = "macos")]
+pub use macos_preferences::forced_values;
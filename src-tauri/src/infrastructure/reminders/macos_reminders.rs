@@ -0,0 +1,44 @@
+//! Reminders creation through AppleScript.
+//!
+//! `osascript` runs a short script that makes one reminder per title in the
+//! default list. Titles are passed as script arguments rather than spliced
+//! into the source, so quotes in a transcript cannot break the script. The
+//! first run triggers the Automation permission prompt for Reminders.
+
+use std::process::Command;
+
+/// Location of the AppleScript runner on every macOS install.
+const OSASCRIPT: &str = "/usr/bin/osascript";
+
+/// Creates a reminder for each argument in the default list.
+const ADD_REMINDERS_SCRIPT: &str = r#"on run argv
+    tell application "Reminders"
+        repeat with reminderTitle in argv
+            make new reminder with properties {name:(reminderTitle as text)}
+        end repeat
+    end tell
+end run"#;
+
+/// Add one reminder per title to the default Reminders list.
+///
+/// # Returns
+/// * `Ok(())` if every reminder was created
+/// * `Err(String)` if the script failed, e.g. because automation of
+///   Reminders was denied in System Settings
+pub fn add_reminders(titles: &[&str]) -> Result<(), String> {
+    let output = Command::new(OSASCRIPT)
+        .arg("-e")
+        .arg(ADD_REMINDERS_SCRIPT)
+        .args(titles)
+        .output()
+        .map_err(|e| format!("Failed to run osascript: {e}"))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Reminders refused the request: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
@@ -0,0 +1,10 @@
+//! Reminders integration.
+//!
+//! This module contains the macOS adapter that adds reminders to the
+//! Reminders app.
+
+#[cfg(target_os = "macos")]
+pub mod macos_reminders;
+
+#[cfg(target_os = "macos")]
+pub use macos_reminders::add_reminders;
@@ -0,0 +1,9 @@
+//! Remote transcription infrastructure.
+//!
+//! This module contains the adapter for servers implementing the OpenAI
+//! audio transcriptions API, which covers OpenAI's hosted Whisper as well as
+//! compatible self-hosted servers.
+
+mod whisper_api;
+
+pub use whisper_api::WhisperApiTranscriber;
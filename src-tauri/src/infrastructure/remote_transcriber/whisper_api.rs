@@ -0,0 +1,218 @@
+//! Transcriber for servers implementing the OpenAI audio transcriptions API.
+//!
+//! The recording is encoded as a 16kHz WAV file and sent as a multipart form
+//! to `POST <base url>/audio/transcriptions`, or `/audio/translations` to
+//! translate it to English. OpenAI (`https://api.openai.com/v1`) needs an API
+//! key, sent as a bearer token; self-hosted servers may not.
+
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::Deserialize;
+
+use crate::domain::CyranoError;
+use crate::infrastructure::audio::wav;
+use crate::traits::transcriber::Transcriber;
+
+/// Timeout for a transcription request, long enough to upload and
+/// transcribe a long recording on a slow connection.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Sample rate of the audio sent to the server.
+const SAMPLE_RATE: u32 = 16_000;
+
+#[derive(Deserialize)]
+struct TranscriptionResponse {
+    text: String,
+}
+
+/// Transcriber talking to an OpenAI-compatible audio transcriptions endpoint.
+pub struct WhisperApiTranscriber {
+    agent: ureq::Agent,
+    base_url: String,
+    model: String,
+    api_key: Option<String>,
+}
+
+impl WhisperApiTranscriber {
+    /// Create a transcriber for the server at `base_url`.
+    ///
+    /// # Arguments
+    /// * `base_url` - API base URL, e.g. `https://api.openai.com/v1`
+    /// * `model` - Model name as known to the server, e.g. `whisper-1`
+    /// * `api_key` - Optional bearer token
+    pub fn new(base_url: &str, model: &str, api_key: Option<&str>) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model: model.to_string(),
+            api_key: api_key.filter(|key| !key.is_empty()).map(str::to_string),
+        }
+    }
+
+    /// Transcribe audio samples on the server.
+    ///
+    /// Audio must be 16kHz mono f32 samples.
+    ///
+    /// # Arguments
+    /// * `language` - Language code of the speech, e.g. `fr`; the server
+    ///   detects it when None
+    /// * `prompt` - Text guiding the spelling of names and terms
+    /// * `translate` - Translate the speech to English instead
+    pub fn transcribe_with_options(
+        &self,
+        samples: &[f32],
+        language: Option<&str>,
+        prompt: Option<&str>,
+        translate: bool,
+    ) -> Result<String, CyranoError> {
+        let mut fields = vec![("model", self.model.as_str()), ("response_format", "json")];
+        // The translations endpoint only translates to English
+        if let Some(language) = language.filter(|_| !translate) {
+            fields.push(("language", language));
+        }
+        if let Some(prompt) = prompt {
+            fields.push(("prompt", prompt));
+        }
+
+        let boundary = format!(
+            "cyrano-{:x}",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_nanos())
+                .unwrap_or_default()
+        );
+        let body = multipart_body(&boundary, &fields, &wav::encode_wav(samples, SAMPLE_RATE));
+
+        let endpoint = if translate {
+            "translations"
+        } else {
+            "transcriptions"
+        };
+        let request = self
+            .agent
+            .post(&format!("{}/audio/{endpoint}", self.base_url))
+            .set(
+                "Content-Type",
+                &format!("multipart/form-data; boundary={boundary}"),
+            );
+        let request = match &self.api_key {
+            Some(key) => request.set("Authorization", &format!("Bearer {key}")),
+            None => request,
+        };
+
+        log::info!(
+            "Sending {:.1}s of audio to {}",
+            samples.len() as f64 / f64::from(SAMPLE_RATE),
+            self.base_url
+        );
+        let body = request
+            .send_bytes(&body)
+            .map_err(transport_error)?
+            .into_string()
+            .map_err(|e| CyranoError::TranscriptionFailed {
+                reason: format!("Failed to read the server response: {e}"),
+            })?;
+        parse_text(&body)
+    }
+}
+
+/// Multipart form with the text `fields` and the WAV recording as `file`.
+fn multipart_body(boundary: &str, fields: &[(&str, &str)], wav: &[u8]) -> Vec<u8> {
+    let mut body = Vec::with_capacity(wav.len() + 512);
+    for (name, value) in fields {
+        body.extend_from_slice(
+            format!(
+                "--{boundary}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n{value}\r\n"
+            )
+            .as_bytes(),
+        );
+    }
+    body.extend_from_slice(
+        format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"dictation.wav\"\r\nContent-Type: audio/wav\r\n\r\n"
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(wav);
+    body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+    body
+}
+
+/// Extract the text from a transcriptions response body.
+fn parse_text(body: &str) -> Result<String, CyranoError> {
+    serde_json::from_str::<TranscriptionResponse>(body)
+        .map(|response| response.text.trim().to_string())
+        .map_err(|e| CyranoError::TranscriptionFailed {
+            reason: format!("Unexpected response from the server: {e}"),
+        })
+}
+
+fn transport_error(error: ureq::Error) -> CyranoError {
+    let reason = match error {
+        ureq::Error::Status(401 | 403, _) => "The server rejected the API key".to_string(),
+        ureq::Error::Status(404, _) => "Unknown model or endpoint".to_string(),
+        ureq::Error::Status(413, _) => "The recording is too large for the server".to_string(),
+        ureq::Error::Status(code, response) => {
+            format!("Server responded with {code} {}", response.status_text())
+        }
+        ureq::Error::Transport(transport) => format!("Could not reach the server: {transport}"),
+    };
+    CyranoError::TranscriptionFailed { reason }
+}
+
+impl Transcriber for WhisperApiTranscriber {
+    /// The server holds the model: there is nothing to load.
+    fn load_model(&mut self, _path: &Path) -> Result<(), CyranoError> {
+        Ok(())
+    }
+
+    fn transcribe(&self, samples: &[f32]) -> Result<String, CyranoError> {
+        self.transcribe_with_options(samples, None, None, false)
+    }
+
+    fn is_loaded(&self) -> bool {
+        true
+    }
+
+    fn unload(&mut self) -> Result<(), CyranoError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multipart_body_has_fields_and_file() {
+        let body = multipart_body("b", &[("model", "whisper-1")], b"RIFF");
+        let body = String::from_utf8(body).unwrap();
+        assert!(body.starts_with(
+            "--b\r\nContent-Disposition: form-data; name=\"model\"\r\n\r\nwhisper-1\r\n"
+        ));
+        assert!(body.contains("name=\"file\"; filename=\"dictation.wav\""));
+        assert!(body.ends_with("\r\n\r\nRIFF\r\n--b--\r\n"));
+    }
+
+    #[test]
+    fn test_parse_text() {
+        assert_eq!(
+            parse_text(r#"{"text":" Hello world. "}"#).unwrap(),
+            "Hello world."
+        );
+        assert!(matches!(
+            parse_text(r#"{"error":{"message":"bad"}}"#),
+            Err(CyranoError::TranscriptionFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn test_base_url_trailing_slash_is_ignored() {
+        let transcriber =
+            WhisperApiTranscriber::new("https://api.openai.com/v1/", "whisper-1", Some(""));
+        assert_eq!(transcriber.base_url, "https://api.openai.com/v1");
+        assert!(transcriber.api_key.is_none());
+        assert!(transcriber.is_loaded());
+    }
+}
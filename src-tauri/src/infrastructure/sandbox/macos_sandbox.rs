@@ -0,0 +1,88 @@
+//! macOS App Sandbox and hardened runtime probes.
+//!
+//! Provides low-level checks for the restrictions that apply to sandboxed
+//! (Mac App Store) and hardened-runtime builds of the app.
+
+use std::ffi::c_void;
+
+/// `csops` operation returning the code signing status flags of a process.
+const CS_OPS_STATUS: u32 = 0;
+
+/// Code signing flag set when the process runs with the hardened runtime.
+const CS_RUNTIME: u32 = 0x0001_0000;
+
+/// Environment variable set by macOS for processes running inside the App Sandbox.
+const SANDBOX_CONTAINER_ENV: &str = "APP_SANDBOX_CONTAINER_ID";
+
+extern "C" {
+    /// Query code signing information for a process (libSystem).
+    fn csops(pid: i32, ops: u32, useraddr: *mut c_void, usersize: usize) -> i32;
+}
+
+// Link to ApplicationServices framework for event posting APIs
+#[link(name = "ApplicationServices", kind = "framework")]
+extern "C" {
+    /// Check whether the process may post synthetic events (macOS 10.15+).
+    fn CGPreflightPostEventAccess() -> bool;
+}
+
+/// Check if the current process runs inside the App Sandbox.
+///
+/// The sandbox sets `APP_SANDBOX_CONTAINER_ID` for every sandboxed process,
+/// which is cheaper and more reliable than inspecting entitlements.
+pub fn is_sandboxed() -> bool {
+    std::env::var_os(SANDBOX_CONTAINER_ENV).is_some()
+}
+
+/// Check if the current process runs with the hardened runtime enabled.
+///
+/// # Returns
+/// * `true` if the `CS_RUNTIME` code signing flag is set
+/// * `false` if the flag is not set or the status could not be read
+pub fn is_hardened_runtime() -> bool {
+    let mut flags: u32 = 0;
+    // SAFETY: csops writes at most `usersize` bytes into the provided buffer,
+    // which points to a valid, properly aligned u32.
+    let result = unsafe {
+        csops(
+            std::process::id() as i32,
+            CS_OPS_STATUS,
+            &mut flags as *mut u32 as *mut c_void,
+            std::mem::size_of::<u32>(),
+        )
+    };
+
+    if result != 0 {
+        log::debug!("csops failed to read code signing status");
+        return false;
+    }
+
+    flags & CS_RUNTIME != 0
+}
+
+/// Check if the process is allowed to post synthetic keyboard events.
+///
+/// Returns `false` when the sandbox or a missing Accessibility grant would
+/// silently drop events posted via CGEvent.
+pub fn can_post_events() -> bool {
+    // SAFETY: CGPreflightPostEventAccess only reads TCC state and never prompts
+    unsafe { CGPreflightPostEventAccess() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_hardened_runtime_returns_bool() {
+        // Unsigned test binaries never run with the hardened runtime
+        assert!(!is_hardened_runtime());
+    }
+
+    #[test]
+    fn test_can_post_events_returns_bool() {
+        // This test verifies the function executes without panic.
+        let result = can_post_events();
+        assert!(result || !result);
+    }
+}
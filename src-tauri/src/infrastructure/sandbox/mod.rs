@@ -0,0 +1,7 @@
+//! App Sandbox and hardened runtime detection.
+//!
+//! Platform-specific probes for restrictions imposed by the App Sandbox
+//! (Mac App Store builds) and the hardened runtime.
+
+#[cfg(target_os = "macos")]
+pub mod macos_sandbox;
@@ -0,0 +1,228 @@
+//! Speech recognition with Apple's Speech framework (SFSpeechRecognizer).
+//!
+//! The recording is written to a temporary WAV file and recognized with an
+//! `SFSpeechURLRecognitionRequest`. Recognition runs on the device when the
+//! language supports it, so the audio never leaves the Mac; otherwise
+//! Apple's servers recognize it.
+//!
+//! The app must hold the Speech Recognition permission. It is requested on
+//! first use, which shows the system prompt.
+
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use block2::RcBlock;
+use objc2::rc::{Allocated, Retained};
+use objc2::runtime::AnyObject;
+use objc2::{class, msg_send, sel};
+use objc2_foundation::{NSArray, NSString, NSURL};
+
+use crate::domain::CyranoError;
+use crate::infrastructure::audio::wav;
+use crate::traits::transcriber::Transcriber;
+
+#[link(name = "Speech", kind = "framework")]
+extern "C" {}
+
+/// `SFSpeechRecognizerAuthorizationStatus` values.
+const STATUS_NOT_DETERMINED: isize = 0;
+const STATUS_AUTHORIZED: isize = 3;
+
+/// How long the user has to answer the permission prompt.
+const AUTHORIZATION_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Time allowed for recognition on top of the audio duration.
+const RECOGNITION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Sample rate of the audio handed to the recognizer.
+const SAMPLE_RATE: u32 = 16_000;
+
+fn failed(reason: impl Into<String>) -> CyranoError {
+    CyranoError::TranscriptionFailed {
+        reason: reason.into(),
+    }
+}
+
+/// Make sure the app may use speech recognition, prompting the user the
+/// first time.
+fn ensure_authorized() -> Result<(), CyranoError> {
+    // SAFETY: authorizationStatus is a class method without arguments.
+    let status: isize = unsafe { msg_send![class!(SFSpeechRecognizer), authorizationStatus] };
+    let status = if status == STATUS_NOT_DETERMINED {
+        log::info!("Requesting the Speech Recognition permission");
+        let (sender, receiver) = mpsc::channel();
+        let handler = RcBlock::new(move |status: isize| {
+            let _ = sender.send(status);
+        });
+        // SAFETY: the handler takes the authorization status, as declared.
+        unsafe {
+            let _: () = msg_send![class!(SFSpeechRecognizer), requestAuthorization: &*handler];
+        }
+        receiver
+            .recv_timeout(AUTHORIZATION_TIMEOUT)
+            .unwrap_or(STATUS_NOT_DETERMINED)
+    } else {
+        status
+    };
+
+    if status == STATUS_AUTHORIZED {
+        Ok(())
+    } else {
+        Err(failed(
+            "Speech Recognition is not allowed for Cyrano (System Settings > Privacy & Security > Speech Recognition)",
+        ))
+    }
+}
+
+/// Recognize the speech in an audio file.
+///
+/// # Arguments
+/// * `path` - Audio file readable by AVFoundation
+/// * `duration` - Duration of the audio, to bound the wait
+/// * `language` - Language code of the speech, e.g. `fr`; the system
+///   language when None
+/// * `vocabulary` - Names and terms likely to be spoken
+fn recognize_file(
+    path: &Path,
+    duration: Duration,
+    language: Option<&str>,
+    vocabulary: &[String],
+) -> Result<String, CyranoError> {
+    ensure_authorized()?;
+    let url = NSURL::from_file_path(path).ok_or_else(|| failed("Invalid recording path"))?;
+
+    // SAFETY: the messages below match the SFSpeechRecognizer,
+    // SFSpeechURLRecognitionRequest and NSOperationQueue APIs: their
+    // argument and return types are as declared.
+    unsafe {
+        let recognizer: Option<Retained<AnyObject>> = match language {
+            Some(language) => {
+                let locale: Retained<AnyObject> = msg_send![
+                    class!(NSLocale),
+                    localeWithLocaleIdentifier: &*NSString::from_str(language)
+                ];
+                let allocated: Allocated<AnyObject> = msg_send![class!(SFSpeechRecognizer), alloc];
+                msg_send![allocated, initWithLocale: &*locale]
+            }
+            None => msg_send![class!(SFSpeechRecognizer), new],
+        };
+        let recognizer = recognizer.ok_or_else(|| {
+            failed(format!(
+                "Apple Speech does not support the language {}",
+                language.unwrap_or("of the system")
+            ))
+        })?;
+        let available: bool = msg_send![&*recognizer, isAvailable];
+        if !available {
+            return Err(failed("Apple Speech is not available right now"));
+        }
+        // Results are delivered on the main queue by default, which may be
+        // busy or not running; use a queue of our own
+        let queue: Retained<AnyObject> = msg_send![class!(NSOperationQueue), new];
+        let _: () = msg_send![&*recognizer, setQueue: &*queue];
+
+        let allocated: Allocated<AnyObject> =
+            msg_send![class!(SFSpeechURLRecognitionRequest), alloc];
+        let request: Option<Retained<AnyObject>> = msg_send![allocated, initWithURL: &*url];
+        let request = request.ok_or_else(|| failed("Failed to create the recognition request"))?;
+        let _: () = msg_send![&*request, setShouldReportPartialResults: false];
+        let on_device: bool = msg_send![&*recognizer, supportsOnDeviceRecognition];
+        if on_device {
+            let _: () = msg_send![&*request, setRequiresOnDeviceRecognition: true];
+        } else {
+            log::info!(
+                "Apple Speech cannot recognize this language on the device, using Apple's servers"
+            );
+        }
+        // Automatic punctuation, macOS 13 and later
+        let punctuates: bool = msg_send![&*request, respondsToSelector: sel!(setAddsPunctuation:)];
+        if punctuates {
+            let _: () = msg_send![&*request, setAddsPunctuation: true];
+        }
+        if !vocabulary.is_empty() {
+            let terms: Vec<Retained<NSString>> = vocabulary
+                .iter()
+                .map(|term| NSString::from_str(term))
+                .collect();
+            let terms = NSArray::from_retained_slice(&terms);
+            let _: () = msg_send![&*request, setContextualStrings: &*terms];
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        let handler = RcBlock::new(move |result: *mut AnyObject, error: *mut AnyObject| {
+            if !result.is_null() {
+                let is_final: bool = msg_send![result, isFinal];
+                if is_final {
+                    let best: *mut AnyObject = msg_send![result, bestTranscription];
+                    let text: Option<Retained<NSString>> = msg_send![best, formattedString];
+                    let _ = sender.send(Ok(text.map(|text| text.to_string()).unwrap_or_default()));
+                }
+            } else if !error.is_null() {
+                let description: Option<Retained<NSString>> =
+                    msg_send![error, localizedDescription];
+                let _ = sender.send(Err(description
+                    .map(|description| description.to_string())
+                    .unwrap_or_default()));
+            }
+        });
+        let task: Option<Retained<AnyObject>> = msg_send![
+            &*recognizer,
+            recognitionTaskWithRequest: &*request,
+            resultHandler: &*handler
+        ];
+        let task = task.ok_or_else(|| failed("Failed to start speech recognition"))?;
+
+        match receiver.recv_timeout(duration + RECOGNITION_TIMEOUT) {
+            Ok(Ok(text)) => Ok(text),
+            Ok(Err(reason)) => Err(failed(format!("Apple Speech failed: {reason}"))),
+            Err(_) => {
+                let _: () = msg_send![&*task, cancel];
+                Err(failed("Apple Speech did not answer in time"))
+            }
+        }
+    }
+}
+
+/// Transcriber using Apple's Speech framework, which needs no model
+/// download.
+pub struct AppleSpeechTranscriber {
+    language: Option<String>,
+    vocabulary: Vec<String>,
+}
+
+impl AppleSpeechTranscriber {
+    /// Create a transcriber for speech in `language` (e.g. `fr`, the system
+    /// language when None), hinted with the `vocabulary` terms.
+    pub fn new(language: Option<&str>, vocabulary: &[String]) -> Self {
+        Self {
+            language: language.map(str::to_string),
+            vocabulary: vocabulary.to_vec(),
+        }
+    }
+}
+
+impl Transcriber for AppleSpeechTranscriber {
+    /// The system provides the model: there is nothing to load.
+    fn load_model(&mut self, _path: &Path) -> Result<(), CyranoError> {
+        Ok(())
+    }
+
+    fn transcribe(&self, samples: &[f32]) -> Result<String, CyranoError> {
+        let path = std::env::temp_dir().join(format!("cyrano-speech-{}.wav", std::process::id()));
+        std::fs::write(&path, wav::encode_wav(samples, SAMPLE_RATE))
+            .map_err(|e| failed(format!("Failed to write the recording: {e}")))?;
+        let duration = Duration::from_millis(samples.len() as u64 * 1000 / u64::from(SAMPLE_RATE));
+        let result = recognize_file(&path, duration, self.language.as_deref(), &self.vocabulary);
+        let _ = std::fs::remove_file(&path);
+        result
+    }
+
+    fn is_loaded(&self) -> bool {
+        true
+    }
+
+    fn unload(&mut self) -> Result<(), CyranoError> {
+        Ok(())
+    }
+}
@@ -0,0 +1,10 @@
+//! Apple Speech framework integration.
+//!
+//! Provides speech recognition built into macOS, used when no Whisper model
+//! is installed or when selected as the transcription engine.
+
+#[cfg(target_os = "macos")]
+mod macos_speech;
+
+#[cfg(target_os = "macos")]
+pub use macos_speech::AppleSpeechTranscriber;
@@ -0,0 +1,190 @@
+//! Summarizer for servers implementing the OpenAI chat completions API.
+//!
+//! The transcription is sent as the user message of a single
+//! `POST <base url>/chat/completions` request, with a system prompt asking
+//! for a summary only. Local servers such as Ollama (`http://localhost:11434/v1`)
+//! need no API key; remote services get it as a bearer token.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::CyranoError;
+use crate::traits::summarizer::Summarizer;
+
+/// Timeout for a summary request; local models can be slow on long texts.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Instructions sent with every transcription.
+const SYSTEM_PROMPT: &str = "You summarize transcriptions of dictations and meetings. \
+Write a short summary of the main points, decisions and open questions, \
+in the language of the transcription. Reply with the summary only.";
+
+#[derive(Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: [ChatMessage<'a>; 2],
+    stream: bool,
+}
+
+#[derive(Serialize)]
+struct ChatMessage<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatResponseMessage,
+}
+
+#[derive(Deserialize)]
+struct ChatResponseMessage {
+    content: Option<String>,
+}
+
+/// Summarizer talking to an OpenAI-compatible chat completions endpoint.
+pub struct ChatCompletionSummarizer {
+    agent: ureq::Agent,
+    base_url: String,
+    model: String,
+    api_key: Option<String>,
+}
+
+impl ChatCompletionSummarizer {
+    /// Create a summarizer for the server at `base_url`.
+    ///
+    /// # Arguments
+    /// * `base_url` - API base URL, e.g. `http://localhost:11434/v1`
+    /// * `model` - Model name as known to the server
+    /// * `api_key` - Optional bearer token for remote services
+    pub fn new(base_url: &str, model: &str, api_key: Option<&str>) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model: model.to_string(),
+            api_key: api_key.filter(|key| !key.is_empty()).map(str::to_string),
+        }
+    }
+}
+
+fn request_body(model: &str, text: &str) -> String {
+    let request = ChatRequest {
+        model,
+        messages: [
+            ChatMessage {
+                role: "system",
+                content: SYSTEM_PROMPT,
+            },
+            ChatMessage {
+                role: "user",
+                content: text,
+            },
+        ],
+        stream: false,
+    };
+    // Serializing plain strings cannot fail
+    serde_json::to_string(&request).unwrap_or_default()
+}
+
+/// Extract the summary from a chat completions response body.
+fn parse_summary(body: &str) -> Result<String, CyranoError> {
+    let response: ChatResponse =
+        serde_json::from_str(body).map_err(|e| CyranoError::SummaryFailed {
+            reason: format!("Unexpected response from the server: {e}"),
+        })?;
+    response
+        .choices
+        .into_iter()
+        .next()
+        .and_then(|choice| choice.message.content)
+        .map(|content| content.trim().to_string())
+        .filter(|summary| !summary.is_empty())
+        .ok_or_else(|| CyranoError::SummaryFailed {
+            reason: "The server returned an empty summary".to_string(),
+        })
+}
+
+fn transport_error(error: ureq::Error) -> CyranoError {
+    let reason = match error {
+        ureq::Error::Status(401 | 403, _) => "The server rejected the API key".to_string(),
+        ureq::Error::Status(404, _) => "Unknown model or endpoint".to_string(),
+        ureq::Error::Status(code, response) => {
+            format!("Server responded with {code} {}", response.status_text())
+        }
+        ureq::Error::Transport(transport) => format!("Could not reach the server: {transport}"),
+    };
+    CyranoError::SummaryFailed { reason }
+}
+
+impl Summarizer for ChatCompletionSummarizer {
+    fn summarize(&self, text: &str) -> Result<String, CyranoError> {
+        let request = self
+            .agent
+            .post(&format!("{}/chat/completions", self.base_url))
+            .set("Content-Type", "application/json");
+        let request = match &self.api_key {
+            Some(key) => request.set("Authorization", &format!("Bearer {key}")),
+            None => request,
+        };
+
+        let body = request
+            .send_string(&request_body(&self.model, text))
+            .map_err(transport_error)?
+            .into_string()
+            .map_err(|e| CyranoError::SummaryFailed {
+                reason: format!("Failed to read the server response: {e}"),
+            })?;
+        parse_summary(&body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_body_sends_transcription_as_user_message() {
+        let body: serde_json::Value =
+            serde_json::from_str(&request_body("llama3.2", "Hello \"world\"")).unwrap();
+        assert_eq!(body["model"], "llama3.2");
+        assert_eq!(body["stream"], false);
+        assert_eq!(body["messages"][0]["role"], "system");
+        assert_eq!(body["messages"][1]["role"], "user");
+        assert_eq!(body["messages"][1]["content"], "Hello \"world\"");
+    }
+
+    #[test]
+    fn test_parse_summary_takes_first_choice() {
+        let body =
+            r#"{"choices":[{"index":0,"message":{"role":"assistant","content":"  Short.\n"}}]}"#;
+        assert_eq!(parse_summary(body).unwrap(), "Short.");
+    }
+
+    #[test]
+    fn test_parse_summary_rejects_empty_and_malformed_responses() {
+        for body in [
+            r#"{"choices":[]}"#,
+            r#"{"choices":[{"message":{"content":"  "}}]}"#,
+            r#"{"choices":[{"message":{"content":null}}]}"#,
+            "not json",
+        ] {
+            assert!(matches!(
+                parse_summary(body),
+                Err(CyranoError::SummaryFailed { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_base_url_trailing_slash_is_ignored() {
+        let summarizer = ChatCompletionSummarizer::new("http://localhost:11434/v1/", "m", Some(""));
+        assert_eq!(summarizer.base_url, "http://localhost:11434/v1");
+        assert!(summarizer.api_key.is_none());
+    }
+}
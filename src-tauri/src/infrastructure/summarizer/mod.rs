@@ -0,0 +1,9 @@
+//! Summarization infrastructure.
+//!
+//! This module contains the adapter for servers implementing the OpenAI chat
+//! completions API, which covers local LLM servers (Ollama, llama.cpp,
+//! LM Studio) as well as remote services.
+
+mod chat_completion;
+
+pub use chat_completion::ChatCompletionSummarizer;
@@ -0,0 +1,144 @@
+//! End-to-end encryption envelope for synced data.
+//!
+//! Data is encrypted on the device with XChaCha20-Poly1305 using a key derived
+//! from the user's passphrase with Argon2id, so the sync server only ever sees
+//! ciphertext. Each envelope carries its own random salt and nonce:
+//!
+//! ```text
+//! "CYRSYNC1" | salt (16 bytes) | nonce (24 bytes) | ciphertext + tag
+//! ```
+//!
+//! The header is authenticated as associated data, so tampering with any
+//! part of the envelope makes decryption fail.
+
+use argon2::Argon2;
+use chacha20poly1305::aead::{Aead, AeadCore, OsRng, Payload};
+use chacha20poly1305::{KeyInit, XChaCha20Poly1305, XNonce};
+
+use crate::domain::CyranoError;
+
+/// Format marker and version at the start of every envelope.
+const MAGIC: &[u8; 8] = b"CYRSYNC1";
+
+/// Length of the Argon2 salt.
+const SALT_LEN: usize = 16;
+
+/// Length of the XChaCha20 nonce.
+const NONCE_LEN: usize = 24;
+
+/// Length of everything before the ciphertext.
+const HEADER_LEN: usize = MAGIC.len() + SALT_LEN + NONCE_LEN;
+
+fn derive_cipher(passphrase: &str, salt: &[u8]) -> Result<XChaCha20Poly1305, CyranoError> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| CyranoError::SyncFailed {
+            reason: format!("Failed to derive encryption key: {e}"),
+        })?;
+    Ok(XChaCha20Poly1305::new(&key.into()))
+}
+
+/// Encrypt `plaintext` with a key derived from `passphrase`.
+pub fn seal(passphrase: &str, plaintext: &[u8]) -> Result<Vec<u8>, CyranoError> {
+    let salt: [u8; SALT_LEN] = rand_bytes();
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+    let mut envelope = Vec::with_capacity(HEADER_LEN + plaintext.len() + 16);
+    envelope.extend_from_slice(MAGIC);
+    envelope.extend_from_slice(&salt);
+    envelope.extend_from_slice(&nonce);
+
+    let ciphertext = derive_cipher(passphrase, &salt)?
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: plaintext,
+                aad: &envelope,
+            },
+        )
+        .map_err(|_| CyranoError::SyncFailed {
+            reason: "Failed to encrypt sync data".to_string(),
+        })?;
+    envelope.extend_from_slice(&ciphertext);
+    Ok(envelope)
+}
+
+/// Decrypt an envelope produced by `seal`.
+///
+/// # Returns
+/// * `Ok(Vec<u8>)` with the plaintext
+/// * `Err(CyranoError::SyncFailed)` if the passphrase is wrong or the data
+///   is not a valid envelope
+pub fn open(passphrase: &str, envelope: &[u8]) -> Result<Vec<u8>, CyranoError> {
+    if envelope.len() < HEADER_LEN || !envelope.starts_with(MAGIC) {
+        return Err(CyranoError::SyncFailed {
+            reason: "Remote data is not a Cyrano sync file".to_string(),
+        });
+    }
+
+    let (header, ciphertext) = envelope.split_at(HEADER_LEN);
+    let salt = &header[MAGIC.len()..MAGIC.len() + SALT_LEN];
+    let nonce = XNonce::from_slice(&header[MAGIC.len() + SALT_LEN..]);
+
+    derive_cipher(passphrase, salt)?
+        .decrypt(
+            nonce,
+            Payload {
+                msg: ciphertext,
+                aad: header,
+            },
+        )
+        .map_err(|_| CyranoError::SyncFailed {
+            reason: "Wrong passphrase or corrupted sync data".to_string(),
+        })
+}
+
+fn rand_bytes<const N: usize>() -> [u8; N] {
+    use chacha20poly1305::aead::rand_core::RngCore;
+
+    let mut bytes = [0u8; N];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_open_round_trip() {
+        let envelope = seal("correct horse", b"hello").unwrap();
+        assert!(envelope.starts_with(MAGIC));
+        assert_eq!(open("correct horse", &envelope).unwrap(), b"hello");
+    }
+
+    #[test]
+    fn test_envelopes_use_fresh_salt_and_nonce() {
+        let first = seal("passphrase", b"same").unwrap();
+        let second = seal("passphrase", b"same").unwrap();
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_wrong_passphrase_is_rejected() {
+        let envelope = seal("right passphrase", b"secret").unwrap();
+        assert!(matches!(
+            open("wrong passphrase", &envelope),
+            Err(CyranoError::SyncFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn test_tampered_header_is_rejected() {
+        let mut envelope = seal("passphrase", b"secret").unwrap();
+        envelope[MAGIC.len()] ^= 1;
+        assert!(open("passphrase", &envelope).is_err());
+    }
+
+    #[test]
+    fn test_foreign_data_is_rejected() {
+        assert!(open("passphrase", b"<html>not found</html>").is_err());
+        assert!(open("passphrase", b"").is_err());
+    }
+}
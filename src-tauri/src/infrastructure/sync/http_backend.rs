@@ -0,0 +1,115 @@
+//! HTTP sync backend for WebDAV servers and minimal relays.
+//!
+//! Objects are stored as `<base url>/<name>` with plain GET and PUT requests.
+//! Conditional writes use `If-Match` / `If-None-Match: *`, which WebDAV
+//! servers (Nextcloud, Apache mod_dav, ...) support out of the box; a relay
+//! only needs to implement the same two verbs.
+
+use std::io::Read;
+use std::time::Duration;
+
+use base64::Engine;
+
+use crate::domain::CyranoError;
+use crate::traits::sync_backend::{RemoteObject, StoreOutcome, SyncBackend, WriteCondition};
+
+/// Timeout for a single request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Largest object we accept from the server.
+const MAX_OBJECT_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Sync backend talking to a WebDAV collection or relay over HTTP(S).
+pub struct HttpSyncBackend {
+    agent: ureq::Agent,
+    base_url: String,
+    authorization: Option<String>,
+}
+
+impl HttpSyncBackend {
+    /// Create a backend for the collection at `base_url`.
+    ///
+    /// # Arguments
+    /// * `base_url` - URL of an existing WebDAV collection or relay endpoint
+    /// * `username` / `password` - Optional HTTP basic auth credentials
+    pub fn new(base_url: &str, username: Option<&str>, password: Option<&str>) -> Self {
+        let authorization = username.map(|user| {
+            let credentials = format!("{user}:{}", password.unwrap_or_default());
+            format!(
+                "Basic {}",
+                base64::engine::general_purpose::STANDARD.encode(credentials)
+            )
+        });
+
+        Self {
+            agent: ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            authorization,
+        }
+    }
+
+    fn request(&self, method: &str, name: &str) -> ureq::Request {
+        let request = self
+            .agent
+            .request(method, &format!("{}/{name}", self.base_url));
+        match &self.authorization {
+            Some(value) => request.set("Authorization", value),
+            None => request,
+        }
+    }
+}
+
+fn transport_error(error: ureq::Error) -> CyranoError {
+    let reason = match error {
+        ureq::Error::Status(401 | 403, _) => "The server rejected the credentials".to_string(),
+        ureq::Error::Status(code, response) => {
+            format!("Server responded with {code} {}", response.status_text())
+        }
+        ureq::Error::Transport(transport) => format!("Could not reach the server: {transport}"),
+    };
+    CyranoError::SyncFailed { reason }
+}
+
+impl SyncBackend for HttpSyncBackend {
+    fn fetch(&self, name: &str) -> Result<Option<RemoteObject>, CyranoError> {
+        let response = match self.request("GET", name).call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(404, _)) => return Ok(None),
+            Err(e) => return Err(transport_error(e)),
+        };
+
+        let etag = response.header("ETag").map(str::to_string);
+        let mut bytes = Vec::new();
+        response
+            .into_reader()
+            .take(MAX_OBJECT_BYTES)
+            .read_to_end(&mut bytes)
+            .map_err(|e| CyranoError::SyncFailed {
+                reason: format!("Failed to download {name}: {e}"),
+            })?;
+
+        Ok(Some(RemoteObject { bytes, etag }))
+    }
+
+    fn store(
+        &self,
+        name: &str,
+        bytes: &[u8],
+        condition: WriteCondition<'_>,
+    ) -> Result<StoreOutcome, CyranoError> {
+        let request = self
+            .request("PUT", name)
+            .set("Content-Type", "application/octet-stream");
+        let request = match condition {
+            WriteCondition::Absent => request.set("If-None-Match", "*"),
+            WriteCondition::Matches(etag) => request.set("If-Match", etag),
+            WriteCondition::Unconditional => request,
+        };
+
+        match request.send_bytes(bytes) {
+            Ok(_) => Ok(StoreOutcome::Stored),
+            Err(ureq::Error::Status(412, _)) => Ok(StoreOutcome::Conflict),
+            Err(e) => Err(transport_error(e)),
+        }
+    }
+}
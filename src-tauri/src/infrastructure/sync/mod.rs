@@ -0,0 +1,9 @@
+//! Encrypted sync infrastructure.
+//!
+//! This module contains the end-to-end encryption envelope and the HTTP
+//! backend (WebDAV or a minimal relay) used by the encrypted sync service.
+
+pub mod envelope;
+mod http_backend;
+
+pub use http_backend::HttpSyncBackend;
@@ -0,0 +1,86 @@
+//! macOS Services menu provider using NSApplication.
+//!
+//! The service is declared under `NSServices` in `Info.plist`. When the user
+//! picks it in Finder (context menu > Quick Actions / Services), macOS calls
+//! `transcribeAudioFiles:userData:error:` (the `NSMessage` value) on the
+//! registered provider with a pasteboard holding the selected files.
+
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use objc2::rc::Retained;
+use objc2::runtime::{AnyObject, NSObject};
+use objc2::{define_class, msg_send, MainThreadMarker, MainThreadOnly};
+use objc2_app_kit::{NSApplication, NSPasteboard, NSUpdateDynamicServices};
+use objc2_foundation::NSString;
+
+use crate::infrastructure::pasteboard::macos_pasteboard::file_paths;
+
+/// Callback receiving the files sent to the service.
+type FilesHandler = Box<dyn Fn(Vec<PathBuf>) + Send + Sync>;
+
+static HANDLER: OnceLock<FilesHandler> = OnceLock::new();
+
+define_class!(
+    // SAFETY:
+    // - NSObject has no subclassing requirements.
+    // - ServicesProvider does not implement Drop.
+    #[unsafe(super(NSObject))]
+    #[thread_kind = MainThreadOnly]
+    #[name = "CyranoServicesProvider"]
+    struct ServicesProvider;
+
+    impl ServicesProvider {
+        #[unsafe(method(transcribeAudioFiles:userData:error:))]
+        fn transcribe_audio_files(
+            &self,
+            pasteboard: &NSPasteboard,
+            _user_data: *mut NSString,
+            _error: *mut *mut NSString,
+        ) {
+            let paths = file_paths(pasteboard);
+            log::info!("Services menu sent {} file(s)", paths.len());
+            if let Some(handler) = HANDLER.get() {
+                handler(paths);
+            }
+        }
+    }
+);
+
+impl ServicesProvider {
+    fn new(mtm: MainThreadMarker) -> Retained<Self> {
+        let this = Self::alloc(mtm).set_ivars(());
+        // SAFETY: NSObject's init has no preconditions.
+        unsafe { msg_send![super(this), init] }
+    }
+}
+
+/// Register the app as provider for the file service declared in `Info.plist`.
+///
+/// MUST be called on the main thread (e.g. from the Tauri setup hook).
+///
+/// # Arguments
+/// * `mtm` - Proof that the caller runs on the main thread
+/// * `handler` - Called with the selected files each time the service is used
+pub fn register_file_service(
+    mtm: MainThreadMarker,
+    handler: impl Fn(Vec<PathBuf>) + Send + Sync + 'static,
+) {
+    if HANDLER.set(Box::new(handler)).is_err() {
+        log::warn!("Services provider already registered");
+        return;
+    }
+
+    let provider = ServicesProvider::new(mtm);
+    let object: &AnyObject = &provider;
+    let app = NSApplication::sharedApplication(mtm);
+    // SAFETY: called on the main thread with a valid provider object.
+    unsafe {
+        app.setServicesProvider(Some(object));
+        NSUpdateDynamicServices();
+    }
+    // NSApplication does not retain its services provider; keep it for the
+    // lifetime of the app.
+    std::mem::forget(provider);
+    log::info!("Registered Services menu provider");
+}
@@ -0,0 +1,10 @@
+//! System Services menu integration.
+//!
+//! Platform-specific providers for services other apps offer on selected
+//! items, such as Finder's "Transcribe Audio File with Cyrano".
+
+#[cfg(target_os = "macos")]
+pub mod macos_services;
+
+#[cfg(target_os = "macos")]
+pub use macos_services::register_file_service;
@@ -0,0 +1,75 @@
+//! Hardware acceleration of whisper.cpp.
+//!
+//! Whisper is built with Metal, so models run on the GPU when the machine
+//! has a Metal device, which is several times faster than the CPU on Apple
+//! Silicon. Builds with the `coreml` feature also run the encoder on the
+//! Neural Engine, when the model's Core ML encoder (generated with
+//! whisper.cpp's `models/generate-coreml-model.sh`) sits next to it.
+
+use std::path::{Path, PathBuf};
+
+/// Whether this build runs Whisper encoders through Core ML.
+pub const COREML_ENABLED: bool = cfg!(feature = "coreml");
+
+/// Check whether the machine has a Metal device Whisper can run on.
+#[cfg(target_os = "macos")]
+pub fn metal_device_available() -> bool {
+    use objc2::rc::Retained;
+    use objc2::runtime::AnyObject;
+
+    #[link(name = "Metal", kind = "framework")]
+    extern "C" {
+        fn MTLCreateSystemDefaultDevice() -> *mut AnyObject;
+    }
+
+    // SAFETY: MTLCreateSystemDefaultDevice has no preconditions and returns
+    // a +1 retained device, or null when there is none.
+    let device = unsafe { Retained::from_raw(MTLCreateSystemDefaultDevice()) };
+    device.is_some()
+}
+
+/// Metal is only available on macOS.
+#[cfg(not(target_os = "macos"))]
+pub fn metal_device_available() -> bool {
+    false
+}
+
+/// Path whisper.cpp loads the Core ML encoder of a model from:
+/// `ggml-base.en.bin` and `ggml-base.en-q5_1.bin` both use
+/// `ggml-base.en-encoder.mlmodelc`.
+pub fn coreml_encoder_path(model_path: &Path) -> PathBuf {
+    let mut stem = model_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    // Quantized models share the encoder of their f16 model
+    if let Some(index) = stem.rfind('-') {
+        let suffix = &stem.as_bytes()[index..];
+        if suffix.len() == 5 && suffix[1] == b'q' && suffix[3] == b'_' {
+            stem.truncate(index);
+        }
+    }
+    model_path.with_file_name(format!("{stem}-encoder.mlmodelc"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coreml_encoder_path() {
+        let dir = Path::new("/models");
+        assert_eq!(
+            coreml_encoder_path(&dir.join("ggml-base.en.bin")),
+            dir.join("ggml-base.en-encoder.mlmodelc")
+        );
+        assert_eq!(
+            coreml_encoder_path(&dir.join("ggml-large-v3-turbo-q5_0.bin")),
+            dir.join("ggml-large-v3-turbo-encoder.mlmodelc")
+        );
+        assert_eq!(
+            coreml_encoder_path(&dir.join("ggml-large-v3.bin")),
+            dir.join("ggml-large-v3-encoder.mlmodelc")
+        );
+    }
+}
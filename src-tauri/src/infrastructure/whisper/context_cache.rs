@@ -0,0 +1,118 @@
+//! Loaded Whisper contexts kept across model switches.
+//!
+//! Loading a model takes seconds, so switching back and forth between two
+//! models (e.g. tiny for quick notes, small for accuracy) reloads each time.
+//! With a memory budget, the contexts of recently used models are kept, and
+//! the least recently used are evicted once the budget is exceeded. A budget
+//! of 0 keeps nothing: only the model in use stays loaded.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// A cached value with the memory it holds.
+struct Cached<T> {
+    path: PathBuf,
+    value: Arc<T>,
+    size_bytes: u64,
+}
+
+/// Values keyed by model path, least recently used first.
+pub struct ContextCache<T> {
+    budget_bytes: u64,
+    entries: Vec<Cached<T>>,
+}
+
+impl<T> ContextCache<T> {
+    /// An empty cache with a budget of 0.
+    pub const fn new() -> Self {
+        Self {
+            budget_bytes: 0,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Set the memory budget, evicting what no longer fits.
+    pub fn set_budget(&mut self, budget_bytes: u64) {
+        self.budget_bytes = budget_bytes;
+        self.evict();
+    }
+
+    /// The value cached for `path`, marked as the most recently used.
+    pub fn get(&mut self, path: &Path) -> Option<Arc<T>> {
+        let index = self.entries.iter().position(|entry| entry.path == path)?;
+        let entry = self.entries.remove(index);
+        let value = Arc::clone(&entry.value);
+        self.entries.push(entry);
+        Some(value)
+    }
+
+    /// Cache `value` for `path` as the most recently used, evicting the least
+    /// recently used values over budget. A value larger than the budget is
+    /// not cached.
+    pub fn insert(&mut self, path: &Path, value: Arc<T>, size_bytes: u64) {
+        self.entries.retain(|entry| entry.path != path);
+        if size_bytes > self.budget_bytes {
+            return;
+        }
+        self.entries.push(Cached {
+            path: path.to_path_buf(),
+            value,
+            size_bytes,
+        });
+        self.evict();
+    }
+
+    /// Drop every cached value.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn evict(&mut self) {
+        while self.used_bytes() > self.budget_bytes {
+            let evicted = self.entries.remove(0);
+            log::info!("Evicting cached Whisper model {}", evicted.path.display());
+        }
+    }
+
+    fn used_bytes(&self) -> u64 {
+        self.entries.iter().map(|entry| entry.size_bytes).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(budget_bytes: u64) -> ContextCache<&'static str> {
+        let mut cache = ContextCache::new();
+        cache.set_budget(budget_bytes);
+        cache
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used_over_budget() {
+        let mut cache = cache(500);
+        cache.insert(Path::new("tiny"), Arc::new("tiny"), 100);
+        cache.insert(Path::new("small"), Arc::new("small"), 300);
+
+        // Using tiny makes small the least recently used
+        assert_eq!(cache.get(Path::new("tiny")).as_deref(), Some(&"tiny"));
+        cache.insert(Path::new("base"), Arc::new("base"), 200);
+
+        assert!(cache.get(Path::new("small")).is_none());
+        assert!(cache.get(Path::new("tiny")).is_some());
+        assert!(cache.get(Path::new("base")).is_some());
+    }
+
+    #[test]
+    fn test_zero_budget_caches_nothing() {
+        let mut cache = cache(0);
+        cache.insert(Path::new("tiny"), Arc::new("tiny"), 100);
+        assert!(cache.get(Path::new("tiny")).is_none());
+
+        let mut cache = self::cache(500);
+        cache.insert(Path::new("tiny"), Arc::new("tiny"), 100);
+        cache.set_budget(0);
+        assert!(cache.get(Path::new("tiny")).is_none());
+    }
+}
@@ -0,0 +1,224 @@
+//! Whisper decoding parameters.
+//!
+//! Greedy decoding is fast; beam search explores several hypotheses and is
+//! more accurate on hard audio at a multiple of the decoding time. When a
+//! decoded segment looks unreliable (its entropy is too low, typically a
+//! repetition loop), Whisper decodes it again at a higher temperature.
+//!
+//! Segments Whisper finds likely to be silence and decodes with little
+//! confidence are dropped; a transcription whose average confidence stays
+//! below the threshold is garbage, usually from a recording triggered by
+//! accident.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use whisper_rs::{FullParams, SamplingStrategy};
+
+/// Decoding strategy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum DecodeStrategy {
+    /// Pick the most likely token at each step.
+    #[default]
+    Greedy,
+    /// Keep the `beam_size` most likely hypotheses.
+    BeamSearch,
+}
+
+/// Parameters of Whisper's decoder. Missing keys fall back to their defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct DecodeOptions {
+    pub strategy: DecodeStrategy,
+    /// Candidates sampled per segment when decoding greedily above zero
+    /// temperature
+    pub best_of: u32,
+    /// Hypotheses kept by beam search
+    pub beam_size: u32,
+    /// Initial sampling temperature; 0 is deterministic
+    pub temperature: f32,
+    /// Temperature added on each fallback; 0 disables the fallback
+    pub temperature_increment: f32,
+    /// Entropy below which a segment is decoded again at a higher temperature
+    pub entropy_threshold: f32,
+    /// Probability of no speech above which a low-confidence segment is
+    /// treated as silence
+    pub no_speech_threshold: f32,
+    /// Average token log probability below which decoding is unreliable
+    pub logprob_threshold: f32,
+    /// Keep Whisper from starting a segment with a blank; turning it off
+    /// helps microphones whose noise floor makes Whisper invent text
+    pub suppress_blank: bool,
+    /// Time each word, e.g. for subtitles; slows decoding slightly
+    pub word_timestamps: bool,
+    /// Translate the speech to English instead of transcribing it
+    pub translate: bool,
+}
+
+impl Default for DecodeOptions {
+    fn default() -> Self {
+        Self {
+            strategy: DecodeStrategy::Greedy,
+            best_of: 1,
+            beam_size: 5,
+            temperature: 0.0,
+            temperature_increment: 0.2,
+            entropy_threshold: 2.4,
+            no_speech_threshold: 0.6,
+            logprob_threshold: -1.0,
+            suppress_blank: true,
+            word_timestamps: false,
+            translate: false,
+        }
+    }
+}
+
+impl DecodeOptions {
+    /// The options with every value brought into its supported range.
+    pub fn sanitized(self) -> Self {
+        let defaults = Self::default();
+        let finite_or = |value: f32, default: f32| if value.is_finite() { value } else { default };
+        Self {
+            strategy: self.strategy,
+            best_of: self.best_of.clamp(1, 10),
+            beam_size: self.beam_size.clamp(1, 16),
+            temperature: finite_or(self.temperature, defaults.temperature).clamp(0.0, 1.0),
+            temperature_increment: finite_or(
+                self.temperature_increment,
+                defaults.temperature_increment,
+            )
+            .clamp(0.0, 1.0),
+            entropy_threshold: finite_or(self.entropy_threshold, defaults.entropy_threshold)
+                .clamp(0.0, 10.0),
+            no_speech_threshold: finite_or(self.no_speech_threshold, defaults.no_speech_threshold)
+                .clamp(0.0, 1.0),
+            logprob_threshold: finite_or(self.logprob_threshold, defaults.logprob_threshold)
+                .clamp(-10.0, 0.0),
+            suppress_blank: self.suppress_blank,
+            word_timestamps: self.word_timestamps,
+            translate: self.translate,
+        }
+    }
+
+    /// The options with the cheapest decoding: greedy with a single
+    /// candidate, no temperature fallback and no word timing.
+    pub fn lightweight(self) -> Self {
+        Self {
+            strategy: DecodeStrategy::Greedy,
+            best_of: 1,
+            temperature: 0.0,
+            temperature_increment: 0.0,
+            word_timestamps: false,
+            ..self
+        }
+    }
+
+    /// Whisper parameters for these options.
+    pub(super) fn full_params<'a, 'b>(&self) -> FullParams<'a, 'b> {
+        let options = self.sanitized();
+        let strategy = match options.strategy {
+            DecodeStrategy::Greedy => SamplingStrategy::Greedy {
+                best_of: options.best_of as i32,
+            },
+            DecodeStrategy::BeamSearch => SamplingStrategy::BeamSearch {
+                beam_size: options.beam_size as i32,
+                // Whisper's default: no patience-based early stop
+                patience: -1.0,
+            },
+        };
+        let mut params = FullParams::new(strategy);
+        params.set_temperature(options.temperature);
+        params.set_temperature_inc(options.temperature_increment);
+        params.set_entropy_thold(options.entropy_threshold);
+        params.set_no_speech_thold(options.no_speech_threshold);
+        params.set_logprob_thold(options.logprob_threshold);
+        params.set_suppress_blank(options.suppress_blank);
+        params.set_token_timestamps(options.word_timestamps);
+        params.set_translate(options.translate);
+        params
+    }
+
+    /// Check whether a transcription decoded with an average token log
+    /// probability of `avg_logprob` is too unreliable to use. A
+    /// transcription without text tokens has nothing to judge.
+    pub fn is_low_confidence(&self, avg_logprob: Option<f32>) -> bool {
+        avg_logprob.is_some_and(|logprob| logprob < self.sanitized().logprob_threshold)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitized_clamps_out_of_range_values() {
+        let options = DecodeOptions {
+            strategy: DecodeStrategy::BeamSearch,
+            best_of: 0,
+            beam_size: 100,
+            temperature: -1.0,
+            temperature_increment: f32::NAN,
+            entropy_threshold: 50.0,
+            no_speech_threshold: 2.0,
+            logprob_threshold: f32::NEG_INFINITY,
+            suppress_blank: false,
+            word_timestamps: true,
+            translate: true,
+        }
+        .sanitized();
+
+        assert_eq!(options.strategy, DecodeStrategy::BeamSearch);
+        assert_eq!((options.best_of, options.beam_size), (1, 16));
+        assert_eq!(options.temperature, 0.0);
+        assert_eq!(options.temperature_increment, 0.2);
+        assert_eq!(options.entropy_threshold, 10.0);
+        assert_eq!(options.no_speech_threshold, 1.0);
+        assert_eq!(options.logprob_threshold, -1.0);
+        assert!(!options.suppress_blank);
+        assert!(options.word_timestamps);
+        assert!(options.translate);
+    }
+
+    #[test]
+    fn test_defaults_are_already_sanitized() {
+        assert_eq!(
+            DecodeOptions::default().sanitized(),
+            DecodeOptions::default()
+        );
+    }
+
+    #[test]
+    fn test_low_confidence_below_logprob_threshold() {
+        let options = DecodeOptions::default();
+        assert!(options.is_low_confidence(Some(-1.6)));
+        assert!(!options.is_low_confidence(Some(-0.3)));
+        assert!(!options.is_low_confidence(None));
+    }
+
+    #[test]
+    fn test_lightweight_keeps_thresholds_and_translation() {
+        let options = DecodeOptions {
+            strategy: DecodeStrategy::BeamSearch,
+            best_of: 5,
+            word_timestamps: true,
+            translate: true,
+            logprob_threshold: -0.5,
+            ..DecodeOptions::default()
+        }
+        .lightweight();
+        assert_eq!(options.strategy, DecodeStrategy::Greedy);
+        assert_eq!((options.best_of, options.temperature_increment), (1, 0.0));
+        assert!(!options.word_timestamps);
+        assert!(options.translate);
+        assert_eq!(options.logprob_threshold, -0.5);
+    }
+
+    #[test]
+    fn test_missing_keys_use_defaults() {
+        let options: DecodeOptions =
+            serde_json::from_str(r#"{"strategy":"beam_search","beam_size":8}"#).unwrap();
+        assert_eq!(options.strategy, DecodeStrategy::BeamSearch);
+        assert_eq!(options.beam_size, 8);
+        assert_eq!(options.best_of, 1);
+    }
+}
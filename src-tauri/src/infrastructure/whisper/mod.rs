@@ -1,7 +1,16 @@
 //! Whisper speech-to-text integration.
 //!
-//! This module provides the whisper-rs adapter for transcription.
+//! This module provides the whisper-rs adapter for transcription, the cache
+//! of loaded models, the reader for model file headers, hardware
+//! acceleration checks and the bridge from whisper.cpp's logs to the app
+//! logger.
 
+pub mod acceleration;
+mod context_cache;
+pub mod decode_options;
+pub mod model_header;
 mod whisper_adapter;
+pub mod whisper_log;
 
-pub use whisper_adapter::WhisperAdapter;
+pub use decode_options::{DecodeOptions, DecodeStrategy};
+pub use whisper_adapter::{Segment, SegmentHandler, Transcription, WhisperAdapter};
@@ -0,0 +1,266 @@
+//! Metadata read from the header of a ggml Whisper model.
+//!
+//! A whisper.cpp model file starts with the `ggml` magic followed by the
+//! model's hyperparameters, enough to tell which Whisper variant it is and
+//! how its weights are stored without loading it.
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+use serde::Serialize;
+use specta::Type;
+
+/// `ggml` read as a little-endian u32.
+const GGML_MAGIC: u32 = 0x6767_6d6c;
+
+/// Magic and the 11 i32 hyperparameters.
+const HEADER_LEN: usize = 4 + 11 * 4;
+
+/// whisper.cpp stores the quantization format version in the ftype's
+/// thousands.
+const QNT_VERSION_FACTOR: i32 = 1000;
+
+/// Vocabulary size from which a model is multilingual.
+const MULTILINGUAL_VOCAB: i32 = 51_865;
+
+const MIB: u64 = 1024 * 1024;
+
+/// What a model file contains, for telling model files apart.
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct ModelMetadata {
+    /// Whisper variant, e.g. `base.en` or `large-v3`
+    pub variant: String,
+    /// Whether the model transcribes languages other than English
+    pub multilingual: bool,
+    /// Approximate number of parameters
+    pub parameters: u64,
+    /// Weight format, e.g. `f16` or `q5_1`
+    pub quantization: String,
+    /// Approximate memory used while transcribing, in bytes
+    pub required_ram_bytes: u64,
+}
+
+/// Hyperparameters stored in the model header, in file order, without the
+/// attention head counts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Hparams {
+    n_vocab: i32,
+    n_audio_ctx: i32,
+    n_audio_state: i32,
+    n_audio_layer: i32,
+    n_text_ctx: i32,
+    n_text_state: i32,
+    n_text_layer: i32,
+    n_mels: i32,
+    ftype: i32,
+}
+
+/// Read the metadata of the model at `path`.
+///
+/// Fails with `InvalidData` if the file is not a ggml Whisper model.
+pub fn read_metadata(path: &Path) -> io::Result<ModelMetadata> {
+    let mut file = File::open(path)?;
+    let file_size = file.metadata()?.len();
+    let mut header = [0u8; HEADER_LEN];
+    file.read_exact(&mut header)?;
+    parse_header(&header)
+        .map(|hparams| hparams.metadata(file_size))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "not a ggml Whisper model"))
+}
+
+fn parse_header(header: &[u8; HEADER_LEN]) -> Option<Hparams> {
+    let mut words = header
+        .chunks_exact(4)
+        .map(|word| [word[0], word[1], word[2], word[3]]);
+    if u32::from_le_bytes(words.next()?) != GGML_MAGIC {
+        return None;
+    }
+
+    let mut next = || words.next().map(i32::from_le_bytes);
+    let n_vocab = next()?;
+    let n_audio_ctx = next()?;
+    let n_audio_state = next()?;
+    let _n_audio_head = next()?;
+    let n_audio_layer = next()?;
+    let n_text_ctx = next()?;
+    let n_text_state = next()?;
+    let _n_text_head = next()?;
+    let hparams = Hparams {
+        n_vocab,
+        n_audio_ctx,
+        n_audio_state,
+        n_audio_layer,
+        n_text_ctx,
+        n_text_state,
+        n_text_layer: next()?,
+        n_mels: next()?,
+        ftype: next()?,
+    };
+    let sizes = [
+        hparams.n_vocab,
+        hparams.n_audio_ctx,
+        hparams.n_audio_state,
+        hparams.n_audio_layer,
+        hparams.n_text_ctx,
+        hparams.n_text_state,
+        hparams.n_text_layer,
+        hparams.n_mels,
+    ];
+    sizes.iter().all(|&size| size > 0).then_some(hparams)
+}
+
+impl Hparams {
+    fn metadata(&self, file_size: u64) -> ModelMetadata {
+        let multilingual = self.n_vocab >= MULTILINGUAL_VOCAB;
+        let size = self.size();
+        let variant = if multilingual {
+            size.to_string()
+        } else {
+            format!("{size}.en")
+        };
+        ModelMetadata {
+            variant,
+            multilingual,
+            parameters: self.parameters(),
+            quantization: quantization(self.ftype % QNT_VERSION_FACTOR).to_string(),
+            required_ram_bytes: estimated_ram_bytes(size, file_size),
+        }
+    }
+
+    /// Model size, from the number of encoder layers like whisper.cpp.
+    fn size(&self) -> &'static str {
+        match self.n_audio_layer {
+            4 => "tiny",
+            6 => "base",
+            12 => "small",
+            24 => "medium",
+            32 if self.n_text_layer == 4 => "large-v3-turbo",
+            32 if self.n_mels == 128 => "large-v3",
+            32 => "large",
+            _ => "unknown",
+        }
+    }
+
+    /// Parameters of the encoder and decoder weights, counted from their
+    /// shapes (biases and layer norms included).
+    fn parameters(&self) -> u64 {
+        let audio = self.n_audio_state as u64;
+        let text = self.n_text_state as u64;
+        // Attention: 4 projections, bias on all but the key
+        let attention = |d: u64| 4 * d * d + 3 * d;
+        let mlp = |d: u64| 8 * d * d + 5 * d;
+        let layer_norm = |d: u64| 2 * d;
+
+        let encoder = self.n_mels as u64 * audio * 3
+            + audio
+            + audio * audio * 3
+            + audio
+            + self.n_audio_ctx as u64 * audio
+            + self.n_audio_layer as u64 * (attention(audio) + mlp(audio) + 2 * layer_norm(audio))
+            + layer_norm(audio);
+        let decoder = (self.n_vocab as u64 + self.n_text_ctx as u64) * text
+            + self.n_text_layer as u64 * (2 * attention(text) + mlp(text) + 3 * layer_norm(text))
+            + layer_norm(text);
+        encoder + decoder
+    }
+}
+
+/// Name of a ggml file type.
+fn quantization(ftype: i32) -> &'static str {
+    match ftype {
+        0 => "f32",
+        1 => "f16",
+        2 => "q4_0",
+        3 => "q4_1",
+        7 => "q8_0",
+        8 => "q5_0",
+        9 => "q5_1",
+        10 => "q2_k",
+        11 => "q3_k",
+        12 => "q4_k",
+        13 => "q5_k",
+        14 => "q6_k",
+        _ => "unknown",
+    }
+}
+
+/// Memory used while transcribing with a model of `size` (e.g. `base` or
+/// `large-v3`, without `.en`) stored in `file_size` bytes: the weights plus
+/// the KV cache and compute buffers, after the figures published by
+/// whisper.cpp.
+pub fn estimated_ram_bytes(size: &str, file_size: u64) -> u64 {
+    file_size + runtime_overhead(size, file_size)
+}
+
+fn runtime_overhead(size: &str, file_size: u64) -> u64 {
+    match size {
+        "tiny" => 200 * MIB,
+        "base" => 250 * MIB,
+        "small" => 400 * MIB,
+        "medium" => 600 * MIB,
+        "large-v3-turbo" => 700 * MIB,
+        "large" | "large-v3" => 1000 * MIB,
+        _ => file_size / 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(hparams: [i32; 11]) -> [u8; HEADER_LEN] {
+        let mut header = [0u8; HEADER_LEN];
+        header[..4].copy_from_slice(&GGML_MAGIC.to_le_bytes());
+        for (i, value) in hparams.iter().enumerate() {
+            header[4 + i * 4..8 + i * 4].copy_from_slice(&value.to_le_bytes());
+        }
+        header
+    }
+
+    #[test]
+    fn test_tiny_f16() {
+        let hparams =
+            parse_header(&header([51_865, 1500, 384, 6, 4, 448, 384, 6, 4, 80, 1])).unwrap();
+        let metadata = hparams.metadata(75 * MIB);
+
+        assert_eq!(metadata.variant, "tiny");
+        assert!(metadata.multilingual);
+        assert_eq!(metadata.quantization, "f16");
+        // Published as 39M
+        assert!((37_000_000..40_000_000).contains(&metadata.parameters));
+        assert_eq!(metadata.required_ram_bytes, 275 * MIB);
+    }
+
+    #[test]
+    fn test_english_only_quantized() {
+        // ftype 2009: q5_1 with quantization version 2
+        let hparams =
+            parse_header(&header([51_864, 1500, 512, 8, 6, 448, 512, 8, 6, 80, 2009])).unwrap();
+        let metadata = hparams.metadata(57 * MIB);
+
+        assert_eq!(metadata.variant, "base.en");
+        assert!(!metadata.multilingual);
+        assert_eq!(metadata.quantization, "q5_1");
+    }
+
+    #[test]
+    fn test_large_variants() {
+        let v3 = parse_header(&header([
+            51_866, 1500, 1280, 20, 32, 448, 1280, 20, 32, 128, 1,
+        ]));
+        let turbo = parse_header(&header([
+            51_866, 1500, 1280, 20, 32, 448, 1280, 20, 4, 128, 1,
+        ]));
+        assert_eq!(v3.unwrap().size(), "large-v3");
+        assert_eq!(turbo.unwrap().size(), "large-v3-turbo");
+    }
+
+    #[test]
+    fn test_rejects_other_files() {
+        let mut not_ggml = header([51_865, 1500, 384, 6, 4, 448, 384, 6, 4, 80, 1]);
+        not_ggml[..4].copy_from_slice(b"RIFF");
+        assert_eq!(parse_header(&not_ggml), None);
+        assert_eq!(parse_header(&header([0; 11])), None);
+    }
+}
@@ -1,53 +1,155 @@
 //! Whisper-rs adapter for speech-to-text transcription.
 
+use super::context_cache::ContextCache;
+use super::decode_options::DecodeOptions;
+use super::model_header;
 use crate::domain::CyranoError;
 use crate::traits::transcriber::Transcriber;
+use crate::utils::text_join;
 use std::path::Path;
-use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use whisper_rs::{
+    SegmentCallbackData, WhisperContext, WhisperContextParameters, WhisperState, WhisperToken,
+};
+
+/// Audio used to detect the language when none is set: 8 seconds at 16kHz.
+const LANGUAGE_DETECTION_SAMPLES: usize = 16_000 * 8;
+
+/// Contexts of recently used models, shared by every adapter.
+static CONTEXT_CACHE: Mutex<ContextCache<WhisperContext>> = Mutex::new(ContextCache::new());
+
+/// Whether the cached contexts were loaded on the GPU. Changing the setting
+/// drops them, so models are loaded again with the new setting.
+static CACHE_USES_GPU: AtomicBool = AtomicBool::new(true);
+
+/// Text transcribed by Whisper, the language it was decoded in, and when
+/// each part of it was spoken.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Transcription {
+    pub text: String,
+    /// Language code set for the transcription or detected from the audio,
+    /// `None` if detection failed
+    pub language: Option<String>,
+    /// Segments of the text with their timing, in order
+    pub segments: Vec<Segment>,
+    /// Words with their timing, in order; empty unless word timestamps were
+    /// requested in the decode options
+    pub words: Vec<Word>,
+    /// Average log probability of the text tokens, a measure of Whisper's
+    /// confidence; `None` if no text was decoded
+    pub avg_logprob: Option<f32>,
+}
+
+/// A segment of text decoded by Whisper, reported while a transcription runs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    /// Position of the segment in the transcription, from 0
+    pub index: u32,
+    /// Start of the segment in the audio, in milliseconds
+    pub start_ms: u64,
+    /// End of the segment in the audio, in milliseconds
+    pub end_ms: u64,
+    pub text: String,
+}
+
+/// A word of a transcription with its timing.
+///
+/// Whisper times tokens, not words: a word is made of the tokens up to the
+/// next one starting with a space, so punctuation stays with its word.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Word {
+    pub text: String,
+    /// Start of the word in the audio, in milliseconds
+    pub start_ms: u64,
+    /// End of the word in the audio, in milliseconds
+    pub end_ms: u64,
+    /// Lowest probability of the word's tokens, from 0 to 1
+    pub probability: f32,
+}
+
+/// A text token decoded by Whisper, timed in centiseconds.
+#[derive(Debug, Clone, PartialEq)]
+struct TimedToken {
+    text: String,
+    start: i64,
+    end: i64,
+    probability: f32,
+}
+
+/// Receives each segment as soon as Whisper decodes it.
+pub type SegmentHandler = Arc<dyn Fn(&Segment) + Send + Sync>;
 
 /// Adapter wrapping whisper-rs for speech-to-text transcription.
 pub struct WhisperAdapter {
-    context: Option<WhisperContext>,
+    context: Option<Arc<WhisperContext>>,
+    cache_budget_bytes: u64,
+    use_gpu: bool,
+    initial_prompt: Option<String>,
+    language: Option<String>,
+    abort_check: Option<fn() -> bool>,
+    segment_handler: Option<SegmentHandler>,
 }
 
 impl WhisperAdapter {
     /// Create a new WhisperAdapter with no model loaded.
     pub fn new() -> Self {
-        Self { context: None }
+        Self {
+            context: None,
+            cache_budget_bytes: 0,
+            use_gpu: true,
+            initial_prompt: None,
+            language: None,
+            abort_check: None,
+            segment_handler: None,
+        }
     }
-}
 
-impl Default for WhisperAdapter {
-    fn default() -> Self {
-        Self::new()
+    /// Set the memory budget for keeping the models loaded by adapters
+    /// cached, so loading one of them again is instant. 0 only keeps the
+    /// model in use. Applies from the next model load.
+    pub fn set_cache_budget(&mut self, budget_bytes: u64) {
+        self.cache_budget_bytes = budget_bytes;
     }
-}
 
-impl Transcriber for WhisperAdapter {
-    fn load_model(&mut self, model_path: &Path) -> Result<(), CyranoError> {
-        if !model_path.exists() {
-            return Err(CyranoError::ModelNotFound {
-                path: model_path.display().to_string(),
-            });
-        }
+    /// Run models on the GPU (Metal) when one is available. Applies from the
+    /// next model load.
+    pub fn set_use_gpu(&mut self, use_gpu: bool) {
+        self.use_gpu = use_gpu;
+    }
 
-        let path_str = model_path
-            .to_str()
-            .ok_or_else(|| CyranoError::ModelLoadFailed {
-                reason: "Invalid path encoding".to_string(),
-            })?;
+    /// Set the prompt used to bias transcription (e.g. custom vocabulary).
+    pub fn set_initial_prompt(&mut self, prompt: Option<String>) {
+        self.initial_prompt = prompt;
+    }
 
-        let ctx = WhisperContext::new_with_params(path_str, WhisperContextParameters::default())
-            .map_err(|e| CyranoError::ModelLoadFailed {
-                reason: e.to_string(),
-            })?;
+    /// Set the language spoken in the audio, as a Whisper language code.
+    /// `None` detects the language of each transcription.
+    pub fn set_language(&mut self, language: Option<String>) {
+        self.language = language;
+    }
 
-        self.context = Some(ctx);
-        log::info!("Whisper model loaded from: {}", model_path.display());
-        Ok(())
+    /// Set a check polled while decoding: when it returns true, the
+    /// transcription stops and fails. `None` always runs to completion.
+    pub fn set_abort_check(&mut self, abort_check: Option<fn() -> bool>) {
+        self.abort_check = abort_check;
     }
 
-    fn transcribe(&self, samples: &[f32]) -> Result<String, CyranoError> {
+    /// Set the handler called with each segment as soon as it is decoded.
+    /// `None` only reports the full text once the transcription completes.
+    pub fn set_segment_handler(&mut self, segment_handler: Option<SegmentHandler>) {
+        self.segment_handler = segment_handler;
+    }
+
+    /// Transcribe audio samples with the given decoding options, detecting
+    /// the language from the first seconds of audio when none is set.
+    ///
+    /// Audio must be 16kHz mono f32 samples.
+    pub fn transcribe_with_language(
+        &self,
+        samples: &[f32],
+        options: &DecodeOptions,
+    ) -> Result<Transcription, CyranoError> {
         let ctx = self
             .context
             .as_ref()
@@ -55,18 +157,46 @@ impl Transcriber for WhisperAdapter {
                 reason: "Model not loaded".to_string(),
             })?;
 
+        if self.abort_check.is_some_and(|should_abort| should_abort()) {
+            return Err(CyranoError::TranscriptionFailed {
+                reason: "Transcription aborted".to_string(),
+            });
+        }
+
         let mut state = ctx
             .create_state()
             .map_err(|e| CyranoError::TranscriptionFailed {
                 reason: format!("Failed to create state: {e}"),
             })?;
 
-        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
-        params.set_language(None); // Auto-detect language
+        let language = match &self.language {
+            Some(language) => Some(language.clone()),
+            // English-only models (`.en`) cannot detect languages
+            None if !ctx.is_multilingual() => Some("en".to_string()),
+            None => detect_language(&mut state, samples)
+                .inspect(|language| log::info!("Detected language: {language}"))
+                .inspect_err(|e| log::warn!("Language detection failed, Whisper decides: {e}"))
+                .ok(),
+        };
+
+        let mut params = options.full_params();
+        params.set_language(language.as_deref());
         params.set_print_special(false);
         params.set_print_progress(false);
         params.set_print_realtime(false);
         params.set_print_timestamps(false);
+        if let Some(prompt) = &self.initial_prompt {
+            // whisper-rs panics on interior NUL bytes
+            params.set_initial_prompt(&prompt.replace('\0', ""));
+        }
+        if let Some(should_abort) = self.abort_check {
+            params.set_abort_callback_safe(should_abort);
+        }
+        if let Some(handler) = self.segment_handler.clone() {
+            params.set_segment_callback_safe(move |data: SegmentCallbackData| {
+                handler(&Segment::from(data));
+            });
+        }
 
         state
             .full(params, samples)
@@ -81,29 +211,225 @@ impl Transcriber for WhisperAdapter {
                     reason: format!("Failed to get segments: {e}"),
                 })?;
 
-        let mut result = String::new();
-        for i in 0..num_segments {
-            if let Ok(segment) = state.full_get_segment_text(i) {
-                result.push_str(&segment);
+        let segments: Vec<Segment> = (0..num_segments)
+            .filter_map(|i| {
+                Some(Segment {
+                    index: i as u32,
+                    start_ms: centiseconds_to_ms(state.full_get_segment_t0(i).ok()?),
+                    end_ms: centiseconds_to_ms(state.full_get_segment_t1(i).ok()?),
+                    text: state.full_get_segment_text(i).ok()?,
+                })
+            })
+            .collect();
+        let words = if options.word_timestamps {
+            group_words(timed_tokens(&state, num_segments, ctx.token_eot()))
+        } else {
+            Vec::new()
+        };
+        let avg_logprob = average_logprob(&state, num_segments, ctx.token_eot());
+
+        Ok(Transcription {
+            text: text_join::join_chunks(segments.iter().map(|segment| segment.text.clone())),
+            language,
+            segments,
+            words,
+            avg_logprob,
+        })
+    }
+
+    /// Whether Whisper knows a language code, e.g. `en` or `fr`.
+    pub fn is_supported_language(code: &str) -> bool {
+        // whisper-rs panics on interior NUL bytes
+        !code.contains('\0') && whisper_rs::get_lang_id(code).is_some()
+    }
+}
+
+impl Default for WhisperAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Transcriber for WhisperAdapter {
+    fn load_model(&mut self, model_path: &Path) -> Result<(), CyranoError> {
+        if !model_path.exists() {
+            return Err(CyranoError::ModelNotFound {
+                path: model_path.display().to_string(),
+            });
+        }
+
+        let path_str = model_path
+            .to_str()
+            .ok_or_else(|| CyranoError::ModelLoadFailed {
+                reason: "Invalid path encoding".to_string(),
+            })?;
+
+        let cached = CONTEXT_CACHE.lock().ok().and_then(|mut cache| {
+            if CACHE_USES_GPU.swap(self.use_gpu, Ordering::SeqCst) != self.use_gpu {
+                cache.clear();
             }
+            cache.set_budget(self.cache_budget_bytes);
+            cache.get(model_path)
+        });
+        if let Some(ctx) = cached {
+            self.context = Some(ctx);
+            log::info!("Whisper model reused from cache: {}", model_path.display());
+            return Ok(());
         }
 
-        Ok(result.trim().to_string())
+        // Release the current model first so two models are not in memory at
+        // once (it stays loaded if it is cached)
+        self.context = None;
+        let mut params = WhisperContextParameters::default();
+        params.use_gpu(self.use_gpu);
+        let ctx = WhisperContext::new_with_params(path_str, params).map_err(|e| {
+            CyranoError::ModelLoadFailed {
+                reason: e.to_string(),
+            }
+        })?;
+        let ctx = Arc::new(ctx);
+
+        if let Ok(mut cache) = CONTEXT_CACHE.lock() {
+            let size_bytes = model_header::read_metadata(model_path)
+                .map(|metadata| metadata.required_ram_bytes)
+                .or_else(|_| model_path.metadata().map(|m| m.len()))
+                .unwrap_or(u64::MAX);
+            cache.insert(model_path, Arc::clone(&ctx), size_bytes);
+        }
+        self.context = Some(ctx);
+        log::info!(
+            "Whisper model loaded from: {} (GPU {})",
+            model_path.display(),
+            if self.use_gpu { "enabled" } else { "disabled" }
+        );
+        Ok(())
+    }
+
+    fn transcribe(&self, samples: &[f32]) -> Result<String, CyranoError> {
+        self.transcribe_with_language(samples, &DecodeOptions::default())
+            .map(|transcription| transcription.text)
     }
 
     fn is_loaded(&self) -> bool {
         self.context.is_some()
     }
 
+    /// Unload the model to free memory, dropping the cached models too.
     fn unload(&mut self) -> Result<(), CyranoError> {
         if self.context.is_some() {
             log::info!("Unloading Whisper model");
         }
         self.context = None;
+        if let Ok(mut cache) = CONTEXT_CACHE.lock() {
+            cache.clear();
+        }
         Ok(())
     }
 }
 
+impl From<SegmentCallbackData> for Segment {
+    fn from(data: SegmentCallbackData) -> Self {
+        // Whisper timestamps are in centiseconds
+        Self {
+            index: data.segment.max(0) as u32,
+            start_ms: centiseconds_to_ms(data.start_timestamp),
+            end_ms: centiseconds_to_ms(data.end_timestamp),
+            text: data.text,
+        }
+    }
+}
+
+/// Whisper timestamps are in centiseconds.
+fn centiseconds_to_ms(timestamp: i64) -> u64 {
+    timestamp.max(0) as u64 * 10
+}
+
+/// Average log probability of the text tokens of every segment, `None` if
+/// there are none.
+fn average_logprob(state: &WhisperState, num_segments: i32, eot: WhisperToken) -> Option<f32> {
+    let logprobs: Vec<f32> = (0..num_segments)
+        .flat_map(|segment| {
+            (0..state.full_n_tokens(segment).unwrap_or(0))
+                .filter_map(move |token| state.full_get_token_data(segment, token).ok())
+        })
+        .filter(|data| data.id < eot)
+        .map(|data| data.plog)
+        .collect();
+    (!logprobs.is_empty()).then(|| logprobs.iter().sum::<f32>() / logprobs.len() as f32)
+}
+
+/// Text tokens of every segment, without special tokens (timestamps,
+/// end of text), which Whisper numbers from `eot`.
+fn timed_tokens(state: &WhisperState, num_segments: i32, eot: WhisperToken) -> Vec<TimedToken> {
+    let mut tokens = Vec::new();
+    for segment in 0..num_segments {
+        let num_tokens = state.full_n_tokens(segment).unwrap_or(0);
+        for token in 0..num_tokens {
+            let Ok(data) = state.full_get_token_data(segment, token) else {
+                continue;
+            };
+            if data.id >= eot {
+                continue;
+            }
+            // Characters split across tokens come out as U+FFFD
+            if let Ok(text) = state.full_get_token_text_lossy(segment, token) {
+                tokens.push(TimedToken {
+                    text,
+                    start: data.t0,
+                    end: data.t1,
+                    probability: data.p,
+                });
+            }
+        }
+    }
+    tokens
+}
+
+/// Group tokens into words: a token starting with whitespace starts a word,
+/// any other token continues the current one.
+fn group_words(tokens: Vec<TimedToken>) -> Vec<Word> {
+    let mut words: Vec<Word> = Vec::new();
+    for token in tokens {
+        let starts_word = token.text.starts_with(char::is_whitespace);
+        match words.last_mut() {
+            Some(word) if !starts_word => {
+                word.text.push_str(&token.text);
+                word.end_ms = centiseconds_to_ms(token.end);
+                word.probability = word.probability.min(token.probability);
+            }
+            _ => words.push(Word {
+                text: token.text.trim_start().to_string(),
+                start_ms: centiseconds_to_ms(token.start),
+                end_ms: centiseconds_to_ms(token.end),
+                probability: token.probability,
+            }),
+        }
+    }
+    words.retain(|word| !word.text.is_empty());
+    words
+}
+
+/// Detect the language spoken in the first seconds of `samples`.
+fn detect_language(state: &mut WhisperState, samples: &[f32]) -> Result<String, String> {
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get().min(4));
+    let head = &samples[..samples.len().min(LANGUAGE_DETECTION_SAMPLES)];
+    state.pcm_to_mel(head, threads).map_err(|e| e.to_string())?;
+    let probabilities = state.lang_detect(0, threads).map_err(|e| e.to_string())?;
+    most_likely_language(&probabilities)
+        .and_then(|id| whisper_rs::get_lang_str(id as i32))
+        .map(str::to_string)
+        .ok_or_else(|| "no language probabilities".to_string())
+}
+
+/// Id of the most probable language.
+fn most_likely_language(probabilities: &[f32]) -> Option<usize> {
+    probabilities
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(id, _)| id)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -141,6 +467,66 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_most_likely_language() {
+        assert_eq!(most_likely_language(&[0.1, 0.7, 0.2]), Some(1));
+        assert_eq!(most_likely_language(&[]), None);
+        assert_eq!(whisper_rs::get_lang_str(0), Some("en"));
+    }
+
+    #[test]
+    fn test_segment_timestamps_in_milliseconds() {
+        let segment = Segment::from(SegmentCallbackData {
+            segment: 2,
+            start_timestamp: 150,
+            end_timestamp: 420,
+            text: " Hello".to_string(),
+        });
+        assert_eq!(
+            (segment.index, segment.start_ms, segment.end_ms),
+            (2, 1500, 4200)
+        );
+        assert_eq!(segment.text, " Hello");
+    }
+
+    fn token(text: &str, start: i64, end: i64, probability: f32) -> TimedToken {
+        TimedToken {
+            text: text.to_string(),
+            start,
+            end,
+            probability,
+        }
+    }
+
+    #[test]
+    fn test_group_words_joins_subword_tokens_and_punctuation() {
+        let words = group_words(vec![
+            token(" Kuber", 0, 30, 0.9),
+            token("netes", 30, 55, 0.6),
+            token(",", 55, 56, 0.95),
+            token(" done", 60, 90, 0.8),
+            token(" ", 90, 91, 0.5),
+        ]);
+
+        assert_eq!(
+            words,
+            vec![
+                Word {
+                    text: "Kubernetes,".to_string(),
+                    start_ms: 0,
+                    end_ms: 560,
+                    probability: 0.6,
+                },
+                Word {
+                    text: "done".to_string(),
+                    start_ms: 600,
+                    end_ms: 900,
+                    probability: 0.8,
+                },
+            ]
+        );
+    }
+
     #[test]
     fn test_unload_when_no_model() {
         let mut adapter = WhisperAdapter::new();
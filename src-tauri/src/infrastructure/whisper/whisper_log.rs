@@ -0,0 +1,104 @@
+//! Bridge from whisper.cpp's logging to the `log` crate.
+//!
+//! whisper.cpp and ggml print to stderr, which ends up nowhere in a bundled
+//! app. Installing a log callback routes their messages through the app
+//! logger under the `whisper_cpp` target, so model-load errors reach the log
+//! files and follow the runtime log levels.
+
+use std::ffi::{c_char, c_void, CStr};
+use std::ptr::null_mut;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use log::Level;
+use whisper_rs_sys::{
+    ggml_log_level, ggml_log_level_GGML_LOG_LEVEL_CONT, ggml_log_level_GGML_LOG_LEVEL_DEBUG,
+    ggml_log_level_GGML_LOG_LEVEL_ERROR, ggml_log_level_GGML_LOG_LEVEL_INFO,
+    ggml_log_level_GGML_LOG_LEVEL_WARN,
+};
+
+/// Log target of whisper.cpp and ggml messages.
+pub const LOG_TARGET: &str = "whisper_cpp";
+
+/// Level of the last message, for continuation lines.
+static LAST_LEVEL: AtomicUsize = AtomicUsize::new(Level::Debug as usize);
+
+/// Route whisper.cpp and ggml logs to the app logger. Call once at startup,
+/// before the first model is loaded.
+pub fn install() {
+    // SAFETY: the callback is a plain function that lives for the whole
+    // program and ignores its user data.
+    unsafe {
+        whisper_rs_sys::whisper_log_set(Some(forward), null_mut());
+        whisper_rs_sys::ggml_log_set(Some(forward), null_mut());
+    }
+}
+
+/// Level of a whisper.cpp message. Its info messages (one per model
+/// parameter on every load) are logged at debug, so they stay out of
+/// production logs while errors and warnings come through.
+// bindgen names the level constants in lowercase
+#[allow(non_upper_case_globals)]
+fn map_level(level: ggml_log_level, previous: Level) -> Option<Level> {
+    match level {
+        ggml_log_level_GGML_LOG_LEVEL_ERROR => Some(Level::Error),
+        ggml_log_level_GGML_LOG_LEVEL_WARN => Some(Level::Warn),
+        ggml_log_level_GGML_LOG_LEVEL_INFO => Some(Level::Debug),
+        ggml_log_level_GGML_LOG_LEVEL_DEBUG => Some(Level::Trace),
+        ggml_log_level_GGML_LOG_LEVEL_CONT => Some(previous),
+        _ => None,
+    }
+}
+
+fn level_from_usize(level: usize) -> Level {
+    [
+        Level::Error,
+        Level::Warn,
+        Level::Info,
+        Level::Debug,
+        Level::Trace,
+    ]
+    .into_iter()
+    .find(|candidate| *candidate as usize == level)
+    .unwrap_or(Level::Debug)
+}
+
+unsafe extern "C" fn forward(level: ggml_log_level, text: *const c_char, _: *mut c_void) {
+    if text.is_null() {
+        return;
+    }
+    let previous = level_from_usize(LAST_LEVEL.load(Ordering::Relaxed));
+    let Some(level) = map_level(level, previous) else {
+        return;
+    };
+    LAST_LEVEL.store(level as usize, Ordering::Relaxed);
+
+    // SAFETY: whisper.cpp passes a NUL-terminated string valid for the call
+    let text = unsafe { CStr::from_ptr(text) }.to_string_lossy();
+    let text = text.trim_end();
+    if !text.is_empty() {
+        log::log!(target: LOG_TARGET, level, "{text}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_mapping() {
+        let map = |level| map_level(level, Level::Warn);
+        assert_eq!(map(ggml_log_level_GGML_LOG_LEVEL_ERROR), Some(Level::Error));
+        assert_eq!(map(ggml_log_level_GGML_LOG_LEVEL_WARN), Some(Level::Warn));
+        assert_eq!(map(ggml_log_level_GGML_LOG_LEVEL_INFO), Some(Level::Debug));
+        assert_eq!(map(ggml_log_level_GGML_LOG_LEVEL_DEBUG), Some(Level::Trace));
+        // Continuation lines keep the level of the message they continue
+        assert_eq!(map(ggml_log_level_GGML_LOG_LEVEL_CONT), Some(Level::Warn));
+    }
+
+    #[test]
+    fn test_level_round_trips_through_usize() {
+        for level in [Level::Error, Level::Info, Level::Trace] {
+            assert_eq!(level_from_usize(level as usize), level);
+        }
+    }
+}
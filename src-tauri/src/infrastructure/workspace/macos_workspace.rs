@@ -0,0 +1,39 @@
+//! macOS running application queries using NSWorkspace.
+
+use objc2_app_kit::{NSRunningApplication, NSWorkspace};
+use objc2_foundation::NSString;
+
+/// Get the bundle identifier of the frontmost application.
+///
+/// # Returns
+/// * `Some(String)` with the bundle ID (e.g. `com.apple.TextEdit`)
+/// * `None` if there is no frontmost app or it has no `Info.plist`
+pub fn frontmost_bundle_id() -> Option<String> {
+    let workspace = NSWorkspace::sharedWorkspace();
+    let app = workspace.frontmostApplication()?;
+    app.bundleIdentifier().map(|id| id.to_string())
+}
+
+/// Whether an application with the given bundle identifier is running.
+pub fn is_running(bundle_id: &str) -> bool {
+    !NSRunningApplication::runningApplicationsWithBundleIdentifier(&NSString::from_str(bundle_id))
+        .is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frontmost_bundle_id_does_not_panic() {
+        // The result depends on the session (headless CI has no frontmost app)
+        if let Some(bundle_id) = frontmost_bundle_id() {
+            assert!(!bundle_id.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_is_running_unknown_bundle_id() {
+        assert!(!is_running("com.example.cyrano.not-installed"));
+    }
+}
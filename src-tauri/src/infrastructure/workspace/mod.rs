@@ -0,0 +1,11 @@
+//! Running application queries.
+//!
+//! Provides access to information about other applications, such as which
+//! app is frontmost and will receive inserted text, or whether a
+//! conferencing app is running.
+
+#[cfg(target_os = "macos")]
+pub mod macos_workspace;
+
+#[cfg(target_os = "macos")]
+pub use macos_workspace::{frontmost_bundle_id, is_running};
@@ -7,6 +7,7 @@
 mod bindings;
 mod commands;
 mod domain;
+pub mod harness;
 mod infrastructure;
 mod services;
 mod traits;
@@ -64,12 +65,10 @@ pub fn run() {
         .plugin(tauri_plugin_notification::init())
         .plugin(
             tauri_plugin_log::Builder::new()
-                // Use Debug level in development, Info in production
-                .level(if cfg!(debug_assertions) {
-                    log::LevelFilter::Debug
-                } else {
-                    log::LevelFilter::Info
-                })
+                // Levels are applied by the filter so they can change at runtime
+                // (Debug in development, Info in production by default)
+                .level(log::LevelFilter::Trace)
+                .filter(services::log_level_service::is_enabled)
                 .targets([
                     // Always log to stdout for development
                     tauri_plugin_log::Target::new(tauri_plugin_log::TargetKind::Stdout),
@@ -97,13 +96,61 @@ pub fn run() {
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_os::init())
+        .on_page_load(|webview, payload| {
+            // Tell the main window about a dictation interrupted by the last quit
+            // and the startup self-test once it can listen, including after a reload
+            if webview.label() == "main"
+                && payload.event() == tauri::webview::PageLoadEvent::Finished
+            {
+                services::recording_recovery_service::announce_interrupted_session(
+                    webview.app_handle(),
+                );
+                services::diagnostics_service::announce_startup_report(webview.app_handle());
+            }
+        })
         .setup(|app| {
+            // The logger accepts every level; skip formatting records the filter drops
+            log::set_max_level(services::log_level_service::default_level());
+            // whisper.cpp logs to stderr otherwise
+            infrastructure::whisper::whisper_log::install();
             log::info!("Application starting up");
+            // Read the Shift key before the user lets go of it
+            let safe_mode = services::safe_mode_service::detect_at_launch();
             log::debug!(
                 "App handle initialized for package: {}",
                 app.package_info().name
             );
 
+            // The main window hears every backend event; other windows subscribe
+            // to their channels when they are created
+            services::notifier_service::subscribe(
+                "main",
+                &services::notifier_service::EventChannel::ALL,
+            );
+
+            // Create the ~/.cyrano layout and migrate legacy files (first run)
+            if let Err(e) = services::data_directory_service::bootstrap() {
+                log::error!("Failed to bootstrap data directories: {e}");
+                // Non-fatal: repair_data_directories can be invoked later
+            }
+
+            // Reset a dictation interrupted by the last quit and keep its audio,
+            // before anything records over it
+            services::recording_recovery_service::restore_after_restart();
+
+            // Pull history changes from other devices without delaying startup
+            if !safe_mode
+                && services::settings_service::get_settings()
+                    .history_sync_folder
+                    .is_some()
+            {
+                std::thread::spawn(|| {
+                    if let Err(e) = services::history_sync_service::sync_history() {
+                        log::warn!("History sync at startup failed: {e}");
+                    }
+                });
+            }
+
             // Set up global shortcut plugin (without any shortcuts - we register them separately)
             #[cfg(desktop)]
             {
@@ -112,36 +159,78 @@ pub fn run() {
                 app.handle().plugin(Builder::new().build())?;
             }
 
-            // Load saved preferences and register the quick pane shortcut
-            #[cfg(desktop)]
-            {
-                let saved_shortcut = commands::preferences::load_quick_pane_shortcut(app.handle());
-                let shortcut_to_register = saved_shortcut
-                    .as_deref()
-                    .unwrap_or(DEFAULT_QUICK_PANE_SHORTCUT);
-
-                log::info!("Registering quick pane shortcut: {shortcut_to_register}");
-                commands::quick_pane::register_quick_pane_shortcut(
-                    app.handle(),
-                    shortcut_to_register,
-                )?;
-            }
+            // Safe mode registers no shortcut, in case one of them is the problem
+            if !safe_mode {
+                // Load saved preferences and register the quick pane shortcut
+                #[cfg(desktop)]
+                {
+                    let saved_shortcut =
+                        commands::preferences::load_quick_pane_shortcut(app.handle());
+                    let shortcut_to_register = saved_shortcut
+                        .as_deref()
+                        .unwrap_or(DEFAULT_QUICK_PANE_SHORTCUT);
 
-            // Load saved preferences and register the recording shortcut
-            #[cfg(desktop)]
-            {
-                use services::shortcut_service::DEFAULT_RECORDING_SHORTCUT;
-
-                let saved_shortcut = commands::preferences::load_recording_shortcut(app.handle());
-                let shortcut_to_register = saved_shortcut
-                    .as_deref()
-                    .unwrap_or(DEFAULT_RECORDING_SHORTCUT);
-
-                log::info!("Registering recording shortcut: {shortcut_to_register}");
-                services::shortcut_service::register_recording_shortcut(
-                    app.handle(),
-                    shortcut_to_register,
-                )?;
+                    log::info!("Registering quick pane shortcut: {shortcut_to_register}");
+                    commands::quick_pane::register_quick_pane_shortcut(
+                        app.handle(),
+                        shortcut_to_register,
+                    )?;
+                }
+
+                // Load saved preferences and register the recording shortcut
+                #[cfg(desktop)]
+                {
+                    use services::shortcut_service::{ShortcutKind, DEFAULT_RECORDING_SHORTCUT};
+
+                    let saved_shortcut =
+                        commands::preferences::load_recording_shortcut(app.handle());
+                    let shortcut_to_register = saved_shortcut
+                        .as_deref()
+                        .unwrap_or(DEFAULT_RECORDING_SHORTCUT);
+
+                    log::info!("Registering recording shortcut: {shortcut_to_register}");
+                    if let Err(e) = services::shortcut_service::register_or_defer(
+                        app.handle(),
+                        ShortcutKind::Recording,
+                        shortcut_to_register,
+                    ) {
+                        log::error!("Failed to register recording shortcut: {e}");
+                        // Retried once Input Monitoring or Accessibility is granted
+                    }
+                    services::diagnostics_service::record_startup_check(
+                        "global_shortcut",
+                        services::shortcut_service::check_recording_shortcut(app.handle()),
+                    );
+                }
+
+                // Register the redo shortcut only once the user has set one
+                #[cfg(desktop)]
+                if let Some(shortcut) = services::settings_service::get_settings().redo_shortcut {
+                    log::info!("Registering redo shortcut: {shortcut}");
+                    if let Err(e) = services::shortcut_service::register_or_defer(
+                        app.handle(),
+                        services::shortcut_service::ShortcutKind::Redo,
+                        &shortcut,
+                    ) {
+                        log::error!("Failed to register redo shortcut: {e}");
+                        // Non-fatal: dictation works without redo
+                    }
+                }
+
+                // Register the optional clipboard audio shortcut
+                #[cfg(desktop)]
+                if let Some(shortcut) =
+                    services::settings_service::get_settings().clipboard_audio_shortcut
+                {
+                    log::info!("Registering clipboard audio shortcut: {shortcut}");
+                    if let Err(e) = services::shortcut_service::register_or_defer(
+                        app.handle(),
+                        services::shortcut_service::ShortcutKind::ClipboardAudio,
+                        &shortcut,
+                    ) {
+                        log::error!("Failed to register clipboard audio shortcut: {e}");
+                    }
+                }
             }
 
             // Create the quick pane window (hidden) - must be done on main thread
@@ -151,17 +240,83 @@ pub fn run() {
             }
 
             // Create the recording overlay window (hidden) - must be done on main thread
-            if let Err(e) = commands::recording_overlay::init_recording_overlay(app.handle()) {
+            let overlay = commands::recording_overlay::init_recording_overlay(app.handle());
+            if let Err(e) = &overlay {
                 log::error!("Failed to create recording overlay: {e}");
                 // Non-fatal: app can still run without recording overlay
             }
+            services::diagnostics_service::record_startup_check("recording_overlay", overlay);
+
+            // Show the recording state in the menu bar when enabled
+            services::tray_status_service::init(app.handle());
+
+            // Turn off the microphone if a capture outlives its recording
+            services::capture_watchdog_service::start_watchdog(app.handle());
+
+            // Safe mode starts no background watcher
+            if !safe_mode {
+                // Retry failed shortcuts once the user grants the missing permission
+                services::permission_watch_service::start_watcher(app.handle());
+
+                // Apply edits of the settings file made by hand or by dotfile managers
+                services::settings_watch_service::start_watcher(app.handle());
+
+                // Announce voice memos synced from the user's iPhone
+                services::voice_memo_service::start_watcher(app.handle());
+
+                // Offer meeting transcription when a call starts (opt-in)
+                services::meeting_assistant_service::start_watcher(app.handle());
+
+                // Free the model's memory once it has been idle long enough
+                services::model_unload_service::start_timer(app.handle());
+            }
+
+            // Cache monitor geometry for overlay positioning until displays change
+            services::monitor_layout_service::start_observing(app.handle());
+
+            // Finder's "Transcribe Audio File with Cyrano" service (declared in Info.plist)
+            #[cfg(target_os = "macos")]
+            if let Some(mtm) = objc2::MainThreadMarker::new() {
+                let handle = app.handle().clone();
+                infrastructure::system_services::register_file_service(mtm, move |paths| {
+                    services::file_transcription_service::enqueue_files(&handle, paths);
+                });
+            }
+
+            // Surface features that failed to initialize (degraded mode)
+            services::diagnostics_service::run_startup_self_test();
 
             // NOTE: Application menu is built from JavaScript for i18n support
             // See src/lib/menu.ts for the menu implementation
 
             Ok(())
         })
+        .on_window_event(|window, event| {
+            // Audio files dropped on the main window
+            if let tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) = event {
+                if window.label() == "main" {
+                    services::file_transcription_service::enqueue_files(
+                        window.app_handle(),
+                        paths.clone(),
+                    );
+                }
+            }
+        })
         .invoke_handler(builder.invoke_handler())
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Audio files opened with Cyrano from Finder ("Open With") or dropped on the Dock icon
+            #[cfg(target_os = "macos")]
+            if let tauri::RunEvent::Opened { urls } = event {
+                let paths = urls
+                    .into_iter()
+                    .filter_map(|url| url.to_file_path().ok())
+                    .collect();
+                services::file_transcription_service::enqueue_files(app, paths);
+            }
+
+            #[cfg(not(target_os = "macos"))]
+            let _ = (app, event);
+        });
 }
@@ -0,0 +1,279 @@
+//! Action items extracted from transcripts.
+//!
+//! Sentences where someone commits to doing something ("I'll send the deck
+//! by Friday", "Anna will book the room", "let's review it next week") are
+//! picked out with simple patterns when a history entry is recorded, and
+//! stored with it. The list can be exported as a Markdown checklist or added
+//! to Reminders on macOS.
+//!
+//! Extraction is rule-based and runs locally on every entry; it favors
+//! precision, so questions and hypotheticals are left out.
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::domain::CyranoError;
+use crate::services::history_service;
+
+/// An action item found in a transcript.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct ActionItem {
+    /// The sentence stating the action
+    pub text: String,
+    /// Deadline as spoken, e.g. "by Friday", if the sentence has one
+    pub due: Option<String>,
+}
+
+/// Phrases that commit someone to an action.
+static COMMITMENT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(?:(?:i|we|you|he|she|they)(?:'ll| will| need to| have to| must| should|'m going to| am going to|'re going to| are going to)|let's|let us|todo|to-do|action item|follow up on|remind me to|don't forget to|make sure to)\b",
+    )
+    .expect("valid commitment pattern")
+});
+
+/// A named person committing to an action, e.g. "Anna will".
+static NAMED_COMMITMENT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b([A-Z][a-z]+) (?:will|needs to|has to|is going to)\b")
+        .expect("valid named commitment pattern")
+});
+
+/// Capitalized words before "will" that are not people.
+const NOT_A_NAME: &[&str] = &[
+    "It",
+    "This",
+    "That",
+    "There",
+    "What",
+    "Which",
+    "Who",
+    "Nothing",
+    "Everything",
+    "Today",
+    "Tomorrow",
+];
+
+/// Openings that make the sentence conditional or not an action at all.
+static NOT_AN_ACTION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^(?:if|unless|maybe|i'll be honest|i will say|i'll say|you'll see|we'll see)\b",
+    )
+    .expect("valid exclusion pattern")
+});
+
+/// Spoken deadlines.
+static DUE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(?:(?:by|before|until|on|this|next) (?:(?:the )?end of (?:the )?(?:day|week|month)|today|tonight|tomorrow|monday|tuesday|wednesday|thursday|friday|saturday|sunday|week|month|morning|afternoon|evening|eod|eow)|(?:by|before) (?:noon|\d{1,2}(?::\d{2})? ?(?:am|pm)?)|today|tonight|tomorrow)\b",
+    )
+    .expect("valid due date pattern")
+});
+
+/// Fewest words in a sentence considered as an action item.
+const MIN_WORDS: usize = 3;
+
+/// Extract the action items from a transcript, in order of appearance.
+pub fn extract_action_items(text: &str) -> Vec<ActionItem> {
+    let mut items: Vec<ActionItem> = Vec::new();
+    for sentence in sentences(text) {
+        let normalized = sentence.replace('\u{2019}', "'");
+        if normalized.ends_with('?')
+            || normalized.split_whitespace().count() < MIN_WORDS
+            || NOT_AN_ACTION.is_match(&normalized)
+            || !(COMMITMENT.is_match(&normalized) || has_named_owner(&normalized))
+        {
+            continue;
+        }
+
+        let item = ActionItem {
+            text: clean_sentence(sentence),
+            due: DUE.find(&normalized).map(|m| m.as_str().to_string()),
+        };
+        if !items.iter().any(|existing| existing.text == item.text) {
+            items.push(item);
+        }
+    }
+    items
+}
+
+/// Whether a person is named as committing to the action.
+fn has_named_owner(sentence: &str) -> bool {
+    NAMED_COMMITMENT
+        .captures_iter(sentence)
+        .any(|captures| !NOT_A_NAME.contains(&&captures[1]))
+}
+
+/// Split text into sentences, keeping their terminators.
+fn sentences(text: &str) -> impl Iterator<Item = &str> {
+    let mut start = 0;
+    let mut bounds = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        let ends = match c {
+            '\n' => true,
+            '.' | '!' | '?' | '…' => chars.peek().is_none_or(|(_, next)| next.is_whitespace()),
+            _ => false,
+        };
+        if ends {
+            let end = index + c.len_utf8();
+            bounds.push(&text[start..end]);
+            start = end;
+        }
+    }
+    bounds.push(&text[start..]);
+    bounds
+        .into_iter()
+        .map(str::trim)
+        .filter(|sentence| !sentence.is_empty())
+}
+
+/// Trim the final period and capitalize the first letter.
+fn clean_sentence(sentence: &str) -> String {
+    let sentence = sentence.trim_end_matches(['.', '!', '…']).trim();
+    let mut chars = sentence.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Format action items as a Markdown checklist.
+pub fn to_markdown(items: &[ActionItem]) -> String {
+    items
+        .iter()
+        .map(|item| format!("- [ ] {}\n", item.text))
+        .collect()
+}
+
+/// Action items of a history entry as a Markdown checklist.
+///
+/// # Returns
+/// * `Ok(String)` with one `- [ ]` line per item (empty if there are none)
+/// * `Err(CyranoError::HistoryEntryNotFound)` if the entry does not exist
+pub fn export_markdown(id: &str) -> Result<String, CyranoError> {
+    Ok(to_markdown(&history_service::get_entry(id)?.action_items))
+}
+
+/// Add the action items of a history entry to Reminders.
+///
+/// # Returns
+/// * `Ok(u32)` with the number of reminders created
+/// * `Err(CyranoError::HistoryEntryNotFound)` if the entry does not exist
+/// * `Err(CyranoError::ReminderExportFailed)` if Reminders refused (e.g.
+///   automation permission denied) or is unavailable
+pub fn export_to_reminders(id: &str) -> Result<u32, CyranoError> {
+    let items = history_service::get_entry(id)?.action_items;
+    if items.is_empty() {
+        return Ok(0);
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        use crate::infrastructure::reminders;
+
+        let titles: Vec<&str> = items.iter().map(|item| item.text.as_str()).collect();
+        reminders::add_reminders(&titles)
+            .map(|()| titles.len() as u32)
+            .map_err(|reason| CyranoError::ReminderExportFailed { reason })
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        Err(CyranoError::ReminderExportFailed {
+            reason: "Reminders is only available on macOS".to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(text: &str) -> Vec<String> {
+        extract_action_items(text)
+            .into_iter()
+            .map(|item| item.text)
+            .collect()
+    }
+
+    #[test]
+    fn test_commitments_are_extracted_with_deadlines() {
+        let items = extract_action_items(
+            "Thanks everyone for joining. I'll send the deck by Friday. \
+             The numbers look good overall.",
+        );
+        assert_eq!(
+            items,
+            vec![ActionItem {
+                text: "I'll send the deck by Friday".to_string(),
+                due: Some("by Friday".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_named_owners_and_suggestions_are_extracted() {
+        assert_eq!(
+            texts("Anna will book the room. let's review the budget next week. It was fine."),
+            vec![
+                "Anna will book the room",
+                "Let's review the budget next week"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_questions_and_hypotheticals_are_ignored() {
+        assert!(texts(
+            "Will you send it? If we have time we'll cover pricing. I'll be honest, it's late. \
+             We'll see. It will rain on Monday."
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_curly_apostrophes_and_line_breaks() {
+        assert_eq!(
+            texts("Notes\nWe\u{2019}ll ship the fix tomorrow\nDone"),
+            vec!["We\u{2019}ll ship the fix tomorrow"]
+        );
+    }
+
+    #[test]
+    fn test_duplicates_are_dropped() {
+        assert_eq!(
+            texts("We need to call Bob. We need to call Bob."),
+            vec!["We need to call Bob"]
+        );
+    }
+
+    #[test]
+    fn test_decimal_points_do_not_split_sentences() {
+        assert_eq!(
+            texts("We need to ship version 2.1 by Monday."),
+            vec!["We need to ship version 2.1 by Monday"]
+        );
+    }
+
+    #[test]
+    fn test_markdown_checklist() {
+        let items = vec![
+            ActionItem {
+                text: "Send the deck".to_string(),
+                due: Some("by Friday".to_string()),
+            },
+            ActionItem {
+                text: "Book the room".to_string(),
+                due: None,
+            },
+        ];
+        assert_eq!(
+            to_markdown(&items),
+            "- [ ] Send the deck\n- [ ] Book the room\n"
+        );
+        assert_eq!(to_markdown(&[]), "");
+    }
+}
@@ -0,0 +1,220 @@
+//! Capabilities service.
+//!
+//! Reports which optional features are currently usable and, for each
+//! unavailable one, why. The frontend renders its toggles from this matrix
+//! instead of guessing from permissions or platform checks of its own.
+
+use tauri::AppHandle;
+
+use crate::domain::PermissionStatus;
+use crate::infrastructure::whisper::acceleration;
+use crate::services::settings_service::{self, Settings};
+use crate::services::{accessibility_service, sandbox_service, transcription_service};
+
+/// Availability of a single feature.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, specta::Type)]
+pub struct Capability {
+    /// Whether the feature can be used right now
+    pub available: bool,
+    /// Why the feature is unavailable (None when available)
+    pub reason: Option<String>,
+}
+
+impl Capability {
+    fn available() -> Self {
+        Self {
+            available: true,
+            reason: None,
+        }
+    }
+
+    fn unavailable(reason: impl Into<String>) -> Self {
+        Self {
+            available: false,
+            reason: Some(reason.into()),
+        }
+    }
+}
+
+/// Current availability of every optional feature.
+#[derive(Debug, Clone, serde::Serialize, specta::Type)]
+pub struct Capabilities {
+    /// Inserting text at the cursor via simulated Cmd+V
+    pub paste_insertion: Capability,
+    /// GPU-accelerated transcription (Metal)
+    pub gpu: Capability,
+    /// Whisper encoder on the Neural Engine (Core ML)
+    pub neural_engine: Capability,
+    /// Transcription through a remote backend
+    pub remote_backend: Capability,
+    /// Capturing system audio output in addition to the microphone
+    pub system_audio_capture: Capability,
+    /// Native system notifications
+    pub notifications: Capability,
+}
+
+/// Check whether text can be pasted at the cursor.
+fn paste_insertion_capability() -> Capability {
+    let status = accessibility_service::check_accessibility_permission();
+    if status != PermissionStatus::Granted {
+        return Capability::unavailable(
+            "Accessibility permission not granted; text is copied to the clipboard only",
+        );
+    }
+    if sandbox_service::is_sandboxed() && !sandbox_service::can_post_events() {
+        return Capability::unavailable(
+            "The App Sandbox blocks keyboard event posting; text is copied to the clipboard only",
+        );
+    }
+    Capability::available()
+}
+
+/// Check whether transcription can run on the GPU.
+///
+/// Whisper is built with Metal support, which is only available on macOS,
+/// and needs a Metal device at runtime.
+fn gpu_capability(use_gpu: bool) -> Capability {
+    if !cfg!(target_os = "macos") {
+        return Capability::unavailable("GPU acceleration requires Metal (macOS only)");
+    }
+    if !acceleration::metal_device_available() {
+        return Capability::unavailable("No Metal GPU found; transcription runs on the CPU");
+    }
+    if !use_gpu {
+        return Capability::unavailable("GPU acceleration is turned off in the settings");
+    }
+    Capability::available()
+}
+
+/// Check whether the Whisper encoder of the active model can run on the
+/// Neural Engine.
+fn neural_engine_capability() -> Capability {
+    if !acceleration::COREML_ENABLED {
+        return Capability::unavailable("This build does not include Core ML support");
+    }
+    match transcription_service::get_model_path() {
+        Ok(model_path) => {
+            let encoder = acceleration::coreml_encoder_path(&model_path);
+            if encoder.exists() {
+                Capability::available()
+            } else {
+                Capability::unavailable(format!(
+                    "No Core ML encoder for the active model at {}",
+                    encoder.display()
+                ))
+            }
+        }
+        Err(e) => Capability::unavailable(e.to_string()),
+    }
+}
+
+/// Check whether the remote transcription backend is configured.
+fn remote_backend_capability(settings: &Settings) -> Capability {
+    let url = settings.cloud_transcription_url.trim();
+    if url.is_empty() {
+        return Capability::unavailable("No remote transcription URL is configured");
+    }
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Capability::unavailable(format!(
+            "The remote transcription URL {url} does not start with http:// or https://"
+        ));
+    }
+    if settings.cloud_transcription_model.trim().is_empty() {
+        return Capability::unavailable("No remote transcription model is configured");
+    }
+    Capability::available()
+}
+
+/// Check whether system audio can be captured.
+pub fn system_audio_capture_capability() -> Capability {
+    Capability::unavailable("Only microphone input is supported")
+}
+
+/// Check whether native notifications can be shown.
+#[cfg(not(mobile))]
+fn notifications_capability(app: &AppHandle) -> Capability {
+    use tauri_plugin_notification::{NotificationExt, PermissionState};
+
+    match app.notification().permission_state() {
+        Ok(PermissionState::Granted) => Capability::available(),
+        Ok(PermissionState::Denied) => {
+            Capability::unavailable("Notification permission denied in System Settings")
+        }
+        Ok(_) => Capability::unavailable("Notification permission not requested yet"),
+        Err(e) => Capability::unavailable(format!("Failed to check notification permission: {e}")),
+    }
+}
+
+/// Check whether native notifications can be shown.
+#[cfg(mobile)]
+fn notifications_capability(_app: &AppHandle) -> Capability {
+    Capability::unavailable("Notifications are not supported on mobile")
+}
+
+/// Collect the availability of every optional feature.
+pub fn get_capabilities(app: &AppHandle) -> Capabilities {
+    let settings = settings_service::get_settings();
+    Capabilities {
+        paste_insertion: paste_insertion_capability(),
+        gpu: gpu_capability(settings.use_gpu),
+        neural_engine: neural_engine_capability(),
+        remote_backend: remote_backend_capability(&settings),
+        system_audio_capture: system_audio_capture_capability(),
+        notifications: notifications_capability(app),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_available_capability_has_no_reason() {
+        let capability = Capability::available();
+        assert!(capability.available);
+        assert!(capability.reason.is_none());
+    }
+
+    #[test]
+    fn test_unavailable_capability_has_reason() {
+        let capability = Capability::unavailable("missing");
+        assert!(!capability.available);
+        assert_eq!(capability.reason.as_deref(), Some("missing"));
+    }
+
+    #[test]
+    fn test_paste_insertion_reason_matches_availability() {
+        let capability = paste_insertion_capability();
+        assert_eq!(capability.available, capability.reason.is_none());
+    }
+
+    #[test]
+    fn test_gpu_capability_matches_platform() {
+        let capability = gpu_capability(true);
+        assert_eq!(capability.available, capability.reason.is_none());
+        if !cfg!(target_os = "macos") {
+            assert!(!capability.available);
+        }
+    }
+
+    #[test]
+    fn test_gpu_capability_respects_setting() {
+        assert!(!gpu_capability(false).available);
+    }
+
+    #[test]
+    fn test_remote_backend_capability_follows_settings() {
+        let mut settings = Settings::default();
+        assert!(remote_backend_capability(&settings).available);
+
+        settings.cloud_transcription_url = "localhost:8000/v1".to_string();
+        assert!(!remote_backend_capability(&settings).available);
+
+        settings.cloud_transcription_url = " ".to_string();
+        let capability = remote_backend_capability(&settings);
+        assert_eq!(
+            capability.reason.as_deref(),
+            Some("No remote transcription URL is configured")
+        );
+    }
+}
@@ -0,0 +1,99 @@
+//! Watchdog for dictation capture threads that outlive their recording.
+//!
+//! An edge-case failure (a stop flag never set, a panicked join, a state
+//! reset that skipped the recording context) can leave the capture thread
+//! running while the workflow is no longer recording, keeping the
+//! microphone hot indefinitely. The watchdog polls for a capture still
+//! running outside the Recording state, and after a grace period (state
+//! transitions briefly look like leaks) stops it, discards its audio and
+//! reports a recording-failed error.
+
+use std::time::Duration;
+
+use tauri::AppHandle;
+
+use crate::domain::{CyranoError, RecordingState};
+use crate::services::recording_service::{self, RecordingFailedPayload};
+use crate::services::{notifier_service, recording_state};
+
+/// How often the watchdog looks for leaked capture threads.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Consecutive polls a capture must look leaked before it is stopped.
+const LEAKED_POLLS: u32 = 2;
+
+/// Tracks how long a capture has looked leaked.
+#[derive(Debug, Default)]
+struct LeakDetector {
+    suspicious_polls: u32,
+}
+
+impl LeakDetector {
+    /// Record a poll, returning true when the capture should be stopped.
+    fn update(&mut self, state: RecordingState, capture_running: bool) -> bool {
+        if !capture_running || state == RecordingState::Recording {
+            self.suspicious_polls = 0;
+            return false;
+        }
+
+        self.suspicious_polls += 1;
+        if self.suspicious_polls < LEAKED_POLLS {
+            return false;
+        }
+        self.suspicious_polls = 0;
+        true
+    }
+}
+
+/// Start polling for leaked capture threads in the background.
+pub fn start_watchdog(app: &AppHandle) {
+    let app = app.clone();
+    let mut detector = LeakDetector::default();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(POLL_INTERVAL);
+        let state = recording_state::get_recording_state();
+        if !detector.update(state, recording_service::is_capture_running()) {
+            continue;
+        }
+
+        log::error!("Audio capture still running while {state:?}, stopping it");
+        recording_service::stop_leaked_capture();
+        let error = CyranoError::RecordingFailed {
+            reason: "The microphone was still recording after the dictation ended and has been \
+                     turned off"
+                .to_string(),
+        };
+        if let Err(e) =
+            notifier_service::emit(&app, "recording-failed", RecordingFailedPayload::new(error))
+        {
+            log::error!("Failed to emit recording-failed event: {e}");
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_outside_recording_is_stopped_after_grace_period() {
+        let mut detector = LeakDetector::default();
+        assert!(!detector.update(RecordingState::Idle, true));
+        assert!(detector.update(RecordingState::Idle, true));
+        // Counting starts over once handled
+        assert!(!detector.update(RecordingState::Done, true));
+    }
+
+    #[test]
+    fn test_recording_and_transitions_are_not_leaks() {
+        let mut detector = LeakDetector::default();
+        for _ in 0..5 {
+            assert!(!detector.update(RecordingState::Recording, true));
+        }
+
+        // Stopping: the capture ends before the next poll
+        assert!(!detector.update(RecordingState::Transcribing, true));
+        assert!(!detector.update(RecordingState::Transcribing, false));
+        assert!(!detector.update(RecordingState::Idle, true));
+    }
+}
@@ -0,0 +1,112 @@
+//! Fitting a dictation to the text before the caret.
+//!
+//! A transcription is formatted as a standalone sentence: capitalized, with
+//! no leading space. Pasted in the middle of a sentence, that produces
+//! "I went to theStore". When `caret_context_enabled` is on and the
+//! Accessibility permission is granted, the text before the caret is read
+//! at insertion time and the dictation is adjusted to continue it: a space
+//! is prepended when needed, and the first word is capitalized only at the
+//! start of a sentence.
+//!
+//! Apps that do not expose their text to Accessibility get the dictation
+//! unchanged.
+
+use crate::domain::PermissionStatus;
+use crate::infrastructure::accessibility;
+use crate::services::{accessibility_service, settings_service};
+use crate::utils::text_join;
+
+/// Text read before the caret, in UTF-16 code units.
+const CONTEXT_UNITS: usize = 100;
+
+/// Fit a dictation to the text before the caret of the focused text field.
+///
+/// # Returns
+/// * `Some(String)` with the adjusted dictation
+/// * `None` if the setting is off, the permission is missing, or the
+///   focused app does not expose the text before the caret
+pub fn fit_to_caret(text: &str) -> Option<String> {
+    if text.is_empty() || !settings_service::get_settings().caret_context_enabled {
+        return None;
+    }
+    if accessibility_service::check_accessibility_permission() != PermissionStatus::Granted {
+        return None;
+    }
+    let before = accessibility::text_before_caret(CONTEXT_UNITS)?;
+    let fitted = fit_to_context(text, &before);
+    if fitted != text {
+        log::debug!("Fitted the dictation to the text before the caret");
+    }
+    Some(fitted)
+}
+
+/// Adjust the spacing and capitalization of `text` to follow `before`.
+///
+/// At the start of a field or of a line, the text is capitalized without a
+/// leading space. Otherwise it continues `before` with the rules of
+/// `text_join::join_pair`: a space unless `before` already ends with one,
+/// the text starts with closing punctuation or either side is in a script
+/// written without spaces, and sentence-aware casing.
+pub fn fit_to_context(text: &str, before: &str) -> String {
+    let text = text.trim_start();
+    let before_trimmed = before.trim_end();
+    let trailing = &before[before_trimmed.len()..];
+    if before_trimmed.is_empty() || trailing.contains('\n') {
+        return text_join::capitalize_first(text);
+    }
+
+    let joined = text_join::join_pair(before_trimmed, &format!(" {text}"));
+    // A hyphenated word split at the caret cannot be merged by inserting text
+    let Some(fitted) = joined.strip_prefix(before_trimmed) else {
+        return text.to_string();
+    };
+    if trailing.is_empty() {
+        fitted.to_string()
+    } else {
+        fitted.trim_start().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_continues_a_sentence() {
+        assert_eq!(
+            fit_to_context("And bought milk.", "I went to the store"),
+            " and bought milk."
+        );
+        assert_eq!(fit_to_context("The cake.", "half of "), "the cake.");
+        assert_eq!(fit_to_context("Paris.", "a flight to"), " Paris.");
+        assert_eq!(fit_to_context(", then left.", "I waited"), ", then left.");
+        assert_eq!(fit_to_context("NASA.", "working at"), " NASA.");
+    }
+
+    #[test]
+    fn test_continues_unspaced_script() {
+        assert_eq!(fit_to_context("去公园。", "我们今天"), "去公园。");
+    }
+
+    #[test]
+    fn test_starts_a_sentence() {
+        assert_eq!(
+            fit_to_context("then we ship.", "It works."),
+            " Then we ship."
+        );
+        assert_eq!(
+            fit_to_context("then we ship.", "It works. "),
+            "Then we ship."
+        );
+        assert_eq!(fit_to_context("hello there.", ""), "Hello there.");
+        assert_eq!(
+            fit_to_context("hello there.", "Dear Ann,\n"),
+            "Hello there."
+        );
+    }
+
+    #[test]
+    fn test_leaves_hyphen_at_caret_alone() {
+        assert_eq!(fit_to_context("ple of this", "an exam-"), "ple of this");
+    }
+}
@@ -4,10 +4,75 @@
 //! keystroke after text has been copied to the clipboard. It follows
 //! the graceful degradation pattern: if insertion fails, the text
 //! remains in the clipboard for manual pasting.
+//!
+//! Some clipboard-sync and snippet tools paste on their own as soon as the
+//! clipboard changes. To avoid inserting the text twice, the service can watch
+//! for a Cmd+V from another source between the clipboard copy and our paste.
+
+use std::time::{Duration, Instant};
 
 use crate::domain::CyranoError;
-use crate::infrastructure::keyboard;
+use crate::infrastructure::{keyboard, workspace};
 use crate::services::output_service;
+use crate::services::settings_service::{self, Settings};
+
+/// How long to watch for a paste from another source after copying.
+const EXTERNAL_PASTE_WINDOW: Duration = Duration::from_millis(150);
+
+/// Cmd+V watch started right before text is copied to the clipboard.
+#[derive(Debug, Clone)]
+pub struct ExternalPasteWatch {
+    shortcut: keyboard::PasteShortcutWatch,
+    started_at: Instant,
+}
+
+/// Check whether duplicate paste detection applies to the given app.
+fn detection_enabled_for(settings: &Settings, bundle_id: Option<&str>) -> bool {
+    settings.skip_duplicate_paste
+        || bundle_id.is_some_and(|id| {
+            settings
+                .duplicate_paste_apps
+                .iter()
+                .any(|app| app.eq_ignore_ascii_case(id))
+        })
+}
+
+/// Start watching for a paste from another source.
+///
+/// Must be called BEFORE the text is copied to the clipboard, since
+/// auto-paste tools react to the clipboard change itself.
+///
+/// # Returns
+/// * `Some(ExternalPasteWatch)` if detection is enabled for the frontmost app
+/// * `None` if detection is disabled or the keyboard cannot be watched
+pub fn watch_for_external_paste() -> Option<ExternalPasteWatch> {
+    let bundle_id = workspace::frontmost_bundle_id();
+    if !detection_enabled_for(&settings_service::get_settings(), bundle_id.as_deref()) {
+        return None;
+    }
+
+    let started_at = Instant::now();
+    let Some(shortcut) = keyboard::watch_paste_shortcut(EXTERNAL_PASTE_WINDOW) else {
+        log::warn!("Cannot watch for external paste - event tap unavailable");
+        return None;
+    };
+    log::debug!("Watching for external paste (frontmost app: {bundle_id:?})");
+    Some(ExternalPasteWatch {
+        shortcut,
+        started_at,
+    })
+}
+
+/// Wait out the watch window and report whether another source pasted.
+///
+/// Only Cmd+V counts, whether an auto-paste tool posted it or the user
+/// pressed it. Other keystrokes in the window are ignored.
+pub fn external_paste_observed(watch: &ExternalPasteWatch) -> bool {
+    if let Some(remaining) = EXTERNAL_PASTE_WINDOW.checked_sub(watch.started_at.elapsed()) {
+        std::thread::sleep(remaining);
+    }
+    watch.shortcut.pasted()
+}
 
 /// Insert text at the current cursor position.
 ///
@@ -16,7 +81,8 @@ use crate::services::output_service;
 /// already been copied to the clipboard.
 ///
 /// # Returns
-/// * `Ok(())` always - this function uses graceful degradation
+/// * `Ok(true)` if the Cmd+V keystroke was posted
+/// * `Ok(false)` if it was not, the text then stays on the clipboard only
 ///
 /// # Graceful Degradation
 /// This function NEVER returns an error to the caller. The philosophy is:
@@ -24,39 +90,46 @@ use crate::services::output_service;
 /// - If paste simulation fails, user can still paste manually
 /// - No error visible to user - this is a bonus feature, not critical
 ///
+/// Callers must not touch the clipboard again when it returns `Ok(false)`,
+/// as the user still has to paste the text by hand.
+///
 /// # Prerequisites
 /// - Text must already be on the clipboard
 /// - Accessibility permission should be granted (checked internally)
-///
-/// # Notes
-/// - If accessibility permission is not granted, the function returns
-///   `Ok(())` without attempting paste simulation (graceful skip).
-/// - If paste simulation fails, the error is logged but `Ok(())` is returned.
-pub fn insert_at_cursor() -> Result<(), CyranoError> {
-    // Check if cursor insertion is available (accessibility permission granted)
-    if !output_service::is_cursor_insertion_available() {
+pub fn insert_at_cursor() -> Result<bool, CyranoError> {
+    Ok(paste_if_available(
+        output_service::is_cursor_insertion_available(),
+        keyboard::simulate_paste,
+    ))
+}
+
+/// Post the paste keystroke if cursor insertion is available, and report
+/// whether it was posted.
+fn paste_if_available(
+    available: bool,
+    simulate_paste: impl FnOnce() -> Result<(), std::io::Error>,
+) -> bool {
+    if !available {
         log::debug!("Cursor insertion skipped: accessibility permission not granted");
-        return Ok(()); // Graceful degradation - not an error
+        return false;
     }
 
     // Small delay to ensure clipboard is ready after write
     // This improves reliability across different applications
     std::thread::sleep(std::time::Duration::from_millis(20));
 
-    // Attempt to simulate Cmd+V paste
-    match keyboard::simulate_paste() {
+    match simulate_paste() {
         Ok(()) => {
             log::info!("Cursor insertion successful via Cmd+V simulation");
-            Ok(())
+            true
         }
         Err(e) => {
-            // Log the error but return Ok - graceful degradation
             // The text is already in the clipboard, user can paste manually
             log::warn!(
                 "Cursor insertion failed, but text is in clipboard for manual paste: {}",
                 e
             );
-            Ok(()) // Still return Ok - this is graceful degradation
+            false
         }
     }
 }
@@ -65,6 +138,41 @@ pub fn insert_at_cursor() -> Result<(), CyranoError> {
 mod tests {
     use super::*;
 
+    fn settings_with(skip: bool, apps: &[&str]) -> Settings {
+        Settings {
+            skip_duplicate_paste: skip,
+            duplicate_paste_apps: apps.iter().map(|a| a.to_string()).collect(),
+            ..Settings::default()
+        }
+    }
+
+    #[test]
+    fn test_detection_disabled_by_default() {
+        assert!(!detection_enabled_for(
+            &Settings::default(),
+            Some("com.apple.TextEdit")
+        ));
+        assert!(!detection_enabled_for(&Settings::default(), None));
+    }
+
+    #[test]
+    fn test_detection_enabled_globally() {
+        let settings = settings_with(true, &[]);
+        assert!(detection_enabled_for(&settings, Some("com.apple.TextEdit")));
+        assert!(detection_enabled_for(&settings, None));
+    }
+
+    #[test]
+    fn test_detection_enabled_per_app() {
+        let settings = settings_with(false, &["com.example.Editor"]);
+        assert!(detection_enabled_for(&settings, Some("com.example.editor")));
+        assert!(!detection_enabled_for(
+            &settings,
+            Some("com.apple.TextEdit")
+        ));
+        assert!(!detection_enabled_for(&settings, None));
+    }
+
     #[test]
     fn test_insert_at_cursor_never_panics() {
         // This test verifies the function executes without panic.
@@ -78,8 +186,21 @@ mod tests {
     #[test]
     fn test_insert_at_cursor_returns_ok_type() {
         // Verify the return type is correct
-        let result: Result<(), CyranoError> = insert_at_cursor();
+        let result: Result<bool, CyranoError> = insert_at_cursor();
         // Should be Ok regardless of system state
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_failed_paste_is_reported() {
+        let failed = paste_if_available(true, || Err(std::io::Error::other("no event")));
+        assert!(!failed);
+        assert!(paste_if_available(true, || Ok(())));
+    }
+
+    #[test]
+    fn test_paste_skipped_without_permission() {
+        let pasted = paste_if_available(false, || panic!("must not paste"));
+        assert!(!pasted);
+    }
 }
@@ -0,0 +1,419 @@
+//! Data directory bootstrap and migration.
+//!
+//! Owns the `~/.cyrano` layout used by every other service:
+//!
+//! ```text
+//! ~/.cyrano/
+//! ├── models/       Whisper ggml models (*.bin)
+//! ├── logs/         Log files
+//! ├── history/      Transcription history
+//! ├── recordings/   Saved audio recordings
+//! └── plugins/      User-provided post-processing plugins
+//! ```
+//!
+//! On first run (and on demand via `repair_data_directories`) the layout is
+//! created, each directory is checked for write access, and files left over
+//! from the legacy flat layout are moved to their new home.
+
+use std::path::{Path, PathBuf};
+
+use crate::domain::CyranoError;
+
+/// Name of the data root directory inside the user's home.
+const DATA_ROOT_NAME: &str = ".cyrano";
+
+/// Subdirectory for Whisper models.
+pub const MODELS_DIR_NAME: &str = "models";
+/// Subdirectory for log files.
+pub const LOGS_DIR_NAME: &str = "logs";
+/// Subdirectory for transcription history.
+pub const HISTORY_DIR_NAME: &str = "history";
+/// Subdirectory for saved recordings.
+pub const RECORDINGS_DIR_NAME: &str = "recordings";
+/// Subdirectory for post-processing plugins.
+pub const PLUGINS_DIR_NAME: &str = "plugins";
+
+/// All subdirectories created by the bootstrap, in creation order.
+const SUBDIRECTORIES: [&str; 5] = [
+    MODELS_DIR_NAME,
+    LOGS_DIR_NAME,
+    HISTORY_DIR_NAME,
+    RECORDINGS_DIR_NAME,
+    PLUGINS_DIR_NAME,
+];
+
+/// File used to probe write access. Removed immediately after the check.
+const WRITE_PROBE_FILE: &str = ".cyrano-write-test";
+
+/// Status of a single data directory after bootstrap.
+#[derive(Debug, Clone, serde::Serialize, specta::Type)]
+pub struct DirectoryStatus {
+    /// Subdirectory name (e.g., "models")
+    pub name: String,
+    /// Absolute path of the directory
+    pub path: String,
+    /// Whether the directory exists
+    pub exists: bool,
+    /// Whether a file could be created inside the directory
+    pub writable: bool,
+}
+
+/// Result of a bootstrap or repair run.
+#[derive(Debug, Clone, serde::Serialize, specta::Type)]
+pub struct DataDirectoryReport {
+    /// Absolute path of the data root (`~/.cyrano`)
+    pub root: String,
+    /// Status of each subdirectory
+    pub directories: Vec<DirectoryStatus>,
+    /// Human-readable descriptions of migrated legacy files
+    pub migrated: Vec<String>,
+}
+
+impl DataDirectoryReport {
+    /// Whether every directory exists and is writable.
+    pub fn is_healthy(&self) -> bool {
+        self.directories.iter().all(|d| d.exists && d.writable)
+    }
+}
+
+/// Get the data root directory (`~/.cyrano`).
+pub fn data_root() -> Result<PathBuf, CyranoError> {
+    let home = dirs::home_dir().ok_or_else(|| CyranoError::StorageFailed {
+        reason: "Could not resolve home directory".to_string(),
+    })?;
+    Ok(home.join(DATA_ROOT_NAME))
+}
+
+/// Get the models directory (`~/.cyrano/models`).
+pub fn models_dir() -> Result<PathBuf, CyranoError> {
+    data_root().map(|root| root.join(MODELS_DIR_NAME))
+}
+
+/// Get the logs directory (`~/.cyrano/logs`).
+pub fn logs_dir() -> Result<PathBuf, CyranoError> {
+    data_root().map(|root| root.join(LOGS_DIR_NAME))
+}
+
+/// Get the history directory (`~/.cyrano/history`).
+pub fn history_dir() -> Result<PathBuf, CyranoError> {
+    data_root().map(|root| root.join(HISTORY_DIR_NAME))
+}
+
+/// Get the recordings directory (`~/.cyrano/recordings`).
+pub fn recordings_dir() -> Result<PathBuf, CyranoError> {
+    data_root().map(|root| root.join(RECORDINGS_DIR_NAME))
+}
+
+/// Get the plugins directory (`~/.cyrano/plugins`).
+pub fn plugins_dir() -> Result<PathBuf, CyranoError> {
+    data_root().map(|root| root.join(PLUGINS_DIR_NAME))
+}
+
+/// Create the full directory layout, migrate legacy files, and validate access.
+///
+/// Safe to call repeatedly: existing directories and files are left untouched.
+pub fn bootstrap() -> Result<DataDirectoryReport, CyranoError> {
+    let root = data_root()?;
+    let report = bootstrap_at(&root)?;
+
+    if report.is_healthy() {
+        log::info!("Data directories ready at {}", report.root);
+    } else {
+        log::warn!("Data directories not fully writable: {report:?}");
+    }
+
+    Ok(report)
+}
+
+/// Bootstrap the layout under an arbitrary root (used directly by tests).
+fn bootstrap_at(root: &Path) -> Result<DataDirectoryReport, CyranoError> {
+    std::fs::create_dir_all(root).map_err(|e| CyranoError::StorageFailed {
+        reason: format!("Failed to create {}: {e}", root.display()),
+    })?;
+
+    for name in SUBDIRECTORIES {
+        let dir = root.join(name);
+        if let Err(e) = std::fs::create_dir_all(&dir) {
+            // Keep going: the report records the missing directory
+            log::error!("Failed to create {}: {e}", dir.display());
+        }
+    }
+
+    let migrated = migrate_legacy_layout(root);
+    Ok(report_at(root, migrated))
+}
+
+/// Report the state of the layout without creating or moving anything.
+pub fn inspect() -> Result<DataDirectoryReport, CyranoError> {
+    let root = data_root()?;
+    Ok(report_at(&root, Vec::new()))
+}
+
+fn report_at(root: &Path, migrated: Vec<String>) -> DataDirectoryReport {
+    let directories = SUBDIRECTORIES
+        .iter()
+        .map(|name| {
+            let path = root.join(name);
+            let exists = path.is_dir();
+            DirectoryStatus {
+                name: name.to_string(),
+                path: path.display().to_string(),
+                exists,
+                writable: exists && is_writable(&path),
+            }
+        })
+        .collect();
+
+    DataDirectoryReport {
+        root: root.display().to_string(),
+        directories,
+        migrated,
+    }
+}
+
+/// Move files from the legacy flat layout into their subdirectories.
+///
+/// Early versions expected models and logs directly in `~/.cyrano`.
+/// Files are only moved when the destination does not already exist.
+fn migrate_legacy_layout(root: &Path) -> Vec<String> {
+    let mut migrated = Vec::new();
+
+    let entries = match std::fs::read_dir(root) {
+        Ok(entries) => entries,
+        Err(e) => {
+            log::warn!("Failed to scan {} for legacy files: {e}", root.display());
+            return migrated;
+        }
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+
+        let target_dir = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("bin") => MODELS_DIR_NAME,
+            Some("log") => LOGS_DIR_NAME,
+            _ => continue,
+        };
+
+        let Some(file_name) = path.file_name() else {
+            continue;
+        };
+        let destination = root.join(target_dir).join(file_name);
+        if destination.exists() {
+            log::warn!(
+                "Skipping legacy file {}: {} already exists",
+                path.display(),
+                destination.display()
+            );
+            continue;
+        }
+
+        match std::fs::rename(&path, &destination) {
+            Ok(()) => {
+                log::info!(
+                    "Migrated legacy file {} to {}",
+                    path.display(),
+                    destination.display()
+                );
+                migrated.push(format!("{} -> {}", file_name.to_string_lossy(), target_dir));
+            }
+            Err(e) => log::warn!("Failed to migrate {}: {e}", path.display()),
+        }
+    }
+
+    migrated
+}
+
+/// Write a file atomically (temp file + rename), creating parent directories.
+///
+/// Readers never observe a partially written file: they see either the old
+/// contents or the new ones. The temp file is `<name>.tmp`, so files that
+/// only differ by extension (`<id>.json`, `<id>.flac`) never share one.
+pub fn write_file_atomically(path: &Path, contents: &[u8]) -> Result<(), CyranoError> {
+    write_atomically(path, contents, false)
+}
+
+/// Write a file atomically like [`write_file_atomically`], readable by the
+/// current user only (mode 0600 on Unix), for files holding credentials.
+pub fn write_private_file_atomically(path: &Path, contents: &[u8]) -> Result<(), CyranoError> {
+    write_atomically(path, contents, true)
+}
+
+fn write_atomically(path: &Path, contents: &[u8], private: bool) -> Result<(), CyranoError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| CyranoError::StorageFailed {
+            reason: format!("Failed to create {}: {e}", parent.display()),
+        })?;
+    }
+
+    let temp_path = temp_path_for(path);
+    write_temp_file(&temp_path, contents, private).map_err(|e| CyranoError::StorageFailed {
+        reason: format!("Failed to write {}: {e}", temp_path.display()),
+    })?;
+
+    if let Err(rename_err) = std::fs::rename(&temp_path, path) {
+        // Clean up the temp file to avoid leaving orphaned files on disk
+        if let Err(remove_err) = std::fs::remove_file(&temp_path) {
+            log::warn!("Failed to remove temp file after rename failure: {remove_err}");
+        }
+        return Err(CyranoError::StorageFailed {
+            reason: format!("Failed to finalize {}: {rename_err}", path.display()),
+        });
+    }
+
+    Ok(())
+}
+
+/// Write the temp file, restricted to the current user when `private`.
+///
+/// The rename keeps the temp file's mode, so the final file gets it too.
+fn write_temp_file(path: &Path, contents: &[u8], private: bool) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    if private {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+
+    // A temp file left behind by a crash keeps its old mode
+    #[cfg(unix)]
+    if private {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+    #[cfg(not(unix))]
+    let _ = private;
+
+    file.write_all(contents)
+}
+
+/// Temp file used while writing `path`: `<name>.tmp` next to it.
+fn temp_path_for(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+/// Check write access by creating and removing a probe file.
+fn is_writable(dir: &Path) -> bool {
+    let probe = dir.join(WRITE_PROBE_FILE);
+    match std::fs::write(&probe, b"") {
+        Ok(()) => {
+            if let Err(e) = std::fs::remove_file(&probe) {
+                log::warn!("Failed to remove write probe {}: {e}", probe.display());
+            }
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::temp_dir;
+
+    #[test]
+    fn test_write_file_atomically_replaces_contents() {
+        let root = temp_dir("atomic");
+        let path = root.join("nested").join("file.json");
+
+        write_file_atomically(&path, b"first").expect("first write should succeed");
+        write_file_atomically(&path, b"second").expect("second write should succeed");
+
+        assert_eq!(std::fs::read(&path).unwrap(), b"second");
+        assert!(!temp_path_for(&path).exists());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_private_file_is_readable_by_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = temp_dir("private");
+        let path = root.join("settings.json");
+        std::fs::write(&path, b"{}").unwrap();
+        std::fs::write(temp_path_for(&path), b"stale").unwrap();
+
+        write_private_file_atomically(&path, b"secret").expect("write should succeed");
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(std::fs::read(&path).unwrap(), b"secret");
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_temp_path_keeps_extension() {
+        let entry = Path::new("/history/1700000000.json");
+        let audio = Path::new("/history/1700000000.flac");
+        assert_eq!(
+            temp_path_for(entry),
+            PathBuf::from("/history/1700000000.json.tmp")
+        );
+        assert_ne!(temp_path_for(entry), temp_path_for(audio));
+    }
+
+    #[test]
+    fn test_data_root_path_resolution() {
+        let root = data_root().expect("home directory should resolve");
+        assert!(root.ends_with(".cyrano"));
+        assert!(models_dir().unwrap().ends_with("models"));
+        assert!(recordings_dir().unwrap().ends_with("recordings"));
+    }
+
+    #[test]
+    fn test_bootstrap_creates_layout() {
+        let root = temp_dir("bootstrap");
+        let report = bootstrap_at(&root).expect("bootstrap should succeed");
+
+        assert_eq!(report.directories.len(), SUBDIRECTORIES.len());
+        assert!(report.is_healthy());
+        for name in SUBDIRECTORIES {
+            assert!(root.join(name).is_dir(), "{name} should exist");
+        }
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_bootstrap_migrates_legacy_files() {
+        let root = temp_dir("migrate");
+        std::fs::write(root.join("ggml-base.en.bin"), b"model").unwrap();
+        std::fs::write(root.join("cyrano.log"), b"log").unwrap();
+        std::fs::write(root.join("notes.txt"), b"keep").unwrap();
+
+        let report = bootstrap_at(&root).expect("bootstrap should succeed");
+
+        assert_eq!(report.migrated.len(), 2);
+        assert!(root.join("models").join("ggml-base.en.bin").exists());
+        assert!(root.join("logs").join("cyrano.log").exists());
+        assert!(root.join("notes.txt").exists());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_bootstrap_does_not_overwrite_existing_models() {
+        let root = temp_dir("conflict");
+        std::fs::create_dir_all(root.join("models")).unwrap();
+        std::fs::write(root.join("models").join("ggml-tiny.bin"), b"new").unwrap();
+        std::fs::write(root.join("ggml-tiny.bin"), b"old").unwrap();
+
+        let report = bootstrap_at(&root).expect("bootstrap should succeed");
+
+        assert!(report.migrated.is_empty());
+        let kept = std::fs::read(root.join("models").join("ggml-tiny.bin")).unwrap();
+        assert_eq!(kept, b"new");
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,569 @@
+//! Diagnostics service.
+//!
+//! Collects a snapshot of everything that commonly breaks dictation
+//! (permissions, model availability, sandbox restrictions) into a single
+//! report the frontend can display or users can attach to bug reports.
+//!
+//! A quicker self-test runs at startup (audio host, global shortcut,
+//! recording overlay, models directory). Its report is published with a
+//! startup-report event and summarized in the diagnostics, so a feature that
+//! silently failed to initialize is visible right away.
+
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use cpal::traits::HostTrait;
+use tauri::AppHandle;
+
+use crate::domain::PermissionStatus;
+use crate::infrastructure::audio::quality::RecordingQuality;
+use crate::services::sandbox_service::{self, SandboxReport};
+use crate::services::{
+    accessibility_service, data_directory_service, hallucination_service, notifier_service,
+    permission_service, recording_state, safe_mode_service, transcription_service,
+};
+
+/// Severity of a single diagnostic check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, specta::Type)]
+pub enum DiagnosticStatus {
+    /// The check passed.
+    Ok,
+    /// The feature works in a degraded mode.
+    Warning,
+    /// The feature is unavailable.
+    Error,
+}
+
+/// A single diagnostic check result.
+#[derive(Debug, Clone, serde::Serialize, specta::Type)]
+pub struct DiagnosticEntry {
+    /// Short identifier of the check (e.g., "microphone")
+    pub name: String,
+    /// Outcome of the check
+    pub status: DiagnosticStatus,
+    /// Human-readable explanation, including the fallback in use if any
+    pub detail: String,
+}
+
+impl DiagnosticEntry {
+    fn new(name: &str, status: DiagnosticStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Full diagnostics report returned to the frontend.
+#[derive(Debug, Clone, serde::Serialize, specta::Type)]
+pub struct DiagnosticsReport {
+    /// Unix timestamp in seconds when the report was generated
+    pub generated_at: u32,
+    /// Individual check results
+    pub entries: Vec<DiagnosticEntry>,
+    /// Raw sandbox audit results
+    pub sandbox: SandboxReport,
+}
+
+/// Result of the startup self-test.
+#[derive(Debug, Clone, serde::Serialize, specta::Type)]
+pub struct StartupReport {
+    /// Unix timestamp in seconds when the self-test ran
+    pub generated_at: u32,
+    /// Individual check results
+    pub entries: Vec<DiagnosticEntry>,
+    /// Whether a check failed, leaving a feature unavailable
+    pub degraded: bool,
+    /// Whether the app started in safe mode, showing only diagnostics
+    pub safe_mode: bool,
+}
+
+/// Checks recorded by the setup steps, until the self-test completes.
+static STARTUP_CHECKS: Mutex<Vec<DiagnosticEntry>> = Mutex::new(Vec::new());
+
+/// Report of the self-test that ran at startup.
+static STARTUP_REPORT: Mutex<Option<StartupReport>> = Mutex::new(None);
+
+/// Get the current Unix timestamp in seconds.
+fn get_timestamp_secs() -> u32 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as u32)
+        .unwrap_or(0)
+}
+
+/// Run all diagnostic checks.
+///
+/// # Arguments
+/// * `app` - The Tauri app handle
+pub fn run_diagnostics(app: &AppHandle) -> DiagnosticsReport {
+    let sandbox = sandbox_service::audit(app);
+
+    let mut entries = vec![
+        microphone_entry(permission_service::check_microphone_permission()),
+        accessibility_entry(accessibility_service::check_accessibility_permission()),
+        model_entry(),
+        data_directory_entry(),
+        hallucination_entry(&hallucination_service::blocked_counts()),
+        recording_quality_entry(
+            recording_state::get_last_transcription()
+                .and_then(|last| last.quality)
+                .as_ref(),
+        ),
+        startup_entry(get_startup_report().as_ref()),
+    ];
+    entries.extend(sandbox_entries(&sandbox));
+
+    log::info!("Diagnostics completed with {} entries", entries.len());
+
+    DiagnosticsReport {
+        generated_at: get_timestamp_secs(),
+        entries,
+        sandbox,
+    }
+}
+
+fn microphone_entry(status: PermissionStatus) -> DiagnosticEntry {
+    match status {
+        PermissionStatus::Granted => {
+            DiagnosticEntry::new("microphone", DiagnosticStatus::Ok, "Microphone accessible")
+        }
+        other => DiagnosticEntry::new(
+            "microphone",
+            DiagnosticStatus::Error,
+            format!("Microphone not accessible ({other:?})"),
+        ),
+    }
+}
+
+fn accessibility_entry(status: PermissionStatus) -> DiagnosticEntry {
+    match status {
+        PermissionStatus::Granted => DiagnosticEntry::new(
+            "accessibility",
+            DiagnosticStatus::Ok,
+            "Accessibility granted, cursor insertion enabled",
+        ),
+        _ => DiagnosticEntry::new(
+            "accessibility",
+            DiagnosticStatus::Warning,
+            "Accessibility not granted, using clipboard-only output",
+        ),
+    }
+}
+
+fn model_entry() -> DiagnosticEntry {
+    match transcription_service::get_models_directory() {
+        Ok(dir) if dir.exists() => DiagnosticEntry::new(
+            "model",
+            DiagnosticStatus::Ok,
+            format!("Models directory found at {}", dir.display()),
+        ),
+        Ok(dir) => DiagnosticEntry::new(
+            "model",
+            DiagnosticStatus::Error,
+            format!("Models directory missing at {}", dir.display()),
+        ),
+        Err(e) => DiagnosticEntry::new("model", DiagnosticStatus::Error, e.to_string()),
+    }
+}
+
+fn data_directory_entry() -> DiagnosticEntry {
+    match data_directory_service::inspect() {
+        Ok(report) if report.is_healthy() => DiagnosticEntry::new(
+            "data_directory",
+            DiagnosticStatus::Ok,
+            format!("Data directories writable at {}", report.root),
+        ),
+        Ok(report) => {
+            let broken: Vec<&str> = report
+                .directories
+                .iter()
+                .filter(|d| !d.exists || !d.writable)
+                .map(|d| d.name.as_str())
+                .collect();
+            DiagnosticEntry::new(
+                "data_directory",
+                DiagnosticStatus::Error,
+                format!("Not writable: {}", broken.join(", ")),
+            )
+        }
+        Err(e) => DiagnosticEntry::new("data_directory", DiagnosticStatus::Error, e.to_string()),
+    }
+}
+
+fn hallucination_entry(counts: &[(String, u32)]) -> DiagnosticEntry {
+    if counts.is_empty() {
+        return DiagnosticEntry::new(
+            "hallucination_filter",
+            DiagnosticStatus::Ok,
+            "No transcription dropped",
+        );
+    }
+    let total: u32 = counts.iter().map(|(_, count)| count).sum();
+    let phrases: Vec<String> = counts
+        .iter()
+        .map(|(phrase, count)| format!("\"{phrase}\" ({count})"))
+        .collect();
+    DiagnosticEntry::new(
+        "hallucination_filter",
+        DiagnosticStatus::Ok,
+        format!(
+            "Dropped {total} transcription(s) matching: {}",
+            phrases.join(", ")
+        ),
+    )
+}
+
+/// Quality of the last dictation's recording, warning about the issues
+/// that explain a bad transcription.
+fn recording_quality_entry(quality: Option<&RecordingQuality>) -> DiagnosticEntry {
+    let Some(quality) = quality else {
+        return DiagnosticEntry::new(
+            "recording_quality",
+            DiagnosticStatus::Ok,
+            "No dictation since launch",
+        );
+    };
+    let measures = format!(
+        "SNR {:.0} dB, {:.1}% clipped, {:.0}% silence, dominant level {:.0} dBFS",
+        quality.snr_db,
+        quality.clipping_ratio * 100.0,
+        quality.silence_ratio * 100.0,
+        quality.dominant_level_db
+    );
+    let issues = quality.issues();
+    if issues.is_empty() {
+        DiagnosticEntry::new("recording_quality", DiagnosticStatus::Ok, measures)
+    } else {
+        DiagnosticEntry::new(
+            "recording_quality",
+            DiagnosticStatus::Warning,
+            format!("{measures}: {}", issues.join(", ")),
+        )
+    }
+}
+
+/// Record the outcome of a startup step for the self-test, e.g. creating the
+/// recording overlay.
+pub fn record_startup_check(name: &str, result: Result<(), String>) {
+    let entry = match result {
+        Ok(()) => DiagnosticEntry::new(name, DiagnosticStatus::Ok, "Initialized"),
+        Err(reason) => DiagnosticEntry::new(name, DiagnosticStatus::Error, reason),
+    };
+    STARTUP_CHECKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(entry);
+}
+
+/// Complete the startup self-test with the checks recorded during setup.
+///
+/// Failed checks are logged; the app keeps running in degraded mode.
+pub fn run_startup_self_test() -> StartupReport {
+    let mut entries = vec![audio_host_entry(), models_directory_entry()];
+    entries.append(&mut STARTUP_CHECKS.lock().unwrap_or_else(|e| e.into_inner()));
+    let report = build_startup_report(
+        entries,
+        get_timestamp_secs(),
+        safe_mode_service::is_safe_mode(),
+    );
+
+    for entry in report
+        .entries
+        .iter()
+        .filter(|e| e.status != DiagnosticStatus::Ok)
+    {
+        log::warn!("Startup check {} failed: {}", entry.name, entry.detail);
+    }
+    log::info!(
+        "Startup self-test completed ({} checks, degraded: {})",
+        report.entries.len(),
+        report.degraded
+    );
+
+    *STARTUP_REPORT.lock().unwrap_or_else(|e| e.into_inner()) = Some(report.clone());
+    report
+}
+
+fn build_startup_report(
+    entries: Vec<DiagnosticEntry>,
+    generated_at: u32,
+    safe_mode: bool,
+) -> StartupReport {
+    StartupReport {
+        generated_at,
+        degraded: entries.iter().any(|e| e.status != DiagnosticStatus::Ok),
+        entries,
+        safe_mode,
+    }
+}
+
+/// The report of the startup self-test, once it ran.
+pub fn get_startup_report() -> Option<StartupReport> {
+    STARTUP_REPORT
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// Emit startup-report with the self-test results.
+///
+/// Called each time the main window finishes loading, so it is never
+/// emitted before a window can listen.
+pub fn announce_startup_report(app: &AppHandle) {
+    if let Some(report) = get_startup_report() {
+        if let Err(e) = notifier_service::emit(app, "startup-report", report) {
+            log::error!("Failed to emit startup-report event: {e}");
+        }
+    }
+}
+
+fn audio_host_entry() -> DiagnosticEntry {
+    let host = cpal::default_host();
+    match host.input_devices() {
+        Err(e) => DiagnosticEntry::new(
+            "audio_host",
+            DiagnosticStatus::Error,
+            format!("Audio host unreachable: {e}"),
+        ),
+        Ok(_) if host.default_input_device().is_none() => DiagnosticEntry::new(
+            "audio_host",
+            DiagnosticStatus::Error,
+            "No input device available",
+        ),
+        Ok(_) => DiagnosticEntry::new("audio_host", DiagnosticStatus::Ok, "Audio host reachable"),
+    }
+}
+
+fn models_directory_entry() -> DiagnosticEntry {
+    let listing = transcription_service::get_models_directory()
+        .map_err(|e| e.to_string())
+        .and_then(|dir| {
+            std::fs::read_dir(&dir).map_err(|e| format!("Cannot read {}: {e}", dir.display()))
+        });
+    match listing {
+        Ok(_) => DiagnosticEntry::new(
+            "models_directory",
+            DiagnosticStatus::Ok,
+            "Models directory readable",
+        ),
+        Err(reason) => DiagnosticEntry::new("models_directory", DiagnosticStatus::Error, reason),
+    }
+}
+
+/// Summarize the startup self-test as a single diagnostic entry.
+fn startup_entry(report: Option<&StartupReport>) -> DiagnosticEntry {
+    let Some(report) = report else {
+        return DiagnosticEntry::new(
+            "startup",
+            DiagnosticStatus::Warning,
+            "Startup self-test has not run",
+        );
+    };
+
+    let failed: Vec<String> = report
+        .entries
+        .iter()
+        .filter(|e| e.status != DiagnosticStatus::Ok)
+        .map(|e| format!("{} ({})", e.name, e.detail))
+        .collect();
+    if failed.is_empty() {
+        DiagnosticEntry::new(
+            "startup",
+            DiagnosticStatus::Ok,
+            format!("All {} startup checks passed", report.entries.len()),
+        )
+    } else {
+        DiagnosticEntry::new(
+            "startup",
+            DiagnosticStatus::Warning,
+            format!("Running in degraded mode: {}", failed.join(", ")),
+        )
+    }
+}
+
+/// Translate the sandbox audit into diagnostic entries, describing the fallback used.
+fn sandbox_entries(report: &SandboxReport) -> Vec<DiagnosticEntry> {
+    let mut entries = Vec::new();
+
+    entries.push(if report.clipboard_available {
+        DiagnosticEntry::new("clipboard", DiagnosticStatus::Ok, "Clipboard readable")
+    } else {
+        DiagnosticEntry::new(
+            "clipboard",
+            DiagnosticStatus::Warning,
+            "Clipboard could not be read (it may hold non-text content)",
+        )
+    });
+
+    entries.push(if report.event_posting_available {
+        DiagnosticEntry::new(
+            "event_posting",
+            DiagnosticStatus::Ok,
+            "Paste simulation allowed",
+        )
+    } else if report.sandboxed {
+        DiagnosticEntry::new(
+            "event_posting",
+            DiagnosticStatus::Warning,
+            "App Sandbox blocks paste simulation, using clipboard-only output",
+        )
+    } else {
+        DiagnosticEntry::new(
+            "event_posting",
+            DiagnosticStatus::Warning,
+            "Paste simulation not permitted, using clipboard-only output",
+        )
+    });
+
+    entries.push(if report.home_directory_accessible {
+        DiagnosticEntry::new(
+            "home_directory",
+            DiagnosticStatus::Ok,
+            "Home directory accessible",
+        )
+    } else {
+        DiagnosticEntry::new(
+            "home_directory",
+            DiagnosticStatus::Warning,
+            format!(
+                "Home directory is a sandbox container ({}), models must be placed there",
+                report.home_directory.as_deref().unwrap_or("unknown")
+            ),
+        )
+    });
+
+    entries.push(DiagnosticEntry::new(
+        "runtime",
+        DiagnosticStatus::Ok,
+        format!(
+            "sandboxed={}, hardened_runtime={}",
+            report.sandboxed, report.hardened_runtime
+        ),
+    ));
+
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sandboxed_report() -> SandboxReport {
+        SandboxReport {
+            sandboxed: true,
+            hardened_runtime: true,
+            clipboard_available: true,
+            event_posting_available: false,
+            home_directory_accessible: false,
+            home_directory: Some("/Users/me/Library/Containers/app/Data".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_sandbox_entries_report_fallbacks() {
+        let entries = sandbox_entries(&sandboxed_report());
+        let posting = entries
+            .iter()
+            .find(|e| e.name == "event_posting")
+            .expect("event_posting entry should exist");
+        assert_eq!(posting.status, DiagnosticStatus::Warning);
+        assert!(posting.detail.contains("clipboard-only"));
+
+        let home = entries
+            .iter()
+            .find(|e| e.name == "home_directory")
+            .expect("home_directory entry should exist");
+        assert_eq!(home.status, DiagnosticStatus::Warning);
+        assert!(home.detail.contains("Containers"));
+    }
+
+    #[test]
+    fn test_microphone_entry_denied_is_error() {
+        let entry = microphone_entry(PermissionStatus::Denied);
+        assert_eq!(entry.status, DiagnosticStatus::Error);
+    }
+
+    #[test]
+    fn test_hallucination_entry_lists_dropped_phrases() {
+        let entry = hallucination_entry(&[
+            ("Subtitles by…".to_string(), 1),
+            ("Thank you for watching".to_string(), 2),
+        ]);
+        assert_eq!(entry.status, DiagnosticStatus::Ok);
+        assert!(entry.detail.starts_with("Dropped 3 transcription(s)"));
+        assert!(entry.detail.contains("\"Thank you for watching\" (2)"));
+    }
+
+    #[test]
+    fn test_recording_quality_entry_warns_about_issues() {
+        let mut quality = RecordingQuality {
+            snr_db: 35.0,
+            clipping_ratio: 0.0,
+            silence_ratio: 0.3,
+            dominant_level_db: -20.0,
+        };
+        let entry = recording_quality_entry(Some(&quality));
+        assert_eq!(entry.status, DiagnosticStatus::Ok);
+        assert_eq!(
+            entry.detail,
+            "SNR 35 dB, 0.0% clipped, 30% silence, dominant level -20 dBFS"
+        );
+
+        quality.clipping_ratio = 0.02;
+        let entry = recording_quality_entry(Some(&quality));
+        assert_eq!(entry.status, DiagnosticStatus::Warning);
+        assert!(entry.detail.ends_with(": clipping (input gain too high)"));
+
+        let entry = recording_quality_entry(None);
+        assert_eq!(entry.status, DiagnosticStatus::Ok);
+    }
+
+    #[test]
+    fn test_failed_startup_check_degrades_the_report() {
+        let report = build_startup_report(
+            vec![
+                DiagnosticEntry::new("audio_host", DiagnosticStatus::Ok, "Audio host reachable"),
+                DiagnosticEntry::new(
+                    "recording_overlay",
+                    DiagnosticStatus::Error,
+                    "window creation failed",
+                ),
+            ],
+            42,
+            false,
+        );
+        assert!(report.degraded);
+
+        let entry = startup_entry(Some(&report));
+        assert_eq!(entry.status, DiagnosticStatus::Warning);
+        assert_eq!(
+            entry.detail,
+            "Running in degraded mode: recording_overlay (window creation failed)"
+        );
+    }
+
+    #[test]
+    fn test_passing_startup_checks() {
+        let report = build_startup_report(
+            vec![DiagnosticEntry::new(
+                "audio_host",
+                DiagnosticStatus::Ok,
+                "ok",
+            )],
+            42,
+            true,
+        );
+        assert!(!report.degraded);
+        assert!(report.safe_mode);
+        assert_eq!(startup_entry(Some(&report)).status, DiagnosticStatus::Ok);
+        assert_eq!(startup_entry(None).status, DiagnosticStatus::Warning);
+    }
+
+    #[test]
+    fn test_diagnostic_status_serialization() {
+        let json = serde_json::to_string(&DiagnosticStatus::Warning).unwrap();
+        assert_eq!(json, "\"Warning\"");
+    }
+}
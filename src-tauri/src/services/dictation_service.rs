@@ -0,0 +1,627 @@
+//! Dictation orchestration.
+//!
+//! Drives the full "press shortcut, speak, paste" workflow:
+//! recording → model loading → transcription → output. Shortcut handlers
+//! and commands call into this service instead of sequencing the individual
+//! services themselves, so every entry point behaves the same way.
+//!
+//! Pressing the shortcut while a transcription is running either queues a new
+//! recording or discards the running transcription, depending on the
+//! `busy_shortcut_action` setting. With `Queue`, the running dictation is
+//! output as usual and the new recording starts once its pipeline finishes.
+//! With `Restart`, the dictation's cancellation token is cancelled: a
+//! transcription that has not started yet is skipped, and one already running
+//! in Whisper has its result discarded instead of output. The new recording
+//! then starts as with `Queue`.
+//!
+//! Each dictation gets its own cancellation token, so cancelling it never
+//! cancels background work. Background file transcriptions are aborted
+//! mid-run through Whisper's abort check as soon as a dictation starts
+//! recording, and run again once it is done.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use tauri::AppHandle;
+
+use crate::commands::recording_overlay;
+use crate::domain::{CyranoError, RecordingState};
+use crate::infrastructure::audio::quality;
+use crate::infrastructure::audio::test_signal::{self, TestSignal};
+use crate::infrastructure::whisper::{Segment, Transcription};
+use crate::infrastructure::workspace;
+use crate::services::output_service::OutputMode;
+use crate::services::recording_export_service::SessionTrace;
+use crate::services::recording_service::{
+    self, ClipboardCopiedPayload, ClipboardFailedPayload, RecordingFailedPayload,
+    TranscriptionCancelledPayload, TranscriptionCompletePayload, TranscriptionFailedPayload,
+    TranscriptionSegmentPayload, TranscriptionSentencePayload, TranscriptionStartedPayload,
+};
+use crate::services::recording_state::SpillBuffer;
+use crate::services::sentence_commit_service::SentenceCommitter;
+use crate::services::settings_service::{self, BusyShortcutAction, DictationOverrides};
+use crate::services::transcription_service::CancellationToken;
+use crate::services::{
+    caret_context_service, hallucination_service, history_service, notifier_service,
+    output_service, plugin_service, recording_export_service, recording_recovery_service,
+    recording_state, redo_service, retranscription_service, summary_service,
+    text_processing_service, transcription_service,
+};
+use crate::utils::text_sanitize;
+
+/// Time for the previous app to get focus back after Cyrano is hidden.
+const FOCUS_RETURN_DELAY: Duration = Duration::from_millis(200);
+
+/// Set when a new recording should start as soon as the running pipeline ends.
+static RECORDING_QUEUED: AtomicBool = AtomicBool::new(false);
+
+/// Overrides of the dictation being recorded, applied to its transcription.
+static DICTATION_OVERRIDES: Mutex<Option<DictationOverrides>> = Mutex::new(None);
+
+/// Payload for the recording-queued event.
+#[derive(Clone, serde::Serialize)]
+pub struct RecordingQueuedPayload {
+    /// Whether a recording is now queued (false if the press un-queued it)
+    pub queued: bool,
+    /// Whether the running transcription will be discarded
+    pub discard_current: bool,
+}
+
+/// Get the current Unix timestamp in milliseconds.
+fn get_timestamp_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Emit a recording-failed event, logging if the emit itself fails.
+fn emit_recording_failed(app: &AppHandle, error: CyranoError) {
+    if let Err(emit_err) =
+        notifier_service::emit(app, "recording-failed", RecordingFailedPayload::new(error))
+    {
+        log::error!("Failed to emit recording-failed event: {emit_err}");
+    }
+}
+
+/// Toggle dictation: stop and transcribe if recording, start recording otherwise.
+///
+/// While a transcription is running, applies the configured
+/// `BusyShortcutAction` instead of starting a second recording.
+pub fn toggle_dictation(app: &AppHandle) {
+    toggle_dictation_with(app, DictationOverrides::default());
+}
+
+/// Toggle dictation like [`toggle_dictation`], applying `overrides` to the
+/// dictation if this starts one (e.g. from a recording shortcut variant).
+pub fn toggle_dictation_with(app: &AppHandle, overrides: DictationOverrides) {
+    if recording_service::is_recording() {
+        stop_dictation(app);
+    } else if recording_state::get_recording_state() == RecordingState::Transcribing {
+        handle_shortcut_while_transcribing(app);
+    } else {
+        start_dictation_with(app, overrides);
+    }
+}
+
+/// Queue a new recording, or discard the running transcription and queue one.
+///
+/// With `Queue`, pressing again before the pipeline ends un-queues the recording.
+fn handle_shortcut_while_transcribing(app: &AppHandle) {
+    let action = settings_service::get_settings().busy_shortcut_action;
+    let payload = match action {
+        BusyShortcutAction::Queue => {
+            let queued = !RECORDING_QUEUED.fetch_xor(true, Ordering::SeqCst);
+            log::info!("Shortcut pressed while transcribing, recording queued: {queued}");
+            RecordingQueuedPayload {
+                queued,
+                discard_current: false,
+            }
+        }
+        BusyShortcutAction::Restart => {
+            log::info!("Shortcut pressed while transcribing, discarding current transcription");
+            transcription_service::request_cancellation();
+            RECORDING_QUEUED.store(true, Ordering::SeqCst);
+            RecordingQueuedPayload {
+                queued: true,
+                discard_current: true,
+            }
+        }
+    };
+
+    if let Err(e) = notifier_service::emit(app, "recording-queued", payload) {
+        log::error!("Failed to emit recording-queued event: {e}");
+    }
+}
+
+/// Drop a queued recording, e.g. when the user cancels from the overlay.
+pub fn clear_queued_recording() {
+    if RECORDING_QUEUED.swap(false, Ordering::SeqCst) {
+        log::info!("Queued recording cleared");
+    }
+}
+
+/// Start the queued recording, if any. Called when a pipeline finishes.
+fn start_queued_recording(app: &AppHandle) {
+    if RECORDING_QUEUED.swap(false, Ordering::SeqCst) {
+        log::info!("Starting queued recording");
+        start_dictation(app);
+    }
+}
+
+/// Start recording and show the recording overlay.
+///
+/// On failure the overlay is still shown so it can display the error carried
+/// by the recording-failed event.
+pub fn start_dictation(app: &AppHandle) {
+    start_dictation_with(app, DictationOverrides::default());
+}
+
+/// Start recording a dictation transcribed with `overrides`.
+///
+/// The app dictated into is frontmost now, so its per-app engine rule is
+/// applied unless `overrides` already set an engine.
+fn start_dictation_with(app: &AppHandle, mut overrides: DictationOverrides) {
+    // A new recording supersedes whatever could still be redone
+    redo_service::clear_last_dictation();
+    if overrides.engine.is_none() {
+        let bundle_id = workspace::frontmost_bundle_id();
+        overrides.engine = transcription_service::app_rule_engine(
+            &settings_service::get_settings(),
+            bundle_id.as_deref(),
+        );
+    }
+    if overrides != DictationOverrides::default() {
+        log::info!("Dictation overrides: {overrides:?}");
+    }
+    *DICTATION_OVERRIDES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner()) = Some(overrides.clone());
+
+    match recording_service::start_recording(app) {
+        Ok(()) => {
+            log::info!("Recording started successfully");
+            transcription_service::preload_model(&overrides);
+            // Show the recording overlay when recording starts
+            if let Err(e) = recording_overlay::show_recording_overlay(app.clone()) {
+                log::error!("Failed to show recording overlay: {e}");
+            }
+        }
+        Err(e) => {
+            log::error!("[{}] Failed to start recording: {e}", e.code());
+            // Show overlay first so it can receive the error event
+            if let Err(overlay_err) = recording_overlay::show_recording_overlay(app.clone()) {
+                log::error!("Failed to show recording overlay: {overlay_err}");
+            }
+            // Now emit the recording-failed event so the overlay displays error state
+            emit_recording_failed(app, e);
+        }
+    }
+}
+
+/// Stop recording and run the transcription pipeline on a background thread.
+pub fn stop_dictation(app: &AppHandle) {
+    // Create the cancellation token before entering the Transcribing state,
+    // so a press that discards this transcription is never lost
+    let cancel = transcription_service::begin_dictation();
+
+    match recording_service::stop_recording(app) {
+        Ok(payload) => {
+            log::info!(
+                "Recording stopped: {}ms, {} samples",
+                payload.duration_ms,
+                payload.sample_count
+            );
+            // Overlay stays visible, state transitions to Transcribing
+
+            let overrides = DICTATION_OVERRIDES
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .take()
+                .unwrap_or_default();
+
+            // Model loading AND transcription are CPU-intensive, so run on spawned thread
+            let app_for_pipeline = app.clone();
+            std::thread::spawn(move || {
+                run_transcription_pipeline(&app_for_pipeline, &overrides, &cancel);
+                // The dictation went through the pipeline, it no longer needs recovering
+                recording_recovery_service::end_session();
+                start_queued_recording(&app_for_pipeline);
+            });
+        }
+        Err(e) => {
+            log::error!("[{}] Failed to stop recording: {e}", e.code());
+            // Emit error event for overlay to display
+            emit_recording_failed(app, e);
+        }
+    }
+}
+
+/// Transcribe and output a synthetic signal as if it had just been recorded,
+/// to check the transcription and output half of the pipeline without a
+/// microphone or its permission.
+///
+/// # Returns
+/// * `Ok(())` once the transcription is started
+/// * `Err(CyranoError::RecordingFailed)` if a dictation is in progress or the
+///   signal cannot be rendered
+pub fn inject_test_signal(app: &AppHandle, signal: &TestSignal) -> Result<(), CyranoError> {
+    if matches!(
+        recording_state::get_recording_state(),
+        RecordingState::Recording | RecordingState::Transcribing
+    ) {
+        return Err(CyranoError::RecordingFailed {
+            reason: "Cannot inject a test signal during a dictation".to_string(),
+        });
+    }
+    let samples =
+        test_signal::render(signal).map_err(|reason| CyranoError::RecordingFailed { reason })?;
+    log::info!(
+        "Injecting test signal {signal:?} ({} samples)",
+        samples.len()
+    );
+
+    let cancel = transcription_service::begin_dictation();
+    let mut buffer = SpillBuffer::new();
+    buffer.append(&samples);
+    recording_state::set_audio_buffer(buffer)
+        .map_err(|reason| CyranoError::RecordingFailed { reason })?;
+    recording_state::set_recording_state(RecordingState::Transcribing);
+    if let Err(e) = recording_overlay::show_recording_overlay(app.clone()) {
+        log::error!("Failed to show recording overlay: {e}");
+    }
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        run_transcription_pipeline(&app, &DictationOverrides::default(), &cancel);
+    });
+    Ok(())
+}
+
+/// Load the model, transcribe the captured samples, and output the result.
+///
+/// The transcription is sanitized (see `text_sanitize`) before it is output,
+/// remembered for redo, or sent to the frontend. Long transcriptions may be
+/// summarized (see `summary_service`), in which case the summary can be
+/// output in place of the text. `overrides` change how this dictation is
+/// transcribed, post-processed and output, and `cancel` discards it.
+///
+/// MUST run off the main thread: both model loading and transcription block.
+fn run_transcription_pipeline(
+    app: &AppHandle,
+    overrides: &DictationOverrides,
+    cancel: &CancellationToken,
+) {
+    if let Err(e) = transcription_service::ensure_model_loaded_with(overrides) {
+        log::error!("[{}] Model loading failed: {e}", e.code());
+        recording_state::set_recording_state(RecordingState::Error);
+        emit_recording_failed(app, e);
+        return;
+    }
+    log::info!("Whisper model ready, starting transcription");
+
+    let transcription_start = get_timestamp_ms();
+    let _ = notifier_service::emit(
+        app,
+        "transcription-started",
+        TranscriptionStartedPayload {
+            timestamp: transcription_start,
+        },
+    );
+
+    let samples = match recording_state::take_audio_samples() {
+        Ok(s) => s,
+        Err(e) => {
+            log::error!("Failed to get audio samples: {e}");
+            recording_state::set_recording_state(RecordingState::Error);
+            let _ = notifier_service::emit(
+                app,
+                "transcription-failed",
+                TranscriptionFailedPayload::new(CyranoError::TranscriptionFailed { reason: e }),
+            );
+            return;
+        }
+    };
+
+    let saved_recording = recording_export_service::keep_recording(&samples);
+    let recording_quality = quality::analyze(&samples);
+    if let Some(measures) = &recording_quality {
+        log::info!("Recording quality: {measures:?}");
+        for issue in measures.issues() {
+            log::warn!("Recording quality issue: {issue}");
+        }
+    }
+
+    let prepare_start = Instant::now();
+    let prepared = transcription_service::prepare_samples(&samples);
+    let prepare_ms = prepare_start.elapsed().as_millis() as u64;
+    let segment_app = app.clone();
+    let committer = Arc::new(Mutex::new(SentenceCommitter::new()));
+    let segment_committer = Arc::clone(&committer);
+    let on_segment = Arc::new(move |segment: &Segment| {
+        let payload = TranscriptionSegmentPayload {
+            index: segment.index,
+            text: segment.text.clone(),
+            start_ms: segment.start_ms,
+            end_ms: segment.end_ms,
+        };
+        if let Err(e) = notifier_service::emit(&segment_app, "transcription-segment", payload) {
+            log::warn!("Failed to emit transcription-segment event: {e}");
+        }
+        let sentences = segment_committer
+            .lock()
+            .ok()
+            .and_then(|mut committer| committer.push(&segment.text));
+        if let Some(text) = sentences {
+            emit_sentence(&segment_app, text);
+        }
+    });
+    // Whisper runs to completion, so a cancellation requested mid-run is applied here
+    let transcribe_start = Instant::now();
+    let result =
+        transcription_service::transcribe_streaming(&prepared, on_segment, overrides, cancel)
+            .and_then(|transcription| {
+                if cancel.is_cancelled() {
+                    Err(CyranoError::TranscriptionFailed {
+                        reason: "Transcription cancelled by user".to_string(),
+                    })
+                } else {
+                    Ok(transcription)
+                }
+            });
+
+    let transcribe_ms = transcribe_start.elapsed().as_millis() as u64;
+
+    match result {
+        Ok(transcription) => {
+            let post_process_start = Instant::now();
+            let low_confidence = transcription_service::is_low_confidence(&transcription);
+            let Transcription {
+                text: raw_text,
+                language,
+                ..
+            } = transcription;
+            if let Some(rest) = committer.lock().ok().and_then(|mut c| c.finish()) {
+                emit_sentence(app, rest);
+            }
+            let text = text_sanitize::sanitize_output(&raw_text);
+            if text != raw_text {
+                log::warn!(
+                    "Sanitized transcription output ({} -> {} chars)",
+                    raw_text.len(),
+                    text.len()
+                );
+            }
+            let blocked = low_confidence
+                || hallucination_service::is_configured_hallucination(&text)
+                || transcription_service::is_silence_hallucination(&text, &samples);
+            let text = if blocked { String::new() } else { text };
+            let text = if overrides.verbatim {
+                text
+            } else {
+                let text = text_processing_service::process_dictation(&text);
+                plugin_service::run_plugins(app, &text)
+            };
+            if let Some(recording) = &saved_recording {
+                let settings = settings_service::get_settings();
+                let trace = SessionTrace {
+                    model: transcription_service::current_model_name_with(overrides),
+                    engine: overrides.engine.unwrap_or(settings.transcription_engine),
+                    decode_options: settings.decode_options,
+                    overrides: overrides.clone(),
+                    prepare_ms,
+                    transcribe_ms,
+                    post_process_ms: post_process_start.elapsed().as_millis() as u64,
+                    raw_text: raw_text.clone(),
+                    text: text.clone(),
+                };
+                recording_export_service::save_trace(recording, &trace);
+            }
+
+            let duration_ms = (get_timestamp_ms() - transcription_start) as u32;
+            log::info!(
+                "Transcription complete: {} chars in {}ms",
+                text.len(),
+                duration_ms
+            );
+
+            let summarized = summary_service::summarize_if_long(text);
+            let mode = overrides
+                .output_mode
+                .unwrap_or(settings_service::get_settings().output_mode);
+            let fitted = if mode != OutputMode::Clipboard && !overrides.verbatim {
+                caret_context_service::fit_to_caret(summarized.output())
+            } else {
+                None
+            };
+            let output = fitted.as_deref().unwrap_or(summarized.output());
+            // A dropped hallucination must not replace the clipboard
+            let cursor_inserted = !blocked && output_text(app, output, mode);
+
+            // History is a convenience: a failed write must not fail the dictation
+            if let Err(e) = history_service::record_dictation(
+                &summarized.text,
+                summarized.summary.as_deref(),
+                &samples,
+                transcription_service::current_model_name_with(overrides),
+                recording_quality,
+            ) {
+                log::warn!("[{}] Failed to save history entry: {e}", e.code());
+            }
+
+            retranscription_service::keep_last_recording(&samples);
+            redo_service::remember_dictation(output, cursor_inserted);
+
+            recording_state::set_last_transcription(output, duration_ms, recording_quality);
+            recording_state::set_recording_state(RecordingState::Done);
+            let _ = notifier_service::emit(
+                app,
+                "transcription-complete",
+                TranscriptionCompletePayload {
+                    text: output.to_string(),
+                    duration_ms,
+                    language,
+                    quality: recording_quality,
+                    low_confidence,
+                },
+            );
+        }
+        Err(e) => {
+            // Check if this was a cancellation
+            let is_cancellation = matches!(&e, CyranoError::TranscriptionFailed { reason } if reason.contains("cancelled"));
+
+            if is_cancellation {
+                log::info!("Transcription was cancelled");
+                recording_state::set_recording_state(RecordingState::Idle);
+                let _ = notifier_service::emit(
+                    app,
+                    "transcription-cancelled",
+                    TranscriptionCancelledPayload {
+                        timestamp: get_timestamp_ms(),
+                    },
+                );
+            } else {
+                log::error!("[{}] Transcription failed: {e}", e.code());
+                recording_state::set_recording_state(RecordingState::Error);
+                let _ = notifier_service::emit(
+                    app,
+                    "transcription-failed",
+                    TranscriptionFailedPayload::new(e),
+                );
+            }
+        }
+    }
+}
+
+/// Emit sentences committed while streaming a dictation.
+fn emit_sentence(app: &AppHandle, text: String) {
+    let payload = TranscriptionSentencePayload { text };
+    if let Err(e) = notifier_service::emit(app, "transcription-sentence", payload) {
+        log::warn!("Failed to emit transcription-sentence event: {e}");
+    }
+}
+
+/// Output the text of a past dictation again, as if it had just been
+/// transcribed: the summary replaces the text with `output_summary`, and the
+/// clipboard-copied or clipboard-failed event is emitted.
+///
+/// Output runs in the background. When pasting, Cyrano is hidden first so the
+/// text goes to the app that was in front of it.
+///
+/// # Returns
+/// * `Ok(())` once the output is started
+/// * `Err(CyranoError::HistoryEntryNotFound)` if the entry does not exist
+pub fn output_history_entry(
+    app: &AppHandle,
+    id: &str,
+    mode: OutputMode,
+) -> Result<(), CyranoError> {
+    let entry = history_service::get_entry(id)?;
+    let output_summary = settings_service::get_settings().output_summary;
+    let text = match entry.summary {
+        Some(summary) if output_summary => summary,
+        _ => entry.text,
+    };
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        if mode != OutputMode::Clipboard {
+            #[cfg(target_os = "macos")]
+            if let Err(e) = app.hide() {
+                log::warn!("Failed to hide Cyrano before inserting: {e}");
+            }
+            std::thread::sleep(FOCUS_RETURN_DELAY);
+        }
+        output_text(&app, &text, mode);
+    });
+    Ok(())
+}
+
+/// Output the dictation waiting for an output mode (see `AskEachTime`) in
+/// the mode the user picked.
+///
+/// Output runs in the background. When pasting or typing, Cyrano is hidden
+/// first so the text goes to the app that was in front of it.
+///
+/// # Returns
+/// `false` if no dictation was waiting, or `mode` is `AskEachTime`.
+pub fn output_pending_dictation(app: &AppHandle, mode: OutputMode) -> bool {
+    if mode == OutputMode::AskEachTime {
+        return false;
+    }
+    let Some(text) = output_service::take_pending_output() else {
+        return false;
+    };
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        if mode != OutputMode::Clipboard {
+            #[cfg(target_os = "macos")]
+            if let Err(e) = app.hide() {
+                log::warn!("Failed to hide Cyrano before inserting: {e}");
+            }
+            std::thread::sleep(FOCUS_RETURN_DELAY);
+        }
+        output_text(&app, &text, mode);
+    });
+    true
+}
+
+/// Output the transcription (FR12 + FR13) and emit the matching UI event.
+///
+/// 1. Copy to clipboard (unless `mode` types the text)
+/// 2. Insert at cursor via Cmd+V or typing (if accessibility granted and
+///    `mode` inserts)
+///
+/// # Returns
+/// `true` if the text was inserted at the cursor.
+fn output_text(app: &AppHandle, text: &str, mode: OutputMode) -> bool {
+    match output_service::output_with_mode(text, app, mode) {
+        Ok(cursor_inserted) => {
+            if cursor_inserted {
+                log::debug!("Clipboard copy and cursor insertion succeeded");
+            } else {
+                log::debug!("Clipboard copy succeeded (cursor insertion not available)");
+            }
+            // Emit clipboard-copied event for UI feedback
+            let _ = notifier_service::emit(
+                app,
+                "clipboard-copied",
+                ClipboardCopiedPayload {
+                    text_length: text.len() as u32,
+                },
+            );
+            cursor_inserted
+        }
+        Err(e) => {
+            // Clipboard failure is non-fatal - log and continue
+            // User still gets the transcription, just needs to manually copy
+            log::warn!("[{}] Output failed: {e}", e.code());
+            let _ = notifier_service::emit(app, "clipboard-failed", ClipboardFailedPayload::new(e));
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn test_clear_queued_recording() {
+        RECORDING_QUEUED.store(true, Ordering::SeqCst);
+        clear_queued_recording();
+        assert!(!RECORDING_QUEUED.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_get_timestamp_ms_returns_reasonable_value() {
+        let ts = get_timestamp_ms();
+        // Should be after Jan 2020
+        let jan_2020_ms: u64 = 1577836800000;
+        assert!(ts > jan_2020_ms, "Timestamp should be after January 2020");
+    }
+}
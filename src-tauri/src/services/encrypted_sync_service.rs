@@ -0,0 +1,547 @@
+//! End-to-end encrypted sync of settings and history (opt-in).
+//!
+//! Devices share a single encrypted snapshot stored on a WebDAV server or a
+//! minimal relay. The snapshot is sealed with a key derived from a passphrase
+//! that never leaves the devices, so the server cannot read its content.
+//!
+//! Sync is a fetch-merge-write loop on that snapshot:
+//! - History changes made locally are queued in `~/.cyrano/sync-outbox.jsonl`
+//!   and merged with the remote records (last writer wins, see
+//!   `history_sync_service`).
+//! - Shared settings are last-writer-wins as a whole; device-bound settings
+//!   (shortcuts, sync configuration) are never synced.
+//! - The write is conditional on the version fetched, so concurrent syncs
+//!   from two devices retry instead of overwriting each other.
+//!
+//! The passphrase and server password are only kept in memory and must be
+//! provided again after a restart through `unlock`.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::domain::CyranoError;
+use crate::infrastructure::sync::{envelope, HttpSyncBackend};
+use crate::services::data_directory_service;
+use crate::services::history_sync_service::{self, SyncRecord};
+use crate::services::settings_service::{self, Settings};
+use crate::traits::sync_backend::{StoreOutcome, SyncBackend, WriteCondition};
+
+/// Name of the shared snapshot on the server.
+const REMOTE_OBJECT: &str = "cyrano-sync.bin";
+
+/// Local queue of history changes not yet uploaded.
+const OUTBOX_FILE: &str = "sync-outbox.jsonl";
+
+/// Local record of the last synced settings.
+const STATE_FILE: &str = "sync-state.json";
+
+/// Number of fetch-merge-write attempts before giving up on a busy server.
+const MAX_ATTEMPTS: usize = 3;
+
+/// Shortest accepted passphrase.
+const MIN_PASSPHRASE_LEN: usize = 8;
+
+/// Settings that stay on the device they were set on.
+///
+/// Shortcuts must be re-registered when they change, the sync configuration
+/// itself is per device, plugins are local executables that must be
+/// approved on each device, and API keys never leave the device.
+const DEVICE_SETTINGS: [&str; 10] = [
+    "redo_shortcut",
+    "clipboard_audio_shortcut",
+    "history_sync_folder",
+    "encrypted_sync_url",
+    "encrypted_sync_username",
+    "plugins_enabled",
+    "plugin_approvals",
+    "voice_memo_folder",
+    "summary_api_key",
+    "cloud_transcription_api_key",
+];
+
+/// Secrets for the current session.
+struct Credentials {
+    passphrase: String,
+    password: Option<String>,
+}
+
+static CREDENTIALS: Mutex<Option<Credentials>> = Mutex::new(None);
+
+/// Prevents overlapping sync runs.
+static SYNC_LOCK: Mutex<()> = Mutex::new(());
+
+/// Serializes outbox rewrites with appends.
+static OUTBOX_LOCK: Mutex<()> = Mutex::new(());
+
+/// Shared settings with the time they were last changed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SettingsSnapshot {
+    at: u64,
+    values: serde_json::Value,
+}
+
+/// Decrypted content of the remote object.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+struct SyncSnapshot {
+    settings: Option<SettingsSnapshot>,
+    history: Vec<SyncRecord>,
+}
+
+/// Settings as of the last successful sync, used to detect local changes.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+struct SyncState {
+    settings: Option<SettingsSnapshot>,
+}
+
+/// What a sync run does with the shared settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SettingsAction {
+    None,
+    Push,
+    Pull,
+}
+
+/// Result of an encrypted sync run.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Type)]
+pub struct EncryptedSyncReport {
+    /// Number of local history entries created or updated
+    pub updated: u32,
+    /// Number of local history entries deleted
+    pub deleted: u32,
+    /// Number of local history changes uploaded
+    pub uploaded: u32,
+    /// Whether shared settings were applied from another device
+    pub settings_pulled: bool,
+    /// Whether local settings were uploaded
+    pub settings_pushed: bool,
+}
+
+fn data_file(name: &str) -> Result<PathBuf, CyranoError> {
+    data_directory_service::data_root().map(|root| root.join(name))
+}
+
+fn is_enabled() -> bool {
+    settings_service::get_settings()
+        .encrypted_sync_url
+        .is_some()
+}
+
+/// Only HTTPS is allowed, except for a relay on this machine.
+fn validate_url(url: &str) -> Result<(), CyranoError> {
+    let is_loopback = ["http://localhost", "http://127.0.0.1", "http://[::1]"]
+        .iter()
+        .any(|prefix| {
+            url.strip_prefix(prefix)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with([':', '/']))
+        });
+    if url.starts_with("https://") || is_loopback {
+        Ok(())
+    } else {
+        Err(CyranoError::SyncFailed {
+            reason: format!("Sync server must use https: {url}"),
+        })
+    }
+}
+
+/// Enable encrypted sync with the server at `url`, or disable it with `None`.
+///
+/// When enabling, every local history entry is queued for upload. Disabling
+/// forgets the session secrets and pending changes.
+pub fn configure(url: Option<String>, username: Option<String>) -> Result<(), CyranoError> {
+    let Some(url) = url.map(|url| url.trim().to_string()) else {
+        settings_service::update_settings(|s| {
+            s.encrypted_sync_url = None;
+            s.encrypted_sync_username = None;
+        })?;
+        lock();
+        for name in [OUTBOX_FILE, STATE_FILE] {
+            let path = data_file(name)?;
+            if path.exists() {
+                if let Err(e) = std::fs::remove_file(&path) {
+                    log::warn!("Failed to remove {}: {e}", path.display());
+                }
+            }
+        }
+        log::info!("Encrypted sync disabled");
+        return Ok(());
+    };
+
+    validate_url(&url)?;
+    let was_enabled = is_enabled();
+    settings_service::update_settings(|s| {
+        s.encrypted_sync_url = Some(url.clone());
+        s.encrypted_sync_username = username.filter(|u| !u.is_empty());
+    })?;
+    log::info!("Encrypted sync enabled with server {url}");
+
+    if !was_enabled {
+        let records = history_sync_service::export_records()?;
+        let _guard = OUTBOX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        history_sync_service::append_records(&data_file(OUTBOX_FILE)?, &records)?;
+    }
+    Ok(())
+}
+
+/// Provide the session secrets and run a first sync to check them.
+///
+/// # Returns
+/// * `Ok(EncryptedSyncReport)` if the passphrase opens the remote data
+/// * `Err(CyranoError::SyncFailed)` otherwise; the secrets are forgotten
+pub fn unlock(
+    passphrase: String,
+    password: Option<String>,
+) -> Result<EncryptedSyncReport, CyranoError> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(CyranoError::SyncFailed {
+            reason: format!("Passphrase must be at least {MIN_PASSPHRASE_LEN} characters"),
+        });
+    }
+
+    set_credentials(Some(Credentials {
+        passphrase,
+        password,
+    }));
+    sync_now().inspect_err(|_| lock())
+}
+
+/// Forget the session secrets.
+pub fn lock() {
+    set_credentials(None);
+}
+
+fn set_credentials(credentials: Option<Credentials>) {
+    *CREDENTIALS.lock().unwrap_or_else(|e| e.into_inner()) = credentials;
+}
+
+/// Queue a history change for the next sync (non-fatal).
+pub(crate) fn queue_change(record: &SyncRecord) {
+    if !is_enabled() {
+        return;
+    }
+    let result = data_file(OUTBOX_FILE).and_then(|path| {
+        let _guard = OUTBOX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        history_sync_service::append_records(&path, std::slice::from_ref(record))
+    });
+    if let Err(e) = result {
+        log::warn!(
+            "[{}] Failed to queue history change for sync: {e}",
+            e.code()
+        );
+    }
+}
+
+/// Sync with the configured server using the session secrets.
+///
+/// # Returns
+/// * `Ok(EncryptedSyncReport)` describing the changes
+/// * `Err(CyranoError::SyncFailed)` if sync is disabled, locked, or fails
+pub fn sync_now() -> Result<EncryptedSyncReport, CyranoError> {
+    let settings = settings_service::get_settings();
+    let url = settings
+        .encrypted_sync_url
+        .ok_or_else(|| CyranoError::SyncFailed {
+            reason: "Encrypted sync is not configured".to_string(),
+        })?;
+
+    let (passphrase, password) = {
+        let guard = CREDENTIALS.lock().unwrap_or_else(|e| e.into_inner());
+        let credentials = guard.as_ref().ok_or_else(|| CyranoError::SyncFailed {
+            reason: "Encrypted sync is locked; enter the passphrase".to_string(),
+        })?;
+        (credentials.passphrase.clone(), credentials.password.clone())
+    };
+
+    let backend = HttpSyncBackend::new(
+        &url,
+        settings.encrypted_sync_username.as_deref(),
+        password.as_deref(),
+    );
+    let report = sync_with(&backend, &passphrase)?;
+    log::info!("Encrypted sync complete: {report:?}");
+    Ok(report)
+}
+
+/// Settings that are shared across devices, as JSON.
+fn shared_settings(settings: &Settings) -> serde_json::Value {
+    let mut values = serde_json::to_value(settings).unwrap_or_default();
+    if let Some(object) = values.as_object_mut() {
+        for key in DEVICE_SETTINGS {
+            object.remove(key);
+        }
+    }
+    values
+}
+
+/// Decide whether shared settings are pushed, pulled, or left alone.
+///
+/// Local changes since the last sync win; otherwise a newer remote snapshot
+/// is applied.
+fn settings_action(
+    local: &serde_json::Value,
+    last_synced: Option<&SettingsSnapshot>,
+    remote: Option<&SettingsSnapshot>,
+) -> SettingsAction {
+    let changed_locally = last_synced.is_none_or(|synced| synced.values != *local);
+    let last_at = last_synced.map_or(0, |synced| synced.at);
+
+    match remote {
+        Some(remote) if remote.values == *local => SettingsAction::None,
+        Some(remote) if !changed_locally && remote.at > last_at => SettingsAction::Pull,
+        _ => SettingsAction::Push,
+    }
+}
+
+/// Overwrite shared settings with the remote values.
+///
+/// Device settings and settings locked by managed preferences are kept.
+fn apply_shared_settings(values: &serde_json::Value) -> Result<Settings, CyranoError> {
+    let managed_keys = settings_service::managed_keys();
+    let mut merged = serde_json::to_value(settings_service::get_settings()).unwrap_or_default();
+    if let (Some(merged), Some(remote)) = (merged.as_object_mut(), values.as_object()) {
+        for (key, value) in remote {
+            if !DEVICE_SETTINGS.contains(&key.as_str()) && !managed_keys.contains(key) {
+                merged.insert(key.clone(), value.clone());
+            }
+        }
+    }
+
+    let updated: Settings =
+        serde_json::from_value(merged).map_err(|e| CyranoError::SyncFailed {
+            reason: format!("Remote settings are invalid: {e}"),
+        })?;
+    settings_service::update_settings(|s| *s = updated)
+}
+
+fn load_state() -> SyncState {
+    data_file(STATE_FILE)
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(state: &SyncState) -> Result<(), CyranoError> {
+    let json = serde_json::to_vec_pretty(state).map_err(|e| CyranoError::StorageFailed {
+        reason: format!("Failed to serialize sync state: {e}"),
+    })?;
+    data_directory_service::write_file_atomically(&data_file(STATE_FILE)?, &json)
+}
+
+/// Drop the first `count` records from the outbox (they were uploaded).
+///
+/// Records queued while the sync was running are kept.
+fn trim_outbox(count: usize) -> Result<(), CyranoError> {
+    let path = data_file(OUTBOX_FILE)?;
+    let _guard = OUTBOX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let mut remaining = String::new();
+    for record in history_sync_service::read_log(&path).iter().skip(count) {
+        if let Ok(line) = serde_json::to_string(record) {
+            remaining.push_str(&line);
+            remaining.push('\n');
+        }
+    }
+    data_directory_service::write_file_atomically(&path, remaining.as_bytes())
+}
+
+fn seal_snapshot(passphrase: &str, snapshot: &SyncSnapshot) -> Result<Vec<u8>, CyranoError> {
+    let json = serde_json::to_vec(snapshot).map_err(|e| CyranoError::SyncFailed {
+        reason: format!("Failed to serialize sync data: {e}"),
+    })?;
+    envelope::seal(passphrase, &json)
+}
+
+fn open_snapshot(passphrase: &str, bytes: &[u8]) -> Result<SyncSnapshot, CyranoError> {
+    let json = envelope::open(passphrase, bytes)?;
+    serde_json::from_slice(&json).map_err(|e| CyranoError::SyncFailed {
+        reason: format!("Remote sync data is invalid: {e}"),
+    })
+}
+
+/// Run the fetch-merge-write loop against `backend`.
+fn sync_with(
+    backend: &dyn SyncBackend,
+    passphrase: &str,
+) -> Result<EncryptedSyncReport, CyranoError> {
+    let _guard = SYNC_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    let outbox = data_file(OUTBOX_FILE)?;
+    let pending = if outbox.exists() {
+        history_sync_service::read_log(&outbox)
+    } else {
+        Vec::new()
+    };
+    let state = load_state();
+    let device = history_sync_service::device_id()?;
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        let remote = backend.fetch(REMOTE_OBJECT)?;
+        let (mut snapshot, condition) = match &remote {
+            Some(object) => (
+                open_snapshot(passphrase, &object.bytes)?,
+                object
+                    .etag
+                    .as_deref()
+                    .map_or(WriteCondition::Unconditional, WriteCondition::Matches),
+            ),
+            None => (SyncSnapshot::default(), WriteCondition::Absent),
+        };
+
+        let local_settings = shared_settings(&settings_service::get_settings());
+        let action = settings_action(
+            &local_settings,
+            state.settings.as_ref(),
+            snapshot.settings.as_ref(),
+        );
+        if action == SettingsAction::Push {
+            snapshot.settings = Some(SettingsSnapshot {
+                at: history_sync_service::get_timestamp_ms(),
+                values: local_settings,
+            });
+        }
+
+        let logs = [
+            ("remote".to_string(), std::mem::take(&mut snapshot.history)),
+            (device.clone(), pending.clone()),
+        ];
+        let merged: Vec<SyncRecord> = history_sync_service::merge_logs(&logs)
+            .into_values()
+            .collect();
+        snapshot.history = history_sync_service::compact(&merged);
+
+        let needs_upload =
+            remote.is_none() || !pending.is_empty() || action == SettingsAction::Push;
+        if needs_upload {
+            let sealed = seal_snapshot(passphrase, &snapshot)?;
+            if backend.store(REMOTE_OBJECT, &sealed, condition)? == StoreOutcome::Conflict {
+                log::info!("Sync snapshot changed during sync (attempt {attempt}), retrying");
+                continue;
+            }
+        }
+
+        let (updated, deleted) = history_sync_service::apply_records(snapshot.history)?;
+        if action == SettingsAction::Pull {
+            if let Some(remote_settings) = &snapshot.settings {
+                apply_shared_settings(&remote_settings.values)?;
+            }
+        }
+        trim_outbox(pending.len())?;
+        save_state(&SyncState {
+            settings: snapshot.settings,
+        })?;
+
+        return Ok(EncryptedSyncReport {
+            updated,
+            deleted,
+            uploaded: if needs_upload {
+                pending.len() as u32
+            } else {
+                0
+            },
+            settings_pulled: action == SettingsAction::Pull,
+            settings_pushed: action == SettingsAction::Push,
+        });
+    }
+
+    Err(CyranoError::SyncFailed {
+        reason: "Another device kept syncing at the same time; try again".to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn snapshot(at: u64, values: serde_json::Value) -> SettingsSnapshot {
+        SettingsSnapshot { at, values }
+    }
+
+    #[test]
+    fn test_validate_url_requires_https() {
+        assert!(validate_url("https://dav.example.com/cyrano").is_ok());
+        assert!(validate_url("http://localhost:8080").is_ok());
+        assert!(validate_url("http://127.0.0.1/relay").is_ok());
+        assert!(validate_url("http://dav.example.com").is_err());
+        assert!(validate_url("http://localhost.evil.com").is_err());
+        assert!(validate_url("ftp://example.com").is_err());
+    }
+
+    #[test]
+    fn test_shared_settings_exclude_device_settings() {
+        let settings = Settings {
+            redo_shortcut: Some("Alt+R".to_string()),
+            encrypted_sync_url: Some("https://dav.example.com".to_string()),
+            ..Settings::default()
+        };
+        let shared = shared_settings(&settings);
+        let object = shared.as_object().unwrap();
+        for key in DEVICE_SETTINGS {
+            assert!(!object.contains_key(key), "{key} should not be shared");
+        }
+        assert!(object.contains_key("history_enabled"));
+    }
+
+    #[test]
+    fn test_first_sync_pushes_local_settings() {
+        let local = json!({"history_audio": false});
+        assert_eq!(settings_action(&local, None, None), SettingsAction::Push);
+    }
+
+    #[test]
+    fn test_unchanged_local_settings_pull_newer_remote() {
+        let local = json!({"history_audio": false});
+        let synced = snapshot(1, local.clone());
+        let remote = snapshot(2, json!({"history_audio": true}));
+        assert_eq!(
+            settings_action(&local, Some(&synced), Some(&remote)),
+            SettingsAction::Pull
+        );
+    }
+
+    #[test]
+    fn test_local_changes_win_over_remote() {
+        let local = json!({"history_audio": true});
+        let synced = snapshot(1, json!({"history_audio": false}));
+        let remote = snapshot(2, json!({"history_enabled": false}));
+        assert_eq!(
+            settings_action(&local, Some(&synced), Some(&remote)),
+            SettingsAction::Push
+        );
+    }
+
+    #[test]
+    fn test_settings_in_sync_do_nothing() {
+        let local = json!({"history_audio": true});
+        let synced = snapshot(1, local.clone());
+        assert_eq!(
+            settings_action(&local, Some(&synced), Some(&synced.clone())),
+            SettingsAction::None
+        );
+        // A device joining with identical settings does not rewrite them
+        let remote = snapshot(5, local.clone());
+        assert_eq!(
+            settings_action(&local, None, Some(&remote)),
+            SettingsAction::None
+        );
+    }
+
+    #[test]
+    fn test_snapshot_round_trip_through_envelope() {
+        let original = SyncSnapshot {
+            settings: Some(snapshot(7, json!({"history_audio": true}))),
+            history: Vec::new(),
+        };
+        let sealed = seal_snapshot("passphrase", &original).unwrap();
+        assert_eq!(open_snapshot("passphrase", &sealed).unwrap(), original);
+        assert!(open_snapshot("other passphrase", &sealed).is_err());
+    }
+}
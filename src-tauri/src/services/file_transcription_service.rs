@@ -0,0 +1,641 @@
+//! Transcription of existing audio files.
+//!
+//! Files arrive from the "Transcribe Audio File with Cyrano" service in the
+//! Finder context menu, from "Open With" in Finder, from drops on the main
+//! window or the Dock icon, from the clipboard, and from the
+//! `transcribe_file` command. They are
+//! queued and transcribed one at a time on a background thread with the
+//! active model, reporting progress through file-transcription-progress
+//! events. Each result is saved to the history and announced with a
+//! notification.
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use tauri::AppHandle;
+
+use crate::domain::CyranoError;
+use crate::infrastructure::audio::cpal_adapter::TARGET_SAMPLE_RATE;
+use crate::infrastructure::audio::flac;
+use crate::infrastructure::audio::quality;
+use crate::infrastructure::audio::resampler::{Resampler, ResamplerQuality};
+use crate::infrastructure::audio::wav;
+use crate::services::summary_service::{self, SummarizedText};
+use crate::services::{
+    history_service, job_scheduler_service, notifier_service, settings_service,
+    text_processing_service, transcription_service,
+};
+use crate::utils::text_sanitize;
+
+/// Extensions decoded natively.
+const NATIVE_EXTENSIONS: &[&str] = &["wav", "flac"];
+
+/// Extensions converted through Core Audio first.
+#[cfg(target_os = "macos")]
+const CONVERTED_EXTENSIONS: &[&str] = &["m4a", "mp3", "aac", "aif", "aiff", "caf"];
+#[cfg(not(target_os = "macos"))]
+const CONVERTED_EXTENSIONS: &[&str] = &[];
+
+/// Longest text shown in the completion notification.
+const NOTIFICATION_PREVIEW_CHARS: usize = 120;
+
+/// Files waiting to be transcribed, with the progress of the current batch.
+///
+/// A batch starts when files are queued while the worker is idle and ends
+/// when the queue is drained.
+struct FileQueue {
+    pending: VecDeque<QueuedFile>,
+    /// Files of the current batch that were processed (successfully or not)
+    completed: u32,
+    /// Files in the current batch: processed, running and pending
+    total: u32,
+}
+
+/// A file waiting in the queue.
+struct QueuedFile {
+    path: PathBuf,
+    /// Written by Cyrano (e.g. clipboard audio) and deleted once processed
+    temporary: bool,
+}
+
+static QUEUE: Mutex<FileQueue> = Mutex::new(FileQueue {
+    pending: VecDeque::new(),
+    completed: 0,
+    total: 0,
+});
+
+/// Set while the worker thread drains the queue.
+static WORKER_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Processing stage of a file, reported by file-transcription-progress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileTranscriptionStage {
+    /// Waiting for earlier files to finish
+    Queued,
+    /// Reading and converting the audio
+    Decoding,
+    /// Running Whisper on the decoded audio
+    Transcribing,
+    /// Finished, successfully or not
+    Done,
+}
+
+/// Payload for the file-transcription-progress event.
+#[derive(Clone, serde::Serialize)]
+pub struct FileTranscriptionProgressPayload {
+    /// Path of the file this update is about
+    pub path: String,
+    /// What is happening to the file
+    pub stage: FileTranscriptionStage,
+    /// Files of the current batch already processed
+    pub completed: u32,
+    /// Files in the current batch
+    pub total: u32,
+}
+
+/// Payload for the file-transcription-complete event.
+#[derive(Clone, serde::Serialize)]
+pub struct FileTranscriptionCompletePayload {
+    /// Path of the transcribed file
+    pub path: String,
+    /// The transcribed text
+    pub text: String,
+    /// Summary of the text, for long transcriptions when summaries are enabled
+    pub summary: Option<String>,
+    /// ID of the history entry, if history is enabled
+    pub history_id: Option<String>,
+}
+
+/// Payload for the file-transcription-failed event.
+#[derive(Clone, serde::Serialize)]
+pub struct FileTranscriptionFailedPayload {
+    /// Path of the file that could not be transcribed
+    pub path: String,
+    /// Error that caused the transcription to fail
+    pub error: CyranoError,
+    /// Stable error code of `error` (e.g. `CYR-013`)
+    pub code: String,
+}
+
+impl FileTranscriptionFailedPayload {
+    /// Create the payload, deriving `code` from the error.
+    pub fn new(path: &Path, error: CyranoError) -> Self {
+        Self {
+            path: path.display().to_string(),
+            code: error.code().to_string(),
+            error,
+        }
+    }
+}
+
+fn unsupported(path: &Path, reason: impl Into<String>) -> CyranoError {
+    CyranoError::UnsupportedAudioFile {
+        path: path.display().to_string(),
+        reason: reason.into(),
+    }
+}
+
+/// Lowercase extension of `path`, if any.
+fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+}
+
+/// Whether `path` has an extension this platform can transcribe.
+pub fn is_supported_file(path: &Path) -> bool {
+    extension(path).is_some_and(|ext| {
+        NATIVE_EXTENSIONS.contains(&ext.as_str()) || CONVERTED_EXTENSIONS.contains(&ext.as_str())
+    })
+}
+
+/// Resample mono audio to the 16kHz Whisper expects.
+fn to_target_rate(samples: Vec<f32>, sample_rate: u32, quality: ResamplerQuality) -> Vec<f32> {
+    if sample_rate == TARGET_SAMPLE_RATE {
+        return samples;
+    }
+    let mut resampler = Resampler::new(sample_rate, TARGET_SAMPLE_RATE, quality);
+    let mut output = Vec::with_capacity(
+        (samples.len() as u64 * u64::from(TARGET_SAMPLE_RATE) / u64::from(sample_rate)) as usize,
+    );
+    for sample in samples {
+        resampler.push_sample(sample, &mut output);
+    }
+    resampler.flush(&mut output);
+    output
+}
+
+/// Decode an audio file into 16kHz mono samples.
+///
+/// # Returns
+/// * `Ok(Vec<f32>)` ready for `transcription_service::transcribe_with_language`
+/// * `Err(CyranoError::UnsupportedAudioFile)` if the file cannot be read or decoded
+pub fn load_audio_file(path: &Path) -> Result<Vec<f32>, CyranoError> {
+    let ext = extension(path).unwrap_or_default();
+
+    let decoded = if NATIVE_EXTENSIONS.contains(&ext.as_str()) {
+        let bytes = std::fs::read(path).map_err(|e| unsupported(path, e.to_string()))?;
+        if ext == "flac" {
+            flac::decode_flac(&bytes)
+        } else {
+            wav::decode_wav(&bytes)
+        }
+    } else if CONVERTED_EXTENSIONS.contains(&ext.as_str()) {
+        convert_and_decode(path)
+    } else {
+        Err(format!("unsupported format .{ext}"))
+    };
+
+    let (samples, sample_rate) = decoded.map_err(|reason| unsupported(path, reason))?;
+    let quality = settings_service::get_settings().resampler_quality;
+    Ok(to_target_rate(samples, sample_rate, quality))
+}
+
+#[cfg(target_os = "macos")]
+fn convert_and_decode(path: &Path) -> Result<(Vec<f32>, u32), String> {
+    let bytes = crate::infrastructure::audio::macos_afconvert::convert_to_wav(path)?;
+    wav::decode_wav(&bytes)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn convert_and_decode(_path: &Path) -> Result<(Vec<f32>, u32), String> {
+    Err("format not supported on this platform".to_string())
+}
+
+/// Queue audio files for transcription and start the worker if needed.
+///
+/// Unsupported files are rejected right away with a
+/// file-transcription-failed event.
+///
+/// # Returns
+/// The number of files queued.
+pub fn enqueue_files(app: &AppHandle, paths: Vec<PathBuf>) -> u32 {
+    let files = paths
+        .into_iter()
+        .map(|path| QueuedFile {
+            path,
+            temporary: false,
+        })
+        .collect();
+    enqueue(app, files)
+}
+
+/// Queue an audio file written by Cyrano, deleted once transcribed.
+pub fn enqueue_temporary_file(app: &AppHandle, path: PathBuf) -> u32 {
+    enqueue(
+        app,
+        vec![QueuedFile {
+            path,
+            temporary: true,
+        }],
+    )
+}
+
+fn enqueue(app: &AppHandle, files: Vec<QueuedFile>) -> u32 {
+    let mut queued = 0;
+    {
+        let mut queue = QUEUE.lock().unwrap_or_else(|e| e.into_inner());
+        for file in files {
+            if !is_supported_file(&file.path) {
+                log::warn!("Ignoring unsupported audio file: {}", file.path.display());
+                emit_failed(
+                    app,
+                    &file.path,
+                    unsupported(&file.path, "unsupported format"),
+                );
+                continue;
+            }
+            log::info!(
+                "Queued audio file for transcription: {}",
+                file.path.display()
+            );
+            queue.total += 1;
+            emit_progress(app, &file.path, FileTranscriptionStage::Queued, &queue);
+            queue.pending.push_back(file);
+            queued += 1;
+        }
+    }
+
+    if queued > 0
+        && WORKER_RUNNING
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+    {
+        let app = app.clone();
+        std::thread::spawn(move || run_worker(&app));
+    }
+    queued
+}
+
+/// Queue the audio copied to the clipboard for transcription.
+///
+/// Looks for, in order: files copied in Finder, audio data copied from an
+/// app such as Voice Memos (macOS only), and a path or file URL copied as
+/// text.
+///
+/// # Returns
+/// * `Ok(u32)` with the number of files queued
+/// * `Err(CyranoError::NoClipboardAudio)` if the clipboard holds no audio
+pub fn transcribe_clipboard_audio(app: &AppHandle) -> Result<u32, CyranoError> {
+    #[cfg(target_os = "macos")]
+    {
+        use crate::infrastructure::pasteboard::{self, PasteboardAudio};
+
+        match pasteboard::read_audio() {
+            Some(PasteboardAudio::Files(paths)) => {
+                let audio: Vec<PathBuf> = paths
+                    .into_iter()
+                    .filter(|path| is_supported_file(path))
+                    .collect();
+                if !audio.is_empty() {
+                    return Ok(enqueue_files(app, audio));
+                }
+            }
+            Some(PasteboardAudio::Data { bytes, extension }) => {
+                let path = clipboard_file_path(extension);
+                std::fs::write(&path, bytes).map_err(|e| CyranoError::StorageFailed {
+                    reason: format!("Failed to save clipboard audio: {e}"),
+                })?;
+                return Ok(enqueue_temporary_file(app, path));
+            }
+            None => {}
+        }
+    }
+
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+
+    let text = app.clipboard().read_text().unwrap_or_default();
+    match path_from_text(&text) {
+        Some(path) => Ok(enqueue_files(app, vec![path])),
+        None => Err(CyranoError::NoClipboardAudio),
+    }
+}
+
+/// Temporary file holding audio data copied to the clipboard.
+#[cfg(target_os = "macos")]
+fn clipboard_file_path(extension: &str) -> PathBuf {
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    std::env::temp_dir().join(format!("cyrano-clipboard-{millis}.{extension}"))
+}
+
+/// Parse copied text as the path of a supported audio file.
+///
+/// Accepts a single absolute path, optionally quoted or starting with `~/`,
+/// or a `file://` URL.
+fn path_from_text(text: &str) -> Option<PathBuf> {
+    let text = text.trim();
+    if text.is_empty() || text.contains('\n') {
+        return None;
+    }
+    let text = text
+        .strip_prefix(['"', '\''])
+        .and_then(|t| t.strip_suffix(['"', '\'']))
+        .unwrap_or(text);
+
+    let path = if text.starts_with("file://") {
+        tauri::Url::parse(text).ok()?.to_file_path().ok()?
+    } else if let Some(rest) = text.strip_prefix("~/") {
+        dirs::home_dir()?.join(rest)
+    } else {
+        PathBuf::from(text)
+    };
+
+    (path.is_absolute() && path.is_file() && is_supported_file(&path)).then_some(path)
+}
+
+/// Pop the next queued file, ending the batch when the queue is empty.
+///
+/// Both happen under the queue lock so a file queued concurrently is never
+/// left without a worker.
+fn next_file() -> Option<QueuedFile> {
+    let mut queue = QUEUE.lock().unwrap_or_else(|e| e.into_inner());
+    let next = queue.pending.pop_front();
+    if next.is_none() {
+        queue.completed = 0;
+        queue.total = 0;
+        WORKER_RUNNING.store(false, Ordering::SeqCst);
+    }
+    next
+}
+
+/// Report a stage change, marking the file as processed when it is done.
+fn report_stage(app: &AppHandle, path: &Path, stage: FileTranscriptionStage) {
+    let mut queue = QUEUE.lock().unwrap_or_else(|e| e.into_inner());
+    if stage == FileTranscriptionStage::Done {
+        queue.completed += 1;
+    }
+    emit_progress(app, path, stage, &queue);
+}
+
+fn emit_progress(app: &AppHandle, path: &Path, stage: FileTranscriptionStage, queue: &FileQueue) {
+    let payload = FileTranscriptionProgressPayload {
+        path: path.display().to_string(),
+        stage,
+        completed: queue.completed,
+        total: queue.total,
+    };
+    if let Err(e) = notifier_service::emit(app, "file-transcription-progress", payload) {
+        log::error!("Failed to emit file-transcription-progress event: {e}");
+    }
+}
+
+/// Transcribe queued files until the queue is empty. MUST run off the main thread.
+fn run_worker(app: &AppHandle) {
+    while let Some(QueuedFile { path, temporary }) = next_file() {
+        let _slot = job_scheduler_service::acquire_slot("file-transcription");
+        let result = transcribe_file(&path, |stage| report_stage(app, &path, stage));
+        report_stage(app, &path, FileTranscriptionStage::Done);
+        if temporary {
+            if let Err(e) = std::fs::remove_file(&path) {
+                log::warn!("Failed to remove {}: {e}", path.display());
+            }
+        }
+
+        match result {
+            Ok(payload) => {
+                log::info!(
+                    "Transcribed {}: {} chars",
+                    path.display(),
+                    payload.text.len()
+                );
+                let preview = payload.summary.as_deref().unwrap_or(&payload.text);
+                notify(app, &path, Some(preview));
+                if let Err(e) = notifier_service::emit(app, "file-transcription-complete", payload)
+                {
+                    log::error!("Failed to emit file-transcription-complete event: {e}");
+                }
+            }
+            Err(e) => {
+                log::error!(
+                    "[{}] Failed to transcribe {}: {e}",
+                    e.code(),
+                    path.display()
+                );
+                notify(app, &path, None);
+                emit_failed(app, &path, e);
+            }
+        }
+    }
+}
+
+/// Decode, transcribe and record one file, reporting each stage.
+fn transcribe_file(
+    path: &Path,
+    on_stage: impl Fn(FileTranscriptionStage),
+) -> Result<FileTranscriptionCompletePayload, CyranoError> {
+    on_stage(FileTranscriptionStage::Decoding);
+    let samples = load_audio_file(path)?;
+
+    on_stage(FileTranscriptionStage::Transcribing);
+    let prepared = transcription_service::prepare_samples(&samples);
+    // Loaded on every attempt: a dictation preempting this one may unload
+    // the model when done, depending on `model_keep_alive`
+    let text = job_scheduler_service::run_preemptible("file-transcription", || {
+        transcription_service::ensure_model_loaded()?;
+        transcription_service::transcribe_preemptible(&prepared)
+    })?;
+    let text = text_sanitize::sanitize_output(&text);
+    let text = if transcription_service::is_silence_hallucination(&text, &samples) {
+        String::new()
+    } else {
+        text_processing_service::process_transcription(&text)
+    };
+
+    let SummarizedText { text, summary, .. } = summary_service::summarize_if_long(text);
+
+    // History is a convenience: a failed write must not lose the transcription
+    let history_id = match history_service::record_dictation(
+        &text,
+        summary.as_deref(),
+        &samples,
+        transcription_service::current_model_name(),
+        quality::analyze(&samples),
+    ) {
+        Ok(entry) => entry.map(|entry| entry.id),
+        Err(e) => {
+            log::warn!(
+                "[{}] Failed to save file transcription to history: {e}",
+                e.code()
+            );
+            None
+        }
+    };
+
+    Ok(FileTranscriptionCompletePayload {
+        path: path.display().to_string(),
+        text,
+        summary,
+        history_id,
+    })
+}
+
+fn emit_failed(app: &AppHandle, path: &Path, error: CyranoError) {
+    if let Err(e) = notifier_service::emit(
+        app,
+        "file-transcription-failed",
+        FileTranscriptionFailedPayload::new(path, error),
+    ) {
+        log::error!("Failed to emit file-transcription-failed event: {e}");
+    }
+}
+
+/// Build the notification body: the file name and a preview of the text.
+fn notification_body(path: &Path, text: Option<&str>) -> String {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    match text {
+        Some(text) if text.chars().count() > NOTIFICATION_PREVIEW_CHARS => {
+            let preview: String = text.chars().take(NOTIFICATION_PREVIEW_CHARS).collect();
+            format!("{name}: {}…", preview.trim_end())
+        }
+        Some(text) => format!("{name}: {text}"),
+        None => format!("{name} could not be transcribed."),
+    }
+}
+
+/// Announce the result with a native notification.
+fn notify(app: &AppHandle, path: &Path, text: Option<&str>) {
+    let title = if text.is_some() {
+        "Transcription ready"
+    } else {
+        "Transcription failed"
+    };
+    show_notification(app, title, &notification_body(path, text));
+}
+
+/// Announce that a transcription could not be started, e.g. because the
+/// clipboard held no audio.
+pub fn notify_failure(app: &AppHandle, error: &CyranoError) {
+    show_notification(app, "Transcription failed", &error.to_string());
+}
+
+fn show_notification(app: &AppHandle, title: &str, body: &str) {
+    #[cfg(not(mobile))]
+    {
+        use tauri_plugin_notification::NotificationExt;
+
+        if let Err(e) = app.notification().builder().title(title).body(body).show() {
+            log::warn!("Failed to show file transcription notification: {e}");
+        }
+    }
+
+    #[cfg(mobile)]
+    let _ = (app, title, body);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::temp_dir;
+
+    #[test]
+    fn test_supported_extensions_are_case_insensitive() {
+        assert!(is_supported_file(Path::new("/tmp/Memo.WAV")));
+        assert!(is_supported_file(Path::new("/tmp/take.flac")));
+        assert!(!is_supported_file(Path::new("/tmp/notes.txt")));
+        assert!(!is_supported_file(Path::new("/tmp/no-extension")));
+    }
+
+    #[test]
+    fn test_to_target_rate_resamples() {
+        let samples = vec![0.0; 48_000];
+        for quality in [ResamplerQuality::Fast, ResamplerQuality::Balanced] {
+            assert_eq!(
+                to_target_rate(samples.clone(), 48_000, quality).len(),
+                16_000
+            );
+        }
+
+        let samples = vec![0.5; 100];
+        assert_eq!(
+            to_target_rate(samples.clone(), TARGET_SAMPLE_RATE, ResamplerQuality::High),
+            samples
+        );
+    }
+
+    #[test]
+    fn test_load_audio_file_decodes_flac() {
+        let dir = temp_dir("file-transcription");
+        let path = dir.join("recording.flac");
+        let samples: Vec<f32> = (0..32_000)
+            .map(|i| ((i % 100) as f32 - 50.0) / 100.0)
+            .collect();
+        std::fs::write(&path, flac::encode_flac(&samples, 32_000)).unwrap();
+
+        let loaded = load_audio_file(&path).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(loaded.len(), 16_000);
+    }
+
+    #[test]
+    fn test_load_audio_file_reports_unsupported_files() {
+        let err = load_audio_file(Path::new("/tmp/recording.ogg")).unwrap_err();
+        assert!(matches!(err, CyranoError::UnsupportedAudioFile { .. }));
+
+        let err = load_audio_file(Path::new("/nonexistent/recording.wav")).unwrap_err();
+        assert!(matches!(err, CyranoError::UnsupportedAudioFile { .. }));
+    }
+
+    #[test]
+    fn test_path_from_text_accepts_paths_and_file_urls() {
+        let dir = temp_dir("clipboard");
+        let path = dir.join("voice memo.wav");
+        std::fs::write(&path, b"RIFF").unwrap();
+        let text = path.display().to_string();
+
+        assert_eq!(path_from_text(&text), Some(path.clone()));
+        assert_eq!(path_from_text(&format!("  '{text}'\n")), Some(path.clone()));
+        assert_eq!(path_from_text(&format!("\"{text}\"")), Some(path.clone()));
+        let url = format!("file://{}", text.replace(' ', "%20"));
+        assert_eq!(path_from_text(&url), Some(path.clone()));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_path_from_text_rejects_other_text() {
+        assert_eq!(path_from_text(""), None);
+        assert_eq!(path_from_text("Meeting notes for Monday"), None);
+        assert_eq!(path_from_text("memo.wav"), None);
+        assert_eq!(path_from_text("/nonexistent/cyrano/memo.wav"), None);
+        assert_eq!(path_from_text("/tmp/a.wav\n/tmp/b.wav"), None);
+    }
+
+    #[test]
+    fn test_progress_payload_serializes() {
+        let payload = FileTranscriptionProgressPayload {
+            path: "/Users/me/Memo.m4a".to_string(),
+            stage: FileTranscriptionStage::Transcribing,
+            completed: 1,
+            total: 3,
+        };
+        let json = serde_json::to_string(&payload).expect("Should serialize");
+        assert!(json.contains("\"stage\":\"transcribing\""));
+        assert!(json.contains("\"completed\":1"));
+        assert!(json.contains("\"total\":3"));
+    }
+
+    #[test]
+    fn test_notification_body_truncates_long_text() {
+        let path = Path::new("/Users/me/Interview.m4a");
+        assert_eq!(
+            notification_body(path, Some("Short text.")),
+            "Interview.m4a: Short text."
+        );
+        let long = "word ".repeat(100);
+        let body = notification_body(path, Some(&long));
+        assert!(body.ends_with('…'));
+        assert!(body.chars().count() < 150);
+        assert_eq!(
+            notification_body(path, None),
+            "Interview.m4a could not be transcribed."
+        );
+    }
+}
@@ -0,0 +1,213 @@
+//! Blocklist of phrases Whisper hallucinates on silence.
+//!
+//! On silent or near-silent audio, Whisper tends to output phrases from the
+//! video subtitles it was trained on ("Thank you for watching", "Subtitles
+//! by the Amara.org community"). A transcription consisting only of a
+//! blocklisted phrase is dropped instead of pasted. Phrases ending with an
+//! ellipsis (`Subtitles by…`) match any transcription starting with them.
+//!
+//! Short phrases such as "Thank you." are also common hallucinations, but
+//! too common in real dictations to block outright: they are only dropped
+//! when the recording is near-silent (see
+//! `transcription_service::is_silence_hallucination`).
+//!
+//! Matching ignores case, punctuation, and repetitions ("Thank you for
+//! watching. Thank you for watching."). The number of transcriptions dropped
+//! for each phrase is shown in the diagnostics.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use crate::services::settings_service;
+
+/// Phrases Whisper outputs on silence that are also said in real
+/// dictations, only dropped when the recording is near-silent.
+const SILENCE_PHRASES: [&str; 7] = [
+    "Thank you",
+    "Thank you very much",
+    "Thank you so much",
+    "Thanks",
+    "Thanks for watching",
+    "Bye",
+    "You",
+];
+
+/// Transcriptions dropped since launch, by blocklisted phrase.
+static BLOCKED_COUNTS: Mutex<BTreeMap<String, u32>> = Mutex::new(BTreeMap::new());
+
+/// Check a transcription against the configured blocklist, counting a match.
+///
+/// # Returns
+/// * `true` if the transcription is only a blocklisted phrase and must be dropped
+/// * `false` otherwise
+pub fn is_configured_hallucination(text: &str) -> bool {
+    let settings = settings_service::get_settings();
+    let Some(phrase) = matching_phrase(text, &settings.hallucination_blocklist) else {
+        return false;
+    };
+    log::info!("Dropped transcription matching blocklisted phrase \"{phrase}\"");
+    count_blocked(phrase);
+    true
+}
+
+/// Check a transcription of a near-silent recording against the phrases
+/// Whisper outputs on silence and the configured blocklist, counting a match.
+///
+/// # Returns
+/// * `true` if the transcription is only such a phrase and must be dropped
+/// * `false` otherwise
+pub fn is_silence_phrase(text: &str) -> bool {
+    let mut phrases: Vec<String> = SILENCE_PHRASES.iter().map(|p| p.to_string()).collect();
+    phrases.extend(settings_service::get_settings().hallucination_blocklist);
+    let Some(phrase) = matching_phrase(text, &phrases) else {
+        return false;
+    };
+    log::info!("Dropped transcription of a near-silent recording: \"{phrase}\"");
+    count_blocked(phrase);
+    true
+}
+
+fn count_blocked(phrase: &str) {
+    *BLOCKED_COUNTS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(phrase.to_string())
+        .or_insert(0) += 1;
+}
+
+/// Transcriptions dropped since launch, by phrase, in alphabetical order.
+pub fn blocked_counts() -> Vec<(String, u32)> {
+    BLOCKED_COUNTS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .map(|(phrase, count)| (phrase.clone(), *count))
+        .collect()
+}
+
+/// The first phrase of `blocklist` that `text` consists of.
+fn matching_phrase<'a>(text: &str, blocklist: &'a [String]) -> Option<&'a str> {
+    let text = normalize(text);
+    if text.is_empty() {
+        return None;
+    }
+    blocklist
+        .iter()
+        .find(|phrase| matches_phrase(&text, phrase))
+        .map(String::as_str)
+}
+
+/// Check whether normalized `text` is `phrase`, possibly repeated, or starts
+/// with it when the phrase ends with an ellipsis.
+fn matches_phrase(text: &str, phrase: &str) -> bool {
+    let trimmed = phrase.trim_end();
+    let (phrase, is_prefix) = match trimmed
+        .strip_suffix('…')
+        .or_else(|| trimmed.strip_suffix("..."))
+    {
+        Some(start) => (normalize(start), true),
+        None => (normalize(trimmed), false),
+    };
+    if phrase.is_empty() {
+        return false;
+    }
+    if is_prefix {
+        return text == phrase || text.starts_with(&format!("{phrase} "));
+    }
+
+    let mut rest = text;
+    while let Some(after) = rest.strip_prefix(phrase.as_str()) {
+        if after.is_empty() {
+            return true;
+        }
+        if !after.starts_with(' ') {
+            return false;
+        }
+        rest = after.trim_start();
+    }
+    false
+}
+
+/// Lowercase words separated by single spaces, without punctuation.
+fn normalize(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blocklist() -> Vec<String> {
+        [
+            "Thank you for watching",
+            "Subtitles by…",
+            "Sous-titres réalisés par...",
+        ]
+        .iter()
+        .map(|phrase| phrase.to_string())
+        .collect()
+    }
+
+    #[test]
+    fn test_matches_phrase_alone_or_repeated() {
+        let blocklist = blocklist();
+
+        assert_eq!(
+            matching_phrase(" Thank you for watching!", &blocklist),
+            Some("Thank you for watching")
+        );
+        assert_eq!(
+            matching_phrase(
+                "THANK YOU FOR WATCHING. Thank you for watching.",
+                &blocklist
+            ),
+            Some("Thank you for watching")
+        );
+        assert_eq!(
+            matching_phrase("Subtitles by the Amara.org community", &blocklist),
+            Some("Subtitles by…")
+        );
+        assert_eq!(
+            matching_phrase(
+                "Sous-titres réalisés par la communauté d'Amara.org",
+                &blocklist
+            ),
+            Some("Sous-titres réalisés par...")
+        );
+    }
+
+    #[test]
+    fn test_silence_phrases_match_whole_transcriptions() {
+        let phrases: Vec<String> = SILENCE_PHRASES.iter().map(|p| p.to_string()).collect();
+
+        assert_eq!(matching_phrase("Thank you.", &phrases), Some("Thank you"));
+        assert_eq!(matching_phrase(" you", &phrases), Some("You"));
+        assert_eq!(
+            matching_phrase("Thank you. Thank you.", &phrases),
+            Some("Thank you")
+        );
+        assert_eq!(matching_phrase("Thank you for the report.", &phrases), None);
+        assert_eq!(matching_phrase("You too!", &phrases), None);
+    }
+
+    #[test]
+    fn test_keeps_real_dictations() {
+        let blocklist = blocklist();
+
+        assert_eq!(
+            matching_phrase("Thank you for watching the kids last night.", &blocklist),
+            None
+        );
+        assert_eq!(matching_phrase("Subtitles byte order", &blocklist), None);
+        assert_eq!(matching_phrase("...", &blocklist), None);
+        assert_eq!(matching_phrase("Thank you for watching", &[]), None);
+        assert_eq!(
+            matching_phrase("Thanks", &["…".to_string(), " ".to_string()]),
+            None
+        );
+    }
+}
@@ -0,0 +1,557 @@
+//! Transcription history.
+//!
+//! Each completed dictation is stored as `~/.cyrano/history/<id>.json`. When
+//! the `history_audio` privacy setting allows it, the recording is stored
+//! next to it as `<id>.flac` so users can re-listen to (and later
+//! re-transcribe) ambiguous dictations.
+//!
+//! One file per entry keeps writes small and atomic, and lets entries be
+//! deleted or updated without rewriting the whole history.
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::domain::CyranoError;
+use crate::infrastructure::audio::cpal_adapter::TARGET_SAMPLE_RATE;
+use crate::infrastructure::audio::flac;
+use crate::infrastructure::audio::quality::RecordingQuality;
+use crate::services::action_item_service::{self, ActionItem};
+use crate::services::{data_directory_service, history_sync_service, settings_service};
+
+/// Extension of history entry files.
+const ENTRY_EXTENSION: &str = "json";
+
+/// Extension of audio attachment files.
+const AUDIO_EXTENSION: &str = "flac";
+
+/// A single transcription history entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct HistoryEntry {
+    /// Unique identifier (creation time in milliseconds, plus a suffix on collision)
+    pub id: String,
+    /// Unix timestamp in seconds when the dictation completed
+    pub created_at: u32,
+    /// The transcribed text
+    pub text: String,
+    /// Summary of the text, for long transcriptions when summaries are enabled
+    pub summary: Option<String>,
+    /// Action items found in the text
+    #[serde(default)]
+    pub action_items: Vec<ActionItem>,
+    /// Duration of the recorded audio in milliseconds
+    pub audio_duration_ms: u32,
+    /// File name of the model that produced the text, if known
+    pub model: Option<String>,
+    /// Whether the recording is stored with the entry
+    pub has_audio: bool,
+    /// Quality measures of the recording, to explain a bad transcription
+    #[serde(default)]
+    pub quality: Option<RecordingQuality>,
+    /// Text before the last re-transcription, set only if it changed the text
+    pub previous_text: Option<String>,
+    /// Unix timestamp in seconds of the last re-transcription
+    pub retranscribed_at: Option<u32>,
+}
+
+impl HistoryEntry {
+    /// Replace the text with a re-transcription, keeping the old text if it differs.
+    ///
+    /// When the text changes, its action items are extracted again and a
+    /// summary of the old text is dropped.
+    ///
+    /// # Returns
+    /// `true` if the text changed
+    pub fn apply_retranscription(&mut self, text: String, model: Option<String>) -> bool {
+        let changed = text != self.text;
+        if changed {
+            self.previous_text = Some(std::mem::replace(&mut self.text, text));
+            self.summary = None;
+            self.action_items = action_item_service::extract_action_items(&self.text);
+        }
+        self.model = model;
+        self.retranscribed_at = Some((get_timestamp_ms() / 1000) as u32);
+        changed
+    }
+}
+
+/// Get the current Unix timestamp in milliseconds.
+fn get_timestamp_ms() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0)
+}
+
+/// Check that an ID cannot escape the history directory.
+fn validate_id(id: &str) -> Result<(), CyranoError> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(CyranoError::HistoryEntryNotFound { id: id.to_string() });
+    }
+    Ok(())
+}
+
+fn entry_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(id).with_extension(ENTRY_EXTENSION)
+}
+
+fn audio_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(id).with_extension(AUDIO_EXTENSION)
+}
+
+/// Pick an unused ID based on the current time.
+fn allocate_id(dir: &Path) -> String {
+    let base = get_timestamp_ms().to_string();
+    let mut id = base.clone();
+    let mut suffix = 1;
+    while entry_path(dir, &id).exists() {
+        id = format!("{base}-{suffix}");
+        suffix += 1;
+    }
+    id
+}
+
+/// Record a completed dictation, honoring the history privacy settings.
+///
+/// # Arguments
+/// * `text` - The final transcription
+/// * `summary` - Summary of the transcription, if it was summarized
+/// * `samples` - The 16kHz mono recording
+/// * `model` - File name of the model used, if known
+///
+/// # Returns
+/// * `Ok(Some(HistoryEntry))` if the entry was saved
+/// * `Ok(None)` if history is disabled or the text is empty
+/// * `Err(CyranoError::StorageFailed)` if writing failed
+pub fn record_dictation(
+    text: &str,
+    summary: Option<&str>,
+    samples: &[f32],
+    model: Option<String>,
+    quality: Option<RecordingQuality>,
+) -> Result<Option<HistoryEntry>, CyranoError> {
+    let settings = settings_service::get_settings();
+    if !settings.history_enabled || text.trim().is_empty() {
+        return Ok(None);
+    }
+
+    let dir = data_directory_service::history_dir()?;
+    let audio = settings.history_audio.then_some(samples);
+    let entry = record_in(&dir, text, summary, samples.len(), audio, model, quality)?;
+    history_sync_service::record_upsert(&entry);
+    Ok(Some(entry))
+}
+
+/// Record an entry under an arbitrary directory (used directly by tests).
+fn record_in(
+    dir: &Path,
+    text: &str,
+    summary: Option<&str>,
+    sample_count: usize,
+    audio: Option<&[f32]>,
+    model: Option<String>,
+    quality: Option<RecordingQuality>,
+) -> Result<HistoryEntry, CyranoError> {
+    let id = allocate_id(dir);
+
+    let has_audio = match audio {
+        Some(samples) => {
+            let encoded = flac::encode_flac(samples, TARGET_SAMPLE_RATE);
+            data_directory_service::write_file_atomically(&audio_path(dir, &id), &encoded)?;
+            true
+        }
+        None => false,
+    };
+
+    let entry = HistoryEntry {
+        id,
+        created_at: (get_timestamp_ms() / 1000) as u32,
+        text: text.to_string(),
+        summary: summary.map(str::to_string),
+        action_items: action_item_service::extract_action_items(text),
+        audio_duration_ms: (sample_count as u64 * 1000 / u64::from(TARGET_SAMPLE_RATE)) as u32,
+        model,
+        has_audio,
+        quality,
+        previous_text: None,
+        retranscribed_at: None,
+    };
+    write_entry(dir, &entry)?;
+
+    log::info!(
+        "Saved history entry {} ({} chars, audio: {})",
+        entry.id,
+        entry.text.len(),
+        entry.has_audio
+    );
+    Ok(entry)
+}
+
+fn write_entry(dir: &Path, entry: &HistoryEntry) -> Result<(), CyranoError> {
+    let json = serde_json::to_string_pretty(entry).map_err(|e| CyranoError::StorageFailed {
+        reason: format!("Failed to serialize history entry: {e}"),
+    })?;
+    data_directory_service::write_file_atomically(&entry_path(dir, &entry.id), json.as_bytes())
+}
+
+/// List all history entries, newest first.
+///
+/// Unreadable or malformed entry files are skipped with a warning.
+pub fn list_entries() -> Result<Vec<HistoryEntry>, CyranoError> {
+    list_entries_in(&data_directory_service::history_dir()?)
+}
+
+fn list_entries_in(dir: &Path) -> Result<Vec<HistoryEntry>, CyranoError> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let read_dir = std::fs::read_dir(dir).map_err(|e| CyranoError::StorageFailed {
+        reason: format!("Failed to read history directory: {e}"),
+    })?;
+
+    let mut entries: Vec<HistoryEntry> = read_dir
+        .flatten()
+        .map(|dir_entry| dir_entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == ENTRY_EXTENSION))
+        .filter_map(|path| {
+            std::fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()))
+                .inspect_err(|e| log::warn!("Skipping history file {}: {e}", path.display()))
+                .ok()
+        })
+        .collect();
+
+    entries.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.id.cmp(&a.id)));
+    Ok(entries)
+}
+
+/// Get a single history entry.
+///
+/// # Returns
+/// * `Ok(HistoryEntry)` if the entry exists
+/// * `Err(CyranoError::HistoryEntryNotFound)` if it does not
+pub fn get_entry(id: &str) -> Result<HistoryEntry, CyranoError> {
+    get_entry_in(&data_directory_service::history_dir()?, id)
+}
+
+fn get_entry_in(dir: &Path, id: &str) -> Result<HistoryEntry, CyranoError> {
+    validate_id(id)?;
+    let json = std::fs::read_to_string(entry_path(dir, id))
+        .map_err(|_| CyranoError::HistoryEntryNotFound { id: id.to_string() })?;
+    serde_json::from_str(&json).map_err(|e| CyranoError::StorageFailed {
+        reason: format!("Failed to parse history entry {id}: {e}"),
+    })
+}
+
+/// Path of the FLAC audio attached to a history entry, for playback.
+///
+/// # Returns
+/// * `Ok(PathBuf)` with the path of the FLAC file
+/// * `Err(CyranoError::HistoryEntryNotFound)` if the entry has no audio
+pub fn audio_file(id: &str) -> Result<PathBuf, CyranoError> {
+    audio_file_in(&data_directory_service::history_dir()?, id)
+}
+
+fn audio_file_in(dir: &Path, id: &str) -> Result<PathBuf, CyranoError> {
+    validate_id(id)?;
+    let path = audio_path(dir, id);
+    if path.is_file() {
+        Ok(path)
+    } else {
+        Err(CyranoError::HistoryEntryNotFound { id: id.to_string() })
+    }
+}
+
+/// Read the FLAC audio attached to a history entry.
+///
+/// # Returns
+/// * `Ok(Vec<u8>)` with the FLAC file contents
+/// * `Err(CyranoError::HistoryEntryNotFound)` if the entry has no audio
+pub fn load_audio(id: &str) -> Result<Vec<u8>, CyranoError> {
+    load_audio_in(&data_directory_service::history_dir()?, id)
+}
+
+fn load_audio_in(dir: &Path, id: &str) -> Result<Vec<u8>, CyranoError> {
+    std::fs::read(audio_file_in(dir, id)?)
+        .map_err(|_| CyranoError::HistoryEntryNotFound { id: id.to_string() })
+}
+
+/// Decode the audio attached to a history entry into 16kHz mono samples.
+///
+/// # Returns
+/// * `Ok(Vec<f32>)` ready for transcription
+/// * `Err(CyranoError::HistoryEntryNotFound)` if the entry has no audio
+/// * `Err(CyranoError::StorageFailed)` if the audio is unreadable
+pub fn load_audio_samples(id: &str) -> Result<Vec<f32>, CyranoError> {
+    let bytes = load_audio(id)?;
+    let (samples, sample_rate) =
+        flac::decode_flac(&bytes).map_err(|reason| CyranoError::StorageFailed { reason })?;
+
+    if sample_rate != TARGET_SAMPLE_RATE {
+        return Err(CyranoError::StorageFailed {
+            reason: format!(
+                "Audio for history entry {id} is {sample_rate}Hz, expected {TARGET_SAMPLE_RATE}Hz"
+            ),
+        });
+    }
+    Ok(samples)
+}
+
+/// Overwrite an existing history entry.
+///
+/// # Returns
+/// * `Ok(())` on success
+/// * `Err(CyranoError::HistoryEntryNotFound)` if the entry does not exist
+pub fn update_entry(entry: &HistoryEntry) -> Result<(), CyranoError> {
+    update_entry_in(&data_directory_service::history_dir()?, entry)?;
+    history_sync_service::record_upsert(entry);
+    Ok(())
+}
+
+fn update_entry_in(dir: &Path, entry: &HistoryEntry) -> Result<(), CyranoError> {
+    validate_id(&entry.id)?;
+    if !entry_path(dir, &entry.id).exists() {
+        return Err(CyranoError::HistoryEntryNotFound {
+            id: entry.id.clone(),
+        });
+    }
+    write_entry(dir, entry)
+}
+
+/// Delete a history entry and its audio attachment.
+pub fn delete_entry(id: &str) -> Result<(), CyranoError> {
+    delete_entry_in(&data_directory_service::history_dir()?, id)?;
+    history_sync_service::record_delete(id);
+    Ok(())
+}
+
+/// Store an entry received through history sync.
+///
+/// Unlike `update_entry`, this creates missing entries and does not log the
+/// change back to the sync folder. `has_audio` reflects the local attachment,
+/// since audio is not synced.
+pub fn store_synced_entry(mut entry: HistoryEntry) -> Result<(), CyranoError> {
+    let dir = data_directory_service::history_dir()?;
+    validate_id(&entry.id)?;
+    entry.has_audio = audio_path(&dir, &entry.id).exists();
+    write_entry(&dir, &entry)
+}
+
+/// Delete an entry removed on another device, without logging it back.
+pub fn remove_synced_entry(id: &str) -> Result<(), CyranoError> {
+    delete_entry_in(&data_directory_service::history_dir()?, id)
+}
+
+fn delete_entry_in(dir: &Path, id: &str) -> Result<(), CyranoError> {
+    validate_id(id)?;
+    std::fs::remove_file(entry_path(dir, id))
+        .map_err(|_| CyranoError::HistoryEntryNotFound { id: id.to_string() })?;
+
+    let audio = audio_path(dir, id);
+    if audio.exists() {
+        std::fs::remove_file(&audio).map_err(|e| CyranoError::StorageFailed {
+            reason: format!("Failed to delete audio for history entry {id}: {e}"),
+        })?;
+    }
+
+    log::info!("Deleted history entry {id}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::temp_dir;
+
+    #[test]
+    fn test_record_and_list_entries() {
+        let dir = temp_dir("list");
+        let first = record_in(&dir, "first", None, 16_000, None, None, None).unwrap();
+        let second = record_in(
+            &dir,
+            "second",
+            None,
+            8_000,
+            None,
+            Some("ggml-base.bin".into()),
+            Some(RecordingQuality {
+                snr_db: 32.0,
+                clipping_ratio: 0.0,
+                silence_ratio: 0.25,
+                dominant_level_db: -18.5,
+            }),
+        )
+        .unwrap();
+
+        assert_ne!(first.id, second.id);
+        assert_eq!(first.audio_duration_ms, 1000);
+        assert_eq!(second.audio_duration_ms, 500);
+
+        let entries = list_entries_in(&dir).unwrap();
+        assert_eq!(entries.len(), 2);
+        // Newest first
+        assert_eq!(entries[0], second);
+        assert_eq!(entries[1], first);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_audio_attachment_round_trip() {
+        let dir = temp_dir("audio");
+        let samples: Vec<f32> = (0..1600).map(|i| (i as f32 / 50.0).sin() * 0.3).collect();
+        let entry = record_in(
+            &dir,
+            "with audio",
+            None,
+            samples.len(),
+            Some(&samples),
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(entry.has_audio);
+
+        assert!(audio_file_in(&dir, &entry.id)
+            .unwrap()
+            .ends_with(format!("{}.flac", entry.id)));
+        let bytes = load_audio_in(&dir, &entry.id).unwrap();
+        let (decoded, sample_rate) = flac::decode_flac(&bytes).unwrap();
+        assert_eq!(sample_rate, TARGET_SAMPLE_RATE);
+        assert_eq!(decoded.len(), samples.len());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_entry_without_audio_has_no_attachment() {
+        let dir = temp_dir("no-audio");
+        let entry = record_in(&dir, "text only", None, 100, None, None, None).unwrap();
+
+        assert!(!entry.has_audio);
+        assert!(matches!(
+            load_audio_in(&dir, &entry.id),
+            Err(CyranoError::HistoryEntryNotFound { .. })
+        ));
+        assert!(matches!(
+            audio_file_in(&dir, &entry.id),
+            Err(CyranoError::HistoryEntryNotFound { .. })
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_retranscription_marks_changed_text() {
+        let dir = temp_dir("retranscribe");
+        let mut entry = record_in(&dir, "their going", None, 16, None, None, None).unwrap();
+
+        assert!(entry.apply_retranscription("they're going".into(), Some("small.bin".into())));
+        update_entry_in(&dir, &entry).unwrap();
+
+        let stored = get_entry_in(&dir, &entry.id).unwrap();
+        assert_eq!(stored.text, "they're going");
+        assert_eq!(stored.previous_text.as_deref(), Some("their going"));
+        assert_eq!(stored.model.as_deref(), Some("small.bin"));
+        assert!(stored.retranscribed_at.is_some());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_summary_is_stored_until_text_changes() {
+        let dir = temp_dir("summary");
+        let mut entry =
+            record_in(&dir, "long text", Some("Summary"), 16, None, None, None).unwrap();
+        assert_eq!(
+            get_entry_in(&dir, &entry.id).unwrap().summary.as_deref(),
+            Some("Summary")
+        );
+
+        entry.apply_retranscription("long text".into(), None);
+        assert_eq!(entry.summary.as_deref(), Some("Summary"));
+        assert!(entry.action_items.is_empty());
+        entry.apply_retranscription("We need to call Bob".into(), None);
+        assert_eq!(entry.action_items.len(), 1);
+        entry.apply_retranscription("longer text".into(), None);
+        assert_eq!(entry.summary, None);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_unchanged_retranscription_keeps_no_diff() {
+        let dir = temp_dir("same");
+        let mut entry = record_in(&dir, "same", None, 16, None, None, None).unwrap();
+        assert!(!entry.apply_retranscription("same".into(), None));
+        assert!(entry.previous_text.is_none());
+        assert!(entry.retranscribed_at.is_some());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_update_missing_entry_fails() {
+        let dir = temp_dir("update-missing");
+        let mut entry = record_in(&dir, "gone", None, 16, None, None, None).unwrap();
+        delete_entry_in(&dir, &entry.id).unwrap();
+
+        entry.text = "changed".into();
+        assert!(matches!(
+            update_entry_in(&dir, &entry),
+            Err(CyranoError::HistoryEntryNotFound { .. })
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_entries_without_retranscription_fields_still_parse() {
+        let json = r#"{"id":"1","created_at":1,"text":"old","audio_duration_ms":0,"model":null,"has_audio":false}"#;
+        let entry: HistoryEntry = serde_json::from_str(json).expect("Should deserialize");
+        assert!(entry.previous_text.is_none());
+        assert!(entry.retranscribed_at.is_none());
+    }
+
+    #[test]
+    fn test_delete_entry_removes_files() {
+        let dir = temp_dir("delete");
+        let entry = record_in(&dir, "bye", None, 16, Some(&[0.0; 16]), None, None).unwrap();
+
+        delete_entry_in(&dir, &entry.id).unwrap();
+        assert!(!entry_path(&dir, &entry.id).exists());
+        assert!(!audio_path(&dir, &entry.id).exists());
+        assert!(get_entry_in(&dir, &entry.id).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_malformed_entries_are_skipped() {
+        let dir = temp_dir("malformed");
+        std::fs::write(dir.join("broken.json"), "{not json").unwrap();
+        record_in(&dir, "valid", None, 0, None, None, None).unwrap();
+
+        let entries = list_entries_in(&dir).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].text, "valid");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_path_traversal_ids_are_rejected() {
+        let dir = temp_dir("traversal");
+        for id in ["", "../settings", "a/b", "x.json"] {
+            assert!(matches!(
+                get_entry_in(&dir, id),
+                Err(CyranoError::HistoryEntryNotFound { .. })
+            ));
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_missing_directory_lists_nothing() {
+        let root = temp_dir("history");
+        assert!(list_entries_in(&root.join("does-not-exist"))
+            .unwrap()
+            .is_empty());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
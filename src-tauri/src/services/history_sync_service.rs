@@ -0,0 +1,493 @@
+//! History synchronization through a user-chosen synced folder.
+//!
+//! Lets several Macs share their transcription history through any folder
+//! synced by a third party (iCloud Drive, Dropbox, ...) without a server:
+//!
+//! ```text
+//! <sync folder>/cyrano-history/
+//! ├── <device-id>.jsonl   Append-only change log written by one device
+//! └── ...
+//! ```
+//!
+//! Each device only ever writes its own log, so sync tools never see two
+//! devices editing the same file. Merging reads every log and keeps the
+//! latest change per entry (last writer wins, deletions included). On each
+//! sync a device compacts its own log down to one record per entry.
+//!
+//! Only entry text and metadata are synced; audio attachments stay local.
+
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::domain::CyranoError;
+use crate::services::history_service::{self, HistoryEntry};
+use crate::services::{data_directory_service, encrypted_sync_service, settings_service};
+
+/// Subdirectory created inside the user's sync folder.
+const SYNC_SUBDIR: &str = "cyrano-history";
+
+/// Extension of per-device change logs.
+const LOG_EXTENSION: &str = "jsonl";
+
+/// File in the data root holding this device's sync identity.
+const DEVICE_ID_FILE: &str = "device-id";
+
+/// Serializes appends so concurrent writers never interleave lines.
+static LOG_LOCK: Mutex<()> = Mutex::new(());
+
+/// A single change in a device log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub(crate) enum SyncRecord {
+    /// The entry was created or modified.
+    Upsert { at: u64, entry: HistoryEntry },
+    /// The entry was deleted.
+    Delete { at: u64, id: String },
+}
+
+impl SyncRecord {
+    fn id(&self) -> &str {
+        match self {
+            Self::Upsert { entry, .. } => &entry.id,
+            Self::Delete { id, .. } => id,
+        }
+    }
+
+    fn at(&self) -> u64 {
+        match self {
+            Self::Upsert { at, .. } | Self::Delete { at, .. } => *at,
+        }
+    }
+}
+
+/// Result of a sync run.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Type)]
+pub struct HistorySyncReport {
+    /// Number of device logs found in the sync folder
+    pub devices: u32,
+    /// Number of local entries created or updated from other devices
+    pub updated: u32,
+    /// Number of local entries deleted by other devices
+    pub deleted: u32,
+    /// Number of records dropped from this device's log by compaction
+    pub compacted: u32,
+}
+
+/// Get the current Unix timestamp in milliseconds.
+pub(crate) fn get_timestamp_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Get this device's sync identity, creating it on first use.
+pub fn device_id() -> Result<String, CyranoError> {
+    let path = data_directory_service::data_root()?.join(DEVICE_ID_FILE);
+    if let Ok(existing) = std::fs::read_to_string(&path) {
+        let existing = existing.trim();
+        if !existing.is_empty() {
+            return Ok(existing.to_string());
+        }
+    }
+
+    // RandomState is randomly seeded per instance, which is enough for an ID
+    let random = || RandomState::new().build_hasher().finish();
+    let id = format!("{:016x}{:016x}", random(), random());
+    data_directory_service::write_file_atomically(&path, id.as_bytes())?;
+    log::info!("Created history sync device ID {id}");
+    Ok(id)
+}
+
+/// Get the sync directory if history sync is enabled.
+fn sync_dir() -> Option<PathBuf> {
+    settings_service::get_settings()
+        .history_sync_folder
+        .map(|folder| PathBuf::from(folder).join(SYNC_SUBDIR))
+}
+
+fn log_path(dir: &Path, device: &str) -> PathBuf {
+    dir.join(device).with_extension(LOG_EXTENSION)
+}
+
+/// Append records to a log file, one JSON object per line.
+pub(crate) fn append_records(path: &Path, records: &[SyncRecord]) -> Result<(), CyranoError> {
+    let storage_error = |e: std::io::Error| CyranoError::StorageFailed {
+        reason: format!("Failed to append to {}: {e}", path.display()),
+    };
+
+    let mut lines = String::new();
+    for record in records {
+        let line = serde_json::to_string(record).map_err(|e| CyranoError::StorageFailed {
+            reason: format!("Failed to serialize sync record: {e}"),
+        })?;
+        lines.push_str(&line);
+        lines.push('\n');
+    }
+
+    let _guard = LOG_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(storage_error)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(storage_error)?;
+    file.write_all(lines.as_bytes()).map_err(storage_error)
+}
+
+/// Append a record to this device's log if sync is enabled (non-fatal).
+fn record_change(record: SyncRecord) {
+    encrypted_sync_service::queue_change(&record);
+
+    let Some(dir) = sync_dir() else {
+        return;
+    };
+    let result = device_id().and_then(|device| append_records(&log_path(&dir, &device), &[record]));
+    if let Err(e) = result {
+        log::warn!(
+            "[{}] Failed to record history change for sync: {e}",
+            e.code()
+        );
+    }
+}
+
+/// Build upsert records for every local entry, for a first upload.
+///
+/// Entries are stamped with their own timestamps so newer remote changes
+/// still win the merge.
+pub(crate) fn export_records() -> Result<Vec<SyncRecord>, CyranoError> {
+    Ok(history_service::list_entries()?
+        .into_iter()
+        .map(|entry| SyncRecord::Upsert {
+            at: u64::from(entry.retranscribed_at.unwrap_or(entry.created_at)) * 1000,
+            entry,
+        })
+        .collect())
+}
+
+/// Apply merged records to the local history.
+///
+/// # Returns
+/// * `Ok((updated, deleted))` with the number of local entries changed
+pub(crate) fn apply_records(
+    records: impl IntoIterator<Item = SyncRecord>,
+) -> Result<(u32, u32), CyranoError> {
+    let local: HashMap<String, HistoryEntry> = history_service::list_entries()?
+        .into_iter()
+        .map(|entry| (entry.id.clone(), entry))
+        .collect();
+
+    let (mut updated, mut deleted) = (0, 0);
+    for record in records {
+        match record {
+            SyncRecord::Upsert { entry, .. } => {
+                // Audio stays local, so only compare what is synced
+                let unchanged = local.get(&entry.id).is_some_and(|existing| {
+                    let mut incoming = entry.clone();
+                    incoming.has_audio = existing.has_audio;
+                    *existing == incoming
+                });
+                if !unchanged {
+                    history_service::store_synced_entry(entry)?;
+                    updated += 1;
+                }
+            }
+            SyncRecord::Delete { id, .. } => {
+                if local.contains_key(&id) {
+                    history_service::remove_synced_entry(&id)?;
+                    deleted += 1;
+                }
+            }
+        }
+    }
+    Ok((updated, deleted))
+}
+
+/// Record that an entry was created or modified.
+pub fn record_upsert(entry: &HistoryEntry) {
+    record_change(SyncRecord::Upsert {
+        at: get_timestamp_ms(),
+        entry: entry.clone(),
+    });
+}
+
+/// Record that an entry was deleted.
+pub fn record_delete(id: &str) {
+    record_change(SyncRecord::Delete {
+        at: get_timestamp_ms(),
+        id: id.to_string(),
+    });
+}
+
+/// Read a log, skipping malformed lines (e.g. a partially synced last line).
+pub(crate) fn read_log(path: &Path) -> Vec<SyncRecord> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            log::warn!("Failed to read sync log {}: {e}", path.display());
+            return Vec::new();
+        }
+    };
+
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| {
+            serde_json::from_str(line)
+                .inspect_err(|e| log::warn!("Skipping sync record in {}: {e}", path.display()))
+                .ok()
+        })
+        .collect()
+}
+
+/// Keep the latest record per entry ID across all logs.
+///
+/// Ties on the timestamp are broken by device ID so every device reaches the
+/// same result.
+pub(crate) fn merge_logs(logs: &[(String, Vec<SyncRecord>)]) -> HashMap<String, SyncRecord> {
+    let mut latest: HashMap<String, (u64, &str, &SyncRecord)> = HashMap::new();
+    for (device, records) in logs {
+        for record in records {
+            let key = (record.at(), device.as_str());
+            let is_newer = latest
+                .get(record.id())
+                .is_none_or(|(at, winner, _)| key > (*at, *winner));
+            if is_newer {
+                latest.insert(record.id().to_string(), (key.0, key.1, record));
+            }
+        }
+    }
+
+    latest
+        .into_iter()
+        .map(|(id, (_, _, record))| (id, record.clone()))
+        .collect()
+}
+
+/// Reduce a log to its latest record per entry, in chronological order.
+pub(crate) fn compact(records: &[SyncRecord]) -> Vec<SyncRecord> {
+    let mut latest: HashMap<&str, &SyncRecord> = HashMap::new();
+    for record in records {
+        if latest
+            .get(record.id())
+            .is_none_or(|r| record.at() >= r.at())
+        {
+            latest.insert(record.id(), record);
+        }
+    }
+
+    let mut compacted: Vec<SyncRecord> = latest.into_values().cloned().collect();
+    compacted.sort_by(|a, b| a.at().cmp(&b.at()).then_with(|| a.id().cmp(b.id())));
+    compacted
+}
+
+/// Merge every device log into the local history and compact this device's log.
+///
+/// # Returns
+/// * `Ok(HistorySyncReport)` describing the changes
+/// * `Err(CyranoError::StorageFailed)` if sync is disabled or the folder is unusable
+pub fn sync_history() -> Result<HistorySyncReport, CyranoError> {
+    let dir = sync_dir().ok_or_else(|| CyranoError::StorageFailed {
+        reason: "History sync folder is not configured".to_string(),
+    })?;
+    let device = device_id()?;
+
+    std::fs::create_dir_all(&dir).map_err(|e| CyranoError::StorageFailed {
+        reason: format!("Failed to create {}: {e}", dir.display()),
+    })?;
+    let read_dir = std::fs::read_dir(&dir).map_err(|e| CyranoError::StorageFailed {
+        reason: format!("Failed to read {}: {e}", dir.display()),
+    })?;
+
+    // Conflict copies created by sync tools are plain extra logs and merge the same way
+    let logs: Vec<(String, Vec<SyncRecord>)> = read_dir
+        .flatten()
+        .map(|dir_entry| dir_entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == LOG_EXTENSION))
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_string_lossy().into_owned();
+            Some((name, read_log(&path)))
+        })
+        .collect();
+
+    let (updated, deleted) = apply_records(merge_logs(&logs).into_values())?;
+    let mut report = HistorySyncReport {
+        devices: logs.len() as u32,
+        updated,
+        deleted,
+        compacted: 0,
+    };
+
+    let own_log = log_path(&dir, &device);
+    if own_log.exists() {
+        let _guard = LOG_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let records = read_log(&own_log);
+        let compacted = compact(&records);
+        if compacted.len() < records.len() {
+            report.compacted = (records.len() - compacted.len()) as u32;
+            let mut contents = String::new();
+            for record in &compacted {
+                if let Ok(line) = serde_json::to_string(record) {
+                    contents.push_str(&line);
+                    contents.push('\n');
+                }
+            }
+            data_directory_service::write_file_atomically(&own_log, contents.as_bytes())?;
+        }
+    }
+
+    log::info!("History sync complete: {report:?}");
+    Ok(report)
+}
+
+/// Enable history sync to `folder`, or disable it with `None`.
+///
+/// When enabling, every local entry is exported to this device's log so
+/// other devices receive the existing history, then a first sync runs.
+///
+/// # Returns
+/// * `Ok(Some(HistorySyncReport))` after the first sync when enabling
+/// * `Ok(None)` when disabling
+/// * `Err(CyranoError::StorageFailed)` if the folder does not exist or sync fails
+pub fn set_sync_folder(folder: Option<String>) -> Result<Option<HistorySyncReport>, CyranoError> {
+    let Some(folder) = folder else {
+        settings_service::update_settings(|s| s.history_sync_folder = None)?;
+        log::info!("History sync disabled");
+        return Ok(None);
+    };
+
+    if !Path::new(&folder).is_dir() {
+        return Err(CyranoError::StorageFailed {
+            reason: format!("Sync folder does not exist: {folder}"),
+        });
+    }
+    settings_service::update_settings(|s| s.history_sync_folder = Some(folder.clone()))?;
+    log::info!("History sync enabled with folder {folder}");
+
+    if let Some(dir) = sync_dir() {
+        append_records(&log_path(&dir, &device_id()?), &export_records()?)?;
+    }
+
+    sync_history().map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::temp_dir;
+
+    fn entry(id: &str, text: &str) -> HistoryEntry {
+        HistoryEntry {
+            id: id.to_string(),
+            created_at: 0,
+            text: text.to_string(),
+            summary: None,
+            action_items: Vec::new(),
+            audio_duration_ms: 0,
+            model: None,
+            has_audio: false,
+            quality: None,
+            previous_text: None,
+            retranscribed_at: None,
+        }
+    }
+
+    fn upsert(at: u64, id: &str, text: &str) -> SyncRecord {
+        SyncRecord::Upsert {
+            at,
+            entry: entry(id, text),
+        }
+    }
+
+    fn delete(at: u64, id: &str) -> SyncRecord {
+        SyncRecord::Delete {
+            at,
+            id: id.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_merge_keeps_latest_change_across_devices() {
+        let logs = vec![
+            (
+                "a".to_string(),
+                vec![upsert(1, "x", "old"), upsert(5, "y", "mac a")],
+            ),
+            (
+                "b".to_string(),
+                vec![upsert(3, "x", "new"), upsert(4, "y", "mac b")],
+            ),
+        ];
+        let merged = merge_logs(&logs);
+        assert_eq!(merged["x"], upsert(3, "x", "new"));
+        assert_eq!(merged["y"], upsert(5, "y", "mac a"));
+    }
+
+    #[test]
+    fn test_merge_applies_later_deletion() {
+        let logs = vec![
+            ("a".to_string(), vec![upsert(1, "x", "text")]),
+            ("b".to_string(), vec![delete(2, "x")]),
+        ];
+        assert_eq!(merge_logs(&logs)["x"], delete(2, "x"));
+    }
+
+    #[test]
+    fn test_merge_breaks_ties_by_device() {
+        let forward = vec![
+            ("a".to_string(), vec![upsert(1, "x", "from a")]),
+            ("b".to_string(), vec![upsert(1, "x", "from b")]),
+        ];
+        let reversed: Vec<_> = forward.iter().cloned().rev().collect();
+        assert_eq!(merge_logs(&forward)["x"], merge_logs(&reversed)["x"]);
+        assert_eq!(merge_logs(&forward)["x"], upsert(1, "x", "from b"));
+    }
+
+    #[test]
+    fn test_compact_keeps_latest_record_per_entry() {
+        let records = vec![
+            upsert(1, "x", "v1"),
+            upsert(2, "y", "only"),
+            upsert(3, "x", "v2"),
+            delete(4, "x"),
+        ];
+        assert_eq!(
+            compact(&records),
+            vec![upsert(2, "y", "only"), delete(4, "x")]
+        );
+    }
+
+    #[test]
+    fn test_append_and_read_log_round_trip() {
+        let path = temp_dir("sync").join("device.jsonl");
+        append_records(&path, &[upsert(1, "x", "hello")]).unwrap();
+        append_records(&path, &[delete(2, "x")]).unwrap();
+
+        assert_eq!(
+            read_log(&path),
+            vec![upsert(1, "x", "hello"), delete(2, "x")]
+        );
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_read_log_skips_truncated_lines() {
+        let dir = temp_dir("sync-truncated");
+        let path = dir.join("device.jsonl");
+        let valid = serde_json::to_string(&delete(1, "x")).unwrap();
+        std::fs::write(&path, format!("{valid}\n{{\"op\":\"ups")).unwrap();
+
+        assert_eq!(read_log(&path), vec![delete(1, "x")]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,138 @@
+//! Scheduler for background transcription work.
+//!
+//! File transcription (including watched folders and voice memos) and
+//! history re-transcription each run on their own thread. Before each unit
+//! of work (one file, one history entry) they take a slot from the
+//! scheduler, which caps how many run at once (`max_parallel_jobs`) and
+//! holds them back while a dictation is recording or transcribing, so the
+//! interactive path never waits behind more than the unit already running.
+//!
+//! The unit already running is preempted too: transcriptions run through
+//! [`run_preemptible`] poll [`preempted`] and abort as soon as a dictation
+//! starts recording, then start over once it is pasted.
+
+use std::sync::{Condvar, Mutex};
+use std::time::Duration;
+
+use crate::domain::{CyranoError, RecordingState};
+use crate::services::{recording_state, settings_service};
+
+/// Bounds of the `max_parallel_jobs` setting.
+const MIN_PARALLEL_JOBS: u32 = 1;
+const MAX_PARALLEL_JOBS: u32 = 8;
+
+/// How often a waiting job checks whether the dictation has ended.
+const RECHECK_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Number of background jobs holding a slot.
+static RUNNING_JOBS: Mutex<u32> = Mutex::new(0);
+
+/// Signaled when a slot is released.
+static SLOT_RELEASED: Condvar = Condvar::new();
+
+/// A slot held by a background job, released when dropped.
+#[derive(Debug)]
+pub struct JobSlot {
+    name: &'static str,
+}
+
+impl Drop for JobSlot {
+    fn drop(&mut self) {
+        let mut running = RUNNING_JOBS.lock().unwrap_or_else(|e| e.into_inner());
+        *running = running.saturating_sub(1);
+        log::debug!("Background job {} released its slot", self.name);
+        SLOT_RELEASED.notify_all();
+    }
+}
+
+/// Whether a background job may start now.
+fn can_start(running: u32, max_parallel_jobs: u32, dictation_active: bool) -> bool {
+    !dictation_active && running < max_parallel_jobs.clamp(MIN_PARALLEL_JOBS, MAX_PARALLEL_JOBS)
+}
+
+fn dictation_active() -> bool {
+    matches!(
+        recording_state::get_recording_state(),
+        RecordingState::Recording | RecordingState::Transcribing
+    )
+}
+
+/// Whether background work should stop to leave the CPU (and the loaded
+/// model) to a dictation.
+pub fn preempted() -> bool {
+    dictation_active()
+}
+
+/// Run a unit of background work, typically a transcription aborting
+/// itself when [`preempted`] returns true. If it fails while a dictation is
+/// in progress, it is retried from the start once the dictation is done.
+///
+/// Blocks, so MUST run off the main thread.
+pub fn run_preemptible<T>(
+    name: &'static str,
+    mut work: impl FnMut() -> Result<T, CyranoError>,
+) -> Result<T, CyranoError> {
+    loop {
+        let result = work();
+        if result.is_ok() || !preempted() {
+            return result;
+        }
+
+        log::info!("Background job {name} preempted by a dictation");
+        while preempted() {
+            std::thread::sleep(RECHECK_INTERVAL);
+        }
+        log::debug!("Background job {name} restarting");
+    }
+}
+
+/// Wait for a background job slot. Blocks while the configured number of
+/// jobs is running or a dictation is in progress, so MUST run off the main
+/// thread.
+///
+/// `name` identifies the job in logs, e.g. `file-transcription`.
+pub fn acquire_slot(name: &'static str) -> JobSlot {
+    let mut running = RUNNING_JOBS.lock().unwrap_or_else(|e| e.into_inner());
+    let mut waiting = false;
+    loop {
+        // Read every time: the setting may change while waiting
+        let max_parallel_jobs = settings_service::get_settings().max_parallel_jobs;
+        if can_start(*running, max_parallel_jobs, dictation_active()) {
+            *running += 1;
+            if waiting {
+                log::debug!("Background job {name} resumed");
+            }
+            return JobSlot { name };
+        }
+
+        if !waiting {
+            log::debug!("Background job {name} waiting for a slot");
+            waiting = true;
+        }
+        // A dictation ending does not signal the condvar: recheck periodically
+        running = SLOT_RELEASED
+            .wait_timeout(running, RECHECK_INTERVAL)
+            .map(|(guard, _)| guard)
+            .unwrap_or_else(|e| e.into_inner().0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jobs_are_capped() {
+        assert!(can_start(0, 1, false));
+        assert!(!can_start(1, 1, false));
+        assert!(can_start(1, 2, false));
+        // Out of range settings are clamped
+        assert!(can_start(0, 0, false));
+        assert!(!can_start(8, 100, false));
+    }
+
+    #[test]
+    fn test_dictation_has_priority() {
+        assert!(!can_start(0, 4, true));
+    }
+}
@@ -0,0 +1,254 @@
+//! List dictation: spoken commands that format Markdown lists.
+//!
+//! When enabled, these words are interpreted as commands instead of text:
+//! - "bullet list", "numbered list": start a list, with a first item
+//! - "bullet", "bullet point", "next item": start a new item
+//! - "end list": close the list; following text is a new paragraph
+//!
+//! "Numbered list, buy milk. Next item, eggs." becomes `1. buy milk` and
+//! `2. eggs` on two lines. The list stays open across dictations, so "next
+//! item" in the following dictation continues the numbering. A dictation
+//! without any list command closes it.
+
+use std::sync::Mutex;
+
+use crate::services::settings_service;
+
+/// Kind of list being dictated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ListKind {
+    Bullet,
+    Numbered,
+}
+
+/// A spoken list command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ListCommand {
+    /// Start a new list of this kind
+    Start(ListKind),
+    /// Start a new item, or a list of this kind if none is open
+    Item(ListKind),
+    End,
+}
+
+/// Spoken phrases and their commands, longest first so "bullet list" wins
+/// over "bullet".
+const COMMANDS: [(&[&str], ListCommand); 6] = [
+    (
+        &["numbered", "list"],
+        ListCommand::Start(ListKind::Numbered),
+    ),
+    (&["bullet", "list"], ListCommand::Start(ListKind::Bullet)),
+    (&["bullet", "point"], ListCommand::Item(ListKind::Bullet)),
+    (&["next", "item"], ListCommand::Item(ListKind::Bullet)),
+    (&["end", "list"], ListCommand::End),
+    (&["bullet"], ListCommand::Item(ListKind::Bullet)),
+];
+
+/// Part of a dictation: text, or a list command.
+#[derive(Debug, PartialEq)]
+enum Piece {
+    Text(String),
+    Command(ListCommand),
+}
+
+/// The list being dictated.
+#[derive(Debug, Clone, Copy)]
+struct OpenList {
+    kind: ListKind,
+    next_number: u32,
+}
+
+/// Turns list commands into Markdown, remembering the open list between
+/// dictations.
+#[derive(Debug, Default)]
+pub struct ListInterpreter {
+    list: Option<OpenList>,
+}
+
+static INTERPRETER: Mutex<ListInterpreter> = Mutex::new(ListInterpreter::new());
+
+/// Apply list commands to a dictation if list dictation is enabled.
+pub fn apply_configured_list_commands(text: &str) -> String {
+    if !settings_service::get_settings().list_dictation_enabled {
+        return text.to_string();
+    }
+    INTERPRETER
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .interpret(text)
+}
+
+impl ListInterpreter {
+    pub const fn new() -> Self {
+        Self { list: None }
+    }
+
+    /// Format the list commands of a dictation.
+    ///
+    /// Text without any command is returned unchanged, and closes the list.
+    pub fn interpret(&mut self, text: &str) -> String {
+        let pieces = parse(text);
+        if !pieces.iter().any(|p| matches!(p, Piece::Command(_))) {
+            self.list = None;
+            return text.to_string();
+        }
+
+        // Items continuing a list from a previous dictation go on a new line
+        let continuing = self.list.is_some();
+        let mut output = String::new();
+        let mut item_pending = false;
+        let mut break_pending = false;
+        for piece in pieces {
+            match piece {
+                Piece::Command(ListCommand::Start(kind)) => {
+                    self.list = Some(OpenList {
+                        kind,
+                        next_number: 1,
+                    });
+                    item_pending = true;
+                }
+                Piece::Command(ListCommand::Item(kind)) => {
+                    self.list.get_or_insert(OpenList {
+                        kind,
+                        next_number: 1,
+                    });
+                    item_pending = true;
+                }
+                Piece::Command(ListCommand::End) => {
+                    self.list = None;
+                    item_pending = false;
+                    break_pending = true;
+                }
+                Piece::Text(text) => {
+                    let text = clean_fragment(&text, self.list.is_some());
+                    if text.is_empty() {
+                        continue;
+                    }
+                    if item_pending || break_pending {
+                        if !output.is_empty() || continuing {
+                            output.push('\n');
+                        }
+                    } else if !output.is_empty() {
+                        output.push(' ');
+                    }
+                    if item_pending {
+                        output.push_str(&self.next_marker());
+                    }
+                    output.push_str(text);
+                    item_pending = false;
+                    break_pending = false;
+                }
+            }
+        }
+        output
+    }
+
+    /// Marker of the next item of the open list (`- ` or `3. `).
+    fn next_marker(&mut self) -> String {
+        match &mut self.list {
+            Some(OpenList {
+                kind: ListKind::Numbered,
+                next_number,
+            }) => {
+                let marker = format!("{next_number}. ");
+                *next_number += 1;
+                marker
+            }
+            _ => "- ".to_string(),
+        }
+    }
+}
+
+/// Split a dictation into text and list commands.
+fn parse(text: &str) -> Vec<Piece> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let normalized: Vec<String> = words
+        .iter()
+        .map(|word| {
+            word.trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+        })
+        .collect();
+
+    let mut pieces = Vec::new();
+    let mut text_words: Vec<&str> = Vec::new();
+    let mut index = 0;
+    while index < words.len() {
+        let command = COMMANDS.iter().find(|(phrase, _)| {
+            normalized[index..].len() >= phrase.len()
+                && phrase
+                    .iter()
+                    .zip(&normalized[index..])
+                    .all(|(expected, word)| expected == word)
+        });
+        match command {
+            Some((phrase, command)) => {
+                if !text_words.is_empty() {
+                    pieces.push(Piece::Text(text_words.join(" ")));
+                    text_words.clear();
+                }
+                pieces.push(Piece::Command(*command));
+                index += phrase.len();
+            }
+            None => {
+                text_words.push(words[index]);
+                index += 1;
+            }
+        }
+    }
+    if !text_words.is_empty() {
+        pieces.push(Piece::Text(text_words.join(" ")));
+    }
+    pieces
+}
+
+/// Trim the punctuation Whisper puts around spoken commands. List items also
+/// lose their final period.
+fn clean_fragment(text: &str, in_list: bool) -> &str {
+    let text = text.trim().trim_start_matches([',', '.', ':', ';']).trim();
+    if in_list {
+        text.trim_end_matches([',', ';', '.', ':'])
+    } else {
+        text.trim_end_matches([',', ';'])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_formats_numbered_and_bullet_lists() {
+        let mut interpreter = ListInterpreter::new();
+        assert_eq!(
+            interpreter.interpret("Groceries: numbered list, buy milk. Next item, eggs."),
+            "Groceries:\n1. buy milk\n2. eggs"
+        );
+
+        let mut interpreter = ListInterpreter::new();
+        assert_eq!(
+            interpreter.interpret(
+                "Bullet list. Bullet point. Tests pass. Bullet, docs. End list. Ship it."
+            ),
+            "- Tests pass\n- docs\nShip it."
+        );
+    }
+
+    #[test]
+    fn test_list_continues_across_dictations() {
+        let mut interpreter = ListInterpreter::new();
+        assert_eq!(
+            interpreter.interpret("Numbered list. First step."),
+            "1. First step"
+        );
+        assert_eq!(
+            interpreter.interpret("Next item. Second step."),
+            "\n2. Second step"
+        );
+
+        // A dictation without commands is plain text and closes the list
+        assert_eq!(interpreter.interpret("Thanks, bye."), "Thanks, bye.");
+        assert_eq!(interpreter.interpret("Next item, again."), "- again");
+    }
+}
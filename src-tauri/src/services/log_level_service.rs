@@ -0,0 +1,278 @@
+//! Logging verbosity, adjustable at runtime.
+//!
+//! The logger passes every record through [`is_enabled`], which applies a
+//! default level and optional per-module overrides. Users can turn on debug
+//! logs for a single problematic dictation (e.g. only for audio capture)
+//! and turn them off again, without restarting with environment variables.
+//!
+//! Levels are not persisted: every launch starts at the default level.
+
+use std::sync::{LazyLock, RwLock};
+
+use log::LevelFilter;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Module names standing for a group of log targets.
+const MODULE_ALIASES: &[(&str, &[&str])] = &[
+    ("audio", &["cyrano_lib::infrastructure::audio"]),
+    (
+        "whisper",
+        &[
+            "cyrano_lib::infrastructure::whisper",
+            "whisper_rs",
+            "whisper_cpp",
+        ],
+    ),
+];
+
+/// Verbosity of the logs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevel {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl From<LogLevel> for LevelFilter {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Off => LevelFilter::Off,
+            LogLevel::Error => LevelFilter::Error,
+            LogLevel::Warn => LevelFilter::Warn,
+            LogLevel::Info => LevelFilter::Info,
+            LogLevel::Debug => LevelFilter::Debug,
+            LogLevel::Trace => LevelFilter::Trace,
+        }
+    }
+}
+
+impl From<LevelFilter> for LogLevel {
+    fn from(level: LevelFilter) -> Self {
+        match level {
+            LevelFilter::Off => LogLevel::Off,
+            LevelFilter::Error => LogLevel::Error,
+            LevelFilter::Warn => LogLevel::Warn,
+            LevelFilter::Info => LogLevel::Info,
+            LevelFilter::Debug => LogLevel::Debug,
+            LevelFilter::Trace => LogLevel::Trace,
+        }
+    }
+}
+
+/// Level override for one module.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Type)]
+pub struct ModuleLogLevel {
+    /// Module alias (`audio`, `whisper`) or log target prefix
+    pub module: String,
+    pub level: LogLevel,
+}
+
+/// Current logging verbosity.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Type)]
+pub struct LogLevels {
+    /// Level of modules without an override
+    pub level: LogLevel,
+    pub modules: Vec<ModuleLogLevel>,
+}
+
+#[derive(Debug, Clone)]
+struct Filters {
+    level: LevelFilter,
+    modules: Vec<(String, LevelFilter)>,
+}
+
+impl Filters {
+    fn new() -> Self {
+        Self {
+            level: default_level(),
+            modules: Vec::new(),
+        }
+    }
+
+    /// Level for a log target: the most specific module override, or the
+    /// default level.
+    fn level_for(&self, target: &str) -> LevelFilter {
+        self.modules
+            .iter()
+            .flat_map(|(module, level)| {
+                target_prefixes(module)
+                    .into_iter()
+                    .filter(|prefix| is_within(target, prefix))
+                    .map(move |prefix| (prefix.len(), *level))
+            })
+            .max_by_key(|(specificity, _)| *specificity)
+            .map_or(self.level, |(_, level)| level)
+    }
+
+    /// Most verbose level in use, so the `log` macros skip nothing needed.
+    fn max_level(&self) -> LevelFilter {
+        self.modules
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.level, Ord::max)
+    }
+
+    fn set(&mut self, level: LevelFilter, module: Option<&str>) {
+        match module.map(str::trim).filter(|module| !module.is_empty()) {
+            None => self.level = level,
+            Some(module) => match self.modules.iter_mut().find(|(name, _)| name == module) {
+                Some((_, existing)) => *existing = level,
+                None => self.modules.push((module.to_string(), level)),
+            },
+        }
+    }
+}
+
+static FILTERS: LazyLock<RwLock<Filters>> = LazyLock::new(|| RwLock::new(Filters::new()));
+
+/// Level at launch: debug in development, info in production.
+pub fn default_level() -> LevelFilter {
+    if cfg!(debug_assertions) {
+        LevelFilter::Debug
+    } else {
+        LevelFilter::Info
+    }
+}
+
+/// Log targets covered by a module name.
+fn target_prefixes(module: &str) -> Vec<&str> {
+    MODULE_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == module)
+        .map_or_else(|| vec![module], |(_, prefixes)| prefixes.to_vec())
+}
+
+/// Whether `target` is the module `prefix` or one of its submodules.
+fn is_within(target: &str, prefix: &str) -> bool {
+    target
+        .strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+}
+
+/// Whether a log record passes the current levels.
+pub fn is_enabled(metadata: &log::Metadata) -> bool {
+    FILTERS
+        .read()
+        .map(|filters| metadata.level() <= filters.level_for(metadata.target()))
+        .unwrap_or(true)
+}
+
+/// Set the default level, or the level of one module.
+///
+/// `module` is an alias (`audio`, `whisper`) or a log target prefix such as
+/// `cyrano_lib::services::dictation_service`.
+pub fn set_log_level(level: LogLevel, module: Option<&str>) {
+    update(|filters| filters.set(level.into(), module));
+    log::info!(
+        "Log level set to {level:?} for {}",
+        module.unwrap_or("all modules")
+    );
+}
+
+/// Back to the default level, without module overrides.
+pub fn reset_log_levels() {
+    update(|filters| *filters = Filters::new());
+    log::info!("Log levels reset to {:?}", default_level());
+}
+
+/// The current default level and module overrides.
+pub fn get_log_levels() -> LogLevels {
+    let filters = FILTERS
+        .read()
+        .map(|filters| filters.clone())
+        .unwrap_or_else(|_| Filters::new());
+    LogLevels {
+        level: filters.level.into(),
+        modules: filters
+            .modules
+            .into_iter()
+            .map(|(module, level)| ModuleLogLevel {
+                module,
+                level: level.into(),
+            })
+            .collect(),
+    }
+}
+
+fn update(change: impl FnOnce(&mut Filters)) {
+    match FILTERS.write() {
+        Ok(mut filters) => {
+            change(&mut filters);
+            log::set_max_level(filters.max_level());
+        }
+        Err(e) => log::error!("Failed to lock log levels: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filters() -> Filters {
+        Filters {
+            level: LevelFilter::Info,
+            modules: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_module_override_applies_to_submodules_only() {
+        let mut filters = filters();
+        filters.set(LevelFilter::Trace, Some("cyrano_lib::services"));
+
+        assert_eq!(
+            filters.level_for("cyrano_lib::services::dictation_service"),
+            LevelFilter::Trace
+        );
+        assert_eq!(
+            filters.level_for("cyrano_lib::services"),
+            LevelFilter::Trace
+        );
+        assert_eq!(
+            filters.level_for("cyrano_lib::services_extra"),
+            LevelFilter::Info
+        );
+        assert_eq!(filters.max_level(), LevelFilter::Trace);
+    }
+
+    #[test]
+    fn test_aliases_and_most_specific_override() {
+        let mut filters = filters();
+        filters.set(LevelFilter::Debug, Some("whisper"));
+        filters.set(LevelFilter::Off, Some("whisper_rs::whisper_ctx"));
+
+        assert_eq!(filters.level_for("whisper_rs"), LevelFilter::Debug);
+        assert_eq!(filters.level_for("whisper_cpp"), LevelFilter::Debug);
+        assert_eq!(
+            filters.level_for("cyrano_lib::infrastructure::whisper::whisper_adapter"),
+            LevelFilter::Debug
+        );
+        assert_eq!(
+            filters.level_for("whisper_rs::whisper_ctx"),
+            LevelFilter::Off
+        );
+        assert_eq!(
+            filters.level_for("cyrano_lib::infrastructure::audio::cpal_adapter"),
+            LevelFilter::Info
+        );
+    }
+
+    #[test]
+    fn test_setting_a_module_again_replaces_its_level() {
+        let mut filters = filters();
+        filters.set(LevelFilter::Debug, Some("audio"));
+        filters.set(LevelFilter::Warn, Some(" audio "));
+        filters.set(LevelFilter::Error, Some(""));
+
+        assert_eq!(
+            filters.modules,
+            vec![("audio".to_string(), LevelFilter::Warn)]
+        );
+        assert_eq!(filters.level, LevelFilter::Error);
+    }
+}
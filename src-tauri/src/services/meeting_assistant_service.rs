@@ -0,0 +1,285 @@
+//! Meeting assistant: detects calls and offers to transcribe them.
+//!
+//! A background thread polls which conferencing apps are running and
+//! whether the microphone is in use by another process. A call is assumed
+//! to start when both hold for a few consecutive polls, and to end when the
+//! microphone has been released for a while. On start, a notification is
+//! shown and meeting-detected is emitted so the frontend can offer meeting
+//! transcription; desktop notifications cannot carry action buttons, so
+//! the offer itself is made in the app. Accepting calls
+//! `start_meeting_transcription`, which needs system audio capture: while
+//! `capabilities_service` reports it unavailable, meetings are still
+//! tracked but no offer is made. Declining needs no call: the offer is not
+//! repeated until the next meeting.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::domain::CyranoError;
+use crate::services::{
+    capabilities_service, notifier_service, recording_service, settings_service,
+};
+
+/// Conferencing apps by bundle identifier, with their display names.
+const CONFERENCING_APPS: &[(&str, &str)] = &[
+    ("us.zoom.xos", "Zoom"),
+    ("com.microsoft.teams2", "Microsoft Teams"),
+    ("com.microsoft.teams", "Microsoft Teams"),
+    // Google Meet installed as a Chrome app
+    (
+        "com.google.Chrome.app.kjgfgldnnfoeklkmfkjfagphfepbbdan",
+        "Google Meet",
+    ),
+    ("com.cisco.webexmeetingsapp", "Webex"),
+    ("com.apple.FaceTime", "FaceTime"),
+];
+
+/// How often the apps and the microphone are checked.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Consecutive polls with a conferencing app using the microphone before
+/// a meeting is reported, so a quick audio test is ignored.
+const START_POLLS: u32 = 2;
+
+/// Consecutive polls without microphone use before the meeting is
+/// considered over, so muting briefly does not end it.
+const END_POLLS: u32 = 3;
+
+/// Change in meeting state reported by the detector.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum MeetingChange {
+    Started(&'static str),
+    Ended(&'static str),
+}
+
+/// Debounces poll results into meeting start and end changes.
+#[derive(Debug, Default)]
+struct MeetingDetector {
+    /// Display name of the app running the current meeting
+    active: Option<&'static str>,
+    /// Consecutive polls agreeing with a change of state
+    streak: u32,
+}
+
+impl MeetingDetector {
+    /// Feed one poll: the conferencing app found running (if any) and
+    /// whether the microphone is in use.
+    fn update(&mut self, app: Option<&'static str>, mic_in_use: bool) -> Option<MeetingChange> {
+        let in_call = app.filter(|_| mic_in_use);
+        match (self.active, in_call) {
+            (None, Some(app)) => {
+                self.streak += 1;
+                if self.streak >= START_POLLS {
+                    self.streak = 0;
+                    self.active = Some(app);
+                    return Some(MeetingChange::Started(app));
+                }
+            }
+            (Some(active), None) => {
+                self.streak += 1;
+                if self.streak >= END_POLLS {
+                    self.streak = 0;
+                    self.active = None;
+                    return Some(MeetingChange::Ended(active));
+                }
+            }
+            _ => self.streak = 0,
+        }
+        None
+    }
+}
+
+static DETECTOR: Mutex<MeetingDetector> = Mutex::new(MeetingDetector {
+    active: None,
+    streak: 0,
+});
+
+/// Payload for the meeting-detected and meeting-ended events.
+#[derive(Clone, Serialize)]
+pub struct MeetingPayload {
+    /// Display name of the conferencing app
+    pub app_name: String,
+}
+
+/// Display name of the first conferencing app running.
+#[cfg(target_os = "macos")]
+fn running_conferencing_app() -> Option<&'static str> {
+    use crate::infrastructure::workspace;
+
+    CONFERENCING_APPS
+        .iter()
+        .find(|(bundle_id, _)| workspace::is_running(bundle_id))
+        .map(|&(_, name)| name)
+}
+
+#[cfg(target_os = "macos")]
+fn microphone_in_use() -> bool {
+    crate::infrastructure::audio::macos_input_activity::default_input_in_use().unwrap_or(false)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn running_conferencing_app() -> Option<&'static str> {
+    let _ = CONFERENCING_APPS;
+    None
+}
+
+#[cfg(not(target_os = "macos"))]
+fn microphone_in_use() -> bool {
+    false
+}
+
+/// Start the background thread that watches for meetings.
+///
+/// Polling is skipped while meeting detection is disabled in the settings
+/// and while Cyrano itself records, since its own capture keeps the
+/// microphone busy.
+pub fn start_watcher(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(POLL_INTERVAL);
+        if !settings_service::get_settings().meeting_detection_enabled
+            || recording_service::is_recording()
+        {
+            continue;
+        }
+
+        let conferencing_app = running_conferencing_app();
+        // Only query Core Audio when it can matter
+        let mic_in_use = conferencing_app.is_some() && microphone_in_use();
+        let change = DETECTOR
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .update(conferencing_app, mic_in_use);
+
+        // Offering notes that cannot be taken would only mislead the user
+        let can_transcribe = capabilities_service::system_audio_capture_capability().available;
+        match change {
+            Some(MeetingChange::Started(name)) => {
+                log::info!("Meeting detected in {name}");
+                if can_transcribe {
+                    notify_meeting(&app, name);
+                    emit(&app, "meeting-detected", name);
+                } else {
+                    log::debug!("Not offering meeting notes: system audio capture is unavailable");
+                }
+            }
+            Some(MeetingChange::Ended(name)) => {
+                log::info!("Meeting in {name} ended");
+                if can_transcribe {
+                    emit(&app, "meeting-ended", name);
+                }
+            }
+            None => {}
+        }
+    });
+}
+
+/// Start transcribing the current meeting.
+///
+/// Meeting audio must be captured from the system output as well as the
+/// microphone, which is not supported yet (see `capabilities_service`).
+///
+/// # Returns
+/// * `Err(CyranoError::RecordingFailed)` if no meeting is in progress or
+///   system audio cannot be captured
+pub fn start_meeting_transcription() -> Result<(), CyranoError> {
+    let active = DETECTOR.lock().unwrap_or_else(|e| e.into_inner()).active;
+    let Some(name) = active else {
+        return Err(CyranoError::RecordingFailed {
+            reason: "No meeting in progress".to_string(),
+        });
+    };
+
+    // Only microphone input exists so far: the capability explains why
+    let reason = capabilities_service::system_audio_capture_capability()
+        .reason
+        .unwrap_or_else(|| "System audio capture is unavailable".to_string());
+    log::warn!("Cannot transcribe meeting in {name}: {reason}");
+    Err(CyranoError::RecordingFailed { reason })
+}
+
+fn emit(app: &AppHandle, event: &str, name: &str) {
+    let payload = MeetingPayload {
+        app_name: name.to_string(),
+    };
+    if let Err(e) = notifier_service::emit(app, event, payload) {
+        log::error!("Failed to emit {event} event: {e}");
+    }
+}
+
+fn notify_meeting(app: &AppHandle, name: &str) {
+    #[cfg(not(mobile))]
+    {
+        use tauri_plugin_notification::NotificationExt;
+
+        if let Err(e) = app
+            .notification()
+            .builder()
+            .title("Meeting detected")
+            .body(format!(
+                "{name} is using the microphone. Open Cyrano to take meeting notes."
+            ))
+            .show()
+        {
+            log::warn!("Failed to show meeting notification: {e}");
+        }
+    }
+
+    #[cfg(mobile)]
+    let _ = (app, name);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_meeting_starts_after_consecutive_polls() {
+        let mut detector = MeetingDetector::default();
+        assert_eq!(detector.update(Some("Zoom"), true), None);
+        assert_eq!(
+            detector.update(Some("Zoom"), true),
+            Some(MeetingChange::Started("Zoom"))
+        );
+        assert_eq!(detector.update(Some("Zoom"), true), None);
+    }
+
+    #[test]
+    fn test_app_without_microphone_is_not_a_meeting() {
+        let mut detector = MeetingDetector::default();
+        for _ in 0..5 {
+            assert_eq!(detector.update(Some("Zoom"), false), None);
+            assert_eq!(detector.update(None, true), None);
+        }
+    }
+
+    #[test]
+    fn test_brief_mute_does_not_end_meeting() {
+        let mut detector = MeetingDetector::default();
+        detector.update(Some("FaceTime"), true);
+        detector.update(Some("FaceTime"), true);
+
+        detector.update(Some("FaceTime"), false);
+        detector.update(Some("FaceTime"), false);
+        assert_eq!(detector.update(Some("FaceTime"), true), None);
+
+        for _ in 1..END_POLLS {
+            assert_eq!(detector.update(None, false), None);
+        }
+        assert_eq!(
+            detector.update(None, false),
+            Some(MeetingChange::Ended("FaceTime"))
+        );
+    }
+
+    #[test]
+    fn test_conferencing_apps_have_names() {
+        for (bundle_id, name) in CONFERENCING_APPS {
+            assert!(bundle_id.contains('.'));
+            assert!(!name.is_empty());
+        }
+    }
+}
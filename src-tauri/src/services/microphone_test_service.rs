@@ -0,0 +1,145 @@
+//! Microphone test.
+//!
+//! Records a couple of seconds from the input device used for dictation and
+//! reports its levels, so the settings UI can check that the microphone
+//! actually produces signal before the user relies on the shortcut. A muted
+//! or wrong device typically shows up as a silent recording.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use specta::Type;
+
+use crate::domain::{CyranoError, PermissionStatus};
+use crate::infrastructure::audio::cpal_adapter::TARGET_SAMPLE_RATE;
+use crate::services::{permission_service, recording_service, settings_service};
+use crate::traits::audio_capture::AudioCapture;
+
+/// How long the test records.
+const TEST_DURATION: Duration = Duration::from_secs(2);
+
+/// Peak level below which the microphone is considered silent, in dBFS.
+const SILENCE_DB: f32 = -60.0;
+
+/// Level reported for digital silence, in dBFS.
+const FLOOR_DB: f32 = -100.0;
+
+/// Levels measured by a microphone test.
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct MicrophoneTestResult {
+    /// Input device that was recorded, if its name is known
+    pub device_name: Option<String>,
+    /// Loudest sample, in dBFS
+    pub peak_db: f32,
+    /// Average level, in dBFS
+    pub rms_db: f32,
+    /// Whether the peak is above the silence threshold
+    pub has_signal: bool,
+    /// Duration of audio measured, in milliseconds
+    pub duration_ms: u32,
+}
+
+/// Record from the dictation input device for a couple of seconds and
+/// measure its levels.
+///
+/// Blocks for the duration of the test.
+///
+/// # Returns
+/// * `Ok(MicrophoneTestResult)` with the levels of the recording
+/// * `Err(CyranoError::MicAccessDenied)` if permission is denied or no input device exists
+/// * `Err(CyranoError::RecordingFailed)` if a dictation is recording or capture failed
+pub fn test_microphone() -> Result<MicrophoneTestResult, CyranoError> {
+    if permission_service::check_microphone_permission() == PermissionStatus::Denied {
+        return Err(CyranoError::MicAccessDenied);
+    }
+    if recording_service::is_recording() {
+        return Err(CyranoError::RecordingFailed {
+            reason: "Cannot test the microphone while recording".to_string(),
+        });
+    }
+
+    // The device dictation would record from, Bluetooth policy included
+    let mut capture = recording_service::input_adapter(
+        &settings_service::get_settings(),
+        recording_service::bluetooth_default_input().as_deref(),
+    );
+    capture.start_capture()?;
+    let device_name = capture.device_name();
+    log::info!(
+        "Testing microphone {}",
+        device_name.as_deref().unwrap_or("unknown device")
+    );
+
+    let started_at = Instant::now();
+    let mut samples = Vec::new();
+    while started_at.elapsed() < TEST_DURATION {
+        samples.extend(capture.take_samples());
+        thread::sleep(Duration::from_millis(10));
+    }
+    samples.extend(capture.stop_capture()?);
+
+    let result = measure(device_name, &samples);
+    log::info!(
+        "Microphone test: peak {:.1} dBFS, RMS {:.1} dBFS",
+        result.peak_db,
+        result.rms_db
+    );
+    Ok(result)
+}
+
+/// Levels of recorded samples.
+fn measure(device_name: Option<String>, samples: &[f32]) -> MicrophoneTestResult {
+    let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+    let rms = if samples.is_empty() {
+        0.0
+    } else {
+        let sum: f64 = samples.iter().map(|s| f64::from(s * s)).sum();
+        (sum / samples.len() as f64).sqrt() as f32
+    };
+    let peak_db = to_db(peak);
+    MicrophoneTestResult {
+        device_name,
+        peak_db,
+        rms_db: to_db(rms),
+        has_signal: peak_db >= SILENCE_DB,
+        duration_ms: (samples.len() as u64 * 1000 / u64::from(TARGET_SAMPLE_RATE)) as u32,
+    }
+}
+
+fn to_db(amplitude: f32) -> f32 {
+    if amplitude > 0.0 {
+        (20.0 * amplitude.log10()).max(FLOOR_DB)
+    } else {
+        FLOOR_DB
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels_of_a_square_wave() {
+        let samples: Vec<f32> = (0..32_000)
+            .map(|i| if i % 2 == 0 { 0.5 } else { -0.5 })
+            .collect();
+        let result = measure(Some("Test Mic".to_string()), &samples);
+
+        assert!((result.peak_db - -6.02).abs() < 0.01);
+        assert!((result.rms_db - -6.02).abs() < 0.01);
+        assert!(result.has_signal);
+        assert_eq!(result.duration_ms, 2_000);
+        assert_eq!(result.device_name.as_deref(), Some("Test Mic"));
+    }
+
+    #[test]
+    fn test_silence_has_no_signal() {
+        let result = measure(None, &[0.0; 16_000]);
+        assert_eq!(result.peak_db, FLOOR_DB);
+        assert!(!result.has_signal);
+
+        let result = measure(None, &[]);
+        assert_eq!((result.rms_db, result.duration_ms), (FLOOR_DB, 0));
+    }
+}
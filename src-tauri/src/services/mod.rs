@@ -4,10 +4,51 @@
 //! Services depend on infrastructure adapters through traits (ports).
 
 pub mod accessibility_service;
+pub mod action_item_service;
+pub mod capabilities_service;
+pub mod capture_watchdog_service;
+pub mod caret_context_service;
 pub mod cursor_insertion_service;
+pub mod data_directory_service;
+pub mod diagnostics_service;
+pub mod dictation_service;
+pub mod encrypted_sync_service;
+pub mod file_transcription_service;
+pub mod hallucination_service;
+pub mod history_service;
+pub mod history_sync_service;
+pub mod job_scheduler_service;
+pub mod list_dictation_service;
+pub mod log_level_service;
+pub mod meeting_assistant_service;
+pub mod microphone_test_service;
+pub mod model_catalog_service;
+pub mod model_download_service;
+pub mod model_integrity_service;
+pub mod model_unload_service;
+pub mod monitor_layout_service;
+pub mod notifier_service;
 pub mod output_service;
 pub mod permission_service;
+pub mod permission_watch_service;
+pub mod plugin_service;
+pub mod quickstart_service;
+pub mod recording_export_service;
+pub mod recording_recovery_service;
 pub mod recording_service;
 pub mod recording_state;
+pub mod redo_service;
+pub mod retranscription_service;
+pub mod safe_mode_service;
+pub mod sandbox_service;
+pub mod sentence_commit_service;
+pub mod settings_service;
+pub mod settings_watch_service;
 pub mod shortcut_service;
+pub mod storage_cleanup_service;
+pub mod summary_service;
+pub mod text_processing_service;
 pub mod transcription_service;
+pub mod tray_status_service;
+pub mod vocabulary_service;
+pub mod voice_memo_service;
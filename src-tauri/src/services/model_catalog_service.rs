@@ -0,0 +1,226 @@
+//! Catalog of the Whisper models offered for download.
+//!
+//! The models are the ggml conversions published by whisper.cpp on Hugging
+//! Face. Each size comes multilingual, English-only (`.en`, slightly more
+//! accurate in English) and quantized (smaller and lighter on memory, at a
+//! small accuracy cost). Sizes and speeds are approximate and only meant to
+//! help pick a model.
+//!
+//! For each variant, one build is recommended: the reference `f16` weights,
+//! or the quantized build on machines where the `f16` build would take more
+//! than a quarter of the physical memory.
+
+use serde::Serialize;
+use specta::Type;
+
+use crate::domain::CyranoError;
+use crate::infrastructure::whisper::model_header;
+use crate::services::transcription_service;
+
+/// Where whisper.cpp publishes its models.
+const DOWNLOAD_BASE_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+
+const MIB: u64 = 1024 * 1024;
+
+/// Fraction (1/n) of the physical memory an `f16` model may use before its
+/// quantized build is recommended instead.
+const MAX_RAM_SHARE: u64 = 4;
+
+/// A model of the catalog.
+struct CatalogEntry {
+    variant: &'static str,
+    /// `f16` for the reference weights
+    quantization: &'static str,
+    size_mib: u64,
+    /// Speed relative to large-v3
+    relative_speed: u32,
+}
+
+const CATALOG: [CatalogEntry; 16] = [
+    entry("tiny", "f16", 75, 10),
+    entry("tiny.en", "f16", 75, 10),
+    entry("tiny", "q5_1", 31, 10),
+    entry("base", "f16", 142, 7),
+    entry("base.en", "f16", 142, 7),
+    entry("base", "q5_1", 57, 7),
+    entry("small", "f16", 466, 4),
+    entry("small.en", "f16", 466, 4),
+    entry("small", "q5_1", 181, 4),
+    entry("medium", "f16", 1463, 2),
+    entry("medium.en", "f16", 1463, 2),
+    entry("medium", "q5_0", 514, 2),
+    entry("large-v3-turbo", "f16", 1549, 8),
+    entry("large-v3-turbo", "q5_0", 547, 8),
+    entry("large-v3", "f16", 2952, 1),
+    entry("large-v3", "q5_0", 1031, 1),
+];
+
+const fn entry(
+    variant: &'static str,
+    quantization: &'static str,
+    size_mib: u64,
+    relative_speed: u32,
+) -> CatalogEntry {
+    CatalogEntry {
+        variant,
+        quantization,
+        size_mib,
+        relative_speed,
+    }
+}
+
+/// A model that can be downloaded.
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct CatalogModel {
+    /// File name in the models directory, e.g. `ggml-base.en.bin`
+    pub file_name: String,
+    /// Whisper variant, e.g. `base.en` or `large-v3`
+    pub variant: String,
+    /// Whether the model transcribes languages other than English
+    pub multilingual: bool,
+    /// Weight format, e.g. `f16` or `q5_1`
+    pub quantization: String,
+    /// Approximate download size, in bytes
+    pub size_bytes: u64,
+    /// Approximate memory used while transcribing, in bytes
+    pub required_ram_bytes: u64,
+    /// Approximate transcription speed relative to large-v3 (10 is ten times
+    /// faster)
+    pub relative_speed: u32,
+    pub download_url: String,
+    /// Whether the file is in the models directory
+    pub installed: bool,
+    /// Whether this is the build of its variant suited to this machine's
+    /// memory
+    pub recommended: bool,
+}
+
+impl CatalogEntry {
+    fn file_name(&self) -> String {
+        match self.quantization {
+            "f16" => format!("ggml-{}.bin", self.variant),
+            quantization => format!("ggml-{}-{quantization}.bin", self.variant),
+        }
+    }
+
+    fn required_ram_bytes(&self) -> u64 {
+        let size = self.variant.trim_end_matches(".en");
+        model_header::estimated_ram_bytes(size, self.size_mib * MIB)
+    }
+
+    /// Whether this build is the one to pick for its variant, given the
+    /// physical memory (unknown: assume enough for `f16`).
+    fn is_recommended(&self, physical_memory_bytes: Option<u64>) -> bool {
+        let same_variant = || CATALOG.iter().filter(|e| e.variant == self.variant);
+        let Some(reference) = same_variant().find(|e| e.quantization == "f16") else {
+            return true;
+        };
+        if !same_variant().any(|e| e.quantization != "f16") {
+            return true;
+        }
+        let low_memory = physical_memory_bytes
+            .is_some_and(|ram| reference.required_ram_bytes() > ram / MAX_RAM_SHARE);
+        (self.quantization != "f16") == low_memory
+    }
+
+    fn model(&self, installed: bool, physical_memory_bytes: Option<u64>) -> CatalogModel {
+        let file_name = self.file_name();
+        CatalogModel {
+            download_url: format!("{DOWNLOAD_BASE_URL}/{file_name}"),
+            file_name,
+            variant: self.variant.to_string(),
+            multilingual: !self.variant.ends_with(".en"),
+            quantization: self.quantization.to_string(),
+            size_bytes: self.size_mib * MIB,
+            required_ram_bytes: self.required_ram_bytes(),
+            relative_speed: self.relative_speed,
+            installed,
+            recommended: self.is_recommended(physical_memory_bytes),
+        }
+    }
+}
+
+/// List the catalog, from the smallest size to the largest.
+///
+/// # Returns
+/// * `Ok(Vec<CatalogModel>)` with installed models marked
+/// * `Err(CyranoError::ModelNotFound)` if the models directory cannot be resolved
+pub fn list_available_models() -> Result<Vec<CatalogModel>, CyranoError> {
+    let models_dir = transcription_service::get_models_directory()?;
+    let physical_memory = physical_memory_bytes();
+    Ok(CATALOG
+        .iter()
+        .map(|entry| {
+            let installed = models_dir.join(entry.file_name()).is_file();
+            entry.model(installed, physical_memory)
+        })
+        .collect())
+}
+
+/// Physical memory of the machine, in bytes.
+#[cfg(target_os = "macos")]
+fn physical_memory_bytes() -> Option<u64> {
+    Some(objc2_foundation::NSProcessInfo::processInfo().physicalMemory())
+}
+
+/// Physical memory is only read on macOS.
+#[cfg(not(target_os = "macos"))]
+fn physical_memory_bytes() -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_names_match_whisper_cpp() {
+        let names: Vec<_> = CATALOG.iter().map(CatalogEntry::file_name).collect();
+        assert!(names.contains(&"ggml-base.en.bin".to_string()));
+        assert!(names.contains(&"ggml-small-q5_1.bin".to_string()));
+        assert!(names.contains(&"ggml-large-v3-turbo-q5_0.bin".to_string()));
+
+        let mut unique = names.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), names.len());
+    }
+
+    #[test]
+    fn test_catalog_model() {
+        let model = entry("base.en", "f16", 142, 7).model(true, None);
+
+        assert_eq!(
+            model.download_url,
+            "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base.en.bin"
+        );
+        assert!(!model.multilingual);
+        assert!(model.installed);
+        assert_eq!(model.size_bytes, 142 * MIB);
+        assert_eq!(model.required_ram_bytes, (142 + 250) * MIB);
+        assert!(model.recommended);
+    }
+
+    #[test]
+    fn test_quantized_builds_recommended_on_low_memory() {
+        let find = |file_name: &str| {
+            CATALOG
+                .iter()
+                .find(|entry| entry.file_name() == file_name)
+                .unwrap()
+        };
+        let gib = 1024 * MIB;
+
+        // 8 GiB: large-v3 in f16 takes more than a quarter
+        assert!(find("ggml-large-v3-q5_0.bin").is_recommended(Some(8 * gib)));
+        assert!(!find("ggml-large-v3.bin").is_recommended(Some(8 * gib)));
+        assert!(find("ggml-base.bin").is_recommended(Some(8 * gib)));
+        assert!(!find("ggml-base-q5_1.bin").is_recommended(Some(8 * gib)));
+
+        // Without a quantized build, the f16 build is the only choice
+        assert!(find("ggml-medium.en.bin").is_recommended(Some(2 * gib)));
+
+        assert!(find("ggml-large-v3.bin").is_recommended(Some(64 * gib)));
+        assert!(find("ggml-large-v3.bin").is_recommended(None));
+    }
+}
@@ -0,0 +1,220 @@
+//! Download of catalog models into the models directory.
+//!
+//! The model is written to `<file name>.part` and renamed once complete, so
+//! an interrupted download never passes for an installed model (storage
+//! cleanup deletes the leftover part). Hugging Face publishes the SHA-256 of
+//! each model in the `X-Linked-Etag` header of the download redirect: the
+//! downloaded bytes are checked against it, and the checksum is recorded so
+//! the model is verified again before it is loaded.
+
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use sha2::{Digest, Sha256};
+
+use crate::domain::CyranoError;
+use crate::services::storage_cleanup_service::PARTIAL_DOWNLOAD_EXTENSION;
+use crate::services::{model_catalog_service, model_integrity_service, transcription_service};
+
+/// Timeout for connecting to the server.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Timeout for each read of the download, so a stalled connection fails
+/// instead of hanging.
+const READ_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Size of the chunks read from the connection.
+const CHUNK_SIZE: usize = 256 * 1024;
+
+fn failed(reason: impl Into<String>) -> CyranoError {
+    CyranoError::ModelDownloadFailed {
+        reason: reason.into(),
+    }
+}
+
+fn transport_error(error: ureq::Error) -> CyranoError {
+    match error {
+        ureq::Error::Status(code, response) => failed(format!(
+            "Server responded with {code} {}",
+            response.status_text()
+        )),
+        ureq::Error::Transport(transport) => {
+            failed(format!("Could not reach the server: {transport}"))
+        }
+    }
+}
+
+/// SHA-256 from an `X-Linked-Etag` header value, e.g. `"3f1c…"`.
+fn parse_linked_etag(value: &str) -> Option<String> {
+    let sha256 = value.trim().trim_start_matches("W/").trim_matches('"');
+    (sha256.len() == 64 && sha256.chars().all(|c| c.is_ascii_hexdigit()))
+        .then(|| sha256.to_ascii_lowercase())
+}
+
+/// SHA-256 the server publishes for `url`, if any.
+fn published_sha256(url: &str) -> Option<String> {
+    // The header is on the redirect to the storage server, not on the file
+    let agent = ureq::AgentBuilder::new()
+        .timeout_connect(CONNECT_TIMEOUT)
+        .redirects(0)
+        .build();
+    let response = agent.head(url).call().ok()?;
+    response.header("X-Linked-Etag").and_then(parse_linked_etag)
+}
+
+/// Download a catalog model into the models directory.
+///
+/// Blocks until the download completes, so MUST run off the main thread.
+///
+/// # Arguments
+/// * `file_name` - File name of a catalog model, e.g. `ggml-tiny.en.bin`
+/// * `on_progress` - Called with the bytes downloaded so far and the total,
+///   when the server tells it
+///
+/// # Returns
+/// * `Ok(PathBuf)` with the path of the installed model
+/// * `Err(CyranoError::ModelNotFound)` if the model is not in the catalog
+/// * `Err(CyranoError::ModelDownloadFailed)` if the download failed or the
+///   file does not match its published checksum
+pub fn download_model(
+    file_name: &str,
+    mut on_progress: impl FnMut(u64, Option<u64>),
+) -> Result<PathBuf, CyranoError> {
+    let model = model_catalog_service::list_available_models()?
+        .into_iter()
+        .find(|model| model.file_name == file_name)
+        .ok_or_else(|| CyranoError::ModelNotFound {
+            path: file_name.to_string(),
+        })?;
+    let models_dir = transcription_service::get_models_directory()?;
+    fs::create_dir_all(&models_dir)
+        .map_err(|e| failed(format!("Failed to create the models directory: {e}")))?;
+    let path = models_dir.join(file_name);
+    let part = models_dir.join(format!("{file_name}.{PARTIAL_DOWNLOAD_EXTENSION}"));
+
+    let expected_sha256 = published_sha256(&model.download_url);
+    if expected_sha256.is_none() {
+        log::warn!("No checksum published for {file_name}");
+    }
+
+    log::info!("Downloading {file_name} from {}", model.download_url);
+    let response = ureq::AgentBuilder::new()
+        .timeout_connect(CONNECT_TIMEOUT)
+        .timeout_read(READ_TIMEOUT)
+        .build()
+        .get(&model.download_url)
+        .call()
+        .map_err(transport_error)?;
+    let total = response
+        .header("Content-Length")
+        .and_then(|length| length.parse().ok());
+
+    let result = write_download(response.into_reader(), &part, total, &mut on_progress);
+    let sha256 = match result {
+        Ok(sha256) => sha256,
+        Err(e) => {
+            let _ = fs::remove_file(&part);
+            return Err(e);
+        }
+    };
+    if expected_sha256
+        .as_ref()
+        .is_some_and(|expected| *expected != sha256)
+    {
+        let _ = fs::remove_file(&part);
+        return Err(failed(format!("{file_name} does not match its checksum")));
+    }
+
+    fs::rename(&part, &path).map_err(|e| failed(format!("Failed to install the model: {e}")))?;
+    model_integrity_service::record_checksum(&path, &sha256)?;
+    log::info!("Downloaded {file_name}");
+    Ok(path)
+}
+
+/// Write the downloaded bytes to `part`, returning their hex SHA-256.
+fn write_download(
+    mut reader: impl Read,
+    part: &Path,
+    total: Option<u64>,
+    on_progress: &mut impl FnMut(u64, Option<u64>),
+) -> Result<String, CyranoError> {
+    let mut file =
+        File::create(part).map_err(|e| failed(format!("Failed to create the model file: {e}")))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut downloaded = 0u64;
+    loop {
+        let read = reader
+            .read(&mut buffer)
+            .map_err(|e| failed(format!("Download interrupted: {e}")))?;
+        if read == 0 {
+            break;
+        }
+        file.write_all(&buffer[..read])
+            .map_err(|e| failed(format!("Failed to write the model file: {e}")))?;
+        hasher.update(&buffer[..read]);
+        downloaded += read as u64;
+        on_progress(downloaded, total);
+    }
+    if total.is_some_and(|total| total != downloaded) {
+        return Err(failed("Download ended early"));
+    }
+    file.sync_all()
+        .map_err(|e| failed(format!("Failed to write the model file: {e}")))?;
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::temp_dir;
+
+    #[test]
+    fn test_parse_linked_etag() {
+        let sha256 = "A".repeat(64);
+        assert_eq!(
+            parse_linked_etag(&format!("\"{sha256}\"")),
+            Some("a".repeat(64))
+        );
+        assert_eq!(
+            parse_linked_etag(&format!("W/\"{sha256}\"")),
+            Some("a".repeat(64))
+        );
+        // Git blob hashes of small files are SHA-1
+        assert_eq!(parse_linked_etag(&format!("\"{}\"", "a".repeat(40))), None);
+        assert_eq!(parse_linked_etag(&format!("\"{}\"", "z".repeat(64))), None);
+    }
+
+    #[test]
+    fn test_write_download_hashes_and_reports_progress() {
+        let dir = temp_dir("download");
+        let part = dir.join("ggml-base.bin.part");
+        let mut progress = Vec::new();
+
+        let sha256 = write_download(&b"abc"[..], &part, Some(3), &mut |done, total| {
+            progress.push((done, total))
+        })
+        .unwrap();
+
+        assert_eq!(
+            sha256,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(progress, vec![(3, Some(3))]);
+        assert_eq!(fs::read(&part).unwrap(), b"abc");
+
+        let truncated = write_download(&b"ab"[..], &part, Some(3), &mut |_, _| {});
+        assert!(matches!(
+            truncated,
+            Err(CyranoError::ModelDownloadFailed { .. })
+        ));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,198 @@
+//! Integrity checks of model files.
+//!
+//! A truncated or damaged model makes whisper.cpp fail with cryptic errors,
+//! or crash. Before a model is loaded, its header is checked and, when its
+//! SHA-256 is known, its contents are hashed and compared. The expected
+//! checksum is recorded next to the model (`ggml-base.bin.checksum.json`)
+//! when it is downloaded. Hashing a large model takes seconds, so the file
+//! is only hashed again when its size or modification time changes.
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::domain::CyranoError;
+use crate::infrastructure::whisper::model_header;
+use crate::services::data_directory_service;
+
+/// Suffix of the checksum record next to a model file.
+const CHECKSUM_SUFFIX: &str = ".checksum.json";
+
+/// Expected checksum of a model file, and the file it was last verified on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ChecksumRecord {
+    /// Hex SHA-256 of the complete file
+    sha256: String,
+    #[serde(default)]
+    verified: Option<FileStamp>,
+}
+
+/// Identifies a version of a file without reading it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct FileStamp {
+    size_bytes: u64,
+    modified_secs: u64,
+}
+
+/// Record the expected SHA-256 of a model, e.g. as published by the server
+/// it is downloaded from. The next `verify_model` hashes the file.
+pub fn record_checksum(model_path: &Path, sha256: &str) -> Result<(), CyranoError> {
+    write_record(
+        model_path,
+        &ChecksumRecord {
+            sha256: sha256.to_ascii_lowercase(),
+            verified: None,
+        },
+    )
+}
+
+/// Check that a model file is complete before loading it.
+///
+/// # Returns
+/// * `Ok(())` if the header is valid and the checksum, if known, matches
+/// * `Err(CyranoError::ModelCorrupted)` if the file is damaged or truncated
+pub fn verify_model(model_path: &Path) -> Result<(), CyranoError> {
+    let corrupted = |reason: String| CyranoError::ModelCorrupted {
+        path: model_path.display().to_string(),
+        reason,
+    };
+
+    model_header::read_metadata(model_path).map_err(|e| match e.kind() {
+        io::ErrorKind::UnexpectedEof => corrupted("file is truncated".to_string()),
+        _ => corrupted(e.to_string()),
+    })?;
+
+    let Some(mut record) = read_record(model_path) else {
+        log::debug!("No checksum recorded for {}", model_path.display());
+        return Ok(());
+    };
+    let stamp = file_stamp(model_path).map_err(|e| corrupted(e.to_string()))?;
+    if record.verified == Some(stamp) {
+        return Ok(());
+    }
+
+    log::info!("Verifying checksum of {}", model_path.display());
+    let sha256 = file_sha256(model_path).map_err(|e| corrupted(e.to_string()))?;
+    if !sha256.eq_ignore_ascii_case(&record.sha256) {
+        log::error!(
+            "Checksum mismatch for {}: expected {}, got {sha256}",
+            model_path.display(),
+            record.sha256
+        );
+        return Err(corrupted("checksum mismatch".to_string()));
+    }
+
+    record.verified = Some(stamp);
+    if let Err(e) = write_record(model_path, &record) {
+        log::warn!("[{}] Failed to save model verification: {e}", e.code());
+    }
+    Ok(())
+}
+
+fn record_path(model_path: &Path) -> PathBuf {
+    let mut path = model_path.as_os_str().to_owned();
+    path.push(CHECKSUM_SUFFIX);
+    PathBuf::from(path)
+}
+
+fn read_record(model_path: &Path) -> Option<ChecksumRecord> {
+    let json = std::fs::read_to_string(record_path(model_path)).ok()?;
+    serde_json::from_str(&json)
+        .inspect_err(|e| log::warn!("Ignoring invalid checksum record: {e}"))
+        .ok()
+}
+
+fn write_record(model_path: &Path, record: &ChecksumRecord) -> Result<(), CyranoError> {
+    let json = serde_json::to_vec_pretty(record).map_err(|e| CyranoError::StorageFailed {
+        reason: format!("Failed to serialize checksum record: {e}"),
+    })?;
+    data_directory_service::write_file_atomically(&record_path(model_path), &json)
+}
+
+fn file_stamp(path: &Path) -> io::Result<FileStamp> {
+    let metadata = path.metadata()?;
+    let modified_secs = metadata
+        .modified()?
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    Ok(FileStamp {
+        size_bytes: metadata.len(),
+        modified_secs,
+    })
+}
+
+/// Hash a file (hex SHA-256) without reading it into memory at once.
+fn file_sha256(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::temp_dir;
+
+    /// A tiny model header followed by a few bytes of weights.
+    fn model_bytes() -> Vec<u8> {
+        let hparams: [i32; 11] = [51_865, 1500, 384, 6, 4, 448, 384, 6, 4, 80, 1];
+        let mut bytes = 0x6767_6d6c_u32.to_le_bytes().to_vec();
+        for value in hparams {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.extend_from_slice(b"weights");
+        bytes
+    }
+
+    #[test]
+    fn test_verify_model_against_recorded_checksum() {
+        let dir = temp_dir("integrity");
+        let path = dir.join("ggml-tiny.bin");
+        std::fs::write(&path, model_bytes()).unwrap();
+
+        // Unknown checksum: only the header is checked
+        assert!(verify_model(&path).is_ok());
+
+        record_checksum(&path, &"0".repeat(64)).unwrap();
+        assert!(matches!(
+            verify_model(&path),
+            Err(CyranoError::ModelCorrupted { reason, .. }) if reason == "checksum mismatch"
+        ));
+
+        let sha256 = file_sha256(&path).unwrap();
+        record_checksum(&path, &sha256.to_ascii_uppercase()).unwrap();
+        assert!(verify_model(&path).is_ok());
+        assert!(read_record(&path).unwrap().verified.is_some());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_truncated_model_is_corrupted() {
+        let dir = temp_dir("integrity-truncated");
+        let path = dir.join("ggml-tiny.bin");
+        std::fs::write(&path, &model_bytes()[..20]).unwrap();
+
+        assert!(matches!(
+            verify_model(&path),
+            Err(CyranoError::ModelCorrupted { reason, .. }) if reason == "file is truncated"
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
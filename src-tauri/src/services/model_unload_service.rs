@@ -0,0 +1,47 @@
+//! Background enforcement of the model keep-alive policy.
+//!
+//! The model is otherwise only checked for idleness when a transcription
+//! starts, so an idle model would stay in memory until the next dictation.
+//! The timer unloads it once `model_keep_alive` allows. `model-unloaded` is
+//! emitted by each unload (idle timeout, or unloaded right after a
+//! transcription) so the UI can show the memory was freed.
+
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use tauri::AppHandle;
+
+use crate::services::{notifier_service, transcription_service};
+
+/// How often the timer enforces the keep-alive policy.
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// Payload for the model-unloaded event.
+#[derive(Clone, serde::Serialize)]
+pub struct ModelUnloadedPayload {
+    /// File name of the unloaded model (e.g. `ggml-base.bin`)
+    pub model: Option<String>,
+}
+
+/// Start unloading the idle model in the background, and reporting unloads.
+pub fn start_timer(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+    std::thread::spawn(|| loop {
+        std::thread::sleep(POLL_INTERVAL);
+        if let Err(e) = transcription_service::check_and_unload_if_idle() {
+            log::warn!("[{}] Failed to unload idle model: {e}", e.code());
+        }
+    });
+}
+
+/// Emit model-unloaded. Called by the transcription service on each unload.
+pub fn notify_unloaded(model: Option<String>) {
+    let Some(app) = APP.get() else {
+        return;
+    };
+    if let Err(e) = notifier_service::emit(app, "model-unloaded", ModelUnloadedPayload { model }) {
+        log::error!("Failed to emit model-unloaded event: {e}");
+    }
+}
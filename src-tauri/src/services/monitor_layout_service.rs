@@ -0,0 +1,155 @@
+//! Cached monitor layout for overlay positioning.
+//!
+//! Enumerating monitors goes through the event loop and the window server,
+//! which stalls for hundreds of milliseconds on some docked setups. The
+//! layout is read once and kept until the display configuration changes;
+//! each change clears it and refreshes it on a background thread, so showing
+//! the overlay never waits on the enumeration.
+//!
+//! Without display change notifications (other platforms), nothing could
+//! tell the cache is stale, so monitors are enumerated on every call.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Monitor};
+
+/// Monitors and the primary monitor, as last enumerated.
+#[derive(Debug, Clone, Default)]
+pub struct MonitorLayout {
+    pub monitors: Vec<Monitor>,
+    pub primary: Option<Monitor>,
+}
+
+static LAYOUT: Mutex<Option<MonitorLayout>> = Mutex::new(None);
+
+/// Set once display changes are observed, which makes caching safe.
+static OBSERVING: AtomicBool = AtomicBool::new(false);
+
+/// Incremented on each display change, so a layout read before the change is
+/// not cached after it.
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Start caching the layout, invalidating it on display changes.
+///
+/// MUST be called on the main thread (e.g. from the Tauri setup hook).
+pub fn start_observing(app: &AppHandle) {
+    #[cfg(target_os = "macos")]
+    if let Some(mtm) = objc2::MainThreadMarker::new() {
+        let handle = app.clone();
+        let observing = crate::infrastructure::display::observe_display_changes(mtm, move || {
+            invalidate(&handle);
+        });
+        if observing {
+            OBSERVING.store(true, Ordering::SeqCst);
+            refresh_in_background(app);
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    let _ = app;
+}
+
+/// Drop the cached layout and read it again on a background thread.
+pub fn invalidate(app: &AppHandle) {
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+    if let Ok(mut layout) = LAYOUT.lock() {
+        *layout = None;
+    }
+    refresh_in_background(app);
+}
+
+/// The current monitor layout, from the cache when it is up to date.
+pub fn layout(app: &AppHandle) -> MonitorLayout {
+    if let Some(layout) = LAYOUT.lock().ok().and_then(|layout| layout.clone()) {
+        return layout;
+    }
+    // Not cached yet, or invalidated and not refreshed yet: read it without
+    // holding the lock
+    let generation = GENERATION.load(Ordering::SeqCst);
+    let layout = enumerate(app);
+    if OBSERVING.load(Ordering::SeqCst) {
+        store(&layout, generation);
+    }
+    layout
+}
+
+/// The monitor containing the point (`x`, `y`) in physical pixels, falling
+/// back to the primary monitor.
+pub fn monitor_at(app: &AppHandle, x: f64, y: f64) -> Option<Monitor> {
+    let layout = layout(app);
+    let bounds: Vec<_> = layout
+        .monitors
+        .iter()
+        .map(|monitor| {
+            let position = monitor.position();
+            let size = monitor.size();
+            (position.x, position.y, size.width, size.height)
+        })
+        .collect();
+    match index_containing(&bounds, x, y) {
+        Some(index) => layout.monitors.into_iter().nth(index),
+        None => {
+            log::warn!("No monitor found at ({x}, {y}), using the primary monitor");
+            layout.primary
+        }
+    }
+}
+
+fn refresh_in_background(app: &AppHandle) {
+    if !OBSERVING.load(Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let generation = GENERATION.load(Ordering::SeqCst);
+        let layout = enumerate(&app);
+        log::debug!(
+            "Monitor layout refreshed: {} monitor(s)",
+            layout.monitors.len()
+        );
+        store(&layout, generation);
+    });
+}
+
+/// Cache `layout`, read at `generation`, unless the displays changed since.
+fn store(layout: &MonitorLayout, generation: u64) {
+    if let Ok(mut cached) = LAYOUT.lock() {
+        if GENERATION.load(Ordering::SeqCst) == generation {
+            *cached = Some(layout.clone());
+        }
+    }
+}
+
+fn enumerate(app: &AppHandle) -> MonitorLayout {
+    let monitors = app.available_monitors().unwrap_or_else(|e| {
+        log::warn!("Failed to enumerate monitors: {e}");
+        Vec::new()
+    });
+    let primary = app.primary_monitor().ok().flatten();
+    MonitorLayout { monitors, primary }
+}
+
+/// Index of the first `(x, y, width, height)` rectangle containing the point.
+fn index_containing(bounds: &[(i32, i32, u32, u32)], x: f64, y: f64) -> Option<usize> {
+    bounds.iter().position(|&(left, top, width, height)| {
+        let (left, top) = (left as f64, top as f64);
+        x >= left && x < left + width as f64 && y >= top && y < top + height as f64
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_containing_picks_the_monitor_under_the_point() {
+        // Laptop screen with an external monitor on its left
+        let bounds = [(0, 0, 2880, 1800), (-2560, -200, 2560, 1440)];
+
+        assert_eq!(index_containing(&bounds, 100.0, 100.0), Some(0));
+        assert_eq!(index_containing(&bounds, -1.0, 0.0), Some(1));
+        assert_eq!(index_containing(&bounds, 2880.0, 0.0), None);
+        assert_eq!(index_containing(&[], 0.0, 0.0), None);
+    }
+}
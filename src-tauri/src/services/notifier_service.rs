@@ -0,0 +1,170 @@
+//! Central notifier routing backend events to the windows that need them.
+//!
+//! Events are grouped into channels by the namespace at the start of their
+//! name (`recording-`, `transcription-`, `history-`...). Each window
+//! subscribes to the channels it needs, and events are emitted only to the
+//! windows subscribed to their channel, so the overlay does not wake up for
+//! history re-transcription progress.
+//!
+//! Windows must listen with their window-scoped `listen` on the frontend:
+//! global listeners receive targeted events whatever the window. Events
+//! outside any channel are broadcast to every window.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{LazyLock, Mutex};
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Emitter, EventTarget};
+
+/// A group of related events, named after their common prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum EventChannel {
+    /// `recording-*`: recording lifecycle, overlay and shortcut
+    Recording,
+    /// `transcription-*` and `dictation-*`: dictation transcription and redo
+    Transcription,
+    /// `clipboard-*`: output of the transcribed text
+    Clipboard,
+    /// `file-transcription-*`: audio file transcription queue
+    FileTranscription,
+    /// `history-*`: history re-transcription jobs
+    History,
+    /// `meeting-*`: meeting detection
+    Meeting,
+    /// `voice-memos-*`: voice memos available for import
+    VoiceMemo,
+}
+
+impl EventChannel {
+    /// Every channel, for windows that want all events.
+    pub const ALL: [EventChannel; 7] = [
+        EventChannel::Recording,
+        EventChannel::Transcription,
+        EventChannel::Clipboard,
+        EventChannel::FileTranscription,
+        EventChannel::History,
+        EventChannel::Meeting,
+        EventChannel::VoiceMemo,
+    ];
+
+    /// Event name prefixes belonging to the channel.
+    fn prefixes(self) -> &'static [&'static str] {
+        match self {
+            EventChannel::Recording => &["recording-"],
+            EventChannel::Transcription => &["transcription-", "dictation-"],
+            EventChannel::Clipboard => &["clipboard-"],
+            EventChannel::FileTranscription => &["file-transcription-"],
+            EventChannel::History => &["history-"],
+            EventChannel::Meeting => &["meeting-"],
+            EventChannel::VoiceMemo => &["voice-memos-"],
+        }
+    }
+
+    /// Channel an event belongs to, or `None` for events broadcast to all windows.
+    pub fn of(event: &str) -> Option<EventChannel> {
+        EventChannel::ALL.into_iter().find(|channel| {
+            channel
+                .prefixes()
+                .iter()
+                .any(|prefix| event.starts_with(prefix))
+        })
+    }
+}
+
+/// Channels each window label is subscribed to.
+static SUBSCRIPTIONS: LazyLock<Mutex<HashMap<String, HashSet<EventChannel>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Subscribe a window to `channels`, replacing its previous subscriptions.
+///
+/// An empty list unsubscribes the window from every channel.
+pub fn subscribe(label: &str, channels: &[EventChannel]) {
+    match SUBSCRIPTIONS.lock() {
+        Ok(mut subscriptions) => {
+            if channels.is_empty() {
+                subscriptions.remove(label);
+            } else {
+                subscriptions.insert(label.to_string(), channels.iter().copied().collect());
+            }
+            log::debug!("Window {label} subscribed to events: {channels:?}");
+        }
+        Err(e) => log::error!("Failed to lock event subscriptions: {e}"),
+    }
+}
+
+/// Labels of the windows subscribed to `channel`, sorted for stable ordering.
+fn subscribers(channel: EventChannel) -> Vec<String> {
+    let mut labels: Vec<String> = SUBSCRIPTIONS
+        .lock()
+        .map(|subscriptions| {
+            subscriptions
+                .iter()
+                .filter(|(_, channels)| channels.contains(&channel))
+                .map(|(label, _)| label.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+    labels.sort();
+    labels
+}
+
+/// Emit an event to the windows subscribed to its channel.
+///
+/// Drop-in replacement for `app.emit`: events outside any channel are
+/// broadcast, and an event nobody subscribed to is dropped.
+pub fn emit<S: Serialize + Clone>(app: &AppHandle, event: &str, payload: S) -> tauri::Result<()> {
+    let Some(channel) = EventChannel::of(event) else {
+        return app.emit(event, payload);
+    };
+
+    for label in subscribers(channel) {
+        app.emit_to(EventTarget::labeled(label), event, payload.clone())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_map_to_channels_by_prefix() {
+        assert_eq!(
+            EventChannel::of("recording-started"),
+            Some(EventChannel::Recording)
+        );
+        assert_eq!(
+            EventChannel::of("dictation-redo"),
+            Some(EventChannel::Transcription)
+        );
+        assert_eq!(
+            EventChannel::of("file-transcription-progress"),
+            Some(EventChannel::FileTranscription)
+        );
+        assert_eq!(EventChannel::of("theme-changed"), None);
+    }
+
+    #[test]
+    fn test_subscriptions_are_replaced_and_removed() {
+        subscribe("test-notifier-a", &[EventChannel::Recording]);
+        subscribe("test-notifier-b", &EventChannel::ALL);
+        assert!(subscribers(EventChannel::Recording)
+            .iter()
+            .any(|label| label == "test-notifier-a"));
+
+        subscribe("test-notifier-a", &[EventChannel::Clipboard]);
+        assert!(!subscribers(EventChannel::Recording)
+            .iter()
+            .any(|label| label == "test-notifier-a"));
+        assert!(subscribers(EventChannel::History)
+            .iter()
+            .any(|label| label == "test-notifier-b"));
+
+        subscribe("test-notifier-b", &[]);
+        assert!(!subscribers(EventChannel::History)
+            .iter()
+            .any(|label| label == "test-notifier-b"));
+    }
+}
@@ -6,13 +6,136 @@
 //!
 //! Graceful degradation: If accessibility permission is not granted, only clipboard
 //! copy is performed with no error shown to user.
+//!
+//! The frontmost app is resolved when the text is output: `app_output_rules`
+//! pick the output mode for that app, e.g. always typing into an editor.
+//! Apps listed in `clipboard_only_apps` (terminals and password prompts by
+//! default) only get the clipboard copy. Terminals the user removes from the
+//! list get the dictation on a single line, so a pasted line break cannot
+//! run a command, whether or not the shell uses bracketed paste.
+//!
+//! While secure keyboard entry is on (a password field or a secure terminal
+//! has focus), the text is only copied and secure-input-detected is
+//! emitted, so a dictation is never typed into a password prompt.
+//!
+//! With `accessibility_insertion` on, the text is first inserted into the
+//! focused text field through the Accessibility API, leaving the clipboard
+//! untouched; apps that do not support it get the text pasted.
+//!
+//! After pasting, the text the user had copied before the dictation is put
+//! back on the clipboard (`restore_clipboard`), unless something else was
+//! copied in the meantime. Only text can be restored.
+//!
+//! In `Type` mode the text is typed as keystrokes instead, so the clipboard
+//! keeps its content. Without the Accessibility permission, the text is
+//! copied to the clipboard as in the other modes.
+//!
+//! In `AskEachTime` mode the text is copied and kept pending, and
+//! output-mode-requested is emitted so the user picks how to output it.
+//! Changes of the output mode are emitted with output-mode-changed.
+
+use std::borrow::Cow;
+use std::sync::Mutex;
+use std::time::Duration;
 
 use crate::domain::{CyranoError, PermissionStatus};
+use crate::infrastructure::{accessibility, keyboard, workspace};
 use crate::services::accessibility_service;
 use crate::services::cursor_insertion_service;
+use crate::services::notifier_service;
+use crate::services::sandbox_service;
+use crate::services::settings_service::{self, Settings, TERMINAL_APPS};
+use serde::{Deserialize, Serialize};
+use specta::Type;
 use tauri::AppHandle;
 use tauri_plugin_clipboard_manager::ClipboardExt;
 
+/// Time for the target app to read the clipboard after the simulated Cmd+V,
+/// before the previous content is put back.
+const CLIPBOARD_RESTORE_DELAY: Duration = Duration::from_millis(500);
+
+/// Text of the last dictation waiting for the user to pick an output mode.
+static PENDING_OUTPUT: Mutex<Option<String>> = Mutex::new(None);
+
+/// Payload for the secure-input-detected event.
+#[derive(Clone, Serialize)]
+pub struct SecureInputDetectedPayload {
+    /// Bundle ID of the frontmost app, if known
+    pub app: Option<String>,
+}
+
+/// How text is delivered to the user.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputMode {
+    /// Copy to the clipboard only
+    Clipboard,
+    /// Copy to the clipboard and paste at the cursor when possible
+    #[default]
+    Paste,
+    /// Type at the cursor as keystrokes, leaving the clipboard untouched
+    Type,
+    /// Copy to the clipboard and ask which mode to use
+    AskEachTime,
+}
+
+/// Payload for the output-mode-changed event.
+#[derive(Clone, Serialize)]
+pub struct OutputModeChangedPayload {
+    pub mode: OutputMode,
+}
+
+/// Payload for the output-mode-requested event.
+#[derive(Clone, Serialize)]
+pub struct OutputModeRequestedPayload {
+    /// Text waiting to be output
+    pub text: String,
+}
+
+/// Set and persist the output mode, and emit output-mode-changed.
+///
+/// # Returns
+/// * `Ok(())` on success
+/// * `Err(CyranoError::SettingManaged)` if the output mode is managed
+/// * `Err(CyranoError::StorageFailed)` if the settings could not be written
+pub fn set_output_mode(app: &AppHandle, mode: OutputMode) -> Result<(), CyranoError> {
+    settings_service::update_settings(|settings| settings.output_mode = mode)?;
+    log::info!("Output mode set to {mode:?}");
+    if let Err(e) = notifier_service::emit(
+        app,
+        "output-mode-changed",
+        OutputModeChangedPayload { mode },
+    ) {
+        log::warn!("Failed to emit output-mode-changed event: {e}");
+    }
+    Ok(())
+}
+
+/// Copy the text and keep it pending until the user picks an output mode.
+fn ask_output_mode(text: &str, app: &AppHandle) -> Result<bool, CyranoError> {
+    // Copied first, so the text is not lost if the question is dismissed
+    copy_to_clipboard(text, app)?;
+    match PENDING_OUTPUT.lock() {
+        Ok(mut pending) => *pending = Some(text.to_string()),
+        Err(e) => log::error!("Failed to keep the dictation pending: {e}"),
+    }
+    let payload = OutputModeRequestedPayload {
+        text: text.to_string(),
+    };
+    if let Err(e) = notifier_service::emit(app, "output-mode-requested", payload) {
+        log::warn!("Failed to emit output-mode-requested event: {e}");
+    }
+    Ok(false)
+}
+
+/// Take the text waiting for an output mode, if any.
+pub fn take_pending_output() -> Option<String> {
+    PENDING_OUTPUT
+        .lock()
+        .ok()
+        .and_then(|mut pending| pending.take())
+}
+
 /// Copy text to the system clipboard.
 ///
 /// # Arguments
@@ -39,6 +162,117 @@ pub fn copy_to_clipboard(text: &str, app: &AppHandle) -> Result<(), CyranoError>
     Ok(())
 }
 
+/// Make sure the clipboard still holds `text` right before pasting it.
+///
+/// Clipboard managers and other apps can write to the clipboard between
+/// our copy and the simulated Cmd+V, which would paste their content into
+/// the user's document. The clipboard is read back, and rewritten once if
+/// it changed.
+///
+/// # Returns
+/// * `true` if the clipboard holds `text` and pasting is safe
+/// * `false` if another app kept replacing it
+fn clipboard_holds(text: &str, app: &AppHandle) -> bool {
+    let holds = || {
+        app.clipboard()
+            .read_text()
+            .is_ok_and(|current| current == text)
+    };
+    if holds() {
+        return true;
+    }
+
+    log::warn!("Clipboard changed after the copy, writing the transcription again");
+    if let Err(e) = copy_to_clipboard(text, app) {
+        log::warn!("[{}] Failed to rewrite the clipboard: {e}", e.code());
+        return false;
+    }
+    holds()
+}
+
+/// Put `previous` back on the clipboard once the paste of `pasted` had time
+/// to complete, unless something else was copied in the meantime.
+fn restore_clipboard_later(app: &AppHandle, pasted: &str, previous: String) {
+    let app = app.clone();
+    let pasted = pasted.to_string();
+    std::thread::spawn(move || {
+        std::thread::sleep(CLIPBOARD_RESTORE_DELAY);
+        let still_ours = app
+            .clipboard()
+            .read_text()
+            .is_ok_and(|current| current == pasted);
+        if !still_ours {
+            log::debug!("Clipboard changed since the paste - not restoring it");
+            return;
+        }
+        match app.clipboard().write_text(previous) {
+            Ok(()) => log::debug!("Restored the previous clipboard content"),
+            Err(e) => log::warn!("Failed to restore the previous clipboard content: {e}"),
+        }
+    });
+}
+
+/// Check whether secure keyboard entry is on, emitting secure-input-detected
+/// if so: text must then not be inserted, as it would go to a password field.
+fn secure_input_detected(app: &AppHandle, bundle_id: Option<&str>) -> bool {
+    if !keyboard::secure_input_enabled() {
+        return false;
+    }
+    log::info!("Secure keyboard entry is on ({bundle_id:?}) - copying without inserting");
+    let payload = SecureInputDetectedPayload {
+        app: bundle_id.map(str::to_string),
+    };
+    if let Err(e) = notifier_service::emit(app, "secure-input-detected", payload) {
+        log::error!("Failed to emit secure-input-detected event: {e}");
+    }
+    true
+}
+
+/// Output mode the rules set for the app, if any.
+fn app_rule_mode(settings: &Settings, bundle_id: Option<&str>) -> Option<OutputMode> {
+    let bundle_id = bundle_id?;
+    settings
+        .app_output_rules
+        .iter()
+        .find(|rule| rule.bundle_id.eq_ignore_ascii_case(bundle_id))
+        .map(|rule| rule.mode)
+}
+
+/// Check whether dictations are only copied to the clipboard in the app.
+fn is_clipboard_only(settings: &Settings, bundle_id: Option<&str>) -> bool {
+    bundle_id.is_some_and(|id| {
+        settings
+            .clipboard_only_apps
+            .iter()
+            .any(|app| app.eq_ignore_ascii_case(id))
+    })
+}
+
+/// Check whether the app is a terminal emulator.
+fn is_terminal(bundle_id: Option<&str>) -> bool {
+    bundle_id.is_some_and(|id| TERMINAL_APPS.iter().any(|app| app.eq_ignore_ascii_case(id)))
+}
+
+/// Join the lines of `text` with spaces, so pasting it into a terminal
+/// cannot press Return.
+fn single_line(text: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Put the text on a single line for a terminal.
+fn fit_to_app<'a>(text: &'a str, bundle_id: Option<&str>) -> Cow<'a, str> {
+    if is_terminal(bundle_id) && text.contains(['\n', '\r']) {
+        log::info!("Terminal frontmost - joining the dictation into a single line");
+        Cow::Owned(single_line(text))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
 /// Check if cursor insertion is available (accessibility permission granted).
 ///
 /// This function checks whether the app has accessibility permission,
@@ -47,7 +281,8 @@ pub fn copy_to_clipboard(text: &str, app: &AppHandle) -> Result<(), CyranoError>
 ///
 /// # Returns
 /// * `true` if accessibility permission is granted (cursor insertion available)
-/// * `false` if permission is not granted (clipboard-only mode)
+/// * `false` if permission is not granted or the App Sandbox blocks event
+///   posting (clipboard-only mode)
 ///
 /// # Note
 /// This function never fails - it simply returns false if permission is not
@@ -56,6 +291,12 @@ pub fn is_cursor_insertion_available() -> bool {
     let status = accessibility_service::check_accessibility_permission();
     let available = status == PermissionStatus::Granted;
 
+    // Sandboxed builds may hold the permission but still have CGEvents dropped
+    if available && sandbox_service::is_sandboxed() && !sandbox_service::can_post_events() {
+        log::debug!("Cursor insertion not available (App Sandbox blocks event posting)");
+        return false;
+    }
+
     if available {
         log::debug!("Cursor insertion available (accessibility permission granted)");
     } else {
@@ -72,13 +313,26 @@ pub fn is_cursor_insertion_available() -> bool {
 /// 2. If accessibility permission granted: inserts at cursor via Cmd+V (FR13)
 /// 3. If accessibility denied: gracefully degrades to clipboard-only
 ///
+/// When duplicate paste detection is enabled and another source pastes right
+/// after the clipboard copy, our Cmd+V is skipped to avoid double insertion.
+/// Cmd+V is also skipped when another app replaced the clipboard and kept it
+/// after one rewrite, so foreign content is never pasted, and when the
+/// frontmost app is in `clipboard_only_apps` or secure keyboard entry is on.
+/// Text for a terminal is put on a single line. After our Cmd+V, the clipboard text copied before the
+/// dictation is restored when `restore_clipboard` is on.
+///
+/// With `accessibility_insertion` on, the text is inserted into the focused
+/// field through the Accessibility API when the app supports it, without
+/// copying it to the clipboard.
+///
 /// # Arguments
 /// * `text` - The transcribed text to output
 /// * `app` - The Tauri app handle
 ///
 /// # Returns
 /// * `Ok(true)` if both clipboard copy and cursor insertion succeeded
-/// * `Ok(false)` if only clipboard copy succeeded (accessibility denied or insertion failed)
+/// * `Ok(false)` if only clipboard copy succeeded (accessibility denied, insertion
+///   failed or another app pasted the text)
 /// * `Err(CyranoError::ClipboardFailed)` if clipboard copy failed
 ///
 /// # Note
@@ -86,22 +340,66 @@ pub fn is_cursor_insertion_available() -> bool {
 /// Cursor insertion failure is not treated as an error - graceful degradation
 /// means the text is always available in the clipboard for manual pasting.
 pub fn output_transcription(text: &str, app: &AppHandle) -> Result<bool, CyranoError> {
+    let settings = settings_service::get_settings();
+    let bundle_id = workspace::frontmost_bundle_id();
+    if is_clipboard_only(&settings, bundle_id.as_deref()) {
+        log::info!("Clipboard-only app frontmost ({bundle_id:?}) - skipping Cmd+V");
+        copy_to_clipboard(text, app)?;
+        return Ok(false);
+    }
+    if secure_input_detected(app, bundle_id.as_deref()) {
+        copy_to_clipboard(text, app)?;
+        return Ok(false);
+    }
+    let fitted = fit_to_app(text, bundle_id.as_deref());
+    let text = fitted.as_ref();
+
+    if settings.accessibility_insertion
+        && is_cursor_insertion_available()
+        && accessibility::insert_text_at_caret(text)
+    {
+        log::info!("Inserted the text into the focused field through Accessibility");
+        return Ok(true);
+    }
+
+    // Watch for Cmd+V before the copy: auto-paste tools react to the copy itself
+    let paste_watch = cursor_insertion_service::watch_for_external_paste();
+    let previous_clipboard = settings
+        .restore_clipboard
+        .then(|| app.clipboard().read_text().ok())
+        .flatten();
+
     // Step 1: Always copy to clipboard first (prerequisite for cursor insertion)
     copy_to_clipboard(text, app)?;
 
     // Step 2: Attempt cursor insertion if accessibility permission is granted
     if is_cursor_insertion_available() {
+        if paste_watch
+            .as_ref()
+            .is_some_and(cursor_insertion_service::external_paste_observed)
+        {
+            // Cyrano inserted nothing itself, so redo must not delete the text
+            log::info!("External paste detected after clipboard copy - skipping Cmd+V");
+            return Ok(false);
+        }
+
+        if !clipboard_holds(text, app) {
+            log::warn!("Clipboard overwritten by another app - skipping Cmd+V");
+            return Ok(false);
+        }
+
         log::info!("Attempting cursor insertion via Cmd+V simulation");
 
-        // Call cursor insertion service - it handles graceful degradation internally
-        // and always returns Ok, so we just check if it worked
-        if cursor_insertion_service::insert_at_cursor().is_ok() {
+        // Restore the clipboard only after a paste that was posted: otherwise
+        // the user still has to paste the text by hand
+        if cursor_insertion_service::insert_at_cursor()? {
             log::info!("Cursor insertion completed (text in clipboard and paste simulated)");
+            if let Some(previous) = previous_clipboard {
+                restore_clipboard_later(app, text, previous);
+            }
             Ok(true)
         } else {
-            // This branch is actually unreachable due to graceful degradation,
-            // but we handle it for completeness
-            log::warn!("Cursor insertion reported failure - text is in clipboard");
+            log::warn!("Paste not posted - text left on the clipboard");
             Ok(false)
         }
     } else {
@@ -111,6 +409,80 @@ pub fn output_transcription(text: &str, app: &AppHandle) -> Result<bool, CyranoE
     }
 }
 
+/// Type transcribed text at the cursor, leaving the clipboard untouched.
+///
+/// Falls back to copying the text to the clipboard when the frontmost app
+/// is in `clipboard_only_apps`, when secure keyboard entry is on, when
+/// cursor insertion is not available, or when typing fails. Text for a
+/// terminal is put on a single line.
+///
+/// # Returns
+/// * `Ok(true)` if the text was typed
+/// * `Ok(false)` if it was copied to the clipboard instead
+/// * `Err(CyranoError::ClipboardFailed)` if the fallback copy failed
+pub fn type_transcription(text: &str, app: &AppHandle) -> Result<bool, CyranoError> {
+    let bundle_id = workspace::frontmost_bundle_id();
+    if is_clipboard_only(&settings_service::get_settings(), bundle_id.as_deref()) {
+        log::info!("Clipboard-only app frontmost ({bundle_id:?}) - copying instead of typing");
+        copy_to_clipboard(text, app)?;
+        return Ok(false);
+    }
+    if secure_input_detected(app, bundle_id.as_deref()) {
+        copy_to_clipboard(text, app)?;
+        return Ok(false);
+    }
+    let text = fit_to_app(text, bundle_id.as_deref());
+
+    if !is_cursor_insertion_available() {
+        log::info!("Cursor insertion not available - copying instead of typing");
+        copy_to_clipboard(&text, app)?;
+        return Ok(false);
+    }
+
+    match keyboard::type_text(&text) {
+        Ok(()) => {
+            log::info!("Typed {} chars at the cursor", text.chars().count());
+            Ok(true)
+        }
+        Err(e) => {
+            log::warn!("Typing failed, copying to the clipboard instead: {e}");
+            copy_to_clipboard(&text, app).map(|()| false)
+        }
+    }
+}
+
+/// Output text in the given mode, or in the mode `app_output_rules` set for
+/// the frontmost app. Text only meant for the clipboard is never inserted,
+/// whatever the rules.
+///
+/// # Returns
+/// * `Ok(true)` if the text was inserted at the cursor
+/// * `Ok(false)` if it was only copied to the clipboard
+/// * `Err(CyranoError::ClipboardFailed)` if clipboard copy failed
+pub fn output_with_mode(
+    text: &str,
+    app: &AppHandle,
+    mode: OutputMode,
+) -> Result<bool, CyranoError> {
+    let mode = match mode {
+        OutputMode::Clipboard => mode,
+        _ => {
+            let bundle_id = workspace::frontmost_bundle_id();
+            let rule = app_rule_mode(&settings_service::get_settings(), bundle_id.as_deref());
+            if let Some(rule) = rule.filter(|rule| *rule != mode) {
+                log::info!("Output rule for {bundle_id:?}: {rule:?} instead of {mode:?}");
+            }
+            rule.unwrap_or(mode)
+        }
+    };
+    match mode {
+        OutputMode::Clipboard => copy_to_clipboard(text, app).map(|()| false),
+        OutputMode::Paste => output_transcription(text, app),
+        OutputMode::Type => type_transcription(text, app),
+        OutputMode::AskEachTime => ask_output_mode(text, app),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -137,6 +509,71 @@ mod tests {
         assert!(json.contains("Test error"));
     }
 
+    #[test]
+    fn test_clipboard_only_apps() {
+        let settings = Settings::default();
+        assert!(is_clipboard_only(&settings, Some("com.apple.Terminal")));
+        assert!(is_clipboard_only(
+            &settings,
+            Some("com.apple.securityagent")
+        ));
+        assert!(!is_clipboard_only(&settings, Some("com.apple.TextEdit")));
+        assert!(!is_clipboard_only(&settings, None));
+
+        let settings = Settings {
+            clipboard_only_apps: Vec::new(),
+            ..Settings::default()
+        };
+        assert!(!is_clipboard_only(&settings, Some("com.apple.Terminal")));
+        assert!(is_terminal(Some("com.apple.Terminal")));
+    }
+
+    #[test]
+    fn test_app_output_rules() {
+        use crate::services::settings_service::AppOutputRule;
+
+        let settings = Settings {
+            app_output_rules: vec![AppOutputRule {
+                bundle_id: "com.microsoft.VSCode".to_string(),
+                mode: OutputMode::Type,
+                engine: None,
+            }],
+            ..Settings::default()
+        };
+        assert_eq!(
+            app_rule_mode(&settings, Some("com.microsoft.vscode")),
+            Some(OutputMode::Type)
+        );
+        assert_eq!(app_rule_mode(&settings, Some("com.apple.TextEdit")), None);
+        assert_eq!(app_rule_mode(&settings, None), None);
+    }
+
+    #[test]
+    fn test_single_line_for_terminals() {
+        assert_eq!(single_line("git status\n"), "git status");
+        assert_eq!(
+            single_line("first line\r\n\nsecond line \n"),
+            "first line second line"
+        );
+    }
+
+    #[test]
+    fn test_output_mode_serialization() {
+        assert_eq!(
+            serde_json::to_string(&OutputMode::Clipboard).unwrap(),
+            "\"clipboard\""
+        );
+        assert_eq!(
+            serde_json::to_string(&OutputMode::Type).unwrap(),
+            "\"type\""
+        );
+        assert_eq!(
+            serde_json::to_string(&OutputMode::AskEachTime).unwrap(),
+            "\"ask_each_time\""
+        );
+        assert_eq!(OutputMode::default(), OutputMode::Paste);
+    }
+
     #[test]
     fn test_is_cursor_insertion_available_returns_bool() {
         // This test verifies the function executes without panic.
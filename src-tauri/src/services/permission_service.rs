@@ -1,11 +1,175 @@
-//! Microphone permission checking service.
+//! Permission checking service.
 //!
 //! Provides methods to check and request microphone permission on macOS.
 //! Uses cpal to implicitly trigger the macOS permission dialog.
+//!
+//! Also reports the three macOS permissions the app may need (Microphone,
+//! Accessibility and Input Monitoring) together, with a readiness score for
+//! onboarding, and checks, requests or opens the settings of any of them.
 
 use cpal::traits::{DeviceTrait, HostTrait};
+use serde::{Deserialize, Serialize};
+use specta::Type;
 
 use crate::domain::{CyranoError, PermissionStatus};
+use crate::services::accessibility_service;
+
+/// A macOS permission the app may need.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    /// Recording audio; required to dictate at all.
+    Microphone,
+    /// Pasting at the cursor and reading the focused text field.
+    Accessibility,
+    /// Listening to keyboard events, for push-to-talk and event taps.
+    InputMonitoring,
+}
+
+impl Permission {
+    /// Every permission, in onboarding order.
+    pub const ALL: [Permission; 3] = [
+        Permission::Microphone,
+        Permission::Accessibility,
+        Permission::InputMonitoring,
+    ];
+
+    /// Share of the readiness score this permission is worth, in percent.
+    fn weight(self) -> u8 {
+        match self {
+            Permission::Microphone => 60,
+            Permission::Accessibility => 25,
+            Permission::InputMonitoring => 15,
+        }
+    }
+}
+
+/// Status of every permission, for onboarding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+pub struct PermissionsReport {
+    pub microphone: PermissionStatus,
+    pub accessibility: PermissionStatus,
+    pub input_monitoring: PermissionStatus,
+    /// Weighted share of the permissions granted, from 0 to 100. The
+    /// microphone counts the most, as nothing works without it.
+    pub readiness: u8,
+}
+
+impl PermissionsReport {
+    fn new(status: impl Fn(Permission) -> PermissionStatus) -> Self {
+        let readiness = Permission::ALL
+            .iter()
+            .filter(|permission| status(**permission) == PermissionStatus::Granted)
+            .map(|permission| permission.weight())
+            .sum();
+        Self {
+            microphone: status(Permission::Microphone),
+            accessibility: status(Permission::Accessibility),
+            input_monitoring: status(Permission::InputMonitoring),
+            readiness,
+        }
+    }
+}
+
+/// Check the status of every permission.
+pub fn permissions_report() -> PermissionsReport {
+    PermissionsReport::new(check_permission)
+}
+
+/// Check the current status of a permission.
+pub fn check_permission(permission: Permission) -> PermissionStatus {
+    match permission {
+        Permission::Microphone => check_microphone_permission(),
+        Permission::Accessibility => accessibility_service::check_accessibility_permission(),
+        Permission::InputMonitoring => check_input_monitoring_permission(),
+    }
+}
+
+/// Request a permission from the user, showing the system prompt if they
+/// were never asked.
+///
+/// # Returns
+/// * `Ok(true)` if the permission is granted
+/// * `Ok(false)` if it is not (Accessibility and Input Monitoring)
+/// * `Err(CyranoError::MicAccessDenied)` if the microphone is denied
+pub fn request_permission(permission: Permission) -> Result<bool, CyranoError> {
+    match permission {
+        Permission::Microphone => request_microphone_permission(),
+        Permission::Accessibility => accessibility_service::request_accessibility_permission(),
+        Permission::InputMonitoring => Ok(request_input_monitoring_permission()),
+    }
+}
+
+/// Open the System Settings pane where a permission is granted.
+pub fn open_permission_settings(permission: Permission) -> Result<(), CyranoError> {
+    match permission {
+        Permission::Microphone => open_microphone_settings(),
+        Permission::Accessibility => accessibility_service::open_accessibility_settings(),
+        Permission::InputMonitoring => open_input_monitoring_settings(),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn check_input_monitoring_permission() -> PermissionStatus {
+    crate::infrastructure::permissions::macos_input_monitoring::check_input_monitoring()
+}
+
+/// Non-macOS stub: always returns Denied.
+#[cfg(not(target_os = "macos"))]
+fn check_input_monitoring_permission() -> PermissionStatus {
+    PermissionStatus::Denied
+}
+
+#[cfg(target_os = "macos")]
+fn request_input_monitoring_permission() -> bool {
+    let granted =
+        crate::infrastructure::permissions::macos_input_monitoring::request_input_monitoring();
+    if granted {
+        log::info!("Input Monitoring permission granted");
+    } else {
+        log::warn!("Input Monitoring permission not granted");
+    }
+    granted
+}
+
+/// Non-macOS stub: always returns false.
+#[cfg(not(target_os = "macos"))]
+fn request_input_monitoring_permission() -> bool {
+    log::warn!("Input Monitoring permission request is only supported on macOS");
+    false
+}
+
+#[cfg(target_os = "macos")]
+fn open_input_monitoring_settings() -> Result<(), CyranoError> {
+    crate::infrastructure::permissions::macos_input_monitoring::open_input_monitoring_preferences()
+        .map_err(|e| CyranoError::OpenSettingsFailed {
+            reason: format!("Failed to open Input Monitoring settings: {e}"),
+        })
+}
+
+#[cfg(target_os = "macos")]
+fn open_microphone_settings() -> Result<(), CyranoError> {
+    let url = "x-apple.systempreferences:com.apple.preference.security?Privacy_Microphone";
+    tauri_plugin_opener::open_url(url, None::<&str>).map_err(|e| CyranoError::OpenSettingsFailed {
+        reason: format!("Failed to open Microphone settings: {e}"),
+    })
+}
+
+/// Non-macOS stub: returns an error.
+#[cfg(not(target_os = "macos"))]
+fn open_input_monitoring_settings() -> Result<(), CyranoError> {
+    Err(CyranoError::OpenSettingsFailed {
+        reason: "Input Monitoring settings are only available on macOS".to_string(),
+    })
+}
+
+/// Non-macOS stub: returns an error.
+#[cfg(not(target_os = "macos"))]
+fn open_microphone_settings() -> Result<(), CyranoError> {
+    Err(CyranoError::OpenSettingsFailed {
+        reason: "Microphone settings are only available on macOS".to_string(),
+    })
+}
 
 /// Check the current microphone permission status.
 ///
@@ -101,4 +265,23 @@ mod tests {
 
     // Note: We cannot easily test request_microphone_permission in unit tests
     // as it requires actual user interaction on macOS
+
+    #[test]
+    fn test_readiness_weighs_granted_permissions() {
+        use PermissionStatus::{Denied, Granted, NotDetermined};
+
+        let none = PermissionsReport::new(|_| NotDetermined);
+        assert_eq!(none.readiness, 0);
+        let all = PermissionsReport::new(|_| Granted);
+        assert_eq!(all.readiness, 100);
+
+        let microphone_only = PermissionsReport::new(|permission| match permission {
+            Permission::Microphone => Granted,
+            _ => Denied,
+        });
+        assert_eq!(microphone_only.microphone, Granted);
+        assert_eq!(microphone_only.input_monitoring, Denied);
+        // The microphone alone is worth more than the other two together
+        assert!(microphone_only.readiness > 50);
+    }
 }
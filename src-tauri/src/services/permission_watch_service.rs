@@ -0,0 +1,103 @@
+//! Watcher for permission changes made in System Settings.
+//!
+//! macOS does not notify an app when the user grants it Accessibility or
+//! Input Monitoring, so the watcher polls both. When one changes it emits
+//! permissions-changed, and when one is newly granted it retries the global
+//! shortcuts that failed to register, so granting a permission takes effect
+//! without restarting the app.
+
+use std::time::Duration;
+
+use serde::Serialize;
+use specta::Type;
+use tauri::AppHandle;
+
+use crate::domain::PermissionStatus;
+use crate::services::permission_service::{self, Permission};
+use crate::services::{notifier_service, shortcut_service};
+
+/// How often permissions are checked.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Payload for the permissions-changed event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+pub struct PermissionsChangedPayload {
+    pub accessibility: PermissionStatus,
+    pub input_monitoring: PermissionStatus,
+}
+
+impl PermissionsChangedPayload {
+    /// Whether a permission missing in `previous` is granted now.
+    fn grants_more_than(&self, previous: &Self) -> bool {
+        let newly_granted = |now: PermissionStatus, before: PermissionStatus| {
+            now == PermissionStatus::Granted && before != PermissionStatus::Granted
+        };
+        newly_granted(self.accessibility, previous.accessibility)
+            || newly_granted(self.input_monitoring, previous.input_monitoring)
+    }
+}
+
+fn current_permissions() -> PermissionsChangedPayload {
+    PermissionsChangedPayload {
+        accessibility: permission_service::check_permission(Permission::Accessibility),
+        input_monitoring: permission_service::check_permission(Permission::InputMonitoring),
+    }
+}
+
+/// Start polling permissions in the background (macOS only).
+pub fn start_watcher(app: &AppHandle) {
+    if !cfg!(target_os = "macos") {
+        return;
+    }
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let mut previous = current_permissions();
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+            let permissions = current_permissions();
+            if permissions == previous {
+                continue;
+            }
+
+            log::info!("Permissions changed: {permissions:?}");
+            if let Err(e) = notifier_service::emit(&app, "permissions-changed", permissions) {
+                log::error!("Failed to emit permissions-changed event: {e}");
+            }
+            if permissions.grants_more_than(&previous) {
+                shortcut_service::retry_deferred_shortcuts(&app);
+            }
+            previous = permissions;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn permissions(
+        accessibility: PermissionStatus,
+        input_monitoring: PermissionStatus,
+    ) -> PermissionsChangedPayload {
+        PermissionsChangedPayload {
+            accessibility,
+            input_monitoring,
+        }
+    }
+
+    #[test]
+    fn test_only_a_new_grant_triggers_retries() {
+        use PermissionStatus::{Denied, Granted, NotDetermined};
+
+        let before = permissions(NotDetermined, Denied);
+        assert!(permissions(Granted, Denied).grants_more_than(&before));
+        assert!(permissions(NotDetermined, Granted).grants_more_than(&before));
+        assert!(!permissions(NotDetermined, NotDetermined).grants_more_than(&before));
+
+        // Revoking a permission does not retry anything
+        let granted = permissions(Granted, Granted);
+        assert!(!permissions(NotDetermined, Granted).grants_more_than(&granted));
+        assert!(!granted.grants_more_than(&granted));
+    }
+}
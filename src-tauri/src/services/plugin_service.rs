@@ -0,0 +1,530 @@
+//! User-provided post-processing plugins.
+//!
+//! Plugins are executables placed in `~/.cyrano/plugins`. When enabled, they
+//! run in file name order (e.g. `10-capitalize`, `20-markdown`) between
+//! post-processing and output, each receiving the previous plugin's text.
+//!
+//! Contract: the plugin reads one JSON object on stdin and writes one on
+//! stdout, then exits with status 0:
+//!
+//! ```text
+//! stdin:  {"version": 1, "text": "hello world", "bundle_id": "com.apple.Notes"}
+//! stdout: {"text": "Hello world."}
+//! ```
+//!
+//! Safety:
+//! - A plugin only runs after the user allowed it. The decision is tied to
+//!   the file's SHA-256, so a modified plugin must be approved again.
+//! - Plugins run with a minimal environment and a time limit, and on macOS
+//!   inside a `sandbox-exec` profile without network or file write access.
+//! - Any failure (crash, timeout, invalid output) skips the plugin and keeps
+//!   the text unchanged; dictation never fails because of a plugin.
+
+use std::collections::HashSet;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use specta::Type;
+use tauri::AppHandle;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::domain::CyranoError;
+use crate::services::data_directory_service;
+use crate::services::safe_mode_service;
+use crate::services::settings_service::{self, PluginApproval, Settings};
+use crate::utils::text_sanitize;
+
+/// Version of the stdin/stdout contract.
+const CONTRACT_VERSION: u32 = 1;
+
+/// Largest plugin output accepted, in bytes.
+const MAX_OUTPUT_BYTES: u64 = 1024 * 1024;
+
+/// Largest stderr excerpt kept for logs, in bytes.
+const MAX_STDERR_BYTES: u64 = 4 * 1024;
+
+/// PATH given to plugins.
+const PLUGIN_PATH: &str = "/usr/bin:/bin:/usr/sbin:/sbin";
+
+/// macOS sandbox launcher.
+#[cfg(target_os = "macos")]
+const SANDBOX_EXEC: &str = "/usr/bin/sandbox-exec";
+
+/// Sandbox profile: no network, no writes outside temporary directories.
+#[cfg(target_os = "macos")]
+const SANDBOX_PROFILE: &str = r#"(version 1)
+(allow default)
+(deny network*)
+(deny file-write*)
+(allow file-write* (literal "/dev/null") (subpath "/private/tmp") (subpath "/private/var/folders"))"#;
+
+/// Hashes for which a permission prompt was already shown this session.
+static PROMPTED: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+/// Input written to a plugin's stdin.
+#[derive(Debug, Serialize)]
+struct PluginInput<'a> {
+    version: u32,
+    text: &'a str,
+    bundle_id: Option<&'a str>,
+}
+
+/// Output read from a plugin's stdout.
+#[derive(Debug, Deserialize)]
+struct PluginOutput {
+    text: String,
+}
+
+/// A plugin found in the plugins directory.
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct PluginInfo {
+    /// Plugin file name
+    pub name: String,
+    /// SHA-256 of the plugin file (hex)
+    pub sha256: String,
+    /// Whether the user allowed the current version, or None if not decided yet
+    pub allowed: Option<bool>,
+}
+
+/// List executable plugin files in `dir`, sorted by name.
+fn discover_plugins(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut plugins: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let hidden = path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+            !hidden && path.is_file() && is_executable(path)
+        })
+        .collect();
+    plugins.sort();
+    plugins
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path).is_ok_and(|m| m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(_path: &Path) -> bool {
+    false
+}
+
+fn plugin_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Hash a plugin file (hex SHA-256).
+fn file_sha256(path: &Path) -> Result<String, CyranoError> {
+    let bytes = std::fs::read(path).map_err(|e| CyranoError::StorageFailed {
+        reason: format!("Failed to read plugin {}: {e}", path.display()),
+    })?;
+    Ok(Sha256::digest(&bytes)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+/// The recorded decision for this exact plugin content, if any.
+fn approval_for(settings: &Settings, name: &str, sha256: &str) -> Option<bool> {
+    settings
+        .plugin_approvals
+        .iter()
+        .find(|approval| approval.name == name && approval.sha256 == sha256)
+        .map(|approval| approval.allowed)
+}
+
+/// Build the command for a plugin, sandboxed where supported.
+fn plugin_command(path: &Path) -> Command {
+    #[cfg(target_os = "macos")]
+    let mut command = if Path::new(SANDBOX_EXEC).exists() {
+        let mut command = Command::new(SANDBOX_EXEC);
+        command.arg("-p").arg(SANDBOX_PROFILE).arg(path);
+        command
+    } else {
+        log::warn!("sandbox-exec not found, running plugin without sandbox");
+        Command::new(path)
+    };
+    #[cfg(not(target_os = "macos"))]
+    let mut command = Command::new(path);
+
+    command
+        .env_clear()
+        .env("PATH", PLUGIN_PATH)
+        .env("LANG", "en_US.UTF-8")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(dir) = path.parent() {
+        command.current_dir(dir);
+    }
+    command
+}
+
+/// Read a pipe to the end on a helper thread.
+fn spawn_reader(mut pipe: impl Read + Send + 'static, limit: u64) -> mpsc::Receiver<Vec<u8>> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut bytes = Vec::new();
+        let _ = (&mut pipe).take(limit).read_to_end(&mut bytes);
+        let _ = sender.send(bytes);
+    });
+    receiver
+}
+
+/// Run one plugin with `input` on stdin and return its stdout.
+///
+/// The process is killed when `timeout` elapses. Output is read on helper
+/// threads so a plugin that leaves a child process holding its stdout open
+/// cannot block dictation either.
+fn run_process(mut command: Command, input: &[u8], timeout: Duration) -> Result<Vec<u8>, String> {
+    let deadline = Instant::now() + timeout;
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("failed to start: {e}"))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        let input = input.to_vec();
+        std::thread::spawn(move || {
+            // A plugin that ignores stdin closes the pipe early; that is fine
+            let _ = stdin.write_all(&input);
+        });
+    }
+    let stdout = child
+        .stdout
+        .take()
+        .map(|pipe| spawn_reader(pipe, MAX_OUTPUT_BYTES));
+    let stderr = child
+        .stderr
+        .take()
+        .map(|pipe| spawn_reader(pipe, MAX_STDERR_BYTES));
+
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("timed out after {}ms", timeout.as_millis()));
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(5)),
+            Err(e) => return Err(format!("failed to wait: {e}")),
+        }
+    };
+
+    let remaining = deadline.saturating_duration_since(Instant::now());
+    let collect = |receiver: Option<mpsc::Receiver<Vec<u8>>>| {
+        receiver.and_then(|r| r.recv_timeout(remaining).ok())
+    };
+    let stdout = collect(stdout);
+
+    if !status.success() {
+        let stderr = collect(stderr).unwrap_or_default();
+        return Err(format!(
+            "exited with {status}: {}",
+            String::from_utf8_lossy(&stderr).trim()
+        ));
+    }
+    stdout.ok_or_else(|| "output was not closed in time".to_string())
+}
+
+/// Run one plugin on `text` and return the transformed text.
+fn run_plugin(
+    path: &Path,
+    text: &str,
+    bundle_id: Option<&str>,
+    timeout: Duration,
+) -> Result<String, String> {
+    let input = serde_json::to_vec(&PluginInput {
+        version: CONTRACT_VERSION,
+        text,
+        bundle_id,
+    })
+    .map_err(|e| e.to_string())?;
+
+    let stdout = run_process(plugin_command(path), &input, timeout)?;
+    let output: PluginOutput =
+        serde_json::from_slice(&stdout).map_err(|e| format!("invalid output: {e}"))?;
+    Ok(text_sanitize::sanitize_output(&output.text))
+}
+
+/// Ask the user whether a plugin may run, without blocking dictation.
+///
+/// The prompt is shown once per plugin version and session; the decision
+/// applies from the next dictation on.
+fn request_approval(app: &AppHandle, name: String, sha256: String) {
+    {
+        let mut prompted = PROMPTED.lock().unwrap_or_else(|e| e.into_inner());
+        if !prompted
+            .get_or_insert_with(HashSet::new)
+            .insert(sha256.clone())
+        {
+            return;
+        }
+    }
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let allowed = app
+            .dialog()
+            .message(format!(
+                "The plugin \"{name}\" wants to process your transcriptions.\n\n\
+                 Plugins are programs you installed in ~/.cyrano/plugins. \
+                 Only allow plugins you trust."
+            ))
+            .title("Allow transcription plugin?")
+            .kind(MessageDialogKind::Warning)
+            .buttons(MessageDialogButtons::OkCancelCustom(
+                "Allow".to_string(),
+                "Don't Allow".to_string(),
+            ))
+            .blocking_show();
+
+        if let Err(e) = record_approval(&name, &sha256, allowed) {
+            log::warn!("[{}] Failed to save plugin approval: {e}", e.code());
+        }
+    });
+}
+
+/// Store the decision for a plugin version, replacing older decisions.
+fn record_approval(name: &str, sha256: &str, allowed: bool) -> Result<(), CyranoError> {
+    log::info!(
+        "Plugin {name} {}",
+        if allowed { "allowed" } else { "denied" }
+    );
+    settings_service::update_settings(|settings| {
+        settings
+            .plugin_approvals
+            .retain(|approval| approval.name != name);
+        settings.plugin_approvals.push(PluginApproval {
+            name: name.to_string(),
+            sha256: sha256.to_string(),
+            allowed,
+        });
+    })
+    .map(|_| ())
+}
+
+/// Run every allowed plugin on the transcription, in order.
+///
+/// Plugins without a decision are skipped and trigger a permission prompt.
+/// Returns `text` unchanged when plugins are disabled or in safe mode.
+pub fn run_plugins(app: &AppHandle, text: &str) -> String {
+    let settings = settings_service::get_settings();
+    if !settings.plugins_enabled || safe_mode_service::is_safe_mode() {
+        return text.to_string();
+    }
+    let Ok(dir) = data_directory_service::plugins_dir() else {
+        return text.to_string();
+    };
+
+    #[cfg(target_os = "macos")]
+    let bundle_id = crate::infrastructure::workspace::frontmost_bundle_id();
+    #[cfg(not(target_os = "macos"))]
+    let bundle_id: Option<String> = None;
+
+    let timeout = Duration::from_millis(u64::from(settings.plugin_timeout_ms));
+    let mut text = text.to_string();
+
+    for path in discover_plugins(&dir) {
+        let name = plugin_name(&path);
+        let sha256 = match file_sha256(&path) {
+            Ok(hash) => hash,
+            Err(e) => {
+                log::warn!("[{}] Skipping plugin {name}: {e}", e.code());
+                continue;
+            }
+        };
+
+        match approval_for(&settings, &name, &sha256) {
+            Some(true) => {}
+            Some(false) => continue,
+            None => {
+                log::info!("Plugin {name} is not approved yet, asking the user");
+                request_approval(app, name, sha256);
+                continue;
+            }
+        }
+
+        let start = Instant::now();
+        match run_plugin(&path, &text, bundle_id.as_deref(), timeout) {
+            Ok(output) => {
+                log::debug!("Plugin {name} ran in {}ms", start.elapsed().as_millis());
+                text = output;
+            }
+            Err(reason) => log::warn!("Plugin {name} failed, skipping it: {reason}"),
+        }
+    }
+
+    text
+}
+
+/// List installed plugins with their approval status.
+pub fn list_plugins() -> Result<Vec<PluginInfo>, CyranoError> {
+    let settings = settings_service::get_settings();
+    discover_plugins(&data_directory_service::plugins_dir()?)
+        .iter()
+        .map(|path| {
+            let name = plugin_name(path);
+            let sha256 = file_sha256(path)?;
+            Ok(PluginInfo {
+                allowed: approval_for(&settings, &name, &sha256),
+                name,
+                sha256,
+            })
+        })
+        .collect()
+}
+
+/// Allow or deny the current version of an installed plugin.
+///
+/// # Returns
+/// * `Ok(())` once the decision is saved
+/// * `Err(CyranoError::StorageFailed)` if no such plugin is installed
+pub fn set_plugin_approval(name: &str, allowed: bool) -> Result<(), CyranoError> {
+    let path = discover_plugins(&data_directory_service::plugins_dir()?)
+        .into_iter()
+        .find(|path| plugin_name(path) == name)
+        .ok_or_else(|| CyranoError::StorageFailed {
+            reason: format!("Plugin not found: {name}"),
+        })?;
+    record_approval(name, &file_sha256(&path)?, allowed)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::temp_dir;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn write_script(dir: &Path, name: &str, body: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, format!("#!/bin/sh\n{body}\n")).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn test_discovery_skips_hidden_and_non_executable_files() {
+        let dir = temp_dir("discover");
+        write_script(&dir, "20-second", "true");
+        write_script(&dir, "10-first", "true");
+        write_script(&dir, ".hidden", "true");
+        std::fs::write(dir.join("README.md"), "docs").unwrap();
+
+        let names: Vec<String> = discover_plugins(&dir)
+            .iter()
+            .map(|p| plugin_name(p))
+            .collect();
+        assert_eq!(names, vec!["10-first", "20-second"]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_plugin_transforms_text() {
+        let dir = temp_dir("transform");
+        let plugin = write_script(
+            &dir,
+            "upper",
+            r#"input=$(cat); case "$input" in *'"text":"hello"'*) printf '{"text":"HELLO"}';; *) exit 3;; esac"#,
+        );
+
+        assert_eq!(
+            run_plugin(&plugin, "hello", None, TIMEOUT).as_deref(),
+            Ok("HELLO")
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_failing_plugin_reports_stderr() {
+        let dir = temp_dir("failing");
+        let plugin = write_script(&dir, "broken", "echo boom >&2; exit 2");
+
+        let error = run_plugin(&plugin, "text", None, TIMEOUT).unwrap_err();
+        assert!(error.contains("boom"), "unexpected error: {error}");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_invalid_output_is_rejected() {
+        let dir = temp_dir("invalid");
+        let plugin = write_script(&dir, "chatty", "echo 'not json'");
+
+        let error = run_plugin(&plugin, "text", None, TIMEOUT).unwrap_err();
+        assert!(
+            error.starts_with("invalid output"),
+            "unexpected error: {error}"
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_slow_plugin_times_out() {
+        let dir = temp_dir("slow");
+        let plugin = write_script(&dir, "slow", "sleep 5");
+
+        let start = Instant::now();
+        let error = run_plugin(&plugin, "text", None, Duration::from_millis(200)).unwrap_err();
+        assert!(error.contains("timed out"), "unexpected error: {error}");
+        assert!(start.elapsed() < Duration::from_secs(2));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_plugin_output_is_sanitized() {
+        let dir = temp_dir("sanitize");
+        let plugin = write_script(
+            &dir,
+            "control",
+            "cat >/dev/null\ncat <<'EOF'\n{\"text\":\"a\\u0007b\"}\nEOF",
+        );
+
+        assert_eq!(run_plugin(&plugin, "x", None, TIMEOUT).as_deref(), Ok("ab"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_approval_is_tied_to_content() {
+        let settings = Settings {
+            plugin_approvals: vec![PluginApproval {
+                name: "fmt".to_string(),
+                sha256: "aa".to_string(),
+                allowed: true,
+            }],
+            ..Settings::default()
+        };
+        assert_eq!(approval_for(&settings, "fmt", "aa"), Some(true));
+        assert_eq!(approval_for(&settings, "fmt", "bb"), None);
+        assert_eq!(approval_for(&settings, "other", "aa"), None);
+    }
+
+    #[test]
+    fn test_file_sha256_is_hex_digest() {
+        let dir = temp_dir("hash");
+        let path = dir.join("empty");
+        std::fs::write(&path, b"").unwrap();
+        assert_eq!(
+            file_sha256(&path).unwrap(),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
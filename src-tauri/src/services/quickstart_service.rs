@@ -0,0 +1,173 @@
+//! One-click setup for new users.
+//!
+//! Downloads the tiny English model, verifies it, makes it the active model
+//! and transcribes a sample sentence spoken by the system voice, so a new
+//! user can dictate without placing a model file by hand. Each step is
+//! reported with a quickstart-progress event, and the end of the flow with
+//! quickstart-complete or quickstart-failed.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use specta::Type;
+use tauri::AppHandle;
+
+use crate::domain::CyranoError;
+use crate::infrastructure::audio::test_signal::{self, TestSignal};
+use crate::services::{
+    model_download_service, model_integrity_service, notifier_service, transcription_service,
+};
+use crate::utils::text_sanitize;
+
+/// Model installed by the quickstart (about 75MB).
+pub const QUICKSTART_MODEL: &str = "ggml-tiny.en.bin";
+
+/// Sentence spoken and transcribed to test the model.
+const SAMPLE_TEXT: &str = "Hello from Cyrano. Dictation is ready.";
+
+/// Minimum time between two download progress events.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Set while the quickstart is running (only one runs at a time).
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Step of the quickstart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum QuickstartStage {
+    /// Fetching the model
+    Downloading,
+    /// Checking the downloaded file
+    Verifying,
+    /// Transcribing the sample sentence
+    Testing,
+}
+
+/// Payload for the quickstart-progress event.
+#[derive(Clone, serde::Serialize)]
+pub struct QuickstartProgressPayload {
+    pub stage: QuickstartStage,
+    /// Bytes of the model downloaded so far
+    pub downloaded_bytes: u64,
+    /// Size of the model, when the server tells it
+    pub total_bytes: Option<u64>,
+}
+
+/// Payload for the quickstart-complete event.
+#[derive(Clone, serde::Serialize)]
+pub struct QuickstartCompletePayload {
+    /// File name of the model now active
+    pub model: String,
+    /// Sentence spoken for the test, if speech could be synthesized
+    pub sample_text: Option<String>,
+    /// What the model heard, if the test ran
+    pub transcript: Option<String>,
+}
+
+/// Payload for the quickstart-failed event.
+#[derive(Clone, serde::Serialize)]
+pub struct QuickstartFailedPayload {
+    /// Error that stopped the quickstart
+    pub error: CyranoError,
+    /// Stable error code of `error` (e.g. `CYR-019`)
+    pub code: String,
+}
+
+fn emit_progress(app: &AppHandle, stage: QuickstartStage, downloaded: u64, total: Option<u64>) {
+    let payload = QuickstartProgressPayload {
+        stage,
+        downloaded_bytes: downloaded,
+        total_bytes: total,
+    };
+    if let Err(e) = notifier_service::emit(app, "quickstart-progress", payload) {
+        log::warn!("Failed to emit quickstart-progress event: {e}");
+    }
+}
+
+/// Start the quickstart in the background.
+///
+/// # Returns
+/// * `Ok(())` once the quickstart is started
+/// * `Err(CyranoError::ModelDownloadFailed)` if it is already running
+pub fn start_quickstart(app: &AppHandle) -> Result<(), CyranoError> {
+    if RUNNING
+        .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+        .is_err()
+    {
+        return Err(CyranoError::ModelDownloadFailed {
+            reason: "The quickstart is already running".to_string(),
+        });
+    }
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let result = run_quickstart(&app);
+        RUNNING.store(false, Ordering::SeqCst);
+        match result {
+            Ok(payload) => {
+                log::info!("Quickstart complete with {}", payload.model);
+                if let Err(e) = notifier_service::emit(&app, "quickstart-complete", payload) {
+                    log::error!("Failed to emit quickstart-complete event: {e}");
+                }
+            }
+            Err(e) => {
+                log::error!("[{}] Quickstart failed: {e}", e.code());
+                let payload = QuickstartFailedPayload {
+                    code: e.code().to_string(),
+                    error: e,
+                };
+                if let Err(e) = notifier_service::emit(&app, "quickstart-failed", payload) {
+                    log::error!("Failed to emit quickstart-failed event: {e}");
+                }
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Install, verify, select and test the quickstart model. MUST run off the
+/// main thread.
+fn run_quickstart(app: &AppHandle) -> Result<QuickstartCompletePayload, CyranoError> {
+    let path = transcription_service::get_models_directory()?.join(QUICKSTART_MODEL);
+    if path.is_file() {
+        log::info!("{QUICKSTART_MODEL} is already installed");
+    } else {
+        emit_progress(app, QuickstartStage::Downloading, 0, None);
+        let mut last_progress = Instant::now();
+        model_download_service::download_model(QUICKSTART_MODEL, |downloaded, total| {
+            if last_progress.elapsed() >= PROGRESS_INTERVAL || Some(downloaded) == total {
+                last_progress = Instant::now();
+                emit_progress(app, QuickstartStage::Downloading, downloaded, total);
+            }
+        })?;
+    }
+
+    emit_progress(app, QuickstartStage::Verifying, 0, None);
+    model_integrity_service::verify_model(&path)?;
+    transcription_service::set_active_model(QUICKSTART_MODEL)?;
+
+    emit_progress(app, QuickstartStage::Testing, 0, None);
+    let samples = match test_signal::render(&TestSignal::Speech {
+        text: SAMPLE_TEXT.to_string(),
+    }) {
+        Ok(samples) => samples,
+        Err(e) => {
+            log::warn!("Skipping the quickstart test transcription: {e}");
+            return Ok(QuickstartCompletePayload {
+                model: QUICKSTART_MODEL.to_string(),
+                sample_text: None,
+                transcript: None,
+            });
+        }
+    };
+    transcription_service::ensure_model_loaded()?;
+    let prepared = transcription_service::prepare_samples(&samples);
+    let transcription = transcription_service::transcribe_with_language(&prepared)?;
+
+    Ok(QuickstartCompletePayload {
+        model: QUICKSTART_MODEL.to_string(),
+        sample_text: Some(SAMPLE_TEXT.to_string()),
+        transcript: Some(text_sanitize::sanitize_output(&transcription.text)),
+    })
+}
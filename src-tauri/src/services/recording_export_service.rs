@@ -0,0 +1,255 @@
+//! WAV copies of dictation recordings.
+//!
+//! With `save_recordings`, the 16kHz mono audio of every dictation is written
+//! to `~/.cyrano/recordings` before it is transcribed. The last recording is
+//! also kept in memory so it can be saved on demand, e.g. to investigate a
+//! bad transcription.
+//!
+//! Files are named after the time the recording was saved, in UTC:
+//! `recording-20261016-134553.wav`. Once the dictation is transcribed, a
+//! session trace with the same name (`recording-20261016-134553.json`)
+//! records how it was transcribed and how long each stage took, so the
+//! `replay` example can run the pipeline on it again and compare timings.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::CyranoError;
+use crate::infrastructure::audio::cpal_adapter::TARGET_SAMPLE_RATE;
+use crate::infrastructure::audio::wav;
+use crate::infrastructure::whisper::DecodeOptions;
+use crate::services::settings_service::{DictationOverrides, TranscriptionEngine};
+use crate::services::{data_directory_service, settings_service};
+
+/// Samples of the last dictation since the app started.
+static LAST_RECORDING: Mutex<Option<Vec<f32>>> = Mutex::new(None);
+
+/// How a saved dictation was transcribed, and how long each stage took.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionTrace {
+    /// Model that transcribed the dictation, e.g. `ggml-base.bin`
+    pub model: Option<String>,
+    pub engine: TranscriptionEngine,
+    pub decode_options: DecodeOptions,
+    pub overrides: DictationOverrides,
+    /// Time spent normalizing the recording, in milliseconds
+    pub prepare_ms: u64,
+    /// Time spent transcribing, in milliseconds
+    pub transcribe_ms: u64,
+    /// Time spent sanitizing and post-processing the text, in milliseconds
+    pub post_process_ms: u64,
+    /// Text as transcribed
+    pub raw_text: String,
+    /// Text after sanitization and post-processing
+    pub text: String,
+}
+
+/// Keep a dictation's audio for export, and save it right away if
+/// `save_recordings` is enabled.
+///
+/// Saving is best effort: a failure is logged and never fails the dictation.
+///
+/// # Returns
+/// The path of the saved WAV file, if it was saved
+pub fn keep_recording(samples: &[f32]) -> Option<PathBuf> {
+    if samples.is_empty() {
+        return None;
+    }
+
+    match LAST_RECORDING.lock() {
+        Ok(mut guard) => *guard = Some(samples.to_vec()),
+        Err(e) => log::error!("Failed to lock last recording: {e}"),
+    }
+
+    if !settings_service::get_settings().save_recordings {
+        return None;
+    }
+    save_to_recordings_dir(samples)
+        .inspect_err(|e| log::warn!("[{}] Failed to save recording: {e}", e.code()))
+        .ok()
+}
+
+/// Save the session trace of the dictation saved at `recording`, next to it.
+///
+/// Best effort, like saving the recording.
+pub fn save_trace(recording: &Path, trace: &SessionTrace) {
+    let result = serde_json::to_vec_pretty(trace)
+        .map_err(|e| CyranoError::StorageFailed {
+            reason: format!("Failed to serialize session trace: {e}"),
+        })
+        .and_then(|json| {
+            data_directory_service::write_file_atomically(&recording.with_extension("json"), &json)
+        });
+    if let Err(e) = result {
+        log::warn!("[{}] Failed to save session trace: {e}", e.code());
+    }
+}
+
+/// Load a session trace and the recording saved next to it.
+///
+/// # Arguments
+/// * `path` - The trace file, or the recording it belongs to
+///
+/// # Returns
+/// * `Ok((SessionTrace, Vec<f32>))` with the trace and the 16kHz samples
+/// * `Err(CyranoError::StorageFailed)` if either file cannot be read
+pub fn load_trace(path: &Path) -> Result<(SessionTrace, Vec<f32>), CyranoError> {
+    let read = |path: &Path| {
+        std::fs::read(path).map_err(|e| CyranoError::StorageFailed {
+            reason: format!("Failed to read {}: {e}", path.display()),
+        })
+    };
+    let trace: SessionTrace = serde_json::from_slice(&read(&path.with_extension("json"))?)
+        .map_err(|e| CyranoError::StorageFailed {
+            reason: format!("Invalid session trace: {e}"),
+        })?;
+    let (samples, rate) = wav::decode_wav(&read(&path.with_extension("wav"))?)
+        .map_err(|reason| CyranoError::StorageFailed { reason })?;
+    if rate != TARGET_SAMPLE_RATE {
+        return Err(CyranoError::StorageFailed {
+            reason: format!("Recording at {rate}Hz instead of 16kHz"),
+        });
+    }
+    Ok((trace, samples))
+}
+
+/// Save the last dictation's audio as a WAV file in `~/.cyrano/recordings`.
+///
+/// # Returns
+/// * `Ok(String)` with the path of the new file
+/// * `Err(CyranoError::RecordingFailed)` if nothing was recorded yet
+/// * `Err(CyranoError::StorageFailed)` if the file could not be written
+pub fn export_last_recording() -> Result<String, CyranoError> {
+    let samples = LAST_RECORDING
+        .lock()
+        .map_err(|e| CyranoError::RecordingFailed {
+            reason: format!("Failed to lock last recording: {e}"),
+        })?
+        .clone()
+        .ok_or_else(|| CyranoError::RecordingFailed {
+            reason: "No recording to export yet".to_string(),
+        })?;
+
+    save_to_recordings_dir(&samples).map(|path| path.to_string_lossy().into_owned())
+}
+
+fn save_to_recordings_dir(samples: &[f32]) -> Result<PathBuf, CyranoError> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let path = save_wav(samples, &data_directory_service::recordings_dir()?, now)?;
+    log::info!("Saved recording to {}", path.display());
+    Ok(path)
+}
+
+/// Write `samples` to a new timestamped WAV file in `dir`.
+fn save_wav(samples: &[f32], dir: &Path, unix_secs: u64) -> Result<PathBuf, CyranoError> {
+    let stem = file_stem(unix_secs);
+    let mut path = dir.join(format!("{stem}.wav"));
+    // Two recordings saved within the same second
+    let mut suffix = 2;
+    while path.exists() {
+        path = dir.join(format!("{stem}-{suffix}.wav"));
+        suffix += 1;
+    }
+
+    data_directory_service::write_file_atomically(
+        &path,
+        &wav::encode_wav(samples, TARGET_SAMPLE_RATE),
+    )?;
+    Ok(path)
+}
+
+/// File name without extension for a recording saved at `unix_secs`.
+fn file_stem(unix_secs: u64) -> String {
+    let days = (unix_secs / 86_400) as i64;
+    let seconds = unix_secs % 86_400;
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "recording-{year:04}{month:02}{day:02}-{:02}{:02}{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Gregorian date of a number of days since 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    // Howard Hinnant's algorithm, with eras of 400 years starting in March
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::temp_dir;
+
+    #[test]
+    fn test_file_stem_uses_utc_date_and_time() {
+        assert_eq!(file_stem(0), "recording-19700101-000000");
+        // 2024-02-29 23:59:59 UTC
+        assert_eq!(file_stem(1_709_251_199), "recording-20240229-235959");
+        // 2026-10-16 13:45:53 UTC
+        assert_eq!(file_stem(1_792_158_353), "recording-20261016-134553");
+    }
+
+    #[test]
+    fn test_saved_wav_does_not_overwrite_earlier_recordings() {
+        let dir = temp_dir("recording-export");
+
+        let first = save_wav(&[0.5, -0.5], &dir, 0).unwrap();
+        let second = save_wav(&[0.25], &dir, 0).unwrap();
+
+        assert_eq!(first.file_name().unwrap(), "recording-19700101-000000.wav");
+        assert_eq!(
+            second.file_name().unwrap(),
+            "recording-19700101-000000-2.wav"
+        );
+        let (samples, rate) = wav::decode_wav(&std::fs::read(&first).unwrap()).unwrap();
+        assert_eq!((samples, rate), (vec![0.5, -0.5], TARGET_SAMPLE_RATE));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_trace_loads_with_its_recording() {
+        let dir = temp_dir("recording-trace");
+        let recording = save_wav(&[0.5, -0.5], &dir, 0).unwrap();
+        let trace = SessionTrace {
+            model: Some("ggml-base.bin".to_string()),
+            engine: TranscriptionEngine::Whisper,
+            decode_options: DecodeOptions::default(),
+            overrides: DictationOverrides::default(),
+            prepare_ms: 1,
+            transcribe_ms: 250,
+            post_process_ms: 2,
+            raw_text: " hello".to_string(),
+            text: "Hello".to_string(),
+        };
+        save_trace(&recording, &trace);
+
+        let (loaded, samples) = load_trace(&recording.with_extension("json")).unwrap();
+        assert_eq!(loaded, trace);
+        assert_eq!(samples, [0.5, -0.5]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
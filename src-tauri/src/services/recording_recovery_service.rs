@@ -0,0 +1,448 @@
+//! Crash recovery for recordings in progress.
+//!
+//! While recording, captured 16kHz samples are appended to
+//! `~/.cyrano/recordings/in-progress.f32` (raw little-endian f32), flushed to
+//! disk every few seconds. The file is deleted once the dictation has been
+//! transcribed or cancelled, so it only survives a crash of the app (or of
+//! the transcription).
+//!
+//! At the next launch the file is renamed to `orphaned.f32` so a new
+//! recording cannot overwrite it, and `recover_last_recording` queues it for
+//! transcription like an imported audio file, then deletes it.
+//!
+//! The pipeline stage (`Recording` or `Transcribing`) is persisted next to it
+//! in `pipeline-state.json` for the same lifetime. Finding it at launch means
+//! the app quit mid-dictation: the state machine is reset to `Idle` and the
+//! interrupted session is announced to the main window with a
+//! recording-interrupted event, along with the audio that can be recovered.
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::AppHandle;
+
+use crate::domain::{CyranoError, RecordingState};
+use crate::infrastructure::audio::cpal_adapter::TARGET_SAMPLE_RATE;
+use crate::infrastructure::audio::wav;
+use crate::services::{
+    data_directory_service, file_transcription_service, notifier_service, recording_state,
+};
+
+/// File receiving the samples of the recording in progress.
+const IN_PROGRESS_FILE_NAME: &str = "in-progress.f32";
+
+/// Recording left behind by a crash, waiting to be recovered.
+const ORPHANED_FILE_NAME: &str = "orphaned.f32";
+
+/// Pipeline stage of the dictation in progress.
+const PIPELINE_STATE_FILE_NAME: &str = "pipeline-state.json";
+
+/// Longest stretch of audio that can be lost in a crash.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Dictation interrupted by the previous quit, until it is recovered or discarded.
+static INTERRUPTED_SESSION: Mutex<Option<InterruptedSession>> = Mutex::new(None);
+
+/// A recording interrupted by a crash.
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct RecoverableRecording {
+    /// Duration of the audio saved before the crash, in milliseconds
+    pub duration_ms: u32,
+    /// Unix timestamp in seconds of the last flush before the crash
+    pub recorded_at: u32,
+}
+
+/// A dictation the app quit in the middle of.
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct InterruptedSession {
+    /// Pipeline stage when the app quit (`Recording` or `Transcribing`)
+    pub state: RecordingState,
+    /// Unix timestamp in seconds when the dictation entered that stage
+    pub interrupted_at: u32,
+    /// Audio saved before the quit, if any
+    pub recording: Option<RecoverableRecording>,
+}
+
+/// Pipeline stage as written to disk.
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedState {
+    state: RecordingState,
+    updated_at: u32,
+}
+
+/// Appends the samples of the recording in progress to the recovery file.
+pub struct RecoveryWriter {
+    writer: BufWriter<File>,
+    last_flush: Instant,
+}
+
+impl RecoveryWriter {
+    /// Start a new recovery file, replacing the one of the previous recording.
+    pub fn create() -> Result<Self, CyranoError> {
+        Self::create_in(&data_directory_service::recordings_dir()?)
+    }
+
+    fn create_in(dir: &Path) -> Result<Self, CyranoError> {
+        let path = dir.join(IN_PROGRESS_FILE_NAME);
+        fs::create_dir_all(dir)
+            .and_then(|()| File::create(&path))
+            .map(|file| Self {
+                writer: BufWriter::new(file),
+                last_flush: Instant::now(),
+            })
+            .map_err(|e| CyranoError::StorageFailed {
+                reason: format!("Failed to create {}: {e}", path.display()),
+            })
+    }
+
+    /// Append samples, flushing them to disk when the flush interval elapsed.
+    pub fn append(&mut self, samples: &[f32]) -> std::io::Result<()> {
+        let bytes: Vec<u8> = samples
+            .iter()
+            .flat_map(|sample| sample.to_le_bytes())
+            .collect();
+        self.writer.write_all(&bytes)?;
+        if self.last_flush.elapsed() >= FLUSH_INTERVAL {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Write everything appended so far to disk.
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        self.last_flush = Instant::now();
+        self.writer.flush()?;
+        self.writer.get_ref().sync_data()
+    }
+}
+
+/// Persist the pipeline stage of the dictation in progress.
+///
+/// Best effort: a failure is logged and never fails the dictation.
+pub fn persist_pipeline_state(state: RecordingState) {
+    let result = data_directory_service::recordings_dir()
+        .and_then(|dir| write_pipeline_state(&dir, state, unix_secs()));
+    if let Err(e) = result {
+        log::warn!("[{}] Failed to persist pipeline state: {e}", e.code());
+    }
+}
+
+fn write_pipeline_state(
+    dir: &Path,
+    state: RecordingState,
+    updated_at: u32,
+) -> Result<(), CyranoError> {
+    let json = serde_json::to_vec(&PersistedState { state, updated_at }).map_err(|e| {
+        CyranoError::StorageFailed {
+            reason: format!("Failed to serialize pipeline state: {e}"),
+        }
+    })?;
+    data_directory_service::write_file_atomically(&dir.join(PIPELINE_STATE_FILE_NAME), &json)
+}
+
+/// Delete the recovery file and pipeline state once the dictation went
+/// through the pipeline or was cancelled.
+pub fn end_session() {
+    if let Ok(dir) = data_directory_service::recordings_dir() {
+        end_session_in(&dir);
+    }
+}
+
+fn end_session_in(dir: &Path) {
+    remove_if_exists(&dir.join(IN_PROGRESS_FILE_NAME));
+    remove_if_exists(&dir.join(PIPELINE_STATE_FILE_NAME));
+}
+
+/// Detect a dictation interrupted by the previous quit, at startup.
+///
+/// Keeps its audio for recovery and resets the state machine, since nothing
+/// is recording or transcribing anymore. Must run before any recording
+/// starts, since starting one replaces the in-progress file.
+pub fn restore_after_restart() -> Option<InterruptedSession> {
+    let dir = data_directory_service::recordings_dir().ok()?;
+    let session = restore_in(&dir);
+
+    if let Some(session) = &session {
+        log::warn!(
+            "Previous session quit while {:?}, resetting to Idle ({})",
+            session.state,
+            match &session.recording {
+                Some(recording) => format!("{}ms of audio can be recovered", recording.duration_ms),
+                None => "no audio to recover".to_string(),
+            }
+        );
+        recording_state::set_recording_state(RecordingState::Idle);
+        if let Err(e) = recording_state::clear_audio_buffer() {
+            log::warn!("Failed to clear audio buffer: {e}");
+        }
+    }
+
+    set_interrupted_session(session.clone());
+    session
+}
+
+fn restore_in(dir: &Path) -> Option<InterruptedSession> {
+    let state_path = dir.join(PIPELINE_STATE_FILE_NAME);
+    let persisted = fs::read(&state_path).ok().and_then(|bytes| {
+        match serde_json::from_slice::<PersistedState>(&bytes) {
+            Ok(persisted) => Some(persisted),
+            Err(e) => {
+                log::warn!("Ignoring unreadable pipeline state: {e}");
+                None
+            }
+        }
+    });
+    remove_if_exists(&state_path);
+
+    let recording = detect_in(dir);
+    persisted
+        .filter(|persisted| {
+            matches!(
+                persisted.state,
+                RecordingState::Recording | RecordingState::Transcribing
+            )
+        })
+        .map(|persisted| InterruptedSession {
+            state: persisted.state,
+            interrupted_at: persisted.updated_at,
+            recording,
+        })
+}
+
+/// The dictation interrupted by the previous quit, while it has not been
+/// recovered or discarded.
+pub fn get_interrupted_session() -> Option<InterruptedSession> {
+    INTERRUPTED_SESSION
+        .lock()
+        .ok()
+        .and_then(|session| session.clone())
+}
+
+fn set_interrupted_session(session: Option<InterruptedSession>) {
+    match INTERRUPTED_SESSION.lock() {
+        Ok(mut guard) => *guard = session,
+        Err(e) => log::error!("Failed to lock interrupted session: {e}"),
+    }
+}
+
+/// Emit recording-interrupted while an interrupted dictation is pending.
+///
+/// Called each time the main window finishes loading, so a reload still
+/// hears about it.
+pub fn announce_interrupted_session(app: &AppHandle) {
+    if let Some(session) = get_interrupted_session() {
+        if let Err(e) = notifier_service::emit(app, "recording-interrupted", session) {
+            log::error!("Failed to emit recording-interrupted event: {e}");
+        }
+    }
+}
+
+/// Keep the recording left in progress by the previous quit, if any, and
+/// describe the recording waiting to be recovered.
+fn detect_in(dir: &Path) -> Option<RecoverableRecording> {
+    let in_progress = dir.join(IN_PROGRESS_FILE_NAME);
+    let orphaned = dir.join(ORPHANED_FILE_NAME);
+    if in_progress.exists() {
+        if let Err(e) = fs::rename(&in_progress, &orphaned) {
+            log::error!("Failed to keep interrupted recording: {e}");
+        }
+    }
+    describe(&orphaned)
+}
+
+/// The recording interrupted by a crash, if one is waiting to be recovered.
+pub fn get_recoverable_recording() -> Option<RecoverableRecording> {
+    describe(
+        &data_directory_service::recordings_dir()
+            .ok()?
+            .join(ORPHANED_FILE_NAME),
+    )
+}
+
+fn describe(path: &Path) -> Option<RecoverableRecording> {
+    let metadata = fs::metadata(path).ok()?;
+    let samples = metadata.len() / 4;
+    if samples == 0 {
+        remove_if_exists(path);
+        return None;
+    }
+
+    let recorded_at = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as u32)
+        .unwrap_or(0);
+    Some(RecoverableRecording {
+        duration_ms: (samples * 1000 / u64::from(TARGET_SAMPLE_RATE)) as u32,
+        recorded_at,
+    })
+}
+
+/// Queue the recording interrupted by a crash for transcription, then
+/// delete it. The result is reported like an imported audio file.
+///
+/// # Returns
+/// * `Ok(())` once the recording is queued
+/// * `Err(CyranoError::RecordingFailed)` if there is nothing to recover
+/// * `Err(CyranoError::StorageFailed)` if the recording could not be read or converted
+pub fn recover_last_recording(app: &AppHandle) -> Result<(), CyranoError> {
+    let orphaned = data_directory_service::recordings_dir()?.join(ORPHANED_FILE_NAME);
+    let wav_path = std::env::temp_dir().join(format!(
+        "cyrano-recovered-{}-{}.wav",
+        std::process::id(),
+        unix_secs()
+    ));
+    convert_to_wav(&orphaned, &wav_path)?;
+
+    file_transcription_service::enqueue_temporary_file(app, wav_path);
+    remove_if_exists(&orphaned);
+    set_interrupted_session(None);
+    log::info!("Interrupted recording queued for transcription");
+    Ok(())
+}
+
+/// Delete the recording interrupted by a crash without transcribing it.
+pub fn discard_recoverable_recording() -> Result<(), CyranoError> {
+    remove_if_exists(&data_directory_service::recordings_dir()?.join(ORPHANED_FILE_NAME));
+    set_interrupted_session(None);
+    Ok(())
+}
+
+/// Convert a raw recovery file to a 16kHz WAV file.
+fn convert_to_wav(raw: &Path, wav_path: &Path) -> Result<(), CyranoError> {
+    let bytes = match fs::read(raw) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(CyranoError::RecordingFailed {
+                reason: "No interrupted recording to recover".to_string(),
+            });
+        }
+        Err(e) => {
+            return Err(CyranoError::StorageFailed {
+                reason: format!("Failed to read {}: {e}", raw.display()),
+            });
+        }
+    };
+
+    // A crash mid-write can leave a partial sample at the end
+    let samples: Vec<f32> = bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect();
+    fs::write(wav_path, wav::encode_wav(&samples, TARGET_SAMPLE_RATE)).map_err(|e| {
+        CyranoError::StorageFailed {
+            reason: format!("Failed to write {}: {e}", wav_path.display()),
+        }
+    })
+}
+
+fn unix_secs() -> u32 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as u32)
+        .unwrap_or(0)
+}
+
+fn remove_if_exists(path: &Path) {
+    match fs::remove_file(path) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => log::warn!("Failed to delete {}: {e}", path.display()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::temp_dir;
+
+    #[test]
+    fn test_interrupted_recording_is_detected_and_converted() {
+        let dir = temp_dir("detect");
+        let mut writer = RecoveryWriter::create_in(&dir).unwrap();
+        writer.append(&vec![0.25; 16_000]).unwrap();
+        writer.append(&[0.5; 8_000]).unwrap();
+        writer.flush().unwrap();
+        // The app crashed here: the writer is never told the recording ended
+        drop(writer);
+
+        let recording = detect_in(&dir).expect("recording should be recoverable");
+        assert_eq!(recording.duration_ms, 1_500);
+        assert!(!dir.join(IN_PROGRESS_FILE_NAME).exists());
+
+        let wav_path = dir.join("recovered.wav");
+        convert_to_wav(&dir.join(ORPHANED_FILE_NAME), &wav_path).unwrap();
+        let (samples, rate) = wav::decode_wav(&fs::read(&wav_path).unwrap()).unwrap();
+        assert_eq!(rate, TARGET_SAMPLE_RATE);
+        assert_eq!(samples.len(), 24_000);
+        assert_eq!(samples[23_999], 0.5);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_empty_or_missing_recordings_are_not_offered() {
+        let dir = temp_dir("empty");
+        assert_eq!(detect_in(&dir), None);
+
+        drop(RecoveryWriter::create_in(&dir).unwrap());
+        assert_eq!(detect_in(&dir), None);
+        assert!(!dir.join(ORPHANED_FILE_NAME).exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_quit_while_transcribing_is_restored_with_its_audio() {
+        let dir = temp_dir("restore");
+        write_pipeline_state(&dir, RecordingState::Recording, 10).unwrap();
+        let mut writer = RecoveryWriter::create_in(&dir).unwrap();
+        writer.append(&[0.5; 16_000]).unwrap();
+        writer.flush().unwrap();
+        drop(writer);
+        write_pipeline_state(&dir, RecordingState::Transcribing, 20).unwrap();
+
+        let session = restore_in(&dir).expect("session should be interrupted");
+        assert_eq!(session.state, RecordingState::Transcribing);
+        assert_eq!(session.interrupted_at, 20);
+        assert_eq!(session.recording.map(|r| r.duration_ms), Some(1_000));
+        assert!(!dir.join(PIPELINE_STATE_FILE_NAME).exists());
+
+        // Restoring again finds nothing new to announce
+        assert_eq!(restore_in(&dir), None);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_finished_session_leaves_nothing_to_restore() {
+        let dir = temp_dir("finished");
+        write_pipeline_state(&dir, RecordingState::Recording, 10).unwrap();
+        drop(RecoveryWriter::create_in(&dir).unwrap());
+        end_session_in(&dir);
+        assert_eq!(restore_in(&dir), None);
+
+        fs::write(dir.join(PIPELINE_STATE_FILE_NAME), b"not json").unwrap();
+        assert_eq!(restore_in(&dir), None);
+        assert!(!dir.join(PIPELINE_STATE_FILE_NAME).exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_converting_a_missing_recording_fails() {
+        let dir = temp_dir("missing");
+        assert!(matches!(
+            convert_to_wav(&dir.join(ORPHANED_FILE_NAME), &dir.join("out.wav")),
+            Err(CyranoError::RecordingFailed { .. })
+        ));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -4,17 +4,22 @@
 //! and state transitions. Uses a dedicated thread for audio capture to handle
 //! cpal's Stream type not being Send-safe.
 
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread::{self, JoinHandle};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use tauri::{AppHandle, Emitter};
+use tauri::AppHandle;
 
 use crate::domain::{CyranoError, PermissionStatus, RecordingState};
 use crate::infrastructure::audio::cpal_adapter::CpalAdapter;
+use crate::infrastructure::audio::quality::RecordingQuality;
+use crate::infrastructure::audio::vad::VoiceActivityDetector;
 use crate::services::permission_service;
-use crate::services::recording_state;
+use crate::services::recording_recovery_service::{self, RecoveryWriter};
+use crate::services::recording_state::{self, SpillBuffer};
+use crate::services::settings_service::{BluetoothInputPolicy, Settings};
+use crate::services::{dictation_service, notifier_service, settings_service};
 use crate::traits::audio_capture::AudioCapture;
 
 /// Payload for the recording-started event.
@@ -24,6 +29,51 @@ pub struct RecordingStartedPayload {
     pub timestamp: u64,
 }
 
+/// Delay between attempts to resume capture after the input device is lost.
+const DEVICE_RETRY_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How long to wait for a fallback device before reporting the recording as failed.
+const DEVICE_FALLBACK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// How long a recording may go without audio before the microphone is
+/// considered held by another app.
+const MIC_IN_USE_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Shortest pause accepted for auto-stop, so a breath never ends a dictation.
+const MIN_AUTO_STOP_SILENCE_MS: u32 = 500;
+
+/// Smallest accepted maximum recording duration, in minutes.
+const MIN_MAX_RECORDING_MINUTES: u32 = 1;
+
+/// Payload for the recording-max-duration-reached event.
+#[derive(Clone, serde::Serialize)]
+pub struct RecordingMaxDurationReachedPayload {
+    /// Maximum recording duration that was reached, in milliseconds
+    pub max_duration_ms: u32,
+    /// Unix timestamp in milliseconds when the limit was reached
+    pub timestamp: u64,
+}
+
+/// Payload for the recording-device-changed event.
+#[derive(Clone, serde::Serialize)]
+pub struct RecordingDeviceChangedPayload {
+    /// Name of the device that disappeared, if known
+    pub previous_device: Option<String>,
+    /// Name of the device now being recorded
+    pub device: String,
+    /// Unix timestamp in milliseconds when capture resumed
+    pub timestamp: u64,
+}
+
+/// Payload for the recording-bluetooth-input event.
+#[derive(Clone, serde::Serialize)]
+pub struct RecordingBluetoothInputPayload {
+    /// Bluetooth headset set as the default input
+    pub bluetooth_device: String,
+    /// Device recorded from instead, if the policy fell back to the built-in microphone
+    pub fallback_device: Option<String>,
+}
+
 /// Payload for the recording-stopped event.
 #[derive(Clone, serde::Serialize, specta::Type)]
 pub struct RecordingStoppedPayload {
@@ -38,6 +88,18 @@ pub struct RecordingStoppedPayload {
 pub struct RecordingFailedPayload {
     /// Error that caused the recording to fail
     pub error: CyranoError,
+    /// Stable error code of `error` (e.g. `CYR-005`)
+    pub code: String,
+}
+
+impl RecordingFailedPayload {
+    /// Create the payload, deriving `code` from the error.
+    pub fn new(error: CyranoError) -> Self {
+        Self {
+            code: error.code().to_string(),
+            error,
+        }
+    }
 }
 
 /// Payload for the transcription-started event.
@@ -54,6 +116,36 @@ pub struct TranscriptionCompletePayload {
     pub text: String,
     /// Duration of transcription in milliseconds
     pub duration_ms: u32,
+    /// Language of the dictation, detected from the audio unless set in
+    /// settings, e.g. `fr`
+    pub language: Option<String>,
+    /// Quality measures of the recording, to explain a bad transcription
+    pub quality: Option<RecordingQuality>,
+    /// Whether Whisper decoded the dictation with too little confidence, in
+    /// which case it was dropped rather than pasted
+    pub low_confidence: bool,
+}
+
+/// Payload for the transcription-segment event, emitted as each segment of
+/// a dictation is decoded.
+#[derive(Clone, serde::Serialize, specta::Type)]
+pub struct TranscriptionSegmentPayload {
+    /// Position of the segment in the transcription, from 0
+    pub index: u32,
+    /// Text of the segment, before sanitization and replacements
+    pub text: String,
+    /// Start of the segment in the audio, in milliseconds
+    pub start_ms: u64,
+    /// End of the segment in the audio, in milliseconds
+    pub end_ms: u64,
+}
+
+/// Payload for the transcription-sentence event, emitted as each sentence of
+/// a dictation is complete.
+#[derive(Clone, serde::Serialize, specta::Type)]
+pub struct TranscriptionSentencePayload {
+    /// Completed sentences, before sanitization and replacements
+    pub text: String,
 }
 
 /// Payload for the transcription-failed event.
@@ -61,6 +153,18 @@ pub struct TranscriptionCompletePayload {
 pub struct TranscriptionFailedPayload {
     /// Error that caused transcription to fail
     pub error: CyranoError,
+    /// Stable error code of `error` (e.g. `CYR-005`)
+    pub code: String,
+}
+
+impl TranscriptionFailedPayload {
+    /// Create the payload, deriving `code` from the error.
+    pub fn new(error: CyranoError) -> Self {
+        Self {
+            code: error.code().to_string(),
+            error,
+        }
+    }
 }
 
 /// Payload for the transcription-cancelled event.
@@ -82,6 +186,27 @@ pub struct ClipboardCopiedPayload {
 pub struct ClipboardFailedPayload {
     /// Error that caused clipboard operation to fail
     pub error: CyranoError,
+    /// Stable error code of `error` (e.g. `CYR-005`)
+    pub code: String,
+}
+
+impl ClipboardFailedPayload {
+    /// Create the payload, deriving `code` from the error.
+    pub fn new(error: CyranoError) -> Self {
+        Self {
+            code: error.code().to_string(),
+            error,
+        }
+    }
+}
+
+/// The dictation in progress, for windows created or reloaded mid-session.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, specta::Type)]
+pub struct ActiveSession {
+    /// Current step of the dictation (Recording or Transcribing)
+    pub state: RecordingState,
+    /// Milliseconds since recording started, while audio is being captured
+    pub recording_elapsed_ms: Option<u32>,
 }
 
 /// Global recording state - holds the audio capture thread and buffer
@@ -89,7 +214,7 @@ struct RecordingContext {
     /// Flag to signal recording should stop
     stop_flag: Arc<AtomicBool>,
     /// Handle to the capture thread
-    capture_thread: Option<JoinHandle<Result<Vec<f32>, CyranoError>>>,
+    capture_thread: Option<JoinHandle<Result<SpillBuffer, CyranoError>>>,
     /// Timestamp when recording started
     start_timestamp: u64,
 }
@@ -101,6 +226,29 @@ fn recording_context() -> &'static Mutex<Option<RecordingContext>> {
     RECORDING_CONTEXT.get_or_init(|| Mutex::new(None))
 }
 
+/// Dictation capture threads currently running.
+static RUNNING_CAPTURES: AtomicUsize = AtomicUsize::new(0);
+
+/// Bumped to stop every capture thread started before, whatever its stop flag.
+static CAPTURE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Counts a capture thread as running for as long as it is alive, including
+/// when it panics.
+struct RunningCapture;
+
+impl RunningCapture {
+    fn enter() -> Self {
+        RUNNING_CAPTURES.fetch_add(1, Ordering::SeqCst);
+        Self
+    }
+}
+
+impl Drop for RunningCapture {
+    fn drop(&mut self) {
+        RUNNING_CAPTURES.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
 /// Check if a recording is currently in progress.
 ///
 /// # Returns
@@ -113,6 +261,65 @@ pub fn is_recording() -> bool {
         .unwrap_or(false)
 }
 
+/// Whether a capture thread is running or a recording context is held.
+pub fn is_capture_running() -> bool {
+    RUNNING_CAPTURES.load(Ordering::SeqCst) > 0 || is_recording()
+}
+
+/// Stop capture threads left running outside a recording, and discard
+/// their audio.
+///
+/// Threads are signaled, not joined, so a thread stuck in the audio driver
+/// cannot block the caller.
+pub fn stop_leaked_capture() {
+    let ctx = recording_context()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take();
+    if let Some(ctx) = ctx {
+        ctx.stop_flag.store(true, Ordering::SeqCst);
+    }
+    CAPTURE_GENERATION.fetch_add(1, Ordering::SeqCst);
+
+    if let Err(e) = recording_state::clear_audio_buffer() {
+        log::warn!("Failed to clear audio buffer: {e}");
+    }
+    recording_recovery_service::end_session();
+}
+
+/// Get the dictation in progress, if any.
+///
+/// # Returns
+/// * `Some(ActiveSession)` while recording or transcribing
+/// * `None` when idle, or once the last dictation completed or failed
+pub fn active_session() -> Option<ActiveSession> {
+    let start_timestamp = recording_context()
+        .lock()
+        .ok()
+        .and_then(|guard| guard.as_ref().map(|ctx| ctx.start_timestamp));
+    session_for(
+        recording_state::get_recording_state(),
+        start_timestamp,
+        get_timestamp_ms(),
+    )
+}
+
+/// Describe the session for a workflow state and recording start time.
+fn session_for(
+    state: RecordingState,
+    start_timestamp: Option<u64>,
+    now_ms: u64,
+) -> Option<ActiveSession> {
+    matches!(
+        state,
+        RecordingState::Recording | RecordingState::Transcribing
+    )
+    .then(|| ActiveSession {
+        state,
+        recording_elapsed_ms: start_timestamp.map(|start| now_ms.saturating_sub(start) as u32),
+    })
+}
+
 /// Get the current Unix timestamp in milliseconds.
 fn get_timestamp_ms() -> u64 {
     SystemTime::now()
@@ -128,6 +335,7 @@ fn get_timestamp_ms() -> u64 {
 /// 2. Spawns a dedicated thread for audio capture
 /// 3. Updates recording state to Recording
 /// 4. Emits recording-started event
+/// 5. Starts loading the model in the background
 ///
 /// # Arguments
 /// * `app` - The Tauri application handle for emitting events
@@ -163,10 +371,11 @@ pub fn start_recording(app: &AppHandle) -> Result<(), CyranoError> {
     let start_timestamp = get_timestamp_ms();
 
     let stop_flag_clone = stop_flag.clone();
+    let app_clone = app.clone();
 
     // Spawn audio capture thread
-    let capture_thread = thread::spawn(move || -> Result<Vec<f32>, CyranoError> {
-        run_audio_capture(stop_flag_clone)
+    let capture_thread = thread::spawn(move || -> Result<SpillBuffer, CyranoError> {
+        run_audio_capture(&app_clone, stop_flag_clone)
     });
 
     *ctx_guard = Some(RecordingContext {
@@ -177,12 +386,13 @@ pub fn start_recording(app: &AppHandle) -> Result<(), CyranoError> {
 
     // Update state
     recording_state::set_recording_state(RecordingState::Recording);
+    recording_recovery_service::persist_pipeline_state(RecordingState::Recording);
 
     // Emit event
     let payload = RecordingStartedPayload {
         timestamp: start_timestamp,
     };
-    if let Err(e) = app.emit("recording-started", payload) {
+    if let Err(e) = notifier_service::emit(app, "recording-started", payload) {
         log::error!("Failed to emit recording-started event: {e}");
     }
 
@@ -228,28 +438,29 @@ pub fn stop_recording(app: &AppHandle) -> Result<RecordingStoppedPayload, Cyrano
             }
             Ok(Err(e)) => {
                 log::warn!("Audio capture thread returned error: {e}");
-                Vec::new()
+                SpillBuffer::new()
             }
             Err(_) => {
                 log::error!("Audio capture thread panicked");
-                Vec::new()
+                SpillBuffer::new()
             }
         }
     } else {
-        Vec::new()
+        SpillBuffer::new()
     };
 
-    // Store samples in the global audio buffer for later use
-    if let Err(e) = store_audio_samples(&samples) {
-        log::error!("Failed to store audio samples: {e}");
-    }
-
     let stop_timestamp = get_timestamp_ms();
     let duration_ms = stop_timestamp.saturating_sub(ctx.start_timestamp) as u32;
     let sample_count = samples.len() as u32;
 
+    // Store samples in the global audio buffer for later use
+    if let Err(e) = store_audio_samples(samples) {
+        log::error!("Failed to store audio samples: {e}");
+    }
+
     // Update state
     recording_state::set_recording_state(RecordingState::Transcribing);
+    recording_recovery_service::persist_pipeline_state(RecordingState::Transcribing);
 
     let payload = RecordingStoppedPayload {
         duration_ms,
@@ -257,7 +468,7 @@ pub fn stop_recording(app: &AppHandle) -> Result<RecordingStoppedPayload, Cyrano
     };
 
     // Emit event
-    if let Err(e) = app.emit("recording-stopped", payload.clone()) {
+    if let Err(e) = notifier_service::emit(app, "recording-stopped", payload.clone()) {
         log::error!("Failed to emit recording-stopped event: {e}");
     }
 
@@ -270,8 +481,8 @@ pub fn stop_recording(app: &AppHandle) -> Result<RecordingStoppedPayload, Cyrano
 }
 
 /// Store audio samples in the global buffer for transcription.
-fn store_audio_samples(samples: &[f32]) -> Result<(), CyranoError> {
-    recording_state::set_audio_samples(samples)
+fn store_audio_samples(samples: SpillBuffer) -> Result<(), CyranoError> {
+    recording_state::set_audio_buffer(samples)
         .map_err(|e| CyranoError::RecordingFailed { reason: e })
 }
 
@@ -327,6 +538,7 @@ pub fn cancel_recording() -> usize {
     if let Err(e) = recording_state::clear_audio_buffer() {
         log::warn!("Failed to clear audio buffer on cancel: {e}");
     }
+    recording_recovery_service::end_session();
 
     log::info!("Recording cancelled, discarded {} samples", sample_count);
     sample_count
@@ -335,20 +547,317 @@ pub fn cancel_recording() -> usize {
 /// Run audio capture in a dedicated thread.
 ///
 /// This function handles the actual cpal audio capture, running until
-/// the stop_flag is set to true.
-fn run_audio_capture(stop_flag: Arc<AtomicBool>) -> Result<Vec<f32>, CyranoError> {
-    let mut capture: Box<dyn AudioCapture> = Box::new(CpalAdapter::new());
-    capture.start_capture()?;
+/// the stop_flag is set to true. If the input device disappears (USB mic
+/// unplugged, AirPods disconnected), capture resumes on the new default
+/// device and a recording-device-changed event is emitted.
+///
+/// With auto-stop enabled, captured audio is also fed to a voice activity
+/// detector, and the dictation is stopped and transcribed once the speaker
+/// has been silent for the configured duration.
+///
+/// Whatever the settings, the dictation is stopped and transcribed once it
+/// reaches the maximum recording duration, and recording-max-duration-reached
+/// is emitted.
+///
+/// Captured audio is moved out of the adapter every iteration into a
+/// [`SpillBuffer`], so long recordings spill to disk instead of growing in memory.
+fn run_audio_capture(
+    app: &AppHandle,
+    stop_flag: Arc<AtomicBool>,
+) -> Result<SpillBuffer, CyranoError> {
+    let _running = RunningCapture::enter();
+    let generation = CAPTURE_GENERATION.load(Ordering::SeqCst);
+    let settings = settings_service::get_settings();
+    let bluetooth_input = bluetooth_default_input();
+    let mut capture = start_input_capture(&settings, bluetooth_input.as_deref())?;
+
+    if let Some(bluetooth_device) = bluetooth_input {
+        if settings.bluetooth_input_policy != BluetoothInputPolicy::Allow {
+            warn_bluetooth_input(app, bluetooth_device, capture.device_name());
+        }
+    }
 
-    log::info!("Audio capture started in dedicated thread");
+    log::info!(
+        "Audio capture started in dedicated thread on {}",
+        capture.device_name().as_deref().unwrap_or("unknown device")
+    );
+
+    let mut lost_device: Option<(Option<String>, Instant)> = None;
+    let mut last_attempt: Option<Instant> = None;
+    let mut failure_reported = false;
+
+    let auto_stop_silence_ms = settings.auto_stop_silence_ms.max(MIN_AUTO_STOP_SILENCE_MS);
+    let mut vad = settings
+        .auto_stop_enabled
+        .then(|| VoiceActivityDetector::new(settings.auto_stop_threshold_db));
+    let mut recorded = SpillBuffer::new();
+    let mut received_audio = false;
+    // Crash recovery is best effort: recording works without it
+    let mut recovery = RecoveryWriter::create()
+        .inspect_err(|e| log::warn!("[{}] Recording will not be recoverable: {e}", e.code()))
+        .ok();
+
+    let max_duration = max_recording_duration(settings.max_recording_minutes);
+    let started_at = Instant::now();
+    let mut max_duration_reached = false;
 
     // Keep the stream alive until stop is signaled
-    while !stop_flag.load(Ordering::SeqCst) {
-        thread::sleep(std::time::Duration::from_millis(10));
+    while !stop_flag.load(Ordering::SeqCst)
+        && CAPTURE_GENERATION.load(Ordering::SeqCst) == generation
+    {
+        if capture.device_lost()
+            && last_attempt.is_none_or(|at| at.elapsed() >= DEVICE_RETRY_INTERVAL)
+        {
+            let (previous_device, lost_at) = lost_device
+                .get_or_insert_with(|| (capture.device_name(), Instant::now()))
+                .clone();
+            last_attempt = Some(Instant::now());
+
+            match capture.switch_to_default_device() {
+                Ok(device) => {
+                    log::warn!(
+                        "Input device {} disconnected, recording continues on {device}",
+                        previous_device.as_deref().unwrap_or("unknown")
+                    );
+                    let payload = RecordingDeviceChangedPayload {
+                        previous_device,
+                        device,
+                        timestamp: get_timestamp_ms(),
+                    };
+                    if let Err(e) = notifier_service::emit(app, "recording-device-changed", payload)
+                    {
+                        log::error!("Failed to emit recording-device-changed event: {e}");
+                    }
+                    lost_device = None;
+                    failure_reported = false;
+                }
+                Err(e) if !failure_reported && lost_at.elapsed() >= DEVICE_FALLBACK_TIMEOUT => {
+                    log::error!(
+                        "[{}] No input device available after disconnect: {e}",
+                        e.code()
+                    );
+                    let error = CyranoError::RecordingFailed {
+                        reason: "Microphone disconnected and no other input device is available"
+                            .to_string(),
+                    };
+                    if let Err(e) = notifier_service::emit(
+                        app,
+                        "recording-failed",
+                        RecordingFailedPayload::new(error),
+                    ) {
+                        log::error!("Failed to emit recording-failed event: {e}");
+                    }
+                    failure_reported = true;
+                }
+                Err(e) => log::debug!("Fallback input device not ready yet: {e}"),
+            }
+        }
+
+        let samples = capture.take_samples();
+        recorded.append(&samples);
+        append_to_recovery_file(&mut recovery, &samples);
+
+        received_audio = received_audio || has_audio(&samples);
+        if !received_audio && lost_device.is_none() && started_at.elapsed() >= MIC_IN_USE_TIMEOUT {
+            log::error!(
+                "No audio from {} after {}ms, the microphone seems in use by another app",
+                capture.device_name().as_deref().unwrap_or("unknown device"),
+                MIC_IN_USE_TIMEOUT.as_millis()
+            );
+            capture.stop_capture()?;
+            if let Err(e) = notifier_service::emit(
+                app,
+                "recording-failed",
+                RecordingFailedPayload::new(CyranoError::MicInUse),
+            ) {
+                log::error!("Failed to emit recording-failed event: {e}");
+            }
+            return Err(CyranoError::MicInUse);
+        }
+
+        if let Some(detector) = vad.as_mut() {
+            detector.push_samples(&samples);
+
+            if detector.trailing_silence_ms() >= auto_stop_silence_ms {
+                log::info!("Silence detected for {auto_stop_silence_ms}ms, stopping recording");
+                request_auto_stop(app);
+                vad = None;
+            }
+        }
+
+        if !max_duration_reached && started_at.elapsed() >= max_duration {
+            log::warn!(
+                "Recording reached the maximum duration of {}s, stopping",
+                max_duration.as_secs()
+            );
+            let payload = RecordingMaxDurationReachedPayload {
+                max_duration_ms: max_duration.as_millis() as u32,
+                timestamp: get_timestamp_ms(),
+            };
+            if let Err(e) = notifier_service::emit(app, "recording-max-duration-reached", payload) {
+                log::error!("Failed to emit recording-max-duration-reached event: {e}");
+            }
+            request_auto_stop(app);
+            max_duration_reached = true;
+        }
+        thread::sleep(Duration::from_millis(10));
     }
 
     log::info!("Audio capture stopping");
-    capture.stop_capture()
+    let samples = capture.stop_capture()?;
+    recorded.append(&samples);
+    append_to_recovery_file(&mut recovery, &samples);
+    if let Some(writer) = recovery.as_mut() {
+        if let Err(e) = writer.flush() {
+            log::warn!("Failed to flush recovery file: {e}");
+        }
+    }
+    Ok(recorded)
+}
+
+/// Whether captured samples carry any signal. When another app holds the
+/// input exclusively or macOS blocks capture, the stream delivers nothing or
+/// only digital silence, while even a quiet room never reads exactly zero.
+fn has_audio(samples: &[f32]) -> bool {
+    samples.iter().any(|sample| *sample != 0.0)
+}
+
+/// Append captured samples to the crash recovery file, giving up on it after
+/// the first write error.
+fn append_to_recovery_file(recovery: &mut Option<RecoveryWriter>, samples: &[f32]) {
+    if let Some(writer) = recovery.as_mut() {
+        if let Err(e) = writer.append(samples) {
+            log::warn!("Failed to write recovery file, recording will not be recoverable: {e}");
+            *recovery = None;
+        }
+    }
+}
+
+/// Capture adapter for the dictation input, applying the Bluetooth input
+/// policy when the default input is `bluetooth_input`.
+pub fn input_adapter(settings: &Settings, bluetooth_input: Option<&str>) -> CpalAdapter {
+    let adapter =
+        CpalAdapter::new(settings.resampler_quality).with_input_channel(settings.input_channel);
+    match bluetooth_fallback(
+        settings.bluetooth_input_policy,
+        bluetooth_input,
+        built_in_input_name,
+    ) {
+        Some(device) => adapter.with_input_device(device),
+        None => adapter,
+    }
+}
+
+/// Start capturing the dictation input, through voice processing when it is
+/// enabled and the default input is recorded. Falls back to the regular
+/// input if voice processing cannot start.
+fn start_input_capture(
+    settings: &Settings,
+    bluetooth_input: Option<&str>,
+) -> Result<Box<dyn AudioCapture>, CyranoError> {
+    let default_input = bluetooth_fallback(
+        settings.bluetooth_input_policy,
+        bluetooth_input,
+        built_in_input_name,
+    )
+    .is_none();
+    if settings.voice_processing && default_input {
+        if let Some(mut capture) = voice_processing_capture() {
+            match capture.start_capture() {
+                Ok(()) => return Ok(capture),
+                Err(e) => log::warn!("[{}] Recording without voice processing: {e}", e.code()),
+            }
+        }
+    }
+    let mut capture: Box<dyn AudioCapture> = Box::new(input_adapter(settings, bluetooth_input));
+    capture.start_capture()?;
+    Ok(capture)
+}
+
+#[cfg(target_os = "macos")]
+fn voice_processing_capture() -> Option<Box<dyn AudioCapture>> {
+    Some(Box::new(
+        crate::infrastructure::audio::macos_voice_processing::VoiceProcessingAdapter::new(),
+    ))
+}
+
+/// Voice processing is only available on macOS.
+#[cfg(not(target_os = "macos"))]
+fn voice_processing_capture() -> Option<Box<dyn AudioCapture>> {
+    None
+}
+
+/// Device to record from instead of a Bluetooth default input, according
+/// to the policy. `None` records from the default input.
+fn bluetooth_fallback(
+    policy: BluetoothInputPolicy,
+    bluetooth_input: Option<&str>,
+    built_in_input: impl FnOnce() -> Option<String>,
+) -> Option<String> {
+    match (policy, bluetooth_input) {
+        (BluetoothInputPolicy::PreferBuiltIn, Some(_)) => built_in_input(),
+        _ => None,
+    }
+}
+
+/// Emit recording-bluetooth-input, telling whether the recording fell back
+/// to another device.
+fn warn_bluetooth_input(app: &AppHandle, bluetooth_device: String, device: Option<String>) {
+    let fallback_device = device.filter(|device| *device != bluetooth_device);
+    match &fallback_device {
+        Some(device) => {
+            log::warn!("Default input {bluetooth_device} is Bluetooth, recording from {device}")
+        }
+        None => {
+            log::warn!("Recording from Bluetooth input {bluetooth_device}, audio quality drops")
+        }
+    }
+    let payload = RecordingBluetoothInputPayload {
+        bluetooth_device,
+        fallback_device,
+    };
+    if let Err(e) = notifier_service::emit(app, "recording-bluetooth-input", payload) {
+        log::error!("Failed to emit recording-bluetooth-input event: {e}");
+    }
+}
+
+/// Name of the default input if it is a Bluetooth device.
+#[cfg(target_os = "macos")]
+pub fn bluetooth_default_input() -> Option<String> {
+    crate::infrastructure::audio::macos_input_device::bluetooth_default_input()
+}
+
+/// Name of the default input if it is a Bluetooth device.
+#[cfg(not(target_os = "macos"))]
+pub fn bluetooth_default_input() -> Option<String> {
+    None
+}
+
+#[cfg(target_os = "macos")]
+fn built_in_input_name() -> Option<String> {
+    crate::infrastructure::audio::macos_input_device::built_in_input_name()
+}
+
+#[cfg(not(target_os = "macos"))]
+fn built_in_input_name() -> Option<String> {
+    None
+}
+
+/// Maximum recording duration for the configured number of minutes.
+fn max_recording_duration(minutes: u32) -> Duration {
+    Duration::from_secs(u64::from(minutes.max(MIN_MAX_RECORDING_MINUTES)) * 60)
+}
+
+/// Stop the dictation and start transcription from outside the capture thread,
+/// since stopping joins the capture thread.
+fn request_auto_stop(app: &AppHandle) {
+    let app = app.clone();
+    thread::spawn(move || {
+        // The user may have stopped or cancelled in the meantime
+        if is_recording() {
+            dictation_service::stop_dictation(&app);
+        }
+    });
 }
 
 #[cfg(test)]
@@ -356,6 +865,20 @@ mod tests {
     use super::*;
     use serial_test::serial;
 
+    fn buffer_of(samples: &[f32]) -> SpillBuffer {
+        let mut buffer = SpillBuffer::new();
+        buffer.append(samples);
+        buffer
+    }
+
+    #[test]
+    fn test_digital_silence_is_not_audio() {
+        assert!(!has_audio(&[]));
+        assert!(!has_audio(&[0.0; 1_600]));
+        assert!(has_audio(&[0.0, 0.0, 0.0001, 0.0]));
+        assert!(has_audio(&[-0.2]));
+    }
+
     #[test]
     fn test_get_timestamp_ms_returns_reasonable_value() {
         let ts = get_timestamp_ms();
@@ -373,6 +896,72 @@ mod tests {
         assert!(json.contains("1234567890"));
     }
 
+    #[test]
+    fn test_recording_device_changed_payload_serializes() {
+        let payload = RecordingDeviceChangedPayload {
+            previous_device: Some("USB Microphone".to_string()),
+            device: "MacBook Pro Microphone".to_string(),
+            timestamp: 42,
+        };
+        let json = serde_json::to_string(&payload).expect("Should serialize");
+        assert!(json.contains("\"previous_device\":\"USB Microphone\""));
+        assert!(json.contains("\"device\":\"MacBook Pro Microphone\""));
+    }
+
+    #[test]
+    fn test_max_recording_duration_has_a_floor() {
+        assert_eq!(max_recording_duration(10), Duration::from_secs(600));
+        assert_eq!(max_recording_duration(0), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_bluetooth_fallback_follows_policy() {
+        let built_in = || Some("MacBook Pro Microphone".to_string());
+        assert_eq!(
+            bluetooth_fallback(
+                BluetoothInputPolicy::PreferBuiltIn,
+                Some("AirPods"),
+                built_in
+            ),
+            Some("MacBook Pro Microphone".to_string())
+        );
+        assert_eq!(
+            bluetooth_fallback(BluetoothInputPolicy::Warn, Some("AirPods"), built_in),
+            None
+        );
+        assert_eq!(
+            bluetooth_fallback(BluetoothInputPolicy::PreferBuiltIn, None, built_in),
+            None
+        );
+        // Mac mini and Mac Pro have no built-in microphone
+        assert_eq!(
+            bluetooth_fallback(BluetoothInputPolicy::PreferBuiltIn, Some("AirPods"), || {
+                None
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn test_session_only_while_recording_or_transcribing() {
+        assert_eq!(session_for(RecordingState::Idle, None, 5_000), None);
+        assert_eq!(session_for(RecordingState::Done, None, 5_000), None);
+        assert_eq!(
+            session_for(RecordingState::Recording, Some(1_000), 5_000),
+            Some(ActiveSession {
+                state: RecordingState::Recording,
+                recording_elapsed_ms: Some(4_000),
+            })
+        );
+        assert_eq!(
+            session_for(RecordingState::Transcribing, None, 5_000),
+            Some(ActiveSession {
+                state: RecordingState::Transcribing,
+                recording_elapsed_ms: None,
+            })
+        );
+    }
+
     #[test]
     fn test_recording_stopped_payload_serializes() {
         let payload = RecordingStoppedPayload {
@@ -388,7 +977,7 @@ mod tests {
     #[serial]
     fn test_store_audio_samples_writes_to_buffer() {
         let samples = vec![0.1_f32, 0.2_f32, 0.3_f32];
-        store_audio_samples(&samples).expect("store_audio_samples should succeed");
+        store_audio_samples(buffer_of(&samples)).expect("store_audio_samples should succeed");
         let stored =
             recording_state::take_audio_samples().expect("take_audio_samples should succeed");
         assert_eq!(stored, samples);
@@ -417,7 +1006,7 @@ mod tests {
             while !stop_flag_clone.load(Ordering::SeqCst) {
                 thread::sleep(std::time::Duration::from_millis(1));
             }
-            Ok(vec![0.0_f32; 10])
+            Ok(buffer_of(&[0.0_f32; 10]))
         });
 
         let ctx = RecordingContext {
@@ -474,7 +1063,7 @@ mod tests {
             while !stop_flag_clone.load(Ordering::SeqCst) {
                 thread::sleep(std::time::Duration::from_millis(1));
             }
-            Ok(vec![0.1_f32, 0.2_f32])
+            Ok(buffer_of(&[0.1_f32, 0.2_f32]))
         });
 
         {
@@ -546,7 +1135,7 @@ mod tests {
             while !stop_flag_clone.load(Ordering::SeqCst) {
                 thread::sleep(std::time::Duration::from_millis(1));
             }
-            Ok(vec![0.0_f32; 10])
+            Ok(buffer_of(&[0.0_f32; 10]))
         });
 
         let ctx = RecordingContext {
@@ -1,21 +1,205 @@
 //! Recording state management.
 //!
 //! This provides a minimal in-memory state holder for the recording workflow.
-//! The actual audio capture and buffer management is handled by recording_service.rs.
+//! The actual audio capture is handled by recording_service.rs.
+//!
+//! Captured audio is kept in a [`SpillBuffer`]: the first minutes stay in
+//! memory, and longer recordings stream the overflow to a temporary file so
+//! memory stays bounded while recording. Samples are re-assembled when they
+//! are taken for transcription.
+//!
+//! The last transcription is kept too, so windows created or reloaded after
+//! it completed can show it without having caught the event.
 
+use std::fs::{self, File};
+use std::io::{BufWriter, Read, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use specta::Type;
 
 use crate::domain::RecordingState;
+use crate::infrastructure::audio::quality::RecordingQuality;
+use crate::services::tray_status_service;
+
+/// Samples kept in memory before spilling to disk (2 minutes at 16kHz).
+const MAX_MEMORY_SAMPLES: usize = 16_000 * 120;
 
 static RECORDING_STATE: OnceLock<Mutex<RecordingState>> = OnceLock::new();
-static AUDIO_BUFFER: OnceLock<Mutex<Vec<f32>>> = OnceLock::new();
+static AUDIO_BUFFER: OnceLock<Mutex<SpillBuffer>> = OnceLock::new();
+static SPILL_COUNTER: AtomicU64 = AtomicU64::new(0);
+static LAST_TRANSCRIPTION: Mutex<Option<LastTranscription>> = Mutex::new(None);
+
+/// The most recent dictation result.
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct LastTranscription {
+    /// The text that was output
+    pub text: String,
+    /// Duration of transcription in milliseconds
+    pub duration_ms: u32,
+    /// Unix timestamp in seconds when the transcription completed
+    pub completed_at: u32,
+    /// Quality measures of the recording
+    pub quality: Option<RecordingQuality>,
+}
+
+/// Temporary file holding the samples spilled out of memory.
+struct SpillFile {
+    path: PathBuf,
+    writer: BufWriter<File>,
+}
+
+/// Audio sample buffer with bounded memory use.
+///
+/// Samples are appended in memory until `memory_limit` is reached; from then
+/// on the memory chunk is written to a temporary file (raw little-endian
+/// f32) each time it fills up. If the file cannot be written, samples stay in
+/// memory rather than being lost. The file is deleted when the samples are
+/// taken or the buffer is dropped.
+pub struct SpillBuffer {
+    memory: Vec<f32>,
+    memory_limit: usize,
+    spill: Option<SpillFile>,
+    spilled_samples: usize,
+}
+
+impl Default for SpillBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SpillBuffer {
+    /// Create an empty buffer with the default memory limit.
+    pub fn new() -> Self {
+        Self::with_memory_limit(MAX_MEMORY_SAMPLES)
+    }
+
+    fn with_memory_limit(memory_limit: usize) -> Self {
+        Self {
+            memory: Vec::new(),
+            memory_limit: memory_limit.max(1),
+            spill: None,
+            spilled_samples: 0,
+        }
+    }
+
+    /// Total number of samples, in memory and on disk.
+    pub fn len(&self) -> usize {
+        self.spilled_samples + self.memory.len()
+    }
+
+    /// Whether the buffer holds no samples.
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Append samples, spilling the memory chunk to disk when it is full.
+    pub fn append(&mut self, samples: &[f32]) {
+        self.memory.extend_from_slice(samples);
+        if self.memory.len() >= self.memory_limit {
+            if let Err(e) = self.spill_memory() {
+                log::warn!("Failed to spill recording to disk, keeping it in memory: {e}");
+            }
+        }
+    }
+
+    fn spill_memory(&mut self) -> std::io::Result<()> {
+        if self.spill.is_none() {
+            let path = spill_path();
+            let writer = BufWriter::new(File::create(&path)?);
+            log::info!("Recording is long, spilling audio to {}", path.display());
+            self.spill = Some(SpillFile { path, writer });
+        }
+        if let Some(spill) = self.spill.as_mut() {
+            let bytes: Vec<u8> = self
+                .memory
+                .iter()
+                .flat_map(|sample| sample.to_le_bytes())
+                .collect();
+            spill.writer.write_all(&bytes)?;
+            self.spilled_samples += self.memory.len();
+            self.memory.clear();
+        }
+        Ok(())
+    }
+
+    /// Re-assemble all samples in order and empty the buffer.
+    pub fn take(&mut self) -> Result<Vec<f32>, String> {
+        let memory = std::mem::take(&mut self.memory);
+        let spilled_samples = std::mem::take(&mut self.spilled_samples);
+        let Some(spill) = self.spill.take() else {
+            return Ok(memory);
+        };
+
+        let result = read_spill_file(spill, spilled_samples).map(|mut samples| {
+            samples.extend_from_slice(&memory);
+            samples
+        });
+        result.map_err(|e| format!("Failed to read spilled recording: {e}"))
+    }
+
+    /// Discard all samples and delete the spill file.
+    pub fn clear(&mut self) {
+        self.memory.clear();
+        self.spilled_samples = 0;
+        if let Some(spill) = self.spill.take() {
+            remove_spill_file(spill);
+        }
+    }
+}
+
+impl Drop for SpillBuffer {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// Unique temporary file path for a recording spill.
+fn spill_path() -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "cyrano-recording-{}-{}.f32",
+        std::process::id(),
+        SPILL_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+/// Read back spilled samples, deleting the file afterwards.
+fn read_spill_file(mut spill: SpillFile, samples: usize) -> std::io::Result<Vec<f32>> {
+    let read = spill.writer.flush().and_then(|()| {
+        let mut bytes = Vec::with_capacity(samples * 4);
+        File::open(&spill.path)?.read_to_end(&mut bytes)?;
+        Ok(bytes)
+    });
+    remove_spill_file(spill);
+
+    let mut out = Vec::with_capacity(samples);
+    out.extend(
+        read?
+            .chunks_exact(4)
+            .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])),
+    );
+    Ok(out)
+}
+
+fn remove_spill_file(spill: SpillFile) {
+    let SpillFile { path, writer } = spill;
+    drop(writer);
+    if let Err(e) = fs::remove_file(&path) {
+        log::warn!("Failed to delete {}: {e}", path.display());
+    }
+}
 
 fn recording_state() -> &'static Mutex<RecordingState> {
     RECORDING_STATE.get_or_init(|| Mutex::new(RecordingState::Idle))
 }
 
-fn audio_buffer() -> &'static Mutex<Vec<f32>> {
-    AUDIO_BUFFER.get_or_init(|| Mutex::new(Vec::new()))
+fn audio_buffer() -> &'static Mutex<SpillBuffer> {
+    AUDIO_BUFFER.get_or_init(|| Mutex::new(SpillBuffer::new()))
 }
 
 /// Set the current recording state.
@@ -28,25 +212,26 @@ pub fn set_recording_state(state: RecordingState) {
             log::error!("Failed to lock recording state mutex: {err}");
         }
     }
+    tray_status_service::show_state(state);
 }
 
-/// Replace the global audio buffer with new samples.
-pub fn set_audio_samples(samples: &[f32]) -> Result<(), String> {
+/// Replace the global audio buffer with a recording.
+///
+/// The previous buffer is dropped, deleting its spill file if any.
+pub fn set_audio_buffer(samples: SpillBuffer) -> Result<(), String> {
     let mut buffer = audio_buffer()
         .lock()
         .map_err(|e| format!("Failed to lock audio buffer: {e}"))?;
-    buffer.clear();
-    buffer.extend_from_slice(samples);
+    *buffer = samples;
     Ok(())
 }
 
-/// Take and clear the global audio buffer.
-#[allow(dead_code)]
+/// Take and clear the global audio buffer, re-assembling spilled samples.
 pub fn take_audio_samples() -> Result<Vec<f32>, String> {
     let mut buffer = audio_buffer()
         .lock()
         .map_err(|e| format!("Failed to lock audio buffer: {e}"))?;
-    Ok(std::mem::take(&mut *buffer))
+    buffer.take()
 }
 
 /// Clear the global audio buffer without returning it.
@@ -58,11 +243,39 @@ pub fn clear_audio_buffer() -> Result<(), String> {
     Ok(())
 }
 
-#[cfg(test)]
+/// Remember the result of the dictation that just completed.
+pub fn set_last_transcription(text: &str, duration_ms: u32, quality: Option<RecordingQuality>) {
+    let completed_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as u32)
+        .unwrap_or(0);
+    match LAST_TRANSCRIPTION.lock() {
+        Ok(mut guard) => {
+            *guard = Some(LastTranscription {
+                text: text.to_string(),
+                duration_ms,
+                completed_at,
+                quality,
+            });
+        }
+        Err(err) => log::error!("Failed to lock last transcription mutex: {err}"),
+    }
+}
+
+/// Get the result of the last dictation since the app started, if any.
+pub fn get_last_transcription() -> Option<LastTranscription> {
+    LAST_TRANSCRIPTION.lock().ok()?.clone()
+}
+
+/// Get the current recording state.
 pub fn get_recording_state() -> RecordingState {
-    *recording_state()
-        .lock()
-        .expect("recording_state lock should succeed in tests")
+    match recording_state().lock() {
+        Ok(guard) => *guard,
+        Err(err) => {
+            log::error!("Failed to lock recording state mutex: {err}");
+            RecordingState::Idle
+        }
+    }
 }
 
 #[cfg(test)]
@@ -75,10 +288,55 @@ mod tests {
         let _ = clear_audio_buffer();
 
         let samples = vec![0.1_f32, 0.2_f32, 0.3_f32];
-        set_audio_samples(&samples).expect("set_audio_samples should succeed");
+        let mut buffer = SpillBuffer::new();
+        buffer.append(&samples);
+        set_audio_buffer(buffer).expect("set_audio_buffer should succeed");
         let taken = take_audio_samples().expect("take_audio_samples should succeed");
         assert_eq!(taken, samples);
         let empty = take_audio_samples().expect("take_audio_samples should succeed");
         assert!(empty.is_empty());
     }
+
+    #[test]
+    fn test_last_transcription_is_replaced() {
+        set_last_transcription("first", 100, None);
+        set_last_transcription("second", 200, None);
+        let last = get_last_transcription().expect("last transcription should be set");
+        assert_eq!(last.text, "second");
+        assert_eq!(last.duration_ms, 200);
+        assert!(last.completed_at > 0);
+    }
+
+    #[test]
+    fn test_spill_buffer_reassembles_spilled_samples_in_order() {
+        let mut buffer = SpillBuffer::with_memory_limit(4);
+        let samples: Vec<f32> = (0..11).map(|i| i as f32 * 0.25).collect();
+        for chunk in samples.chunks(3) {
+            buffer.append(chunk);
+        }
+
+        assert_eq!(buffer.len(), 11);
+        assert!(buffer.memory.len() < 4, "Full chunks should be on disk");
+        let path = buffer.spill.as_ref().map(|spill| spill.path.clone());
+        assert!(path.as_ref().is_some_and(|path| path.exists()));
+
+        assert_eq!(buffer.take().unwrap(), samples);
+        assert!(buffer.is_empty());
+        assert!(!path.unwrap().exists(), "Spill file should be deleted");
+    }
+
+    #[test]
+    fn test_spill_file_is_deleted_on_clear_and_drop() {
+        let mut buffer = SpillBuffer::with_memory_limit(2);
+        buffer.append(&[0.1, 0.2, 0.3]);
+        let cleared = buffer.spill.as_ref().unwrap().path.clone();
+        buffer.clear();
+        assert!(!cleared.exists());
+        assert!(buffer.is_empty());
+
+        buffer.append(&[0.1, 0.2]);
+        let dropped = buffer.spill.as_ref().unwrap().path.clone();
+        drop(buffer);
+        assert!(!dropped.exists());
+    }
 }
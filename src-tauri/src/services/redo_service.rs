@@ -0,0 +1,175 @@
+//! "Re-record" grace window.
+//!
+//! After a dictation completes, the length of the inserted text is kept for
+//! a few seconds. Pressing the redo shortcut within
+//! that window deletes the inserted text and immediately starts a new
+//! recording — the common flow when the user stumbles mid-sentence.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tauri::AppHandle;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::domain::{CyranoError, RecordingState};
+use crate::infrastructure::keyboard;
+use crate::services::{dictation_service, notifier_service, recording_service, recording_state};
+
+/// How long after a dictation completes the redo shortcut remains effective.
+pub const REDO_GRACE_WINDOW: Duration = Duration::from_secs(5);
+
+/// The most recent completed dictation, kept for the grace window.
+struct LastDictation {
+    /// Number of characters inserted at the cursor (0 if clipboard-only),
+    /// counted as grapheme clusters since one backspace deletes one cluster
+    inserted_chars: usize,
+    /// When the dictation output completed
+    completed_at: Instant,
+}
+
+static LAST_DICTATION: Mutex<Option<LastDictation>> = Mutex::new(None);
+
+/// Payload for the dictation-redo event.
+#[derive(Clone, serde::Serialize)]
+pub struct DictationRedoPayload {
+    /// Number of characters deleted from the target application
+    pub deleted_chars: u32,
+}
+
+/// Remember a completed dictation so it can be redone within the grace window.
+///
+/// # Arguments
+/// * `text` - The text that was output
+/// * `cursor_inserted` - Whether the text was inserted at the cursor
+pub fn remember_dictation(text: &str, cursor_inserted: bool) {
+    let inserted_chars = if cursor_inserted {
+        text.graphemes(true).count()
+    } else {
+        0
+    };
+
+    match LAST_DICTATION.lock() {
+        Ok(mut guard) => {
+            *guard = Some(LastDictation {
+                inserted_chars,
+                completed_at: Instant::now(),
+            });
+        }
+        Err(e) => log::error!("Failed to lock last dictation: {e}"),
+    }
+}
+
+/// Forget the last dictation (e.g., when a new recording starts).
+pub fn clear_last_dictation() {
+    if let Ok(mut guard) = LAST_DICTATION.lock() {
+        *guard = None;
+    }
+}
+
+/// Take the last dictation if it is still within the grace window.
+fn take_redo_candidate() -> Option<LastDictation> {
+    let mut guard = LAST_DICTATION.lock().ok()?;
+    let candidate = guard.take()?;
+    is_within_window(candidate.completed_at.elapsed()).then_some(candidate)
+}
+
+/// Whether an elapsed time since completion is still inside the grace window.
+fn is_within_window(elapsed: Duration) -> bool {
+    elapsed <= REDO_GRACE_WINDOW
+}
+
+/// Delete the last dictation's inserted text and start a new recording.
+///
+/// # Returns
+/// * `Ok(true)` if the redo was performed
+/// * `Ok(false)` if there was nothing to redo (window expired or busy)
+/// * `Err(CyranoError::RecordingFailed)` if the inserted text could not be deleted
+pub fn redo_last_dictation(app: &AppHandle) -> Result<bool, CyranoError> {
+    let state = recording_state::get_recording_state();
+    if recording_service::is_recording() || state == RecordingState::Transcribing {
+        log::debug!("Redo ignored: dictation in progress ({state:?})");
+        return Ok(false);
+    }
+
+    let Some(last) = take_redo_candidate() else {
+        log::debug!("Redo ignored: no dictation within the grace window");
+        return Ok(false);
+    };
+
+    if last.inserted_chars > 0 {
+        keyboard::simulate_backspaces(last.inserted_chars).map_err(|e| {
+            CyranoError::RecordingFailed {
+                reason: format!("Failed to delete previous dictation: {e}"),
+            }
+        })?;
+    }
+
+    log::info!(
+        "Redoing dictation: deleted {} chars, restarting recording",
+        last.inserted_chars
+    );
+    if let Err(e) = notifier_service::emit(
+        app,
+        "dictation-redo",
+        DictationRedoPayload {
+            deleted_chars: last.inserted_chars as u32,
+        },
+    ) {
+        log::error!("Failed to emit dictation-redo event: {e}");
+    }
+
+    dictation_service::start_dictation(app);
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    #[test]
+    fn test_grace_window_boundaries() {
+        assert!(is_within_window(Duration::from_secs(0)));
+        assert!(is_within_window(REDO_GRACE_WINDOW));
+        assert!(!is_within_window(
+            REDO_GRACE_WINDOW + Duration::from_millis(1)
+        ));
+    }
+
+    #[test]
+    #[serial]
+    fn test_remember_counts_chars_only_when_inserted() {
+        remember_dictation("héllo", true);
+        let candidate = take_redo_candidate().expect("candidate should exist");
+        assert_eq!(candidate.inserted_chars, 5);
+
+        remember_dictation("héllo", false);
+        let candidate = take_redo_candidate().expect("candidate should exist");
+        assert_eq!(candidate.inserted_chars, 0);
+    }
+
+    #[test]
+    #[serial]
+    fn test_remember_counts_grapheme_clusters() {
+        // Decomposed accent, family emoji (ZWJ sequence) and flag
+        remember_dictation("e\u{301} 👨\u{200d}👩\u{200d}👧 🇫🇷", true);
+        let candidate = take_redo_candidate().expect("candidate should exist");
+        assert_eq!(candidate.inserted_chars, 5);
+    }
+
+    #[test]
+    #[serial]
+    fn test_candidate_is_consumed_once() {
+        remember_dictation("text", true);
+        assert!(take_redo_candidate().is_some());
+        assert!(take_redo_candidate().is_none());
+    }
+
+    #[test]
+    #[serial]
+    fn test_clear_last_dictation() {
+        remember_dictation("text", true);
+        clear_last_dictation();
+        assert!(take_redo_candidate().is_none());
+    }
+}
@@ -0,0 +1,345 @@
+//! Re-transcription with another model.
+//!
+//! After downloading a better model, users can re-run the audio stored with
+//! their history entries through it. The job runs on a background thread with
+//! its own Whisper instance, so dictation keeps using the active model in the
+//! meantime. Entries whose text changes keep the previous text for review.
+//!
+//! With `keep_last_recording` on, the audio of the last dictation is kept in
+//! memory too, so a quick result from a small model can be re-run with a
+//! larger one without speaking again.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use serde::Deserialize;
+use specta::Type;
+use tauri::AppHandle;
+
+use crate::domain::CyranoError;
+use crate::infrastructure::audio::quality;
+use crate::infrastructure::whisper::WhisperAdapter;
+use crate::services::history_service::{self, HistoryEntry};
+use crate::services::{
+    job_scheduler_service, model_integrity_service, notifier_service, recording_state,
+    settings_service, text_processing_service, transcription_service, vocabulary_service,
+};
+use crate::traits::transcriber::Transcriber;
+use crate::utils::text_sanitize;
+
+/// Set while a re-transcription job is running (only one runs at a time).
+static JOB_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Audio of the last dictation, when `keep_last_recording` is on.
+static LAST_RECORDING: Mutex<Option<Vec<f32>>> = Mutex::new(None);
+
+/// Range of history entries to re-transcribe, by creation time.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Type)]
+pub struct HistoryRange {
+    /// Only entries created at or after this Unix timestamp (seconds)
+    pub since: Option<u32>,
+    /// Only entries created at or before this Unix timestamp (seconds)
+    pub until: Option<u32>,
+}
+
+impl HistoryRange {
+    fn contains(&self, entry: &HistoryEntry) -> bool {
+        self.since.is_none_or(|since| entry.created_at >= since)
+            && self.until.is_none_or(|until| entry.created_at <= until)
+    }
+}
+
+/// Payload for the history-retranscription-progress event.
+#[derive(Clone, serde::Serialize)]
+pub struct RetranscriptionProgressPayload {
+    /// ID of the entry that was just processed
+    pub id: String,
+    /// Number of entries processed so far
+    pub processed: u32,
+    /// Total number of entries in the job
+    pub total: u32,
+    /// Whether the entry's text changed
+    pub changed: bool,
+}
+
+/// Payload for the history-retranscription-complete event.
+#[derive(Clone, serde::Serialize)]
+pub struct RetranscriptionCompletePayload {
+    /// Number of entries processed
+    pub processed: u32,
+    /// Number of entries whose text changed
+    pub changed: u32,
+    /// Number of entries that could not be re-transcribed
+    pub failed: u32,
+}
+
+/// Resolve a model file name inside the models directory.
+///
+/// Only bare `.bin` file names are accepted, so the command cannot be used
+/// to load arbitrary files.
+fn resolve_model_path(model: &str) -> Result<PathBuf, CyranoError> {
+    let is_bare_bin_name =
+        !model.contains(['/', '\\']) && !model.starts_with('.') && model.ends_with(".bin");
+    let path = transcription_service::get_models_directory()?.join(model);
+
+    if !is_bare_bin_name || !path.is_file() {
+        return Err(CyranoError::ModelNotFound {
+            path: path.display().to_string(),
+        });
+    }
+    Ok(path)
+}
+
+/// Start re-transcribing the history entries in `range` with `model`.
+///
+/// Only entries with stored audio are processed. Progress is reported through
+/// history-retranscription-progress events and the end of the job through a
+/// history-retranscription-complete event.
+///
+/// # Returns
+/// * `Ok(u32)` with the number of entries queued
+/// * `Err(CyranoError::ModelNotFound)` if the model file does not exist
+/// * `Err(CyranoError::TranscriptionFailed)` if a job is already running
+pub fn retranscribe_history(
+    app: &AppHandle,
+    range: HistoryRange,
+    model: &str,
+) -> Result<u32, CyranoError> {
+    let model_path = resolve_model_path(model)?;
+    let entries: Vec<HistoryEntry> = history_service::list_entries()?
+        .into_iter()
+        .filter(|entry| entry.has_audio && range.contains(entry))
+        .collect();
+    let total = entries.len() as u32;
+
+    if JOB_RUNNING
+        .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+        .is_err()
+    {
+        return Err(CyranoError::TranscriptionFailed {
+            reason: "A history re-transcription is already running".to_string(),
+        });
+    }
+
+    log::info!("Re-transcribing {total} history entries with {model}");
+    let app = app.clone();
+    let model_name = model.to_string();
+    std::thread::spawn(move || {
+        let summary = run_job(&app, &model_path, &model_name, entries);
+        JOB_RUNNING.store(false, Ordering::SeqCst);
+
+        log::info!(
+            "History re-transcription finished: {} processed, {} changed, {} failed",
+            summary.processed,
+            summary.changed,
+            summary.failed
+        );
+        if let Err(e) = notifier_service::emit(&app, "history-retranscription-complete", summary) {
+            log::error!("Failed to emit history-retranscription-complete event: {e}");
+        }
+    });
+
+    Ok(total)
+}
+
+/// Load `model_path` in a Whisper instance of its own, configured like the
+/// dictation one. MUST run off the main thread.
+fn load_adapter(
+    model_path: &Path,
+    settings: &settings_service::Settings,
+) -> Result<WhisperAdapter, CyranoError> {
+    let mut adapter = WhisperAdapter::new();
+    adapter.set_initial_prompt(vocabulary_service::initial_prompt(&settings.vocabulary));
+    adapter.set_abort_check(Some(job_scheduler_service::preempted));
+    adapter.set_cache_budget(u64::from(settings.model_cache_mb) * 1024 * 1024);
+    adapter.set_use_gpu(settings.use_gpu);
+    model_integrity_service::verify_model(model_path)?;
+    adapter.load_model(model_path)?;
+    Ok(adapter)
+}
+
+/// Keep the audio of the last dictation for [`retranscribe_last`], or drop
+/// it when `keep_last_recording` is off.
+pub fn keep_last_recording(samples: &[f32]) {
+    let kept = settings_service::get_settings()
+        .keep_last_recording
+        .then(|| samples.to_vec());
+    match LAST_RECORDING.lock() {
+        Ok(mut guard) => *guard = kept,
+        Err(e) => log::error!("Failed to lock last recording: {e}"),
+    }
+}
+
+/// Transcribe the last dictation again with `model`, and make the result the
+/// last transcription. Blocks, so MUST run off the main thread.
+///
+/// # Returns
+/// * `Ok(String)` with the new text
+/// * `Err(CyranoError::ModelNotFound)` if the model file does not exist
+/// * `Err(CyranoError::TranscriptionFailed)` if no recording was kept or
+///   the transcription failed
+pub fn retranscribe_last(model: &str) -> Result<String, CyranoError> {
+    let model_path = resolve_model_path(model)?;
+    let samples = LAST_RECORDING
+        .lock()
+        .ok()
+        .and_then(|guard| guard.clone())
+        .ok_or_else(|| CyranoError::TranscriptionFailed {
+            reason: "No recording kept to re-transcribe (enable keep_last_recording)".to_string(),
+        })?;
+
+    log::info!("Re-transcribing the last recording with {model}");
+    let settings = settings_service::get_settings();
+    let _slot = job_scheduler_service::acquire_slot("last-retranscription");
+    let adapter = load_adapter(&model_path, &settings)?;
+    let prepared = transcription_service::prepare_samples(&samples);
+    let start = std::time::Instant::now();
+    let text = job_scheduler_service::run_preemptible("last-retranscription", || {
+        adapter
+            .transcribe_with_language(&prepared, &settings.decode_options)
+            .map(|transcription| transcription.text)
+    })?;
+    let text = text_sanitize::sanitize_output(&text);
+    let text = text_processing_service::process_transcription(&text);
+
+    recording_state::set_last_transcription(
+        &text,
+        start.elapsed().as_millis() as u32,
+        quality::analyze(&samples),
+    );
+    Ok(text)
+}
+
+/// Load the model and re-transcribe each entry. MUST run off the main thread.
+fn run_job(
+    app: &AppHandle,
+    model_path: &Path,
+    model_name: &str,
+    entries: Vec<HistoryEntry>,
+) -> RetranscriptionCompletePayload {
+    let total = entries.len() as u32;
+    let mut summary = RetranscriptionCompletePayload {
+        processed: 0,
+        changed: 0,
+        failed: 0,
+    };
+
+    let settings = settings_service::get_settings();
+    let adapter = match load_adapter(model_path, &settings) {
+        Ok(adapter) => adapter,
+        Err(e) => {
+            log::error!(
+                "[{}] Failed to load model for re-transcription: {e}",
+                e.code()
+            );
+            summary.failed = total;
+            return summary;
+        }
+    };
+
+    for mut entry in entries {
+        let _slot = job_scheduler_service::acquire_slot("history-retranscription");
+        let result = history_service::load_audio_samples(&entry.id)
+            .and_then(|samples| {
+                let prepared = transcription_service::prepare_samples(&samples);
+                job_scheduler_service::run_preemptible("history-retranscription", || {
+                    adapter
+                        .transcribe_with_language(&prepared, &settings.decode_options)
+                        .map(|transcription| transcription.text)
+                })
+            })
+            .and_then(|text| {
+                let text = text_sanitize::sanitize_output(&text);
+                let text = text_processing_service::process_transcription(&text);
+                let changed = entry.apply_retranscription(text, Some(model_name.to_string()));
+                history_service::update_entry(&entry).map(|()| changed)
+            });
+
+        summary.processed += 1;
+        let changed = match result {
+            Ok(changed) => {
+                if changed {
+                    summary.changed += 1;
+                }
+                changed
+            }
+            Err(e) => {
+                log::warn!(
+                    "[{}] Failed to re-transcribe entry {}: {e}",
+                    e.code(),
+                    entry.id
+                );
+                summary.failed += 1;
+                false
+            }
+        };
+
+        let progress = RetranscriptionProgressPayload {
+            id: entry.id,
+            processed: summary.processed,
+            total,
+            changed,
+        };
+        if let Err(e) = notifier_service::emit(app, "history-retranscription-progress", progress) {
+            log::error!("Failed to emit history-retranscription-progress event: {e}");
+        }
+    }
+
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry_created_at(created_at: u32) -> HistoryEntry {
+        HistoryEntry {
+            id: created_at.to_string(),
+            created_at,
+            text: String::new(),
+            summary: None,
+            action_items: Vec::new(),
+            audio_duration_ms: 0,
+            model: None,
+            has_audio: true,
+            quality: None,
+            previous_text: None,
+            retranscribed_at: None,
+        }
+    }
+
+    #[test]
+    fn test_unbounded_range_contains_everything() {
+        let range = HistoryRange::default();
+        assert!(range.contains(&entry_created_at(0)));
+        assert!(range.contains(&entry_created_at(u32::MAX)));
+    }
+
+    #[test]
+    fn test_range_bounds_are_inclusive() {
+        let range = HistoryRange {
+            since: Some(100),
+            until: Some(200),
+        };
+        assert!(!range.contains(&entry_created_at(99)));
+        assert!(range.contains(&entry_created_at(100)));
+        assert!(range.contains(&entry_created_at(200)));
+        assert!(!range.contains(&entry_created_at(201)));
+    }
+
+    #[test]
+    fn test_model_names_cannot_escape_models_directory() {
+        for model in [
+            "../settings.json",
+            "/etc/passwd",
+            ".hidden.bin",
+            "model.txt",
+        ] {
+            assert!(matches!(
+                resolve_model_path(model),
+                Err(CyranoError::ModelNotFound { .. })
+            ));
+        }
+    }
+}
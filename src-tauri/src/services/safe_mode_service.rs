@@ -0,0 +1,69 @@
+//! Safe mode startup.
+//!
+//! Launching with `--safe-mode`, or while holding Shift, starts Cyrano with
+//! only what is needed to diagnose it: no global shortcuts, no model
+//! preload, no background watchers, and no plugins. The frontend shows the
+//! diagnostics instead of the regular UI, so users whose configuration broke
+//! the app can find out why without deleting `~/.cyrano` blindly.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[cfg(target_os = "macos")]
+use crate::infrastructure::keyboard;
+
+/// Command line flag that starts in safe mode.
+const SAFE_MODE_FLAG: &str = "--safe-mode";
+
+/// Whether this launch runs in safe mode, decided once at startup.
+static SAFE_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Decide whether this launch runs in safe mode, from the command line and
+/// the Shift key. Must be called at startup, while the user may still hold
+/// Shift down.
+pub fn detect_at_launch() -> bool {
+    let from_flag = has_safe_mode_flag(std::env::args());
+    #[cfg(target_os = "macos")]
+    let from_shift = keyboard::shift_key_down();
+    #[cfg(not(target_os = "macos"))]
+    let from_shift = false;
+
+    let safe_mode = from_flag || from_shift;
+    if safe_mode {
+        let reason = if from_flag {
+            SAFE_MODE_FLAG
+        } else {
+            "Shift held"
+        };
+        log::warn!("Starting in safe mode ({reason}): shortcuts, watchers and plugins are off");
+    }
+    SAFE_MODE.store(safe_mode, Ordering::SeqCst);
+    safe_mode
+}
+
+/// Check whether the app runs in safe mode.
+pub fn is_safe_mode() -> bool {
+    SAFE_MODE.load(Ordering::SeqCst)
+}
+
+/// Check whether the command line asks for safe mode.
+fn has_safe_mode_flag(args: impl IntoIterator<Item = String>) -> bool {
+    args.into_iter().skip(1).any(|arg| arg == SAFE_MODE_FLAG)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_detects_flag_after_program_name() {
+        assert!(has_safe_mode_flag(args(&["cyrano", "--safe-mode"])));
+        assert!(has_safe_mode_flag(args(&["cyrano", "-v", "--safe-mode"])));
+        assert!(!has_safe_mode_flag(args(&["cyrano"])));
+        assert!(!has_safe_mode_flag(args(&["--safe-mode"])));
+        assert!(!has_safe_mode_flag(args(&["cyrano", "--safe-mode=1"])));
+    }
+}
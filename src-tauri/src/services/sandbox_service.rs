@@ -0,0 +1,127 @@
+//! Sandbox and hardened runtime compatibility audit.
+//!
+//! Detects restrictions that the App Sandbox (Mac App Store builds) and the
+//! hardened runtime impose on features Cyrano relies on: clipboard access,
+//! CGEvent posting for paste simulation, and access to `~/.cyrano`.
+//! Other services consult these checks to pick a working alternative path
+//! instead of silently losing a feature.
+
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+#[cfg(target_os = "macos")]
+use crate::infrastructure::sandbox::macos_sandbox;
+
+/// Path fragment present in the home directory of sandboxed apps.
+const SANDBOX_CONTAINER_MARKER: &str = "/Library/Containers/";
+
+/// Result of the sandbox compatibility audit.
+#[derive(Debug, Clone, serde::Serialize, specta::Type)]
+pub struct SandboxReport {
+    /// Whether the app runs inside the App Sandbox
+    pub sandboxed: bool,
+    /// Whether the app runs with the hardened runtime enabled
+    pub hardened_runtime: bool,
+    /// Whether the clipboard could be read
+    pub clipboard_available: bool,
+    /// Whether synthetic keyboard events (Cmd+V) can be posted
+    pub event_posting_available: bool,
+    /// Whether the home directory resolves to the real user home (not a container)
+    pub home_directory_accessible: bool,
+    /// Home directory as seen by the app
+    pub home_directory: Option<String>,
+}
+
+/// Check if the app runs inside the App Sandbox.
+#[cfg(target_os = "macos")]
+pub fn is_sandboxed() -> bool {
+    macos_sandbox::is_sandboxed()
+}
+
+/// Non-macOS stub: there is no App Sandbox.
+#[cfg(not(target_os = "macos"))]
+pub fn is_sandboxed() -> bool {
+    false
+}
+
+/// Check if the app runs with the hardened runtime.
+#[cfg(target_os = "macos")]
+pub fn is_hardened_runtime() -> bool {
+    macos_sandbox::is_hardened_runtime()
+}
+
+/// Non-macOS stub: there is no hardened runtime.
+#[cfg(not(target_os = "macos"))]
+pub fn is_hardened_runtime() -> bool {
+    false
+}
+
+/// Check if synthetic keyboard events can be posted to other apps.
+///
+/// Sandboxed builds cannot post CGEvents, so paste simulation must fall
+/// back to clipboard-only output.
+#[cfg(target_os = "macos")]
+pub fn can_post_events() -> bool {
+    macos_sandbox::can_post_events()
+}
+
+/// Non-macOS stub: keyboard simulation is not implemented.
+#[cfg(not(target_os = "macos"))]
+pub fn can_post_events() -> bool {
+    false
+}
+
+/// Check if the home directory is the real user home rather than a sandbox container.
+///
+/// Inside the sandbox `~` resolves to the app container, so models placed in
+/// the user's real `~/.cyrano` are not visible to the app.
+pub fn is_home_directory_accessible() -> bool {
+    dirs::home_dir()
+        .map(|home| !is_container_path(&home.display().to_string()))
+        .unwrap_or(false)
+}
+
+/// Returns true if the path points inside an App Sandbox container.
+fn is_container_path(path: &str) -> bool {
+    path.contains(SANDBOX_CONTAINER_MARKER)
+}
+
+/// Run the full compatibility audit.
+///
+/// # Arguments
+/// * `app` - The Tauri app handle (needed for clipboard plugin access)
+pub fn audit(app: &AppHandle) -> SandboxReport {
+    let report = SandboxReport {
+        sandboxed: is_sandboxed(),
+        hardened_runtime: is_hardened_runtime(),
+        // Reading never clobbers the user's clipboard, unlike a write probe
+        clipboard_available: app.clipboard().read_text().is_ok(),
+        event_posting_available: can_post_events(),
+        home_directory_accessible: is_home_directory_accessible(),
+        home_directory: dirs::home_dir().map(|p| p.display().to_string()),
+    };
+
+    log::debug!("Sandbox audit: {report:?}");
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_container_path_detection() {
+        assert!(is_container_path(
+            "/Users/me/Library/Containers/com.cyrano.app/Data"
+        ));
+        assert!(!is_container_path("/Users/me"));
+    }
+
+    #[test]
+    fn test_sandbox_checks_do_not_panic() {
+        // The actual values depend on how the test binary was launched
+        let _ = is_sandboxed();
+        let _ = is_hardened_runtime();
+        let _ = is_home_directory_accessible();
+    }
+}
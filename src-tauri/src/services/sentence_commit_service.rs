@@ -0,0 +1,103 @@
+//! Sentence-level commit policy for streamed transcriptions.
+//!
+//! Segments streamed while Whisper decodes can end mid-sentence, and the
+//! words at the end of a segment are the least reliable. Text is only
+//! committed (handed to consumers that cannot take it back, such as text
+//! typed into another app) once its sentence is complete, so a later
+//! correction never has to rewrite committed words. Whatever follows the
+//! last sentence boundary waits for the next segment or the end of the
+//! transcription.
+
+/// Characters ending a sentence.
+const SENTENCE_TERMINATORS: [char; 6] = ['.', '!', '?', '…', '。', '？'];
+
+/// Closing characters kept with the sentence they follow.
+const CLOSING_CHARS: [char; 4] = ['"', '\'', ')', '”'];
+
+/// Accumulates streamed segments and releases whole sentences.
+#[derive(Debug, Default)]
+pub struct SentenceCommitter {
+    pending: String,
+}
+
+impl SentenceCommitter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a decoded segment.
+    ///
+    /// # Returns
+    /// * `Some(text)` with the sentences completed by this segment
+    /// * `None` if no sentence is complete yet
+    pub fn push(&mut self, segment: &str) -> Option<String> {
+        self.pending.push_str(segment);
+        let end = last_sentence_end(&self.pending)?;
+        let committed: String = self.pending.drain(..end).collect();
+        let committed = committed.trim();
+        (!committed.is_empty()).then(|| committed.to_string())
+    }
+
+    /// Release whatever is left once the transcription is complete.
+    pub fn finish(&mut self) -> Option<String> {
+        let rest = std::mem::take(&mut self.pending);
+        let rest = rest.trim();
+        (!rest.is_empty()).then(|| rest.to_string())
+    }
+}
+
+/// Byte offset just past the last complete sentence of `text`: a terminator,
+/// with its closing quotes or brackets, followed by whitespace or the end of
+/// the text. Terminators inside a word ("3.5", "e.g.x") do not count.
+fn last_sentence_end(text: &str) -> Option<usize> {
+    let mut end = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((_, c)) = chars.next() {
+        if !SENTENCE_TERMINATORS.contains(&c) {
+            continue;
+        }
+        while let Some(&(_, next)) = chars.peek() {
+            if SENTENCE_TERMINATORS.contains(&next) || CLOSING_CHARS.contains(&next) {
+                chars.next();
+            } else {
+                break;
+            }
+        }
+        match chars.peek() {
+            Some(&(index, next)) if next.is_whitespace() => end = Some(index),
+            None => end = Some(text.len()),
+            _ => {}
+        }
+    }
+    end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commits_only_complete_sentences() {
+        let mut committer = SentenceCommitter::new();
+
+        assert_eq!(committer.push(" The build is"), None);
+        assert_eq!(
+            committer.push(" green. Deploy version 3.5"),
+            Some("The build is green.".to_string())
+        );
+        assert_eq!(
+            committer.push(" now! Then \"ping me.\" After"),
+            Some("Deploy version 3.5 now! Then \"ping me.\"".to_string())
+        );
+        assert_eq!(committer.finish(), Some("After".to_string()));
+        assert_eq!(committer.finish(), None);
+    }
+
+    #[test]
+    fn test_sentence_end_at_end_of_segment() {
+        let mut committer = SentenceCommitter::new();
+        assert_eq!(committer.push(" Done?!"), Some("Done?!".to_string()));
+        assert_eq!(committer.push(" "), None);
+        assert_eq!(committer.finish(), None);
+    }
+}
@@ -0,0 +1,976 @@
+//! Backend settings persisted in `~/.cyrano/settings.json`.
+//!
+//! Unlike `AppPreferences` (owned and saved wholesale by the frontend), these
+//! settings are owned by the backend services that consume them. Each service
+//! reads the cached value through `get_settings()` and commands persist changes
+//! through `update_settings()`. Edits made to the file by hand are picked up
+//! with `reload_from_disk()` (see `settings_watch_service`).
+//!
+//! Organizations can lock settings for managed deployments, either with a
+//! configuration profile (forced values in the app's preference domain) or
+//! with a system-level JSON file at `MANAGED_SETTINGS_PATH`. Managed values
+//! override the user's settings and cannot be changed through
+//! `update_settings()`; profiles take precedence over the file.
+
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use specta::Type;
+
+use crate::domain::CyranoError;
+use crate::infrastructure::audio::resampler::ResamplerQuality;
+use crate::infrastructure::whisper::DecodeOptions;
+use crate::services::data_directory_service;
+use crate::services::output_service::OutputMode;
+use crate::services::text_processing_service::{self, PostProcessorKind};
+
+/// File name of the settings file inside the data root.
+const SETTINGS_FILE_NAME: &str = "settings.json";
+
+/// System-level managed settings file (writable by administrators only).
+const MANAGED_SETTINGS_PATH: &str = "/Library/Application Support/Cyrano/managed-settings.json";
+
+/// Preference domain read for configuration profiles (the bundle identifier).
+#[cfg(target_os = "macos")]
+const MANAGED_PREFERENCES_DOMAIN: &str = "com.nvergez.cyrano";
+
+/// Settings holding API keys. They are kept in the settings file, which
+/// only the user can read, and never sent to the frontend.
+const SECRET_SETTINGS: [&str; 2] = ["cloud_transcription_api_key", "summary_api_key"];
+
+/// Phrases Whisper commonly outputs on silent audio.
+const DEFAULT_HALLUCINATION_BLOCKLIST: [&str; 5] = [
+    "Thank you for watching",
+    "Thanks for watching",
+    "Please subscribe to my channel",
+    "Subtitles by…",
+    "Sous-titres réalisés par…",
+];
+
+/// Bundle IDs of terminal emulators, where a pasted line break runs the
+/// command typed so far.
+pub const TERMINAL_APPS: [&str; 8] = [
+    "com.apple.Terminal",
+    "com.googlecode.iterm2",
+    "dev.warp.Warp-Stable",
+    "com.mitchellh.ghostty",
+    "net.kovidgoyal.kitty",
+    "org.alacritty",
+    "com.github.wez.wezterm",
+    "co.zeit.hyper",
+];
+
+/// Bundle IDs of system password prompts, which must never receive a
+/// dictation.
+const PASSWORD_PROMPT_APPS: [&str; 2] = ["com.apple.SecurityAgent", "com.apple.loginwindow"];
+
+/// What pressing the recording shortcut does while a transcription is running.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum BusyShortcutAction {
+    /// Start a new recording as soon as the current output completes.
+    #[default]
+    Queue,
+    /// Discard the current transcription and start a new recording.
+    Restart,
+}
+
+/// How the recording overlay looks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlayStyle {
+    /// Panel centered on screen with the state and its label.
+    #[default]
+    Panel,
+    /// Colored dot below the menu bar, for screen shares.
+    Dot,
+}
+
+/// Where the recording overlay appears.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlayPlacement {
+    /// Panel centered on the screen with the mouse, dot below its menu bar.
+    #[default]
+    Center,
+    /// Just below the text cursor of the focused app, or the mouse pointer
+    /// when the app does not report its text cursor.
+    Cursor,
+}
+
+/// What recording does when the default input is a Bluetooth headset. Its
+/// microphone switches the headset to the hands-free profile, degrading
+/// both the recording and the audio the user is listening to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum BluetoothInputPolicy {
+    /// Record from the headset without warning.
+    Allow,
+    /// Record from the headset and warn that audio quality drops.
+    #[default]
+    Warn,
+    /// Record from the built-in microphone instead, when there is one.
+    PreferBuiltIn,
+}
+
+/// How long the Whisper model stays in memory after a transcription.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelKeepAlive {
+    /// Unload the model after each transcription, loading it again for the
+    /// next one.
+    UnloadImmediately,
+    /// Unload the model after this many minutes without a transcription.
+    AfterMinutes(u32),
+    /// Keep the model loaded until Cyrano quits.
+    NeverUnload,
+}
+
+impl Default for ModelKeepAlive {
+    fn default() -> Self {
+        Self::AfterMinutes(30)
+    }
+}
+
+/// Speech-to-text engine used for transcriptions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptionEngine {
+    /// Whisper running locally, falling back to Apple Speech on macOS while
+    /// no model is installed.
+    #[default]
+    Whisper,
+    /// Whisper with the smallest installed model (e.g. tiny) and the
+    /// cheapest decoding: less accurate, but usable on old or low-memory
+    /// machines.
+    WhisperLite,
+    /// Apple's Speech framework, built into macOS: no model download.
+    AppleSpeech,
+    /// A remote OpenAI-compatible transcription API. Faster on low-end
+    /// machines, but the audio leaves the device.
+    Remote,
+}
+
+impl TranscriptionEngine {
+    /// Whether the engine runs a local Whisper model.
+    pub fn uses_whisper(self) -> bool {
+        matches!(self, Self::Whisper | Self::WhisperLite)
+    }
+}
+
+/// Remote service whose API key is stored in the settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiKeyService {
+    /// The remote transcription API (`cloud_transcription_api_key`)
+    CloudTranscription,
+    /// The summary API (`summary_api_key`)
+    Summary,
+}
+
+/// A text replacement applied to every transcription.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct ReplacementRule {
+    /// Text to find (whole words, case-insensitive)
+    pub from: String,
+    /// Replacement text
+    pub to: String,
+}
+
+/// The user's decision about running a post-processing plugin.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct PluginApproval {
+    /// Plugin file name
+    pub name: String,
+    /// SHA-256 of the plugin file the decision applies to (hex)
+    pub sha256: String,
+    /// Whether the plugin may run
+    pub allowed: bool,
+}
+
+/// Behavior changes applied to a single dictation.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct DictationOverrides {
+    /// Translate the speech to English
+    pub translate: bool,
+    /// Language spoken, as a Whisper language code, in place of
+    /// `transcription_language`
+    pub language: Option<String>,
+    /// Output the text as transcribed, without list commands, replacement
+    /// rules or plugins, e.g. when dictating code
+    pub verbatim: bool,
+    /// How the text is output, in place of the `output_mode` setting
+    pub output_mode: Option<OutputMode>,
+    /// Engine transcribing the dictation, in place of the
+    /// `transcription_engine` setting
+    pub engine: Option<TranscriptionEngine>,
+}
+
+/// The recording shortcut with an extra modifier, starting dictations that
+/// behave differently.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct ShortcutVariant {
+    /// Modifier added to the recording shortcut: `Alt`, `Control`, `Shift`
+    /// or `Super`
+    pub modifier: String,
+    /// Behavior of the dictations it starts
+    pub overrides: DictationOverrides,
+}
+
+/// Output mode and engine used for dictations into an app.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct AppOutputRule {
+    /// Bundle ID of the app, e.g. `com.microsoft.VSCode`
+    pub bundle_id: String,
+    /// How dictations are output while the app is frontmost
+    pub mode: OutputMode,
+    /// Engine transcribing dictations started while the app is frontmost,
+    /// in place of `transcription_engine`
+    #[serde(default)]
+    pub engine: Option<TranscriptionEngine>,
+}
+
+/// Backend settings. Missing keys fall back to their defaults when loading.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct Settings {
+    /// Global shortcut that deletes the last dictation and records again.
+    /// If None, no redo shortcut is registered.
+    pub redo_shortcut: Option<String>,
+    /// Variants of the recording shortcut, e.g. with Alt to translate to
+    /// English.
+    pub recording_shortcut_variants: Vec<ShortcutVariant>,
+    /// Global shortcut that transcribes the audio copied to the clipboard.
+    /// If None, no shortcut is registered.
+    pub clipboard_audio_shortcut: Option<String>,
+    /// Skip our Cmd+V when another source pastes right after we copy, e.g.
+    /// clipboard-sync or snippet tools that auto-paste on clipboard change.
+    pub skip_duplicate_paste: bool,
+    /// Bundle IDs of apps where duplicate paste detection always runs,
+    /// even when `skip_duplicate_paste` is off.
+    pub duplicate_paste_apps: Vec<String>,
+    /// Bundle IDs of apps where dictations are only copied to the clipboard,
+    /// never pasted. Defaults to terminals and password prompts.
+    pub clipboard_only_apps: Vec<String>,
+    /// Read the text before the caret when pasting (Accessibility
+    /// permission) to fit the spacing and capitalization of the dictation
+    /// to the sentence being typed.
+    pub caret_context_enabled: bool,
+    /// How dictations are output: pasted (default), typed as keystrokes to
+    /// leave the clipboard untouched, only copied, or as asked each time.
+    pub output_mode: OutputMode,
+    /// Output mode and engine per app, in place of `output_mode` and
+    /// `transcription_engine` while the app is frontmost, e.g. always type
+    /// into an editor, or transcribe remotely in a chat app.
+    pub app_output_rules: Vec<AppOutputRule>,
+    /// Put the previous clipboard text back after pasting a dictation.
+    pub restore_clipboard: bool,
+    /// Insert pasted dictations into the focused text field through the
+    /// Accessibility API, without the clipboard or keystrokes, in apps that
+    /// support it.
+    pub accessibility_insertion: bool,
+    /// Behavior of the recording shortcut while a transcription is running.
+    pub busy_shortcut_action: BusyShortcutAction,
+    /// Look of the recording overlay.
+    pub overlay_style: OverlayStyle,
+    /// Where the recording overlay appears.
+    pub overlay_placement: OverlayPlacement,
+    /// Show the recording time (and `…` while transcribing) in the menu bar.
+    pub tray_status_enabled: bool,
+    /// Save completed dictations to the transcription history (privacy opt-in).
+    pub history_enabled: bool,
+    /// Also store the recorded audio with each history entry (privacy opt-in).
+    pub history_audio: bool,
+    /// Also save each dictation's audio as a WAV file in `~/.cyrano/recordings`.
+    pub save_recordings: bool,
+    /// Keep the audio of the last dictation in memory, so it can be
+    /// re-transcribed with another model without speaking again.
+    pub keep_last_recording: bool,
+    /// Folder synced by a third party (iCloud Drive, Dropbox, ...) used to
+    /// share history between devices. If None, history sync is disabled.
+    pub history_sync_folder: Option<String>,
+    /// WebDAV collection or relay URL for end-to-end encrypted sync.
+    /// If None, encrypted sync is disabled.
+    pub encrypted_sync_url: Option<String>,
+    /// Username for the encrypted sync server, if it requires one.
+    pub encrypted_sync_username: Option<String>,
+    /// Language spoken in dictations, as a Whisper language code (`en`,
+    /// `fr`...). If None, Whisper detects the language of each recording.
+    pub transcription_language: Option<String>,
+    /// How Whisper decodes dictations: greedy or beam search, temperature
+    /// fallback.
+    pub decode_options: DecodeOptions,
+    /// File name of the model used for transcription in the models
+    /// directory. If None, the first model by name is used.
+    pub active_model: Option<String>,
+    /// Memory in MiB for keeping recently used models loaded, so switching
+    /// back to one is instant. 0 keeps only the model in use.
+    pub model_cache_mb: u32,
+    /// Run Whisper on the GPU (Metal) when the machine has one. Turn off if
+    /// GPU transcription misbehaves on this machine.
+    pub use_gpu: bool,
+    /// When the model is unloaded to free memory.
+    pub model_keep_alive: ModelKeepAlive,
+    /// Engine transcribing dictations and files.
+    pub transcription_engine: TranscriptionEngine,
+    /// Base URL of the remote transcription API, e.g. OpenAI's.
+    pub cloud_transcription_url: String,
+    /// Model used for remote transcription, as named by the server.
+    pub cloud_transcription_model: String,
+    /// API key for the transcription API. Never synced nor sent to the
+    /// frontend.
+    pub cloud_transcription_api_key: Option<String>,
+    /// Domain terms (names, jargon) passed to Whisper as spelling hints.
+    pub vocabulary: Vec<String>,
+    /// Replacements applied to transcriptions, in order.
+    pub replacements: Vec<ReplacementRule>,
+    /// Formatting steps applied to transcriptions, in order.
+    pub post_processors: Vec<PostProcessorKind>,
+    /// Phrases Whisper outputs on silence ("Thank you for watching"). A
+    /// transcription consisting only of one of them is dropped. A phrase
+    /// ending with `…` matches any transcription starting with it.
+    pub hallucination_blocklist: Vec<String>,
+    /// Interpret "bullet", "numbered list", "next item" and "end list" as
+    /// commands that format Markdown lists.
+    pub list_dictation_enabled: bool,
+    /// Run user-provided plugins from `~/.cyrano/plugins` on transcriptions.
+    pub plugins_enabled: bool,
+    /// Time limit for a single plugin run, in milliseconds.
+    pub plugin_timeout_ms: u32,
+    /// Per-plugin run permissions, tied to the plugin's content.
+    pub plugin_approvals: Vec<PluginApproval>,
+    /// Stop recording and transcribe automatically once the speaker pauses.
+    pub auto_stop_enabled: bool,
+    /// Silence after speech that ends the recording, in milliseconds.
+    pub auto_stop_silence_ms: u32,
+    /// Level below which audio counts as silence, in dBFS: for stopping
+    /// automatically, and for dropping what Whisper outputs on silent
+    /// recordings. Raise it in noisy rooms, lower it for quiet voices.
+    pub auto_stop_threshold_db: f32,
+    /// Longest recording, in minutes: recording stops and is transcribed
+    /// once reached, so a forgotten recording cannot grow without bound.
+    pub max_recording_minutes: u32,
+    /// Boost quiet recordings to a consistent level before transcription.
+    pub normalize_audio: bool,
+    /// Algorithm used to convert microphone and file audio to 16kHz.
+    pub resampler_quality: ResamplerQuality,
+    /// Input used when the default microphone is a Bluetooth headset.
+    pub bluetooth_input_policy: BluetoothInputPolicy,
+    /// Channel recorded from audio interfaces with more than two inputs,
+    /// numbered from 1. If None, the loudest channel is recorded.
+    pub input_channel: Option<u16>,
+    /// Record through macOS voice processing: echo cancellation of audio
+    /// playing on the speakers and automatic gain control. macOS lowers the
+    /// volume of other audio while recording.
+    pub voice_processing: bool,
+    /// Background jobs (file transcription, history re-transcription)
+    /// allowed to run at the same time. Dictations always run first.
+    pub max_parallel_jobs: u32,
+    /// Folder watched for voice memos to import. If None, the Voice Memos
+    /// iCloud sync folder is used when it exists.
+    pub voice_memo_folder: Option<String>,
+    /// Watch for calls in conferencing apps and offer meeting transcription.
+    pub meeting_detection_enabled: bool,
+    /// Summarize long transcriptions and store the summary in history.
+    pub summary_enabled: bool,
+    /// Shortest transcription that is summarized, in words.
+    pub summary_min_words: u32,
+    /// Base URL of the OpenAI-compatible API used for summaries, e.g. a
+    /// local Ollama server.
+    pub summary_url: String,
+    /// Model used for summaries, as named by the summary server.
+    pub summary_model: String,
+    /// API key for a remote summary service. Never synced nor sent to the
+    /// frontend.
+    pub summary_api_key: Option<String>,
+    /// Paste or copy the summary instead of the full transcription.
+    pub output_summary: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            redo_shortcut: None,
+            recording_shortcut_variants: Vec::new(),
+            clipboard_audio_shortcut: None,
+            skip_duplicate_paste: false,
+            duplicate_paste_apps: Vec::new(),
+            clipboard_only_apps: TERMINAL_APPS
+                .iter()
+                .chain(&PASSWORD_PROMPT_APPS)
+                .map(|app| app.to_string())
+                .collect(),
+            caret_context_enabled: false,
+            output_mode: OutputMode::default(),
+            app_output_rules: Vec::new(),
+            restore_clipboard: true,
+            accessibility_insertion: false,
+            busy_shortcut_action: BusyShortcutAction::default(),
+            overlay_style: OverlayStyle::default(),
+            overlay_placement: OverlayPlacement::default(),
+            tray_status_enabled: false,
+            history_enabled: false,
+            history_audio: false,
+            save_recordings: false,
+            keep_last_recording: false,
+            history_sync_folder: None,
+            encrypted_sync_url: None,
+            encrypted_sync_username: None,
+            transcription_language: None,
+            decode_options: DecodeOptions::default(),
+            active_model: None,
+            model_cache_mb: 0,
+            use_gpu: true,
+            model_keep_alive: ModelKeepAlive::default(),
+            transcription_engine: TranscriptionEngine::default(),
+            cloud_transcription_url: "https://api.openai.com/v1".to_string(),
+            cloud_transcription_model: "whisper-1".to_string(),
+            cloud_transcription_api_key: None,
+            vocabulary: Vec::new(),
+            replacements: Vec::new(),
+            post_processors: text_processing_service::default_post_processors(),
+            hallucination_blocklist: DEFAULT_HALLUCINATION_BLOCKLIST
+                .iter()
+                .map(|phrase| phrase.to_string())
+                .collect(),
+            list_dictation_enabled: false,
+            plugins_enabled: false,
+            plugin_timeout_ms: 2000,
+            plugin_approvals: Vec::new(),
+            auto_stop_enabled: false,
+            auto_stop_silence_ms: 2000,
+            auto_stop_threshold_db: -45.0,
+            max_recording_minutes: 10,
+            normalize_audio: true,
+            resampler_quality: ResamplerQuality::default(),
+            bluetooth_input_policy: BluetoothInputPolicy::default(),
+            input_channel: None,
+            voice_processing: false,
+            max_parallel_jobs: 1,
+            voice_memo_folder: None,
+            meeting_detection_enabled: false,
+            summary_enabled: false,
+            summary_min_words: 300,
+            summary_url: "http://localhost:11434/v1".to_string(),
+            summary_model: "llama3.2".to_string(),
+            summary_api_key: None,
+            output_summary: false,
+        }
+    }
+}
+
+/// Settings together with the keys locked by managed preferences.
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct SettingsState {
+    /// Effective settings (managed values applied), without the API keys
+    pub settings: Settings,
+    /// Setting keys locked by the organization, sorted
+    pub managed_keys: Vec<String>,
+    /// Whether an API key is set for remote transcription
+    pub cloud_transcription_api_key_set: bool,
+    /// Whether an API key is set for summaries
+    pub summary_api_key_set: bool,
+}
+
+impl SettingsState {
+    /// Build the state sent to the frontend, leaving the API keys out.
+    fn new(mut settings: Settings, managed_keys: Vec<String>) -> Self {
+        Self {
+            cloud_transcription_api_key_set: settings.cloud_transcription_api_key.take().is_some(),
+            summary_api_key_set: settings.summary_api_key.take().is_some(),
+            settings,
+            managed_keys,
+        }
+    }
+}
+
+/// A setting changed by an edit of the settings file.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SettingChange {
+    /// Setting key, as in the settings file
+    pub key: String,
+    /// Value before the edit
+    pub old: Value,
+    /// Value after the edit
+    pub new: Value,
+}
+
+static SETTINGS: OnceLock<RwLock<Settings>> = OnceLock::new();
+
+/// Managed values by setting key, read once at startup.
+static MANAGED: OnceLock<Map<String, Value>> = OnceLock::new();
+
+fn settings_cache() -> &'static RwLock<Settings> {
+    SETTINGS.get_or_init(|| RwLock::new(apply_managed(load_from_disk(), managed_values())))
+}
+
+fn managed_values() -> &'static Map<String, Value> {
+    MANAGED.get_or_init(load_managed)
+}
+
+/// Read managed values from the system file and configuration profiles.
+fn load_managed() -> Map<String, Value> {
+    let managed = read_managed_file(Path::new(MANAGED_SETTINGS_PATH));
+
+    #[cfg(target_os = "macos")]
+    let managed = {
+        let mut managed = managed;
+        let keys: Vec<String> = setting_keys().collect();
+        managed.extend(crate::infrastructure::preferences::forced_values(
+            MANAGED_PREFERENCES_DOMAIN,
+            &keys,
+        ));
+        managed
+    };
+
+    let managed = validate_managed(managed);
+    if !managed.is_empty() {
+        log::info!(
+            "Managed settings in effect: {:?}",
+            managed.keys().collect::<Vec<_>>()
+        );
+    }
+    managed
+}
+
+fn read_managed_file(path: &Path) -> Map<String, Value> {
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return Map::new();
+    };
+    match serde_json::from_str(&contents) {
+        Ok(Value::Object(values)) => values,
+        Ok(_) => {
+            log::warn!("Ignoring {}: expected a JSON object", path.display());
+            Map::new()
+        }
+        Err(e) => {
+            log::warn!("Ignoring {}: {e}", path.display());
+            Map::new()
+        }
+    }
+}
+
+/// Names of all settings keys.
+fn setting_keys() -> impl Iterator<Item = String> {
+    match serde_json::to_value(Settings::default()) {
+        Ok(Value::Object(defaults)) => defaults.into_iter().map(|(key, _)| key).collect(),
+        _ => Vec::new(),
+    }
+    .into_iter()
+}
+
+/// Keep only managed values for known keys that have a valid type.
+fn validate_managed(managed: Map<String, Value>) -> Map<String, Value> {
+    let known: Vec<String> = setting_keys().collect();
+    managed
+        .into_iter()
+        .filter(|(key, value)| {
+            if !known.contains(key) {
+                log::warn!("Ignoring unknown managed setting {key}");
+                return false;
+            }
+            let mut single = Map::new();
+            single.insert(key.clone(), value.clone());
+            let valid = serde_json::from_value::<Settings>(Value::Object(single)).is_ok();
+            if !valid {
+                log::warn!("Ignoring managed setting {key}: invalid value {value}");
+            }
+            valid
+        })
+        .collect()
+}
+
+/// Override settings with managed values.
+fn apply_managed(settings: Settings, managed: &Map<String, Value>) -> Settings {
+    if managed.is_empty() {
+        return settings;
+    }
+    let Ok(Value::Object(mut values)) = serde_json::to_value(&settings) else {
+        return settings;
+    };
+    values.extend(managed.clone());
+    serde_json::from_value(Value::Object(values)).unwrap_or(settings)
+}
+
+/// Find a managed key whose value differs in `settings`.
+fn changed_managed_key(settings: &Settings, managed: &Map<String, Value>) -> Option<String> {
+    let values = serde_json::to_value(settings).ok()?;
+    managed
+        .iter()
+        .find(|(key, value)| values.get(key.as_str()) != Some(*value))
+        .map(|(key, _)| key.clone())
+}
+
+/// Setting keys locked by managed preferences, sorted.
+pub fn managed_keys() -> Vec<String> {
+    let mut keys: Vec<String> = managed_values().keys().cloned().collect();
+    keys.sort();
+    keys
+}
+
+/// Get the settings along with the keys locked by managed preferences, for
+/// the frontend: API keys are replaced by whether they are set.
+pub fn get_settings_state() -> SettingsState {
+    SettingsState::new(get_settings(), managed_keys())
+}
+
+/// Get the path to the settings file.
+pub fn settings_path() -> Result<PathBuf, CyranoError> {
+    data_directory_service::data_root().map(|root| root.join(SETTINGS_FILE_NAME))
+}
+
+/// Read settings from disk, returning defaults on any failure.
+fn load_from_disk() -> Settings {
+    let Ok(path) = settings_path() else {
+        return Settings::default();
+    };
+    if !path.exists() {
+        return Settings::default();
+    }
+
+    std::fs::read_to_string(&path)
+        .inspect_err(|e| log::warn!("Failed to read settings: {e}"))
+        .ok()
+        .and_then(|contents| {
+            serde_json::from_str(&contents)
+                .inspect_err(|e| log::warn!("Failed to parse settings, using defaults: {e}"))
+                .ok()
+        })
+        .unwrap_or_default()
+}
+
+/// Re-read the settings file after it was edited outside of Cyrano.
+///
+/// Managed values still override the edited ones. An invalid file, e.g.
+/// saved halfway through an edit, leaves the current settings in effect.
+///
+/// # Returns
+/// * `Ok(Vec<SettingChange>)` with the settings that changed, empty if none
+/// * `Err(CyranoError::StorageFailed)` if the file cannot be read or parsed
+pub fn reload_from_disk() -> Result<Vec<SettingChange>, CyranoError> {
+    let path = settings_path()?;
+    let contents = std::fs::read_to_string(&path).map_err(|e| CyranoError::StorageFailed {
+        reason: format!("Failed to read {}: {e}", path.display()),
+    })?;
+    let edited: Settings =
+        serde_json::from_str(&contents).map_err(|e| CyranoError::StorageFailed {
+            reason: format!("Invalid settings file, keeping the current settings: {e}"),
+        })?;
+    let edited = apply_managed(edited, managed_values());
+
+    let mut guard = settings_cache()
+        .write()
+        .map_err(|e| CyranoError::StorageFailed {
+            reason: format!("Failed to lock settings: {e}"),
+        })?;
+    let changes = diff_settings(&guard, &edited);
+    *guard = edited;
+    Ok(changes)
+}
+
+/// List the settings whose value differs between `old` and `new`.
+///
+/// For API keys, only whether they are set is reported.
+fn diff_settings(old: &Settings, new: &Settings) -> Vec<SettingChange> {
+    let (Ok(Value::Object(old)), Ok(Value::Object(mut new))) =
+        (serde_json::to_value(old), serde_json::to_value(new))
+    else {
+        return Vec::new();
+    };
+    old.into_iter()
+        .filter_map(|(key, old)| {
+            let new = new.remove(&key)?;
+            if old == new {
+                return None;
+            }
+            let (old, new) = if SECRET_SETTINGS.contains(&key.as_str()) {
+                (Value::Bool(!old.is_null()), Value::Bool(!new.is_null()))
+            } else {
+                (old, new)
+            };
+            Some(SettingChange { key, old, new })
+        })
+        .collect()
+}
+
+/// Get a snapshot of the current settings.
+pub fn get_settings() -> Settings {
+    match settings_cache().read() {
+        Ok(guard) => guard.clone(),
+        Err(e) => {
+            log::error!("Failed to lock settings: {e}");
+            Settings::default()
+        }
+    }
+}
+
+/// Apply a change to the settings and persist them.
+///
+/// # Returns
+/// * `Ok(Settings)` with the updated settings
+/// * `Err(CyranoError::SettingManaged)` if the change touches a managed setting
+/// * `Err(CyranoError::StorageFailed)` if the settings could not be written
+pub fn update_settings<F>(change: F) -> Result<Settings, CyranoError>
+where
+    F: FnOnce(&mut Settings),
+{
+    let mut guard = settings_cache()
+        .write()
+        .map_err(|e| CyranoError::StorageFailed {
+            reason: format!("Failed to lock settings: {e}"),
+        })?;
+
+    let mut updated = guard.clone();
+    change(&mut updated);
+    if let Some(key) = changed_managed_key(&updated, managed_values()) {
+        return Err(CyranoError::SettingManaged { key });
+    }
+    save_to_disk(&updated)?;
+    *guard = updated.clone();
+
+    Ok(updated)
+}
+
+/// Write settings to disk using an atomic write (temp file + rename),
+/// readable by the user only since they may hold API keys.
+fn save_to_disk(settings: &Settings) -> Result<(), CyranoError> {
+    let path = settings_path()?;
+    let json_content =
+        serde_json::to_string_pretty(settings).map_err(|e| CyranoError::StorageFailed {
+            reason: format!("Failed to serialize settings: {e}"),
+        })?;
+
+    data_directory_service::write_private_file_atomically(&path, json_content.as_bytes())?;
+
+    log::debug!("Settings saved to {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::whisper::DecodeStrategy;
+    use crate::utils::test_support::temp_dir;
+
+    #[test]
+    fn test_missing_keys_use_defaults() {
+        let settings: Settings = serde_json::from_str("{}").expect("Should deserialize");
+        assert_eq!(settings, Settings::default());
+    }
+
+    #[test]
+    fn test_history_is_opt_in() {
+        let settings = Settings::default();
+        assert!(!settings.history_enabled);
+        assert!(!settings.history_audio);
+    }
+
+    #[test]
+    fn test_unknown_keys_are_ignored() {
+        let settings: Settings =
+            serde_json::from_str(r#"{"removed_setting": true}"#).expect("Should deserialize");
+        assert_eq!(settings, Settings::default());
+    }
+
+    #[test]
+    fn test_busy_shortcut_action_uses_snake_case() {
+        let settings: Settings = serde_json::from_str(r#"{"busy_shortcut_action": "restart"}"#)
+            .expect("Should deserialize");
+        assert_eq!(settings.busy_shortcut_action, BusyShortcutAction::Restart);
+    }
+
+    fn managed(json: &str) -> Map<String, Value> {
+        match serde_json::from_str(json).unwrap() {
+            Value::Object(values) => values,
+            _ => panic!("expected an object"),
+        }
+    }
+
+    #[test]
+    fn test_managed_values_override_user_settings() {
+        let user = Settings {
+            history_audio: true,
+            ..Settings::default()
+        };
+        let effective = apply_managed(user, &managed(r#"{"history_audio": false}"#));
+        assert!(!effective.history_audio);
+    }
+
+    #[test]
+    fn test_invalid_managed_values_are_ignored() {
+        let values = validate_managed(managed(
+            r#"{"history_audio": "yes", "unknown_key": true, "history_enabled": false}"#,
+        ));
+        assert_eq!(values, managed(r#"{"history_enabled": false}"#));
+    }
+
+    #[test]
+    fn test_changing_a_managed_key_is_detected() {
+        let locked = managed(r#"{"encrypted_sync_url": null}"#);
+        assert_eq!(changed_managed_key(&Settings::default(), &locked), None);
+
+        let changed = Settings {
+            encrypted_sync_url: Some("https://dav.example.com".to_string()),
+            ..Settings::default()
+        };
+        assert_eq!(
+            changed_managed_key(&changed, &locked).as_deref(),
+            Some("encrypted_sync_url")
+        );
+    }
+
+    #[test]
+    fn test_diff_lists_changed_settings() {
+        let old = Settings::default();
+        assert!(diff_settings(&old, &old).is_empty());
+
+        let new = Settings {
+            redo_shortcut: Some("Alt+R".to_string()),
+            use_gpu: false,
+            ..Settings::default()
+        };
+        let changes = diff_settings(&old, &new);
+        let keys: Vec<&str> = changes.iter().map(|c| c.key.as_str()).collect();
+        assert_eq!(keys, ["redo_shortcut", "use_gpu"]);
+        assert_eq!(changes[0].old, Value::Null);
+        assert_eq!(changes[0].new, Value::from("Alt+R"));
+    }
+
+    #[test]
+    fn test_diff_hides_api_keys() {
+        let old = Settings::default();
+        let new = Settings {
+            summary_api_key: Some("sk-test".to_string()),
+            ..Settings::default()
+        };
+        let changes = diff_settings(&old, &new);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].key, "summary_api_key");
+        assert_eq!(changes[0].old, Value::Bool(false));
+        assert_eq!(changes[0].new, Value::Bool(true));
+    }
+
+    #[test]
+    fn test_settings_state_hides_api_keys() {
+        let settings = Settings {
+            cloud_transcription_api_key: Some("sk-cloud".to_string()),
+            ..Settings::default()
+        };
+        let state = SettingsState::new(settings, Vec::new());
+        assert!(state.cloud_transcription_api_key_set);
+        assert!(!state.summary_api_key_set);
+        assert_eq!(state.settings.cloud_transcription_api_key, None);
+
+        let json = serde_json::to_string(&state).expect("Should serialize");
+        assert!(!json.contains("sk-cloud"));
+    }
+
+    #[test]
+    fn test_managed_file_must_be_an_object() {
+        let dir = temp_dir("managed");
+        let path = dir.join("managed-settings.json");
+
+        std::fs::write(&path, "[1, 2]").unwrap();
+        assert!(read_managed_file(&path).is_empty());
+        std::fs::write(&path, r#"{"history_audio": false}"#).unwrap();
+        assert_eq!(read_managed_file(&path).len(), 1);
+        assert!(read_managed_file(&dir.join("missing.json")).is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_settings_round_trip() {
+        let settings = Settings {
+            redo_shortcut: Some("Alt+R".to_string()),
+            recording_shortcut_variants: vec![ShortcutVariant {
+                modifier: "Alt".to_string(),
+                overrides: DictationOverrides {
+                    translate: true,
+                    output_mode: Some(OutputMode::Clipboard),
+                    ..DictationOverrides::default()
+                },
+            }],
+            clipboard_audio_shortcut: Some("Alt+Shift+V".to_string()),
+            skip_duplicate_paste: true,
+            duplicate_paste_apps: vec!["com.example.Editor".to_string()],
+            clipboard_only_apps: vec!["com.example.Shell".to_string()],
+            caret_context_enabled: true,
+            output_mode: OutputMode::Type,
+            app_output_rules: vec![AppOutputRule {
+                bundle_id: "com.microsoft.VSCode".to_string(),
+                mode: OutputMode::Paste,
+                engine: Some(TranscriptionEngine::WhisperLite),
+            }],
+            restore_clipboard: false,
+            accessibility_insertion: true,
+            busy_shortcut_action: BusyShortcutAction::Restart,
+            overlay_style: OverlayStyle::Dot,
+            overlay_placement: OverlayPlacement::Cursor,
+            tray_status_enabled: true,
+            history_enabled: true,
+            history_audio: true,
+            save_recordings: true,
+            keep_last_recording: true,
+            history_sync_folder: Some("/Users/me/Dropbox".to_string()),
+            encrypted_sync_url: Some("https://dav.example.com/cyrano".to_string()),
+            encrypted_sync_username: Some("me".to_string()),
+            transcription_language: Some("fr".to_string()),
+            decode_options: DecodeOptions {
+                strategy: DecodeStrategy::BeamSearch,
+                beam_size: 8,
+                no_speech_threshold: 0.8,
+                suppress_blank: false,
+                word_timestamps: true,
+                ..DecodeOptions::default()
+            },
+            active_model: Some("ggml-small.bin".to_string()),
+            model_cache_mb: 4096,
+            use_gpu: false,
+            model_keep_alive: ModelKeepAlive::NeverUnload,
+            transcription_engine: TranscriptionEngine::Remote,
+            cloud_transcription_url: "http://localhost:8000/v1".to_string(),
+            cloud_transcription_model: "large-v3".to_string(),
+            cloud_transcription_api_key: Some("sk-cloud".to_string()),
+            vocabulary: vec!["Kubernetes".to_string()],
+            replacements: vec![ReplacementRule {
+                from: "new line".to_string(),
+                to: "\n".to_string(),
+            }],
+            post_processors: vec![PostProcessorKind::Trim, PostProcessorKind::Capitalize],
+            hallucination_blocklist: vec!["Thanks for listening".to_string()],
+            list_dictation_enabled: true,
+            plugins_enabled: true,
+            plugin_timeout_ms: 500,
+            plugin_approvals: vec![PluginApproval {
+                name: "10-format".to_string(),
+                sha256: "ab".repeat(32),
+                allowed: true,
+            }],
+            auto_stop_enabled: true,
+            auto_stop_silence_ms: 1500,
+            auto_stop_threshold_db: -38.5,
+            max_recording_minutes: 30,
+            normalize_audio: false,
+            resampler_quality: ResamplerQuality::High,
+            bluetooth_input_policy: BluetoothInputPolicy::PreferBuiltIn,
+            input_channel: Some(3),
+            voice_processing: true,
+            max_parallel_jobs: 2,
+            voice_memo_folder: Some("/Users/me/Downloads".to_string()),
+            meeting_detection_enabled: true,
+            summary_enabled: true,
+            summary_min_words: 150,
+            summary_url: "https://api.example.com/v1".to_string(),
+            summary_model: "gpt-4o-mini".to_string(),
+            summary_api_key: Some("sk-test".to_string()),
+            output_summary: true,
+        };
+        let json = serde_json::to_string(&settings).expect("Should serialize");
+        let parsed: Settings = serde_json::from_str(&json).expect("Should deserialize");
+        assert_eq!(parsed, settings);
+    }
+}
@@ -0,0 +1,127 @@
+//! Watcher for edits of the settings file made outside of Cyrano.
+//!
+//! Users and dotfile managers edit `~/.cyrano/settings.json` directly. The
+//! watcher polls its modification time and, when it changes, reloads the
+//! settings and applies what services do not read on each use: global
+//! shortcuts are re-registered and the loaded model is swapped. It then
+//! emits settings-reloaded with the changed settings, so the frontend can
+//! refresh its view.
+//!
+//! Saves made by Cyrano itself also touch the file, but leave nothing to
+//! reload, so they emit nothing.
+
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use tauri::AppHandle;
+
+use crate::services::settings_service::{self, SettingChange};
+use crate::services::{notifier_service, transcription_service};
+
+/// How often the settings file is checked.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Payload for the settings-reloaded event.
+#[derive(Clone, serde::Serialize)]
+pub struct SettingsReloadedPayload {
+    /// Settings changed by the edit, with their old and new values
+    pub changes: Vec<SettingChange>,
+}
+
+/// Modification time of the file, if it exists.
+fn modified_at(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Start watching the settings file for external edits.
+pub fn start_watcher(app: &AppHandle) {
+    let Ok(path) = settings_service::settings_path() else {
+        log::warn!("Settings file location unknown, edits will not be reloaded");
+        return;
+    };
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let mut last_modified = modified_at(&path);
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+            let modified = modified_at(&path);
+            if modified == last_modified {
+                continue;
+            }
+            last_modified = modified;
+            if modified.is_some() {
+                reload(&app);
+            }
+        }
+    });
+}
+
+/// Reload the edited settings, apply them and announce the changes.
+fn reload(app: &AppHandle) {
+    let changes = match settings_service::reload_from_disk() {
+        Ok(changes) if changes.is_empty() => return,
+        Ok(changes) => changes,
+        Err(e) => {
+            log::warn!("[{}] Failed to reload the settings file: {e}", e.code());
+            return;
+        }
+    };
+    let keys: Vec<&str> = changes.iter().map(|c| c.key.as_str()).collect();
+    log::info!("Settings file edited, reloaded: {}", keys.join(", "));
+
+    for key in &keys {
+        apply_change(app, key);
+    }
+
+    let payload = SettingsReloadedPayload { changes };
+    if let Err(e) = notifier_service::emit(app, "settings-reloaded", payload) {
+        log::error!("Failed to emit settings-reloaded event: {e}");
+    }
+}
+
+/// Apply a reloaded setting that is not read on each use.
+fn apply_change(app: &AppHandle, key: &str) {
+    match key {
+        "active_model" | "use_gpu" | "transcription_engine" => {
+            transcription_service::reload_model_if_loaded()
+        }
+        #[cfg(desktop)]
+        "redo_shortcut" | "clipboard_audio_shortcut" | "recording_shortcut_variants" => {
+            if let Err(e) = register_shortcut(app, key) {
+                log::error!("[{}] Failed to re-register {key}: {e}", e.code());
+            }
+        }
+        _ => {}
+    }
+    #[cfg(not(desktop))]
+    let _ = app;
+}
+
+/// Re-register the global shortcut stored under `key`.
+#[cfg(desktop)]
+fn register_shortcut(app: &AppHandle, key: &str) -> Result<(), crate::domain::CyranoError> {
+    use crate::services::shortcut_service;
+
+    let settings = settings_service::get_settings();
+    match key {
+        "redo_shortcut" => match settings.redo_shortcut.as_deref() {
+            Some(shortcut) => shortcut_service::register_redo_shortcut(app, shortcut),
+            None => {
+                shortcut_service::unregister_redo_shortcut(app);
+                Ok(())
+            }
+        },
+        "clipboard_audio_shortcut" => match settings.clipboard_audio_shortcut.as_deref() {
+            Some(shortcut) => shortcut_service::register_clipboard_audio_shortcut(app, shortcut),
+            None => {
+                shortcut_service::unregister_clipboard_audio_shortcut(app);
+                Ok(())
+            }
+        },
+        "recording_shortcut_variants" => shortcut_service::register_recording_variants(
+            app,
+            &settings.recording_shortcut_variants,
+        ),
+        _ => Ok(()),
+    }
+}
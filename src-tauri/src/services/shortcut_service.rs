@@ -6,15 +6,57 @@
 
 use std::sync::Mutex;
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
-use tauri::{AppHandle, Emitter};
+use tauri::AppHandle;
 
 use crate::domain::CyranoError;
+use crate::services::settings_service::{self, DictationOverrides, ShortcutVariant};
+use crate::services::{
+    dictation_service, file_transcription_service, notifier_service, redo_service,
+};
+
 /// Default recording shortcut (Cmd+Shift+Space on macOS, Ctrl+Shift+Space elsewhere)
 pub const DEFAULT_RECORDING_SHORTCUT: &str = "CommandOrControl+Shift+Space";
 
+/// Redo shortcut suggested when the user enables redo (Cmd+Shift+Backspace on
+/// macOS, Ctrl+Shift+Backspace elsewhere). Nothing is registered until the
+/// user sets one.
+pub const DEFAULT_REDO_SHORTCUT: &str = "CommandOrControl+Shift+Backspace";
+
 /// Tracks the currently registered recording shortcut for selective unregistration.
 static CURRENT_RECORDING_SHORTCUT: Mutex<Option<String>> = Mutex::new(None);
 
+/// Tracks the registered variants of the recording shortcut.
+static CURRENT_RECORDING_VARIANTS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Tracks the currently registered redo shortcut for selective unregistration.
+static CURRENT_REDO_SHORTCUT: Mutex<Option<String>> = Mutex::new(None);
+
+/// Tracks the currently registered clipboard audio shortcut, if any.
+static CURRENT_CLIPBOARD_AUDIO_SHORTCUT: Mutex<Option<String>> = Mutex::new(None);
+
+/// Shortcuts that failed to register, with the shortcut string, retried
+/// when permissions change.
+static DEFERRED_SHORTCUTS: Mutex<Vec<(ShortcutKind, String)>> = Mutex::new(Vec::new());
+
+/// A global shortcut registered by the app.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortcutKind {
+    Recording,
+    Redo,
+    ClipboardAudio,
+}
+
+impl ShortcutKind {
+    /// Tracks the currently registered shortcut of this kind.
+    fn current(self) -> &'static Mutex<Option<String>> {
+        match self {
+            ShortcutKind::Recording => &CURRENT_RECORDING_SHORTCUT,
+            ShortcutKind::Redo => &CURRENT_REDO_SHORTCUT,
+            ShortcutKind::ClipboardAudio => &CURRENT_CLIPBOARD_AUDIO_SHORTCUT,
+        }
+    }
+}
+
 /// Payload emitted when the recording shortcut is pressed.
 #[derive(Clone, serde::Serialize)]
 pub struct RecordingShortcutPayload {
@@ -38,292 +80,444 @@ fn get_timestamp_ms() -> u64 {
 ///
 /// # Returns
 /// * `Ok(())` if the shortcut was registered successfully
-/// * `Err(String)` if registration failed
+/// * `Err(CyranoError::ShortcutRegistrationFailed)` if registration failed
 #[cfg(desktop)]
 pub fn register_recording_shortcut(
     app_handle: &AppHandle,
     shortcut_str: &str,
 ) -> Result<(), CyranoError> {
-    use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+    let app_handle_clone = app_handle.clone();
+
+    replace_shortcut(
+        app_handle,
+        ShortcutKind::Recording,
+        shortcut_str,
+        move || on_recording_shortcut(&app_handle_clone, DictationOverrides::default()),
+    )
+    .map_err(|e| CyranoError::ShortcutRegistrationFailed {
+        shortcut: shortcut_str.to_string(),
+        reason: e,
+    })?;
+
+    // The variants follow the recording shortcut
+    let variants = settings_service::get_settings().recording_shortcut_variants;
+    if let Err(e) = register_recording_variants(app_handle, &variants) {
+        log::warn!("[{}] Recording shortcut variant unavailable: {e}", e.code());
+    }
+    Ok(())
+}
+
+/// Handle a press of the recording shortcut or one of its variants.
+#[cfg(desktop)]
+fn on_recording_shortcut(app_handle: &AppHandle, overrides: DictationOverrides) {
+    let start = Instant::now();
+    let timestamp = get_timestamp_ms();
+    log::info!("Recording shortcut triggered at timestamp: {timestamp}");
+
+    let payload = RecordingShortcutPayload { timestamp };
+
+    if let Err(e) = notifier_service::emit(app_handle, "recording-shortcut-pressed", payload) {
+        log::error!("Failed to emit recording-shortcut-pressed event: {e}");
+    }
+
+    // Toggle behavior: stop and transcribe if recording, start if not
+    dictation_service::toggle_dictation_with(app_handle, overrides);
+
+    let elapsed_ms = start.elapsed().as_millis();
+    log::info!("Recording shortcut handler duration: {elapsed_ms}ms");
+    if elapsed_ms > 100 {
+        log::warn!("Recording shortcut handler exceeded 100ms threshold: {elapsed_ms}ms");
+    }
+}
+
+/// Registers the variants of the recording shortcut (the recording shortcut
+/// plus a modifier), replacing the previous ones.
+///
+/// A variant toggles dictation like the recording shortcut, and a dictation
+/// it starts applies the variant's overrides, e.g. translation to English.
+/// Variants with an unknown modifier, or one already in the recording
+/// shortcut, are skipped.
+///
+/// # Returns
+/// * `Ok(())` if every valid variant was registered
+/// * `Err(CyranoError::ShortcutRegistrationFailed)` for the last variant that
+///   failed to register; the others are still registered
+#[cfg(desktop)]
+pub fn register_recording_variants(
+    app_handle: &AppHandle,
+    variants: &[ShortcutVariant],
+) -> Result<(), CyranoError> {
+    use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
 
     let global_shortcut = app_handle.global_shortcut();
+    let mut current = CURRENT_RECORDING_VARIANTS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    for shortcut in current.drain(..) {
+        unregister_tracked(global_shortcut, &mut Some(shortcut));
+    }
 
-    // Lock the mutex to get the current shortcut and update it atomically
-    let mut current_shortcut =
-        CURRENT_RECORDING_SHORTCUT
-            .lock()
-            .map_err(|e| CyranoError::RecordingFailed {
-                reason: format!("Failed to lock recording shortcut mutex: {e}"),
-            })?;
+    // Registered along with the recording shortcut otherwise
+    let Some(base) = CURRENT_RECORDING_SHORTCUT
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+    else {
+        return Ok(());
+    };
 
-    // Unregister the old shortcut if one exists
-    if let Some(old_shortcut_str) = current_shortcut.take() {
-        log::debug!("Unregistering old recording shortcut: {old_shortcut_str}");
-        match old_shortcut_str.parse::<Shortcut>() {
-            Ok(old_shortcut) => {
-                if let Err(e) = global_shortcut.unregister(old_shortcut) {
-                    log::warn!(
-                        "Failed to unregister old recording shortcut '{old_shortcut_str}': {e}"
-                    );
-                    // Continue anyway - the old shortcut may have already been unregistered
+    let mut result = Ok(());
+    for variant in variants {
+        let Some(shortcut_str) = variant_shortcut(&base, &variant.modifier) else {
+            log::warn!(
+                "Skipping recording shortcut variant with modifier '{}'",
+                variant.modifier
+            );
+            continue;
+        };
+        let app_handle_clone = app_handle.clone();
+        let overrides = variant.overrides.clone();
+        let registered =
+            global_shortcut.on_shortcut(shortcut_str.as_str(), move |_app, _shortcut, event| {
+                if event.state == ShortcutState::Pressed {
+                    on_recording_shortcut(&app_handle_clone, overrides.clone());
                 }
+            });
+        match registered {
+            Ok(()) => {
+                log::debug!("Registered recording shortcut variant: {shortcut_str}");
+                current.push(shortcut_str);
             }
             Err(e) => {
-                log::warn!("Failed to parse old recording shortcut '{old_shortcut_str}': {e}");
-                // Continue anyway - if we can't parse it, we can't unregister it
+                result = Err(CyranoError::ShortcutRegistrationFailed {
+                    shortcut: shortcut_str,
+                    reason: e.to_string(),
+                })
             }
         }
     }
+    result
+}
+
+/// The recording shortcut `base` with `modifier` added.
+///
+/// # Returns
+/// * `Some(shortcut)`, e.g. `Alt+CommandOrControl+Shift+Space`
+/// * `None` if the modifier is unknown or already part of `base`
+fn variant_shortcut(base: &str, modifier: &str) -> Option<String> {
+    let modifier = canonical_modifier(modifier)?;
+    let in_base = base
+        .split('+')
+        .filter_map(canonical_modifier)
+        .any(|existing| existing == modifier);
+    (!in_base).then(|| format!("{modifier}+{base}"))
+}
 
-    // Clone app_handle for the closure
+/// Name of a modifier as accepted by the global shortcut parser.
+fn canonical_modifier(name: &str) -> Option<&'static str> {
+    let command_or_control = if cfg!(target_os = "macos") {
+        "Super"
+    } else {
+        "Control"
+    };
+    match name.trim().to_ascii_lowercase().as_str() {
+        "alt" | "option" => Some("Alt"),
+        "control" | "ctrl" => Some("Control"),
+        "shift" => Some("Shift"),
+        "super" | "command" | "cmd" => Some("Super"),
+        "commandorcontrol" | "cmdorctrl" | "commandorctrl" | "cmdorcontrol" => {
+            Some(command_or_control)
+        }
+        _ => None,
+    }
+}
+
+/// Registers the redo global shortcut, unregistering any previously registered one.
+///
+/// Pressing it shortly after a dictation deletes the inserted text and
+/// starts a new recording (see `redo_service`).
+///
+/// # Arguments
+/// * `app_handle` - The Tauri application handle
+/// * `shortcut_str` - The shortcut string to register (e.g., "CommandOrControl+Shift+Backspace")
+///
+/// # Returns
+/// * `Ok(())` if the shortcut was registered successfully
+/// * `Err(CyranoError::ShortcutRegistrationFailed)` if registration failed
+#[cfg(desktop)]
+pub fn register_redo_shortcut(
+    app_handle: &AppHandle,
+    shortcut_str: &str,
+) -> Result<(), CyranoError> {
     let app_handle_clone = app_handle.clone();
 
-    // Register the new shortcut with handler
-    global_shortcut
-        .on_shortcut(shortcut_str, move |_app, _shortcut, event| {
-            use tauri_plugin_global_shortcut::ShortcutState;
-            if event.state == ShortcutState::Pressed {
-                let start = Instant::now();
-                let timestamp = get_timestamp_ms();
-                log::info!("Recording shortcut triggered at timestamp: {timestamp}");
+    replace_shortcut(app_handle, ShortcutKind::Redo, shortcut_str, move || {
+        log::info!("Redo shortcut triggered");
+        if let Err(e) = redo_service::redo_last_dictation(&app_handle_clone) {
+            log::warn!("Redo failed: {e}");
+        }
+    })
+    .map_err(|e| CyranoError::ShortcutRegistrationFailed {
+        shortcut: shortcut_str.to_string(),
+        reason: e,
+    })
+}
 
-                let payload = RecordingShortcutPayload { timestamp };
+/// Unregisters the redo shortcut, if one is registered.
+#[cfg(desktop)]
+pub fn unregister_redo_shortcut(app_handle: &AppHandle) {
+    use tauri_plugin_global_shortcut::GlobalShortcutExt;
 
-                if let Err(e) = app_handle_clone.emit("recording-shortcut-pressed", payload) {
-                    log::error!("Failed to emit recording-shortcut-pressed event: {e}");
-                }
+    forget_deferred(ShortcutKind::Redo);
+    let mut current = CURRENT_REDO_SHORTCUT
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    unregister_tracked(app_handle.global_shortcut(), &mut current);
+}
 
-                // Toggle behavior: Check if recording is active, stop if so, start if not
-                if crate::services::recording_service::is_recording() {
-                    // Toggle off: stop recording
-                    match crate::services::recording_service::stop_recording(&app_handle_clone) {
-                        Ok(payload) => {
-                            log::info!(
-                                "Recording stopped: {}ms, {} samples",
-                                payload.duration_ms,
-                                payload.sample_count
-                            );
-                            // Overlay stays visible, state transitions to Transcribing
-
-                            // Ensure model is loaded before transcription (Story 2.1)
-                            // Model loading AND transcription are CPU-intensive, so run on spawned thread
-                            let app_for_model = app_handle_clone.clone();
-                            std::thread::spawn(move || {
-                                // Clear any previous cancellation flag
-                                crate::services::transcription_service::clear_cancellation();
-
-                                match crate::services::transcription_service::ensure_model_loaded() {
-                                    Ok(()) => {
-                                        log::info!("Whisper model ready, starting transcription");
-
-                                        // Emit transcription-started event
-                                        let transcription_start = get_timestamp_ms();
-                                        let _ = app_for_model.emit(
-                                            "transcription-started",
-                                            crate::services::recording_service::TranscriptionStartedPayload {
-                                                timestamp: transcription_start,
-                                            },
-                                        );
-
-                                        // Get audio samples
-                                        let samples = match crate::services::recording_state::take_audio_samples() {
-                                            Ok(s) => s,
-                                            Err(e) => {
-                                                log::error!("Failed to get audio samples: {e}");
-                                                crate::services::recording_state::set_recording_state(
-                                                    crate::domain::RecordingState::Error,
-                                                );
-                                                let _ = app_for_model.emit(
-                                                    "transcription-failed",
-                                                    crate::services::recording_service::TranscriptionFailedPayload {
-                                                        error: crate::domain::CyranoError::TranscriptionFailed {
-                                                            reason: e,
-                                                        },
-                                                    },
-                                                );
-                                                return;
-                                            }
-                                        };
-
-                                        // Perform transcription
-                                        match crate::services::transcription_service::transcribe(&samples) {
-                                            Ok(text) => {
-                                                let duration_ms = (get_timestamp_ms() - transcription_start) as u32;
-                                                log::info!(
-                                                    "Transcription complete: {} chars in {}ms",
-                                                    text.len(),
-                                                    duration_ms
-                                                );
-
-                                                // Output transcription (FR12 + FR13):
-                                                // 1. Copy to clipboard (always)
-                                                // 2. Insert at cursor via Cmd+V (if accessibility granted)
-                                                match crate::services::output_service::output_transcription(&text, &app_for_model) {
-                                                    Ok(cursor_inserted) => {
-                                                        if cursor_inserted {
-                                                            log::debug!("Clipboard copy and cursor insertion succeeded");
-                                                        } else {
-                                                            log::debug!("Clipboard copy succeeded (cursor insertion not available)");
-                                                        }
-                                                        // Emit clipboard-copied event for UI feedback
-                                                        let _ = app_for_model.emit(
-                                                            "clipboard-copied",
-                                                            crate::services::recording_service::ClipboardCopiedPayload {
-                                                                text_length: text.len() as u32,
-                                                            },
-                                                        );
-                                                    }
-                                                    Err(e) => {
-                                                        // Clipboard failure is non-fatal - log and continue
-                                                        // User still gets the transcription, just needs to manually copy
-                                                        log::warn!("Output failed: {e}");
-                                                        let _ = app_for_model.emit(
-                                                            "clipboard-failed",
-                                                            crate::services::recording_service::ClipboardFailedPayload {
-                                                                error: e,
-                                                            },
-                                                        );
-                                                    }
-                                                }
-
-                                                crate::services::recording_state::set_recording_state(
-                                                    crate::domain::RecordingState::Done,
-                                                );
-                                                let _ = app_for_model.emit(
-                                                    "transcription-complete",
-                                                    crate::services::recording_service::TranscriptionCompletePayload {
-                                                        text,
-                                                        duration_ms,
-                                                    },
-                                                );
-                                            }
-                                            Err(e) => {
-                                                // Check if this was a cancellation
-                                                let is_cancellation = matches!(&e, crate::domain::CyranoError::TranscriptionFailed { reason } if reason.contains("cancelled"));
-
-                                                if is_cancellation {
-                                                    log::info!("Transcription was cancelled");
-                                                    crate::services::recording_state::set_recording_state(
-                                                        crate::domain::RecordingState::Idle,
-                                                    );
-                                                    let _ = app_for_model.emit(
-                                                        "transcription-cancelled",
-                                                        crate::services::recording_service::TranscriptionCancelledPayload {
-                                                            timestamp: get_timestamp_ms(),
-                                                        },
-                                                    );
-                                                } else {
-                                                    log::error!("Transcription failed: {e}");
-                                                    crate::services::recording_state::set_recording_state(
-                                                        crate::domain::RecordingState::Error,
-                                                    );
-                                                    let _ = app_for_model.emit(
-                                                        "transcription-failed",
-                                                        crate::services::recording_service::TranscriptionFailedPayload {
-                                                            error: e,
-                                                        },
-                                                    );
-                                                }
-                                            }
-                                        }
-                                    }
-                                    Err(e) => {
-                                        log::error!("Model loading failed: {e}");
-                                        // Set state to Error and emit recording-failed event
-                                        crate::services::recording_state::set_recording_state(
-                                            crate::domain::RecordingState::Error,
-                                        );
-                                        let payload =
-                                            crate::services::recording_service::RecordingFailedPayload {
-                                                error: e,
-                                            };
-                                        if let Err(emit_err) =
-                                            app_for_model.emit("recording-failed", payload)
-                                        {
-                                            log::error!(
-                                                "Failed to emit recording-failed event: {emit_err}"
-                                            );
-                                        }
-                                    }
-                                }
-                            });
-                        }
-                        Err(e) => {
-                            log::error!("Failed to stop recording: {e}");
-                            // Emit error event for overlay to display
-                            let payload =
-                                crate::services::recording_service::RecordingFailedPayload {
-                                    error: e,
-                                };
-                            if let Err(emit_err) =
-                                app_handle_clone.emit("recording-failed", payload)
-                            {
-                                log::error!("Failed to emit recording-failed event: {emit_err}");
-                            }
-                        }
-                    }
-                } else {
-                    // Toggle on: start recording
-                    match crate::services::recording_service::start_recording(&app_handle_clone) {
-                        Ok(()) => {
-                            log::info!("Recording started successfully");
-                            // Show the recording overlay when recording starts
-                            if let Err(e) =
-                                crate::commands::recording_overlay::show_recording_overlay(
-                                    app_handle_clone.clone(),
-                                )
-                            {
-                                log::error!("Failed to show recording overlay: {e}");
-                            }
-                        }
-                        Err(e) => {
-                            log::error!("Failed to start recording: {e}");
-                            // Show overlay first so it can receive the error event
-                            if let Err(overlay_err) =
-                                crate::commands::recording_overlay::show_recording_overlay(
-                                    app_handle_clone.clone(),
-                                )
-                            {
-                                log::error!("Failed to show recording overlay: {overlay_err}");
-                            }
-                            // Now emit the recording-failed event so the overlay displays error state
-                            let payload =
-                                crate::services::recording_service::RecordingFailedPayload {
-                                    error: e,
-                                };
-                            if let Err(emit_err) =
-                                app_handle_clone.emit("recording-failed", payload)
-                            {
-                                log::error!("Failed to emit recording-failed event: {emit_err}");
-                            }
-                        }
-                    }
-                }
+/// Registers the global shortcut that transcribes the audio on the clipboard,
+/// unregistering any previously registered one.
+///
+/// # Arguments
+/// * `app_handle` - The Tauri application handle
+/// * `shortcut_str` - The shortcut string to register (e.g., "Alt+Shift+V")
+///
+/// # Returns
+/// * `Ok(())` if the shortcut was registered successfully
+/// * `Err(CyranoError::ShortcutRegistrationFailed)` if registration failed
+#[cfg(desktop)]
+pub fn register_clipboard_audio_shortcut(
+    app_handle: &AppHandle,
+    shortcut_str: &str,
+) -> Result<(), CyranoError> {
+    let app_handle_clone = app_handle.clone();
 
-                let elapsed_ms = start.elapsed().as_millis();
-                log::info!("Recording shortcut handler duration: {elapsed_ms}ms");
-                if elapsed_ms > 100 {
-                    log::warn!(
-                        "Recording shortcut handler exceeded 100ms threshold: {elapsed_ms}ms"
-                    );
-                }
+    replace_shortcut(
+        app_handle,
+        ShortcutKind::ClipboardAudio,
+        shortcut_str,
+        move || {
+            log::info!("Clipboard audio shortcut triggered");
+            if let Err(e) =
+                file_transcription_service::transcribe_clipboard_audio(&app_handle_clone)
+            {
+                log::warn!("[{}] Clipboard audio transcription failed: {e}", e.code());
+                file_transcription_service::notify_failure(&app_handle_clone, &e);
+            }
+        },
+    )
+    .map_err(|e| CyranoError::ShortcutRegistrationFailed {
+        shortcut: shortcut_str.to_string(),
+        reason: e,
+    })
+}
+
+/// Unregisters the clipboard audio shortcut, if one is registered.
+#[cfg(desktop)]
+pub fn unregister_clipboard_audio_shortcut(app_handle: &AppHandle) {
+    use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+    forget_deferred(ShortcutKind::ClipboardAudio);
+    let mut current = CURRENT_CLIPBOARD_AUDIO_SHORTCUT
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    unregister_tracked(app_handle.global_shortcut(), &mut current);
+}
+
+/// Check that the recording shortcut is registered with the system, for the
+/// startup self-test.
+#[cfg(desktop)]
+pub fn check_recording_shortcut(app_handle: &AppHandle) -> Result<(), String> {
+    use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+    let current = CURRENT_RECORDING_SHORTCUT
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+    match current {
+        Some(shortcut)
+            if app_handle
+                .global_shortcut()
+                .is_registered(shortcut.as_str()) =>
+        {
+            Ok(())
+        }
+        Some(shortcut) => Err(format!("{shortcut} is not registered")),
+        None => Err("No recording shortcut registered".to_string()),
+    }
+}
+
+/// Register a shortcut of the given kind, replacing the previous one.
+#[cfg(desktop)]
+fn register_shortcut(
+    app_handle: &AppHandle,
+    kind: ShortcutKind,
+    shortcut_str: &str,
+) -> Result<(), CyranoError> {
+    match kind {
+        ShortcutKind::Recording => register_recording_shortcut(app_handle, shortcut_str),
+        ShortcutKind::Redo => register_redo_shortcut(app_handle, shortcut_str),
+        ShortcutKind::ClipboardAudio => register_clipboard_audio_shortcut(app_handle, shortcut_str),
+    }
+}
+
+/// Register a shortcut, keeping it for [`retry_deferred_shortcuts`] if the
+/// system refuses it.
+///
+/// On macOS, shortcuts can fail to register until the app is allowed under
+/// Input Monitoring or Accessibility; retrying once the user grants the
+/// permission saves a restart.
+#[cfg(desktop)]
+pub fn register_or_defer(
+    app_handle: &AppHandle,
+    kind: ShortcutKind,
+    shortcut_str: &str,
+) -> Result<(), CyranoError> {
+    let result = register_shortcut(app_handle, kind, shortcut_str);
+    if result.is_err() {
+        let mut deferred = DEFERRED_SHORTCUTS.lock().unwrap_or_else(|e| e.into_inner());
+        defer(&mut deferred, kind, shortcut_str);
+    }
+    result
+}
+
+/// Retry registering the shortcuts that failed, keeping those that still fail.
+#[cfg(desktop)]
+pub fn retry_deferred_shortcuts(app_handle: &AppHandle) {
+    let deferred =
+        std::mem::take(&mut *DEFERRED_SHORTCUTS.lock().unwrap_or_else(|e| e.into_inner()));
+    for (kind, shortcut) in deferred {
+        match register_or_defer(app_handle, kind, &shortcut) {
+            Ok(()) => {
+                log::info!("Registered {kind:?} shortcut {shortcut} after a permission change")
+            }
+            Err(e) => log::warn!("[{}] {kind:?} shortcut still unavailable: {e}", e.code()),
+        }
+    }
+}
+
+/// Keep `shortcut` for a retry, replacing any earlier shortcut of its kind.
+fn defer(deferred: &mut Vec<(ShortcutKind, String)>, kind: ShortcutKind, shortcut: &str) {
+    deferred.retain(|(deferred_kind, _)| *deferred_kind != kind);
+    deferred.push((kind, shortcut.to_string()));
+}
+
+/// Drop the pending retry of a kind, once it is registered or turned off.
+fn forget_deferred(kind: ShortcutKind) {
+    DEFERRED_SHORTCUTS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .retain(|(deferred_kind, _)| *deferred_kind != kind);
+}
+
+/// Unregisters the current shortcut of `kind`, then registers `shortcut_str`
+/// with `on_pressed` as its key-down handler and tracks it.
+#[cfg(desktop)]
+fn replace_shortcut<F>(
+    app_handle: &AppHandle,
+    kind: ShortcutKind,
+    shortcut_str: &str,
+    on_pressed: F,
+) -> Result<(), String>
+where
+    F: Fn() + Send + Sync + 'static,
+{
+    use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+    let global_shortcut = app_handle.global_shortcut();
+
+    // Lock the mutex to get the current shortcut and update it atomically
+    let mut current_shortcut = kind
+        .current()
+        .lock()
+        .map_err(|e| format!("Failed to lock shortcut mutex: {e}"))?;
+
+    // Unregister the old shortcut if one exists
+    unregister_tracked(global_shortcut, &mut current_shortcut);
+
+    // Register the new shortcut with handler
+    global_shortcut
+        .on_shortcut(shortcut_str, move |_app, _shortcut, event| {
+            if event.state == ShortcutState::Pressed {
+                on_pressed();
             }
         })
-        .map_err(|e| CyranoError::RecordingFailed {
-            reason: format!("Failed to register recording shortcut '{shortcut_str}': {e}"),
-        })?;
+        .map_err(|e| e.to_string())?;
 
     // Store the new shortcut for future unregistration
     *current_shortcut = Some(shortcut_str.to_string());
-    log::debug!("Registered recording shortcut: {shortcut_str}");
+    forget_deferred(kind);
+    log::debug!("Registered shortcut: {shortcut_str}");
 
     Ok(())
 }
 
+/// Unregisters the shortcut tracked in `current` and clears it.
+#[cfg(desktop)]
+fn unregister_tracked(
+    global_shortcut: &tauri_plugin_global_shortcut::GlobalShortcut<tauri::Wry>,
+    current: &mut Option<String>,
+) {
+    use tauri_plugin_global_shortcut::Shortcut;
+
+    let Some(old_shortcut_str) = current.take() else {
+        return;
+    };
+    log::debug!("Unregistering old shortcut: {old_shortcut_str}");
+    match old_shortcut_str.parse::<Shortcut>() {
+        Ok(old_shortcut) => {
+            if let Err(e) = global_shortcut.unregister(old_shortcut) {
+                log::warn!("Failed to unregister old shortcut '{old_shortcut_str}': {e}");
+                // Continue anyway - the old shortcut may have already been unregistered
+            }
+        }
+        Err(e) => {
+            log::warn!("Failed to parse old shortcut '{old_shortcut_str}': {e}");
+            // Continue anyway - if we can't parse it, we can't unregister it
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_deferring_a_shortcut_replaces_its_earlier_retry() {
+        let mut deferred = Vec::new();
+        defer(&mut deferred, ShortcutKind::Recording, "Alt+Space");
+        defer(&mut deferred, ShortcutKind::Redo, "Alt+Backspace");
+        defer(&mut deferred, ShortcutKind::Recording, "Alt+R");
+
+        assert_eq!(
+            deferred,
+            vec![
+                (ShortcutKind::Redo, "Alt+Backspace".to_string()),
+                (ShortcutKind::Recording, "Alt+R".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_variant_shortcut_adds_a_new_modifier() {
+        let base = DEFAULT_RECORDING_SHORTCUT;
+        assert_eq!(
+            variant_shortcut(base, "Option").as_deref(),
+            Some("Alt+CommandOrControl+Shift+Space")
+        );
+        assert_eq!(variant_shortcut(base, " shift "), None);
+        assert_eq!(variant_shortcut(base, "Hyper"), None);
+        // CommandOrControl already holds Command on macOS, Control elsewhere
+        assert_eq!(
+            variant_shortcut(base, "Control").is_some(),
+            cfg!(target_os = "macos")
+        );
+        assert_eq!(
+            variant_shortcut(base, "Super").is_some(),
+            !cfg!(target_os = "macos")
+        );
+    }
+
     #[test]
     fn test_default_recording_shortcut_is_valid() {
         // Verify the default shortcut constant is a non-empty string
@@ -333,6 +527,13 @@ mod tests {
         assert!(DEFAULT_RECORDING_SHORTCUT.contains("Space"));
     }
 
+    #[test]
+    fn test_default_redo_shortcut_is_valid() {
+        assert!(DEFAULT_REDO_SHORTCUT.contains("CommandOrControl"));
+        assert!(DEFAULT_REDO_SHORTCUT.contains("Backspace"));
+        assert_ne!(DEFAULT_REDO_SHORTCUT, DEFAULT_RECORDING_SHORTCUT);
+    }
+
     #[test]
     fn test_get_timestamp_ms_returns_reasonable_value() {
         let ts = get_timestamp_ms();
@@ -0,0 +1,274 @@
+//! Cleanup of files left behind in the data directories.
+//!
+//! Interrupted imports and crashes can leave temporary WAVs in the system
+//! temp directory, atomic writes can leave `.tmp` files next to their target,
+//! and the legacy layout kept logs forever. The scan only reports what could
+//! be deleted; deleting is a separate, confirmed step.
+//!
+//! History, saved recordings, models and the crash recovery files are never
+//! candidates.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use serde::Serialize;
+use specta::Type;
+
+use crate::domain::CyranoError;
+use crate::services::data_directory_service::{
+    self, LOGS_DIR_NAME, MODELS_DIR_NAME, RECORDINGS_DIR_NAME,
+};
+
+/// Temporary files younger than this may still be in use.
+const TEMP_FILE_MIN_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Logs are kept this long.
+const LOG_MAX_AGE: Duration = Duration::from_secs(14 * 24 * 60 * 60);
+
+/// Prefix of the files the app writes to the system temp directory.
+const TEMP_FILE_PREFIX: &str = "cyrano-";
+
+/// Extension of a model file being downloaded.
+pub const PARTIAL_DOWNLOAD_EXTENSION: &str = "part";
+
+/// Raw sample files in the recordings directory owned by crash recovery.
+const RECOVERY_FILE_NAMES: [&str; 2] = ["in-progress.f32", "orphaned.f32"];
+
+/// Why a file can be deleted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum CleanupCategory {
+    /// Audio converted or recovered for transcription and left behind
+    TempAudio,
+    /// Leftover of an interrupted atomic write or write probe
+    TempFile,
+    /// Raw samples dumped for debugging
+    DebugDump,
+    /// Log file older than two weeks
+    OldLog,
+    /// Model download that never completed
+    PartialDownload,
+}
+
+/// A file that can be deleted.
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct CleanupCandidate {
+    pub path: String,
+    pub category: CleanupCategory,
+    pub size_bytes: u64,
+}
+
+/// Result of a scan, and of the deletion if it was confirmed.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct CleanupReport {
+    pub candidates: Vec<CleanupCandidate>,
+    /// Total size of the candidates
+    pub reclaimable_bytes: u64,
+    /// Whether the candidates were deleted
+    pub deleted: bool,
+    /// Size of the files actually deleted
+    pub freed_bytes: u64,
+}
+
+/// Scan the data directories for files that can be deleted, and delete them
+/// if `delete` is true. Scan first with `delete` false to show the user
+/// what will be removed.
+///
+/// # Returns
+/// * `Ok(CleanupReport)` listing the candidates and what was freed
+/// * `Err(CyranoError::StorageFailed)` if the home directory cannot be resolved
+pub fn cleanup_storage(delete: bool) -> Result<CleanupReport, CyranoError> {
+    let root = data_directory_service::data_root()?;
+    let candidates = scan(&root, &std::env::temp_dir(), SystemTime::now());
+    let reclaimable_bytes = candidates.iter().map(|c| c.size_bytes).sum();
+
+    let freed_bytes = if delete {
+        candidates
+            .iter()
+            .filter(|candidate| match std::fs::remove_file(&candidate.path) {
+                Ok(()) => true,
+                Err(e) => {
+                    log::warn!("Failed to delete {}: {e}", candidate.path);
+                    false
+                }
+            })
+            .map(|candidate| candidate.size_bytes)
+            .sum()
+    } else {
+        0
+    };
+
+    log::info!(
+        "Storage cleanup: {} files, {reclaimable_bytes} bytes reclaimable, {freed_bytes} freed",
+        candidates.len()
+    );
+    Ok(CleanupReport {
+        candidates,
+        reclaimable_bytes,
+        deleted: delete,
+        freed_bytes,
+    })
+}
+
+/// Find the files that can be deleted in the data root `root` and in
+/// `temp_dir`, as of `now`.
+fn scan(root: &Path, temp_dir: &Path, now: SystemTime) -> Vec<CleanupCandidate> {
+    let mut candidates = Vec::new();
+    let mut add = |path: PathBuf, category: CleanupCategory, min_age: Duration| {
+        let Ok(metadata) = path.metadata() else {
+            return;
+        };
+        let age = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .unwrap_or_default();
+        if metadata.is_file() && age >= min_age {
+            candidates.push(CleanupCandidate {
+                path: path.display().to_string(),
+                category,
+                size_bytes: metadata.len(),
+            });
+        }
+    };
+
+    for path in files_in(temp_dir) {
+        if file_name(&path).starts_with(TEMP_FILE_PREFIX) && has_extension(&path, "wav") {
+            add(path, CleanupCategory::TempAudio, TEMP_FILE_MIN_AGE);
+        }
+    }
+
+    let mut data_dirs = vec![root.to_path_buf()];
+    data_dirs.extend(files_in(root).filter(|path| path.is_dir()));
+    for path in data_dirs.iter().flat_map(|dir| files_in(dir)) {
+        if has_extension(&path, "tmp") || file_name(&path) == ".cyrano-write-test" {
+            add(path, CleanupCategory::TempFile, TEMP_FILE_MIN_AGE);
+        }
+    }
+
+    for path in files_in(&root.join(RECORDINGS_DIR_NAME)) {
+        if has_extension(&path, "f32") && !RECOVERY_FILE_NAMES.contains(&file_name(&path).as_str())
+        {
+            add(path, CleanupCategory::DebugDump, TEMP_FILE_MIN_AGE);
+        }
+    }
+
+    for path in files_in(&root.join(LOGS_DIR_NAME)) {
+        if has_extension(&path, "log") {
+            add(path, CleanupCategory::OldLog, LOG_MAX_AGE);
+        }
+    }
+
+    for path in files_in(&root.join(MODELS_DIR_NAME)) {
+        if has_extension(&path, PARTIAL_DOWNLOAD_EXTENSION) {
+            add(path, CleanupCategory::PartialDownload, TEMP_FILE_MIN_AGE);
+        }
+    }
+
+    candidates
+}
+
+/// Entries of `dir`, empty if it cannot be read.
+fn files_in(dir: &Path) -> impl Iterator<Item = PathBuf> {
+    std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension().is_some_and(|ext| ext == extension)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::temp_dir;
+
+    fn write(path: &Path) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, b"data").unwrap();
+    }
+
+    fn categories(candidates: &[CleanupCandidate], root: &Path) -> Vec<(String, CleanupCategory)> {
+        let mut categories: Vec<_> = candidates
+            .iter()
+            .map(|c| {
+                let path = Path::new(&c.path).strip_prefix(root).unwrap();
+                (path.display().to_string(), c.category)
+            })
+            .collect();
+        categories.sort_by(|a, b| a.0.cmp(&b.0));
+        categories
+    }
+
+    #[test]
+    fn test_scan_finds_leftovers_only() {
+        let base = temp_dir("cleanup");
+        let root = base.join("data");
+        let temp = base.join("tmp");
+        for path in [
+            "data/settings.tmp",
+            "data/history/history.tmp",
+            "data/history/history.json",
+            "data/recordings/orphaned.f32",
+            "data/recordings/capture.f32",
+            "data/recordings/2024-01-01.wav",
+            "data/logs/cyrano.log",
+            "data/models/ggml-base.bin",
+            "data/models/ggml-small.bin.part",
+            "tmp/cyrano-import-1-2.wav",
+            "tmp/other.wav",
+        ] {
+            write(&base.join(path));
+        }
+
+        let later = SystemTime::now() + Duration::from_secs(2 * 60 * 60);
+        let candidates = scan(&root, &temp, later);
+
+        assert_eq!(
+            categories(&candidates, &base),
+            vec![
+                ("data/history/history.tmp".into(), CleanupCategory::TempFile),
+                (
+                    "data/models/ggml-small.bin.part".into(),
+                    CleanupCategory::PartialDownload
+                ),
+                (
+                    "data/recordings/capture.f32".into(),
+                    CleanupCategory::DebugDump
+                ),
+                ("data/settings.tmp".into(), CleanupCategory::TempFile),
+                (
+                    "tmp/cyrano-import-1-2.wav".into(),
+                    CleanupCategory::TempAudio
+                ),
+            ]
+        );
+        assert!(candidates.iter().all(|c| c.size_bytes == 4));
+
+        // Logs are only old after two weeks
+        let much_later = SystemTime::now() + LOG_MAX_AGE + Duration::from_secs(60);
+        assert!(scan(&root, &temp, much_later)
+            .iter()
+            .any(|c| c.category == CleanupCategory::OldLog));
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn test_recent_files_are_kept() {
+        let base = temp_dir("cleanup-recent");
+        write(&base.join("data/settings.tmp"));
+        write(&base.join("tmp/cyrano-import-1-2.wav"));
+
+        assert!(scan(&base.join("data"), &base.join("tmp"), SystemTime::now()).is_empty());
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
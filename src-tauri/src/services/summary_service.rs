@@ -0,0 +1,163 @@
+//! Summaries of long transcriptions.
+//!
+//! When enabled, dictations and file or meeting transcriptions of at least
+//! `summary_min_words` words are summarized by an LLM behind an
+//! OpenAI-compatible API: a local server such as Ollama by default, or a
+//! remote service with an API key. The summary is stored next to the full
+//! text in history and, with `output_summary`, output instead of it.
+//!
+//! Summaries are best effort: when the server fails, the transcription is
+//! used as if summaries were disabled.
+
+use crate::infrastructure::summarizer::ChatCompletionSummarizer;
+use crate::services::settings_service::{self, Settings};
+use crate::traits::summarizer::Summarizer;
+
+/// A transcription with its optional summary.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SummarizedText {
+    /// The full transcription
+    pub text: String,
+    /// Summary of the transcription, if it was summarized
+    pub summary: Option<String>,
+    /// Whether the summary replaces the text on output
+    pub output_summary: bool,
+}
+
+impl SummarizedText {
+    /// Text to paste or copy: the summary if configured, the transcription otherwise.
+    pub fn output(&self) -> &str {
+        match &self.summary {
+            Some(summary) if self.output_summary => summary,
+            _ => &self.text,
+        }
+    }
+}
+
+/// Whether `text` is long enough to be summarized under `settings`.
+fn should_summarize(text: &str, settings: &Settings) -> bool {
+    settings.summary_enabled
+        && text.split_whitespace().count() >= settings.summary_min_words.max(1) as usize
+}
+
+/// Summarize `text` with `summarizer` if the settings call for it.
+fn summarize_with(
+    text: String,
+    settings: &Settings,
+    summarizer: impl FnOnce() -> Box<dyn Summarizer>,
+) -> SummarizedText {
+    let summary = if should_summarize(&text, settings) {
+        match summarizer().summarize(&text) {
+            Ok(summary) => {
+                log::info!(
+                    "Summarized transcription: {} -> {} chars",
+                    text.len(),
+                    summary.len()
+                );
+                Some(summary)
+            }
+            Err(e) => {
+                log::warn!("[{}] Failed to summarize transcription: {e}", e.code());
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    SummarizedText {
+        text,
+        summary,
+        output_summary: settings.output_summary,
+    }
+}
+
+/// Summarize a transcription if summaries are enabled and it is long enough.
+///
+/// Blocks until the server answers, so it MUST run off the main thread.
+pub fn summarize_if_long(text: String) -> SummarizedText {
+    let settings = settings_service::get_settings();
+    summarize_with(text, &settings, || {
+        Box::new(ChatCompletionSummarizer::new(
+            &settings.summary_url,
+            &settings.summary_model,
+            settings.summary_api_key.as_deref(),
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::CyranoError;
+
+    struct FixedSummarizer(Result<String, CyranoError>);
+
+    impl Summarizer for FixedSummarizer {
+        fn summarize(&self, _text: &str) -> Result<String, CyranoError> {
+            self.0.clone()
+        }
+    }
+
+    fn settings(min_words: u32, output_summary: bool) -> Settings {
+        Settings {
+            summary_enabled: true,
+            summary_min_words: min_words,
+            output_summary,
+            ..Settings::default()
+        }
+    }
+
+    fn summarizer(result: Result<&str, &str>) -> impl FnOnce() -> Box<dyn Summarizer> {
+        let result = result
+            .map(str::to_string)
+            .map_err(|reason| CyranoError::SummaryFailed {
+                reason: reason.to_string(),
+            });
+        move || Box::new(FixedSummarizer(result))
+    }
+
+    #[test]
+    fn test_short_text_is_not_summarized() {
+        let result = summarize_with("only three words".to_string(), &settings(4, true), || {
+            panic!("summarizer must not be created")
+        });
+        assert_eq!(result.summary, None);
+        assert_eq!(result.output(), "only three words");
+    }
+
+    #[test]
+    fn test_disabled_summaries_are_skipped() {
+        let settings = Settings {
+            summary_enabled: false,
+            ..settings(1, true)
+        };
+        let result = summarize_with("some text".to_string(), &settings, || {
+            panic!("summarizer must not be created")
+        });
+        assert_eq!(result.summary, None);
+    }
+
+    #[test]
+    fn test_summary_is_output_only_when_configured() {
+        let text = "one two three four".to_string();
+
+        let kept = summarize_with(text.clone(), &settings(4, false), summarizer(Ok("Sum")));
+        assert_eq!(kept.summary.as_deref(), Some("Sum"));
+        assert_eq!(kept.output(), text);
+
+        let replaced = summarize_with(text, &settings(4, true), summarizer(Ok("Sum")));
+        assert_eq!(replaced.output(), "Sum");
+    }
+
+    #[test]
+    fn test_failed_summary_falls_back_to_text() {
+        let result = summarize_with(
+            "one two three four".to_string(),
+            &settings(1, true),
+            summarizer(Err("offline")),
+        );
+        assert_eq!(result.summary, None);
+        assert_eq!(result.output(), "one two three four");
+    }
+}
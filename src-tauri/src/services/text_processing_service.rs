@@ -0,0 +1,212 @@
+//! Post-processing of transcriptions before they are output.
+//!
+//! Each formatting step is a `PostProcessor`, and a `Pipeline` runs them in
+//! order on the sanitized transcription. The steps applied to dictations
+//! are configured with the `post_processors` setting, list commands then
+//! replacement rules by default. Plugins run after the pipeline.
+//!
+//! Re-transcriptions and file transcriptions skip list commands, which
+//! remember the open list between dictations.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::domain::CyranoError;
+use crate::services::{list_dictation_service, settings_service, vocabulary_service};
+
+/// A formatting step applied to a transcription.
+pub trait PostProcessor: Send + Sync {
+    /// Return the processed text.
+    fn process(&self, text: &str) -> String;
+}
+
+/// Post-processors that can be configured, in the `post_processors` setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum PostProcessorKind {
+    /// Remove leading and trailing whitespace
+    Trim,
+    /// Format spoken list commands as Markdown lists (see `list_dictation_enabled`)
+    ListCommands,
+    /// Apply the replacement rules
+    Replacements,
+    /// Capitalize the first letter
+    Capitalize,
+    /// Lowercase the whole text
+    Lowercase,
+    /// Remove a single period at the end, e.g. for chat messages
+    NoTrailingPeriod,
+}
+
+/// Post-processors applied when none are configured.
+pub fn default_post_processors() -> Vec<PostProcessorKind> {
+    vec![
+        PostProcessorKind::ListCommands,
+        PostProcessorKind::Replacements,
+    ]
+}
+
+struct Trim;
+
+impl PostProcessor for Trim {
+    fn process(&self, text: &str) -> String {
+        text.trim().to_string()
+    }
+}
+
+struct ListCommands;
+
+impl PostProcessor for ListCommands {
+    fn process(&self, text: &str) -> String {
+        list_dictation_service::apply_configured_list_commands(text)
+    }
+}
+
+struct Replacements;
+
+impl PostProcessor for Replacements {
+    fn process(&self, text: &str) -> String {
+        vocabulary_service::apply_configured_replacements(text)
+    }
+}
+
+struct Capitalize;
+
+impl PostProcessor for Capitalize {
+    fn process(&self, text: &str) -> String {
+        let Some((start, first)) = text.char_indices().find(|(_, c)| c.is_alphabetic()) else {
+            return text.to_string();
+        };
+        let rest = &text[start + first.len_utf8()..];
+        format!("{}{}{rest}", &text[..start], first.to_uppercase())
+    }
+}
+
+struct Lowercase;
+
+impl PostProcessor for Lowercase {
+    fn process(&self, text: &str) -> String {
+        text.to_lowercase()
+    }
+}
+
+struct NoTrailingPeriod;
+
+impl PostProcessor for NoTrailingPeriod {
+    fn process(&self, text: &str) -> String {
+        let trimmed = text.trim_end();
+        match trimmed.strip_suffix('.') {
+            // Keep ellipses
+            Some(rest) if !rest.ends_with('.') => format!("{rest}{}", &text[trimmed.len()..]),
+            _ => text.to_string(),
+        }
+    }
+}
+
+impl PostProcessorKind {
+    /// The post-processor of this kind.
+    pub fn processor(self) -> Box<dyn PostProcessor> {
+        match self {
+            Self::Trim => Box::new(Trim),
+            Self::ListCommands => Box::new(ListCommands),
+            Self::Replacements => Box::new(Replacements),
+            Self::Capitalize => Box::new(Capitalize),
+            Self::Lowercase => Box::new(Lowercase),
+            Self::NoTrailingPeriod => Box::new(NoTrailingPeriod),
+        }
+    }
+}
+
+/// Post-processors run in order, each on the output of the previous one.
+#[derive(Default)]
+pub struct Pipeline {
+    processors: Vec<Box<dyn PostProcessor>>,
+}
+
+impl Pipeline {
+    /// Create an empty pipeline, which leaves the text unchanged.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pipeline of the given kinds, in order.
+    pub fn from_kinds(kinds: impl IntoIterator<Item = PostProcessorKind>) -> Self {
+        kinds.into_iter().fold(Self::new(), |pipeline, kind| {
+            pipeline.with(kind.processor())
+        })
+    }
+
+    /// Append a post-processor to the pipeline.
+    pub fn with(mut self, processor: Box<dyn PostProcessor>) -> Self {
+        self.processors.push(processor);
+        self
+    }
+
+    /// Run the text through every post-processor.
+    pub fn run(&self, text: &str) -> String {
+        self.processors
+            .iter()
+            .fold(text.to_string(), |text, processor| processor.process(&text))
+    }
+}
+
+/// Set the post-processors applied to dictations, in order, and persist them.
+///
+/// # Returns
+/// * `Ok(())` if the setting was saved
+/// * `Err(CyranoError::SettingManaged)` if the post-processors are managed
+/// * `Err(CyranoError::StorageFailed)` if the settings could not be written
+pub fn set_post_processors(kinds: Vec<PostProcessorKind>) -> Result<(), CyranoError> {
+    let updated = settings_service::update_settings(|settings| settings.post_processors = kinds)?;
+    log::info!("Post-processors set to {:?}", updated.post_processors);
+    Ok(())
+}
+
+/// Run a dictation through the configured post-processors.
+pub fn process_dictation(text: &str) -> String {
+    Pipeline::from_kinds(settings_service::get_settings().post_processors).run(text)
+}
+
+/// Run a re-transcription or file transcription through the configured
+/// post-processors, except list commands.
+pub fn process_transcription(text: &str) -> String {
+    let kinds = settings_service::get_settings()
+        .post_processors
+        .into_iter()
+        .filter(|kind| *kind != PostProcessorKind::ListCommands);
+    Pipeline::from_kinds(kinds).run(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(kinds: &[PostProcessorKind], text: &str) -> String {
+        Pipeline::from_kinds(kinds.iter().copied()).run(text)
+    }
+
+    #[test]
+    fn test_pipeline_runs_in_order() {
+        use PostProcessorKind::*;
+
+        assert_eq!(run(&[], " Hello. "), " Hello. ");
+        assert_eq!(
+            run(&[Trim, Lowercase, Capitalize], "  hELLO World "),
+            "Hello world"
+        );
+        assert_eq!(run(&[Capitalize, Lowercase], "hello"), "hello");
+    }
+
+    #[test]
+    fn test_capitalize() {
+        assert_eq!(Capitalize.process("\"élan vital\""), "\"Élan vital\"");
+        assert_eq!(Capitalize.process("42"), "42");
+    }
+
+    #[test]
+    fn test_no_trailing_period() {
+        assert_eq!(NoTrailingPeriod.process("See you soon. "), "See you soon ");
+        assert_eq!(NoTrailingPeriod.process("Well..."), "Well...");
+        assert_eq!(NoTrailingPeriod.process("Really?"), "Really?");
+    }
+}
@@ -0,0 +1,110 @@
+//! Recording status in the menu bar.
+//!
+//! With `tray_status_enabled` on, a menu bar item shows the elapsed time
+//! while recording and `…` while transcribing, so the dictation state stays
+//! visible when the overlay is hidden or off screen. The item is created on
+//! the first state change after the setting is turned on, and removed on the
+//! first one after it is turned off.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use tauri::tray::TrayIconBuilder;
+use tauri::AppHandle;
+
+use crate::domain::RecordingState;
+use crate::services::settings_service;
+
+/// Identifier of the menu bar item.
+const TRAY_ID: &str = "recording-status";
+
+/// How often the elapsed time is refreshed while recording.
+const TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Title shown while transcribing.
+const TRANSCRIBING_TITLE: &str = "…";
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// Incremented on each state change, so the timer of a previous recording
+/// stops updating the title.
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Show the recording state in the menu bar from now on.
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+/// Elapsed recording time as `m:ss`, or `h:mm:ss` past an hour.
+fn format_elapsed(elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+/// Set the title of the menu bar item, creating or removing the item to
+/// follow `tray_status_enabled`.
+fn set_title(app: &AppHandle, title: Option<&str>) {
+    if !settings_service::get_settings().tray_status_enabled {
+        let _ = app.remove_tray_by_id(TRAY_ID);
+        return;
+    }
+    let tray = match app.tray_by_id(TRAY_ID) {
+        Some(tray) => tray,
+        None => {
+            let mut builder = TrayIconBuilder::with_id(TRAY_ID).tooltip("Cyrano");
+            if let Some(icon) = app.default_window_icon() {
+                builder = builder.icon(icon.clone());
+            }
+            match builder.build(app) {
+                Ok(tray) => tray,
+                Err(e) => {
+                    log::warn!("Failed to create the menu bar status item: {e}");
+                    return;
+                }
+            }
+        }
+    };
+    if let Err(e) = tray.set_title(title) {
+        log::warn!("Failed to update the menu bar status: {e}");
+    }
+}
+
+/// Reflect a recording state change in the menu bar.
+pub fn show_state(state: RecordingState) {
+    let Some(app) = APP.get() else {
+        return;
+    };
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    match state {
+        RecordingState::Recording => {
+            let app = app.clone();
+            let started = Instant::now();
+            std::thread::spawn(move || {
+                while GENERATION.load(Ordering::SeqCst) == generation {
+                    set_title(&app, Some(&format_elapsed(started.elapsed())));
+                    std::thread::sleep(TICK_INTERVAL);
+                }
+            });
+        }
+        RecordingState::Transcribing => set_title(app, Some(TRANSCRIBING_TITLE)),
+        RecordingState::Idle | RecordingState::Done | RecordingState::Error => set_title(app, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_elapsed() {
+        assert_eq!(format_elapsed(Duration::from_millis(900)), "0:00");
+        assert_eq!(format_elapsed(Duration::from_secs(65)), "1:05");
+        assert_eq!(format_elapsed(Duration::from_secs(3_725)), "1:02:05");
+    }
+}
@@ -0,0 +1,501 @@
+//! Custom vocabulary and replacement rules.
+//!
+//! The vocabulary is passed to Whisper as an initial prompt so domain terms
+//! (drug names, product names, jargon) are spelled correctly. Replacement
+//! rules then rewrite the transcription, e.g. to expand abbreviations.
+//!
+//! Both tables can be exported and imported on their own, separately from the
+//! rest of the settings, so teams can share domain dictionaries:
+//! - JSON: `{"version": 1, "vocabulary": [...], "replacements": [{"from", "to"}]}`
+//! - CSV: a `type,from,to` header, then `term,<word>,` and `replace,<from>,<to>` rows
+
+use std::path::Path;
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::domain::CyranoError;
+use crate::services::data_directory_service;
+use crate::services::settings_service::{self, ReplacementRule};
+
+/// Version of the JSON export format.
+const EXPORT_VERSION: u32 = 1;
+
+/// Header row of the CSV format.
+const CSV_HEADER: [&str; 3] = ["type", "from", "to"];
+
+/// Longest initial prompt passed to Whisper, in characters.
+///
+/// Whisper only keeps the last ~224 prompt tokens, so later terms would be
+/// dropped silently anyway.
+const MAX_PROMPT_CHARS: usize = 600;
+
+/// File format for vocabulary export and import.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VocabularyFormat {
+    Json,
+    Csv,
+}
+
+impl VocabularyFormat {
+    /// Pick the format from the file extension.
+    fn from_path(path: &Path) -> Result<Self, CyranoError> {
+        match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase)
+            .as_deref()
+        {
+            Some("json") => Ok(Self::Json),
+            Some("csv") => Ok(Self::Csv),
+            _ => Err(CyranoError::StorageFailed {
+                reason: format!(
+                    "Unsupported vocabulary file {}: use .json or .csv",
+                    path.display()
+                ),
+            }),
+        }
+    }
+}
+
+/// How imported entries are combined with the existing ones.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMode {
+    /// Add new terms and rules; imported rules override rules for the same text.
+    #[default]
+    Merge,
+    /// Replace both tables with the imported ones.
+    Replace,
+}
+
+/// Result of a vocabulary import.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Type)]
+pub struct VocabularyImportReport {
+    /// Number of vocabulary terms added
+    pub terms_added: u32,
+    /// Number of replacement rules added
+    pub rules_added: u32,
+    /// Number of existing replacement rules whose replacement changed
+    pub rules_updated: u32,
+}
+
+/// Vocabulary and replacement tables as exported.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+struct VocabularyFile {
+    version: u32,
+    vocabulary: Vec<String>,
+    replacements: Vec<ReplacementRule>,
+}
+
+/// Build the Whisper initial prompt from the vocabulary.
+///
+/// # Returns
+/// * `Some(String)` listing the terms, truncated to whole terms
+/// * `None` if the vocabulary is empty
+pub fn initial_prompt(vocabulary: &[String]) -> Option<String> {
+    let mut prompt = String::new();
+    for term in vocabulary
+        .iter()
+        .map(|t| t.trim())
+        .filter(|t| !t.is_empty())
+    {
+        let separator = if prompt.is_empty() { "" } else { ", " };
+        if prompt.chars().count() + separator.len() + term.chars().count() > MAX_PROMPT_CHARS {
+            break;
+        }
+        prompt.push_str(separator);
+        prompt.push_str(term);
+    }
+    (!prompt.is_empty()).then_some(prompt)
+}
+
+/// Build a case-insensitive whole-word pattern for a rule.
+///
+/// Word boundaries are only required on sides where the text starts or ends
+/// with a word character, so rules like "c++" or ":)" still match.
+fn rule_pattern(from: &str) -> Option<Regex> {
+    let from = from.trim();
+    if from.is_empty() {
+        return None;
+    }
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    let start = if from.starts_with(is_word_char) {
+        r"\b"
+    } else {
+        ""
+    };
+    let end = if from.ends_with(is_word_char) {
+        r"\b"
+    } else {
+        ""
+    };
+
+    RegexBuilder::new(&format!("{start}{}{end}", regex::escape(from)))
+        .case_insensitive(true)
+        .build()
+        .ok()
+}
+
+/// Apply replacement rules to a transcription, in order.
+pub fn apply_replacements(text: &str, rules: &[ReplacementRule]) -> String {
+    rules.iter().fold(text.to_string(), |text, rule| {
+        match rule_pattern(&rule.from) {
+            Some(pattern) => pattern
+                .replace_all(&text, regex::NoExpand(&rule.to))
+                .into_owned(),
+            None => text,
+        }
+    })
+}
+
+/// Apply the configured replacement rules to a transcription.
+pub fn apply_configured_replacements(text: &str) -> String {
+    let settings = settings_service::get_settings();
+    if settings.replacements.is_empty() {
+        return text.to_string();
+    }
+    let replaced = apply_replacements(text, &settings.replacements);
+    if replaced != text {
+        log::debug!("Replacement rules changed the transcription");
+    }
+    replaced
+}
+
+/// Quote a CSV field when needed (RFC 4180).
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Parse CSV into records of fields (RFC 4180 quoting, CRLF or LF).
+fn parse_csv(contents: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = contents.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', true) => in_quotes = false,
+            ('"', false) if field.is_empty() => in_quotes = true,
+            (',', false) => record.push(std::mem::take(&mut field)),
+            ('\r', false) if chars.peek() == Some(&'\n') => {}
+            ('\n', false) => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err("unterminated quoted field".to_string());
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+fn to_csv(file: &VocabularyFile) -> String {
+    let mut csv = CSV_HEADER.join(",");
+    csv.push('\n');
+    for term in &file.vocabulary {
+        csv.push_str(&format!("term,{},\n", csv_field(term)));
+    }
+    for rule in &file.replacements {
+        csv.push_str(&format!(
+            "replace,{},{}\n",
+            csv_field(&rule.from),
+            csv_field(&rule.to)
+        ));
+    }
+    csv
+}
+
+fn from_csv(contents: &str) -> Result<VocabularyFile, String> {
+    let records = parse_csv(contents.trim_start_matches('\u{feff}'))?;
+    let mut file = VocabularyFile {
+        version: EXPORT_VERSION,
+        ..VocabularyFile::default()
+    };
+
+    for (index, record) in records.iter().enumerate() {
+        let field = |i: usize| record.get(i).map(String::as_str).unwrap_or_default();
+        match field(0).trim().to_ascii_lowercase().as_str() {
+            "type" if index == 0 => {}
+            "" if record.iter().all(|f| f.trim().is_empty()) => {}
+            "term" => file.vocabulary.push(field(1).to_string()),
+            "replace" => file.replacements.push(ReplacementRule {
+                from: field(1).to_string(),
+                to: field(2).to_string(),
+            }),
+            other => return Err(format!("line {}: unknown row type '{other}'", index + 1)),
+        }
+    }
+    Ok(file)
+}
+
+/// Combine imported tables with the current ones.
+fn merge_into(
+    vocabulary: &mut Vec<String>,
+    replacements: &mut Vec<ReplacementRule>,
+    imported: VocabularyFile,
+    mode: ImportMode,
+) -> VocabularyImportReport {
+    if mode == ImportMode::Replace {
+        vocabulary.clear();
+        replacements.clear();
+    }
+
+    let mut report = VocabularyImportReport::default();
+    for term in imported.vocabulary {
+        let term = term.trim().to_string();
+        if !term.is_empty() && !vocabulary.iter().any(|t| t.eq_ignore_ascii_case(&term)) {
+            vocabulary.push(term);
+            report.terms_added += 1;
+        }
+    }
+
+    for rule in imported.replacements {
+        if rule.from.trim().is_empty() {
+            continue;
+        }
+        match replacements
+            .iter_mut()
+            .find(|existing| existing.from.trim().eq_ignore_ascii_case(rule.from.trim()))
+        {
+            Some(existing) if existing.to != rule.to => {
+                existing.to = rule.to;
+                report.rules_updated += 1;
+            }
+            Some(_) => {}
+            None => {
+                replacements.push(rule);
+                report.rules_added += 1;
+            }
+        }
+    }
+    report
+}
+
+/// Export the vocabulary and replacement rules to `path` (.json or .csv).
+pub fn export_vocabulary(path: &Path) -> Result<(), CyranoError> {
+    let format = VocabularyFormat::from_path(path)?;
+    let settings = settings_service::get_settings();
+    let file = VocabularyFile {
+        version: EXPORT_VERSION,
+        vocabulary: settings.vocabulary,
+        replacements: settings.replacements,
+    };
+
+    let contents = match format {
+        VocabularyFormat::Json => {
+            serde_json::to_string_pretty(&file).map_err(|e| CyranoError::StorageFailed {
+                reason: format!("Failed to serialize vocabulary: {e}"),
+            })?
+        }
+        VocabularyFormat::Csv => to_csv(&file),
+    };
+    data_directory_service::write_file_atomically(path, contents.as_bytes())?;
+
+    log::info!(
+        "Exported {} terms and {} replacement rules to {}",
+        file.vocabulary.len(),
+        file.replacements.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+/// Import vocabulary and replacement rules from `path` (.json or .csv).
+///
+/// # Returns
+/// * `Ok(VocabularyImportReport)` describing what changed
+/// * `Err(CyranoError::StorageFailed)` if the file cannot be read or parsed
+pub fn import_vocabulary(
+    path: &Path,
+    mode: ImportMode,
+) -> Result<VocabularyImportReport, CyranoError> {
+    let format = VocabularyFormat::from_path(path)?;
+    let contents = std::fs::read_to_string(path).map_err(|e| CyranoError::StorageFailed {
+        reason: format!("Failed to read {}: {e}", path.display()),
+    })?;
+
+    let imported = match format {
+        VocabularyFormat::Json => serde_json::from_str(&contents).map_err(|e| e.to_string()),
+        VocabularyFormat::Csv => from_csv(&contents),
+    }
+    .map_err(|e| CyranoError::StorageFailed {
+        reason: format!("Invalid vocabulary file {}: {e}", path.display()),
+    })?;
+
+    let mut report = VocabularyImportReport::default();
+    settings_service::update_settings(|settings| {
+        report = merge_into(
+            &mut settings.vocabulary,
+            &mut settings.replacements,
+            imported,
+            mode,
+        );
+    })?;
+
+    log::info!("Imported vocabulary from {}: {report:?}", path.display());
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(from: &str, to: &str) -> ReplacementRule {
+        ReplacementRule {
+            from: from.to_string(),
+            to: to.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_initial_prompt_lists_terms() {
+        let vocabulary = vec![
+            "Kubernetes".to_string(),
+            " ".to_string(),
+            "Tauri".to_string(),
+        ];
+        assert_eq!(
+            initial_prompt(&vocabulary).as_deref(),
+            Some("Kubernetes, Tauri")
+        );
+        assert_eq!(initial_prompt(&[]), None);
+    }
+
+    #[test]
+    fn test_initial_prompt_is_capped_at_whole_terms() {
+        let vocabulary = vec!["x".repeat(400), "y".repeat(400)];
+        assert_eq!(initial_prompt(&vocabulary), Some("x".repeat(400)));
+    }
+
+    #[test]
+    fn test_replacements_match_whole_words_case_insensitively() {
+        let rules = [rule("acme", "ACME Corp"), rule("new line", "\n")];
+        assert_eq!(
+            apply_replacements("Acme said new line hello, acmes", &rules),
+            "ACME Corp said \n hello, acmes"
+        );
+    }
+
+    #[test]
+    fn test_replacements_with_symbols_and_dollar_signs() {
+        let rules = [rule("c plus plus", "C++"), rule("price", "$1")];
+        assert_eq!(apply_replacements("c plus plus price", &rules), "C++ $1");
+        let rules = [rule(":)", "🙂")];
+        assert_eq!(apply_replacements("ok:)", &rules), "ok🙂");
+    }
+
+    #[test]
+    fn test_empty_rules_are_ignored() {
+        assert_eq!(apply_replacements("text", &[rule(" ", "x")]), "text");
+    }
+
+    #[test]
+    fn test_csv_round_trip_with_quoting() {
+        let file = VocabularyFile {
+            version: EXPORT_VERSION,
+            vocabulary: vec!["Smith, John".to_string(), "say \"hi\"".to_string()],
+            replacements: vec![rule("bp", "blood pressure"), rule("nl", "\n")],
+        };
+        let parsed = from_csv(&to_csv(&file)).unwrap();
+        assert_eq!(parsed, file);
+    }
+
+    #[test]
+    fn test_csv_accepts_crlf_bom_and_blank_lines() {
+        let csv = "\u{feff}type,from,to\r\nterm,ibuprofen,\r\n\r\nREPLACE,bp,blood pressure\r\n";
+        let parsed = from_csv(csv).unwrap();
+        assert_eq!(parsed.vocabulary, vec!["ibuprofen"]);
+        assert_eq!(parsed.replacements, vec![rule("bp", "blood pressure")]);
+    }
+
+    #[test]
+    fn test_csv_rejects_unknown_rows() {
+        assert!(from_csv("type,from,to\nsynonym,a,b\n").is_err());
+        assert!(from_csv("term,\"unterminated\n").is_err());
+    }
+
+    #[test]
+    fn test_merge_adds_new_entries_and_updates_rules() {
+        let mut vocabulary = vec!["Tauri".to_string()];
+        let mut replacements = vec![rule("bp", "BP")];
+        let imported = VocabularyFile {
+            version: EXPORT_VERSION,
+            vocabulary: vec!["tauri".to_string(), "Whisper".to_string()],
+            replacements: vec![rule("BP", "blood pressure"), rule("hr", "heart rate")],
+        };
+
+        let report = merge_into(
+            &mut vocabulary,
+            &mut replacements,
+            imported,
+            ImportMode::Merge,
+        );
+
+        assert_eq!(vocabulary, vec!["Tauri", "Whisper"]);
+        assert_eq!(
+            replacements,
+            vec![rule("bp", "blood pressure"), rule("hr", "heart rate")]
+        );
+        assert_eq!(
+            report,
+            VocabularyImportReport {
+                terms_added: 1,
+                rules_added: 1,
+                rules_updated: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_replace_mode_drops_existing_entries() {
+        let mut vocabulary = vec!["Old".to_string()];
+        let mut replacements = vec![rule("old", "new")];
+        let imported = VocabularyFile {
+            vocabulary: vec!["New".to_string()],
+            ..VocabularyFile::default()
+        };
+
+        merge_into(
+            &mut vocabulary,
+            &mut replacements,
+            imported,
+            ImportMode::Replace,
+        );
+
+        assert_eq!(vocabulary, vec!["New"]);
+        assert!(replacements.is_empty());
+    }
+
+    #[test]
+    fn test_format_from_extension() {
+        assert_eq!(
+            VocabularyFormat::from_path(Path::new("terms.JSON")).unwrap(),
+            VocabularyFormat::Json
+        );
+        assert_eq!(
+            VocabularyFormat::from_path(Path::new("terms.csv")).unwrap(),
+            VocabularyFormat::Csv
+        );
+        assert!(VocabularyFormat::from_path(Path::new("terms.txt")).is_err());
+    }
+}
@@ -0,0 +1,263 @@
+//! Import of iPhone voice memos.
+//!
+//! With iCloud sync enabled, the Voice Memos app keeps every recording from
+//! the user's iPhone in a folder on the Mac. Users can also point Cyrano at
+//! any folder, e.g. the one AirDrop saves to. A background watcher polls
+//! the folder and emits voice-memos-available when new recordings appear;
+//! the frontend lists them with `list_importable_memos` and transcribes
+//! the chosen ones with `transcribe_memo`.
+//!
+//! Memos are remembered by file name once transcribed, so they are only
+//! offered once.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+use serde::Serialize;
+use specta::Type;
+use tauri::AppHandle;
+
+use crate::domain::CyranoError;
+use crate::services::{
+    data_directory_service, file_transcription_service, notifier_service, settings_service,
+};
+
+/// Folders the Voice Memos app syncs to, relative to the home directory:
+/// macOS 14 and later, then earlier versions.
+const VOICE_MEMOS_FOLDERS: &[&str] = &[
+    "Library/Group Containers/group.com.apple.VoiceMemos.shared/Recordings",
+    "Library/Application Support/com.apple.voicememos/Recordings",
+];
+
+/// File in the data root listing the memos already transcribed.
+const IMPORTED_MEMOS_FILE: &str = "imported-memos.json";
+
+/// How often the watcher looks for new memos.
+const WATCH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A memo that has not been transcribed yet.
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct ImportableMemo {
+    /// Absolute path of the audio file
+    pub path: String,
+    /// File name without extension (the recording date for synced memos)
+    pub name: String,
+    /// Unix timestamp in seconds when the file was last modified
+    pub modified_at: u32,
+    /// Size of the audio file in bytes
+    pub size_bytes: u32,
+}
+
+/// Payload for the voice-memos-available event.
+#[derive(Clone, Serialize)]
+pub struct VoiceMemosAvailablePayload {
+    /// Number of memos waiting to be transcribed
+    pub count: u32,
+}
+
+/// Folder watched for memos: the configured one, or the Voice Memos sync
+/// folder if it exists.
+pub fn memo_folder() -> Option<PathBuf> {
+    if let Some(folder) = settings_service::get_settings().voice_memo_folder {
+        return Some(PathBuf::from(folder));
+    }
+    if !cfg!(target_os = "macos") {
+        return None;
+    }
+    let home = dirs::home_dir()?;
+    VOICE_MEMOS_FOLDERS
+        .iter()
+        .map(|folder| home.join(folder))
+        .find(|folder| folder.is_dir())
+}
+
+/// List the memos that have not been transcribed yet, newest first.
+///
+/// # Returns
+/// * `Ok(Vec<ImportableMemo>)`, empty if no memo folder is available
+/// * `Err(CyranoError::StorageFailed)` if the folder cannot be read (on
+///   macOS, the Voice Memos folder requires Full Disk Access)
+pub fn list_importable_memos() -> Result<Vec<ImportableMemo>, CyranoError> {
+    let Some(folder) = memo_folder() else {
+        return Ok(Vec::new());
+    };
+    let imported = load_imported(&imported_memos_path()?);
+    importable_in(&folder, &imported)
+}
+
+/// Queue a memo for transcription and remember it as imported.
+///
+/// Progress and results are reported like any file transcription.
+///
+/// # Returns
+/// * `Ok(())` if the memo was queued
+/// * `Err(CyranoError::UnsupportedAudioFile)` if `path` is not an audio
+///   file in the memo folder
+pub fn transcribe_memo(app: &AppHandle, path: &Path) -> Result<(), CyranoError> {
+    let in_folder = memo_folder().is_some_and(|folder| path.parent() == Some(folder.as_path()));
+    if !in_folder || !path.is_file() || !file_transcription_service::is_supported_file(path) {
+        return Err(CyranoError::UnsupportedAudioFile {
+            path: path.display().to_string(),
+            reason: "not a voice memo".to_string(),
+        });
+    }
+
+    file_transcription_service::enqueue_files(app, vec![path.to_path_buf()]);
+
+    // Losing track of the import only means the memo is offered again
+    if let Err(e) = mark_imported(path) {
+        log::warn!("[{}] Failed to remember imported memo: {e}", e.code());
+    }
+    Ok(())
+}
+
+/// Start the background thread that announces new memos.
+pub fn start_watcher(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let mut announced: HashSet<String> = HashSet::new();
+        loop {
+            match list_importable_memos() {
+                Ok(memos) => {
+                    let current: HashSet<String> = memos.into_iter().map(|m| m.path).collect();
+                    if current.iter().any(|path| !announced.contains(path)) {
+                        log::info!("{} voice memos available for import", current.len());
+                        let payload = VoiceMemosAvailablePayload {
+                            count: current.len() as u32,
+                        };
+                        if let Err(e) =
+                            notifier_service::emit(&app, "voice-memos-available", payload)
+                        {
+                            log::error!("Failed to emit voice-memos-available event: {e}");
+                        }
+                    }
+                    announced = current;
+                }
+                Err(e) => log::debug!("[{}] Could not check for voice memos: {e}", e.code()),
+            }
+            std::thread::sleep(WATCH_INTERVAL);
+        }
+    });
+}
+
+fn imported_memos_path() -> Result<PathBuf, CyranoError> {
+    data_directory_service::data_root().map(|root| root.join(IMPORTED_MEMOS_FILE))
+}
+
+/// Read the names of imported memos; a missing or corrupt file means none.
+fn load_imported(path: &Path) -> HashSet<String> {
+    std::fs::read(path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+fn mark_imported(memo: &Path) -> Result<(), CyranoError> {
+    let Some(name) = memo.file_name() else {
+        return Ok(());
+    };
+    let path = imported_memos_path()?;
+    let mut imported = load_imported(&path);
+    imported.insert(name.to_string_lossy().into_owned());
+
+    let mut names: Vec<&String> = imported.iter().collect();
+    names.sort();
+    let json = serde_json::to_vec_pretty(&names).map_err(|e| CyranoError::StorageFailed {
+        reason: format!("Failed to serialize imported memos: {e}"),
+    })?;
+    data_directory_service::write_file_atomically(&path, &json)
+}
+
+/// Supported audio files in `folder` whose names are not in `imported`.
+fn importable_in(
+    folder: &Path,
+    imported: &HashSet<String>,
+) -> Result<Vec<ImportableMemo>, CyranoError> {
+    let entries = std::fs::read_dir(folder).map_err(|e| CyranoError::StorageFailed {
+        reason: format!("Failed to read {}: {e}", folder.display()),
+    })?;
+
+    let mut memos: Vec<ImportableMemo> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let path = entry.path();
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            if imported.contains(&file_name)
+                || !file_transcription_service::is_supported_file(&path)
+            {
+                return None;
+            }
+            let metadata = entry.metadata().ok().filter(|m| m.is_file())?;
+            let modified_at = metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs() as u32)
+                .unwrap_or(0);
+            Some(ImportableMemo {
+                name: path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or(file_name),
+                path: path.display().to_string(),
+                modified_at,
+                size_bytes: u32::try_from(metadata.len()).unwrap_or(u32::MAX),
+            })
+        })
+        .collect();
+
+    memos.sort_by(|a, b| {
+        b.modified_at
+            .cmp(&a.modified_at)
+            .then_with(|| b.name.cmp(&a.name))
+    });
+    Ok(memos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::temp_dir;
+
+    #[test]
+    fn test_importable_in_skips_imported_and_unsupported_files() {
+        let dir = temp_dir("list");
+        std::fs::write(dir.join("20240105 093000.wav"), b"new").unwrap();
+        std::fs::write(dir.join("20240101 120000.wav"), b"old").unwrap();
+        std::fs::write(dir.join("CloudRecordings.db"), b"").unwrap();
+        std::fs::create_dir(dir.join("folder.wav")).unwrap();
+
+        let imported = HashSet::from(["20240101 120000.wav".to_string()]);
+        let memos = importable_in(&dir, &imported).unwrap();
+        assert_eq!(memos.len(), 1);
+        assert_eq!(memos[0].name, "20240105 093000");
+        assert_eq!(memos[0].size_bytes, 3);
+        assert!(memos[0].modified_at > 0);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_importable_in_missing_folder_is_an_error() {
+        let root = temp_dir("voice-memos");
+        let missing = root.join("missing-folder");
+        assert!(importable_in(&missing, &HashSet::new()).is_err());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_load_imported_tolerates_missing_and_corrupt_files() {
+        let dir = temp_dir("imported");
+        let path = dir.join(IMPORTED_MEMOS_FILE);
+        assert!(load_imported(&path).is_empty());
+
+        std::fs::write(&path, b"not json").unwrap();
+        assert!(load_imported(&path).is_empty());
+
+        std::fs::write(&path, br#"["a.m4a", "b.m4a"]"#).unwrap();
+        assert_eq!(load_imported(&path).len(), 2);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -15,4 +15,20 @@ pub trait AudioCapture {
     /// Whether audio capture is currently active.
     #[allow(dead_code)]
     fn is_capturing(&self) -> bool;
+
+    /// Remove and return the samples captured so far, so the caller can
+    /// store and analyze them while capture is still running.
+    fn take_samples(&mut self) -> Vec<f32>;
+
+    /// Name of the input device being captured, if known.
+    fn device_name(&self) -> Option<String>;
+
+    /// Whether the input device disappeared while capturing.
+    fn device_lost(&self) -> bool;
+
+    /// Resume capture on the current default input device after the previous
+    /// one was lost, keeping the samples captured so far.
+    ///
+    /// Returns the name of the new device.
+    fn switch_to_default_device(&mut self) -> Result<String, CyranoError>;
 }
@@ -4,4 +4,6 @@
 //! Services depend on these traits, not on concrete implementations.
 
 pub mod audio_capture;
+pub mod summarizer;
+pub mod sync_backend;
 pub mod transcriber;
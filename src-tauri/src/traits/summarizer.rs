@@ -0,0 +1,12 @@
+//! Summarization port (trait).
+//!
+//! Defines the interface that summarization adapters (a local LLM server or a
+//! remote service) must implement.
+
+use crate::domain::CyranoError;
+
+/// Abstraction over text summarization implementations.
+pub trait Summarizer {
+    /// Summarize a transcription, in the language it is written in.
+    fn summarize(&self, text: &str) -> Result<String, CyranoError>;
+}
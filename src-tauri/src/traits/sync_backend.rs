@@ -0,0 +1,50 @@
+//! Remote storage port (trait) for encrypted sync.
+//!
+//! Defines the minimal object store that sync backends must provide. Objects
+//! are opaque (already encrypted) blobs; concurrent writers are detected with
+//! entity tags, so a backend only needs conditional writes.
+
+use crate::domain::CyranoError;
+
+/// An object fetched from the remote store.
+#[derive(Debug, Clone)]
+pub struct RemoteObject {
+    /// Object contents
+    pub bytes: Vec<u8>,
+    /// Version tag used for conditional writes, if the server provides one
+    pub etag: Option<String>,
+}
+
+/// Precondition for a write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteCondition<'a> {
+    /// Only write if the object does not exist yet.
+    Absent,
+    /// Only write if the object still has this version tag.
+    Matches(&'a str),
+    /// Write regardless (for servers that do not report version tags).
+    Unconditional,
+}
+
+/// Outcome of a conditional write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoreOutcome {
+    /// The object was written.
+    Stored,
+    /// The object changed since it was fetched; fetch and merge again.
+    Conflict,
+}
+
+/// Abstraction over remote object stores (WebDAV, relay server, ...).
+pub trait SyncBackend {
+    /// Fetch an object, or `None` if it does not exist yet.
+    fn fetch(&self, name: &str) -> Result<Option<RemoteObject>, CyranoError>;
+
+    /// Write an object if `condition` holds.
+    fn store(
+        &self,
+        name: &str,
+        bytes: &[u8],
+        condition: WriteCondition<'_>,
+    ) -> Result<StoreOutcome, CyranoError>;
+}
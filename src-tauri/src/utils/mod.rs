@@ -1,3 +1,8 @@
 //! Utility modules for cross-platform support and common operations.
 
 pub mod platform;
+pub mod text_join;
+pub mod text_sanitize;
+
+#[cfg(test)]
+pub mod test_support;
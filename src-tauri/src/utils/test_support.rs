@@ -0,0 +1,17 @@
+//! Helpers shared by unit tests.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static NEXT_DIR: AtomicUsize = AtomicUsize::new(0);
+
+/// Create an empty directory under the system temp directory, unique to
+/// this call. Tests remove it when done.
+pub fn temp_dir(label: &str) -> PathBuf {
+    let id = NEXT_DIR.fetch_add(1, Ordering::Relaxed);
+    let dir = std::env::temp_dir().join(format!("cyrano-{label}-{}-{id}", std::process::id()));
+    // Left over by an earlier run that had the same process id
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("temp dir should be creatable");
+    dir
+}
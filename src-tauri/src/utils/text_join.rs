@@ -0,0 +1,288 @@
+//! Smart joining of independently transcribed text pieces.
+//!
+//! Whisper segments and separately transcribed audio chunks are decoded
+//! without knowledge of their neighbours, so naive concatenation produces
+//! double spaces, words split at chunk boundaries ("tran" + "scription"),
+//! and a capital on words like "And" or "The" wherever a chunk happens to
+//! start mid-sentence.
+//! This module repairs those boundaries.
+//!
+//! Pieces follow Whisper's convention: a piece that starts a new word begins
+//! with a space, while a piece without leading whitespace continues the
+//! previous word. Scripts written without spaces between words (Chinese,
+//! Japanese, Thai...) never get a separator.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use crate::utils::text_join::join_chunks;
+//!
+//! let text = join_chunks(["I went to the store", " And bought", " milk."]);
+//! assert_eq!(text, "I went to the store and bought milk.");
+//! ```
+
+/// Characters that end a sentence.
+const SENTENCE_TERMINATORS: [char; 4] = ['.', '!', '?', '…'];
+
+/// Characters that attach to the preceding word without a space.
+const CLOSING_PUNCTUATION: [char; 9] = ['.', ',', '!', '?', ';', ':', ')', ']', '…'];
+
+/// Common words that are only capitalized at the start of a sentence, so a
+/// capital on them mid-sentence is a false sentence start. Other words may
+/// be proper nouns ("Paris", "John") and keep their capital.
+const SENTENCE_START_WORDS: [&str; 24] = [
+    "A", "An", "And", "As", "At", "Because", "But", "For", "From", "If", "In", "It", "Of", "On",
+    "Or", "So", "That", "The", "Then", "This", "To", "We", "With", "You",
+];
+
+/// Join a sequence of text pieces, repairing each boundary.
+///
+/// Empty and whitespace-only pieces are skipped and the result is trimmed.
+pub fn join_chunks<I, S>(pieces: I) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let joined = pieces
+        .into_iter()
+        .fold(String::new(), |acc, piece| join_pair(&acc, piece.as_ref()));
+    joined.trim_end().to_string()
+}
+
+/// Join two text pieces, repairing the boundary between them.
+///
+/// Rules applied at the boundary, in order:
+/// 1. A trailing hyphen followed by a lowercase continuation is merged
+///    ("exam-" + "ple" → "example").
+/// 2. Pieces that touch without whitespace on either side and have letters
+///    on both sides of the boundary are merged as one split word.
+/// 3. Otherwise exactly one space separates the pieces, or none before
+///    closing punctuation, when the pieces touch, or when either side of
+///    the boundary is in a script written without spaces.
+/// 4. The first word of `right` is capitalized when `left` ends a sentence,
+///    and lowercased when `left` ends mid-sentence and the word is only
+///    capitalized at a sentence start ("And", "The"; see
+///    `SENTENCE_START_WORDS`).
+pub fn join_pair(left: &str, right: &str) -> String {
+    let left_trimmed = left.trim_end();
+    let right_trimmed = right.trim_start();
+
+    if right_trimmed.is_empty() {
+        return left_trimmed.to_string();
+    }
+    if left_trimmed.is_empty() {
+        return right_trimmed.to_string();
+    }
+
+    let left_last = left_trimmed.chars().last().unwrap_or(' ');
+    let right_first = right_trimmed.chars().next().unwrap_or(' ');
+
+    // Rule 1: hyphenated split word
+    if left_last == '-' && right_first.is_lowercase() {
+        let before_hyphen = &left_trimmed[..left_trimmed.len() - 1];
+        if before_hyphen
+            .chars()
+            .last()
+            .is_some_and(char::is_alphabetic)
+        {
+            return format!("{before_hyphen}{right_trimmed}");
+        }
+    }
+
+    // Rule 2: touching pieces form a single split word
+    let touching = left.len() == left_trimmed.len() && right.len() == right_trimmed.len();
+    if touching && left_last.is_alphabetic() && right_first.is_lowercase() {
+        return format!("{left_trimmed}{right_trimmed}");
+    }
+
+    // Rule 4: sentence-aware casing of the first word
+    let right_fixed = if ends_sentence(left_trimmed) {
+        capitalize_first(right_trimmed)
+    } else {
+        decapitalize_first_word(right_trimmed)
+    };
+
+    // Rule 3: single separator
+    if touching
+        || CLOSING_PUNCTUATION.contains(&right_first)
+        || is_unspaced_script(left_last)
+        || is_unspaced_script(right_first)
+    {
+        format!("{left_trimmed}{right_fixed}")
+    } else {
+        format!("{left_trimmed} {right_fixed}")
+    }
+}
+
+/// Returns true for characters of scripts written without spaces between
+/// words: Chinese, Japanese, Thai, Lao, Khmer and Myanmar. Korean uses
+/// spaces and is not included.
+fn is_unspaced_script(c: char) -> bool {
+    matches!(c,
+        '\u{0E00}'..='\u{0EFF}' // Thai, Lao
+        | '\u{1000}'..='\u{109F}' // Myanmar
+        | '\u{1780}'..='\u{17FF}' // Khmer
+        | '\u{3000}'..='\u{30FF}' // CJK punctuation, Hiragana, Katakana
+        | '\u{3400}'..='\u{4DBF}' // CJK Extension A
+        | '\u{4E00}'..='\u{9FFF}' // CJK Unified Ideographs
+        | '\u{F900}'..='\u{FAFF}' // CJK Compatibility Ideographs
+        | '\u{FF00}'..='\u{FFEF}' // Fullwidth forms
+        | '\u{20000}'..='\u{2FA1F}' // CJK Extensions B and later
+    )
+}
+
+/// Returns true if the text ends with a sentence terminator (ignoring closing quotes).
+fn ends_sentence(text: &str) -> bool {
+    text.trim_end_matches(['"', '\'', '”', '’', ')'])
+        .chars()
+        .last()
+        .is_some_and(|c| SENTENCE_TERMINATORS.contains(&c))
+}
+
+/// Uppercase the first character if it is a lowercase letter.
+pub fn capitalize_first(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) if first.is_lowercase() => first.to_uppercase().chain(chars).collect(),
+        _ => text.to_string(),
+    }
+}
+
+/// Lowercase the first word if it is a false sentence start.
+///
+/// Only the common words of `SENTENCE_START_WORDS` are touched, so proper
+/// nouns, acronyms ("NASA") and "I" keep their capital.
+fn decapitalize_first_word(text: &str) -> String {
+    let word_end = text
+        .find(|c: char| c.is_whitespace() || CLOSING_PUNCTUATION.contains(&c))
+        .unwrap_or(text.len());
+    let word = &text[..word_end];
+
+    if !SENTENCE_START_WORDS.contains(&word) {
+        return text.to_string();
+    }
+
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join_avoids_double_spaces() {
+        assert_eq!(join_pair("Hello  ", "  world"), "Hello world");
+    }
+
+    #[test]
+    fn test_join_attaches_closing_punctuation() {
+        assert_eq!(join_pair("Hello", " , world"), "Hello, world");
+        assert_eq!(join_pair("Done", "."), "Done.");
+    }
+
+    #[test]
+    fn test_join_merges_hyphen_split_word() {
+        assert_eq!(join_pair("an exam-", "ple of this"), "an example of this");
+    }
+
+    #[test]
+    fn test_join_keeps_hyphen_before_capitalized_word() {
+        assert_eq!(join_pair("pre-", " COVID"), "pre- COVID");
+    }
+
+    #[test]
+    fn test_join_keeps_touching_pieces_together() {
+        assert_eq!(join_pair("pre-", "COVID"), "pre-COVID");
+    }
+
+    #[test]
+    fn test_join_chunks_chinese_segments() {
+        let text = join_chunks(["我们今天", "去公园。", " 天气很好。"]);
+        assert_eq!(text, "我们今天去公园。天气很好。");
+    }
+
+    #[test]
+    fn test_join_chunks_japanese_segments() {
+        let text = join_chunks(["今日は", " いい天気ですね。", " 散歩しましょう。"]);
+        assert_eq!(text, "今日はいい天気ですね。散歩しましょう。");
+    }
+
+    #[test]
+    fn test_join_chunks_thai_segments() {
+        let text = join_chunks(["วันนี้", " อากาศดี", "มาก"]);
+        assert_eq!(text, "วันนี้อากาศดีมาก");
+    }
+
+    #[test]
+    fn test_join_spaces_korean_words() {
+        assert_eq!(
+            join_pair("안녕하세요", " 반갑습니다"),
+            "안녕하세요 반갑습니다"
+        );
+    }
+
+    #[test]
+    fn test_join_merges_touching_split_word() {
+        assert_eq!(join_pair("the tran", "scription"), "the transcription");
+    }
+
+    #[test]
+    fn test_join_lowercases_false_sentence_start() {
+        assert_eq!(
+            join_pair("I went to the store", " And bought"),
+            "I went to the store and bought"
+        );
+        assert_eq!(join_pair("half of", " The cake"), "half of the cake");
+    }
+
+    #[test]
+    fn test_join_keeps_proper_nouns() {
+        assert_eq!(
+            join_pair("book a flight to", " Paris tomorrow"),
+            "book a flight to Paris tomorrow"
+        );
+        assert_eq!(
+            join_pair("a meeting with", " John."),
+            "a meeting with John."
+        );
+        assert_eq!(join_pair("the app", " Then"), "the app then");
+    }
+
+    #[test]
+    fn test_join_keeps_acronyms_and_pronoun_i() {
+        assert_eq!(join_pair("working at", " NASA now"), "working at NASA now");
+        assert_eq!(join_pair("and then", " I left"), "and then I left");
+        assert_eq!(join_pair("and then", " I'm gone"), "and then I'm gone");
+    }
+
+    #[test]
+    fn test_join_capitalizes_after_sentence_end() {
+        assert_eq!(
+            join_pair("It works.", " then we ship"),
+            "It works. Then we ship"
+        );
+        assert_eq!(join_pair("Really?\"", " yes"), "Really?\" Yes");
+    }
+
+    #[test]
+    fn test_join_chunks_skips_empty_pieces() {
+        let text = join_chunks(["I went to the store", "   ", " And bought", " milk."]);
+        assert_eq!(text, "I went to the store and bought milk.");
+    }
+
+    #[test]
+    fn test_join_chunks_whisper_segments() {
+        let text = join_chunks([" Hello there.", " How are you?", " Fine "]);
+        assert_eq!(text, "Hello there. How are you? Fine");
+    }
+
+    #[test]
+    fn test_join_chunks_empty_input() {
+        let pieces: [&str; 0] = [];
+        assert_eq!(join_chunks(pieces), "");
+    }
+}
@@ -0,0 +1,171 @@
+//! Output safeguards applied before text reaches the clipboard or target app.
+//!
+//! Whisper occasionally hallucinates pathological output: a character or
+//! short pattern repeated hundreds of times without a single space, or stray
+//! control characters. Some
+//! target apps freeze while laying out a huge unbreakable line or misbehave
+//! on control input, so transcriptions are sanitized before output.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use crate::utils::text_sanitize::sanitize_output;
+//!
+//! assert_eq!(sanitize_output("Hello\u{7}\r\nworld"), "Hello\nworld");
+//! ```
+
+/// Longest repetition run kept intact.
+const MAX_RUN_CHARS: usize = 100;
+
+/// Longest pattern whose repetitions form a run ("aaaa", "hahaha").
+const MAX_PATTERN_CHARS: usize = 8;
+
+/// Bidirectional override and isolate characters, which can reorder
+/// surrounding text in the target app.
+const BIDI_CONTROLS: [char; 9] = [
+    '\u{202A}', '\u{202B}', '\u{202C}', '\u{202D}', '\u{202E}', '\u{2066}', '\u{2067}', '\u{2068}',
+    '\u{2069}',
+];
+
+/// Sanitize transcribed text for output.
+///
+/// 1. Normalizes `\r\n` and `\r` line endings to `\n`
+/// 2. Strips control characters (except newline and tab) and bidi overrides
+/// 3. Breaks overlong repetition runs by inserting a space every
+///    `MAX_RUN_CHARS` characters. Other long tokens are kept: URLs, paths,
+///    and text in scripts written without spaces (Chinese, Japanese, Thai)
+///
+/// Applying it twice gives the same result as applying it once.
+pub fn sanitize_output(text: &str) -> String {
+    let cleaned: String = text
+        .replace("\r\n", "\n")
+        .chars()
+        .map(|c| if c == '\r' { '\n' } else { c })
+        .filter(|&c| !is_unsafe_char(c))
+        .collect();
+
+    break_long_tokens(&cleaned)
+}
+
+/// Check whether a character should never reach the target app.
+fn is_unsafe_char(c: char) -> bool {
+    (c.is_control() && c != '\n' && c != '\t') || BIDI_CONTROLS.contains(&c)
+}
+
+/// Check whether a token is a pattern of up to `MAX_PATTERN_CHARS`
+/// characters repeated over its whole length.
+fn is_repetition_run(chars: &[char]) -> bool {
+    (1..=MAX_PATTERN_CHARS.min(chars.len() / 2))
+        .any(|period| chars[period..].iter().zip(chars).all(|(a, b)| a == b))
+}
+
+/// Insert a space into every overlong repetition run.
+fn break_long_tokens(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut token = String::new();
+
+    for c in text.chars() {
+        if c.is_whitespace() {
+            push_token(&mut output, &token);
+            token.clear();
+            output.push(c);
+        } else {
+            token.push(c);
+        }
+    }
+    push_token(&mut output, &token);
+
+    output
+}
+
+/// Append a token, splitting a repetition run into chunks of at most
+/// `MAX_RUN_CHARS`.
+fn push_token(output: &mut String, token: &str) {
+    let chars: Vec<char> = token.chars().collect();
+    if chars.len() <= MAX_RUN_CHARS || !is_repetition_run(&chars) {
+        output.push_str(token);
+        return;
+    }
+
+    for (index, chunk) in chars.chunks(MAX_RUN_CHARS).enumerate() {
+        if index > 0 {
+            output.push(' ');
+        }
+        output.extend(chunk);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_text_is_unchanged() {
+        let text = "Hello, world! Ça va? 👋\tNext line\nDone.";
+        assert_eq!(sanitize_output(text), text);
+    }
+
+    #[test]
+    fn test_control_characters_are_stripped() {
+        assert_eq!(
+            sanitize_output("Hel\u{0}lo\u{7} wor\u{1b}ld"),
+            "Hello world"
+        );
+    }
+
+    #[test]
+    fn test_line_endings_are_normalized() {
+        assert_eq!(sanitize_output("one\r\ntwo\rthree"), "one\ntwo\nthree");
+    }
+
+    #[test]
+    fn test_bidi_overrides_are_stripped() {
+        assert_eq!(sanitize_output("abc\u{202E}def\u{2066}"), "abcdef");
+    }
+
+    #[test]
+    fn test_long_repetition_run_is_broken() {
+        let token = "x".repeat(250);
+        let sanitized = sanitize_output(&format!("start {token} end"));
+
+        let words: Vec<&str> = sanitized.split(' ').collect();
+        assert_eq!(words.len(), 5);
+        assert_eq!(words[1].len(), MAX_RUN_CHARS);
+        assert_eq!(words[2].len(), MAX_RUN_CHARS);
+        assert_eq!(words[3].len(), 50);
+        assert_eq!(words[4], "end");
+
+        let sanitized = sanitize_output(&"haha".repeat(30));
+        assert_eq!(sanitized.split(' ').count(), 2);
+    }
+
+    #[test]
+    fn test_long_tokens_are_kept_intact() {
+        let url = format!("https://example.com/{}", "a".repeat(300));
+        assert_eq!(sanitize_output(&url), url);
+        let path = format!("/Users/me/{}/notes.txt", "project-".repeat(20));
+        assert_eq!(sanitize_output(&path), path);
+    }
+
+    #[test]
+    fn test_scripts_without_spaces_are_kept_intact() {
+        let chinese = "今天天气很好我们一起去公园散步然后吃午饭".repeat(8);
+        assert_eq!(sanitize_output(&chinese), chinese);
+        let thai = "วันนี้อากาศดีมากเราไปเดินเล่นที่สวนสาธารณะกัน".repeat(4);
+        assert_eq!(sanitize_output(&thai), thai);
+    }
+
+    #[test]
+    fn test_multibyte_tokens_are_broken_on_char_boundaries() {
+        let token = "é".repeat(MAX_RUN_CHARS + 1);
+        let sanitized = sanitize_output(&token);
+        assert_eq!(sanitized, format!("{} é", "é".repeat(MAX_RUN_CHARS)));
+    }
+
+    #[test]
+    fn test_sanitize_is_idempotent() {
+        let text = format!("a\u{7}b\r\n{}", "y".repeat(333));
+        let once = sanitize_output(&text);
+        assert_eq!(sanitize_output(&once), once);
+    }
+}
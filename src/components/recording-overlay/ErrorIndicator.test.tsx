@@ -32,6 +32,16 @@ describe('ErrorIndicator', () => {
     expect(screen.getByText('Open System Preferences')).toBeInTheDocument()
   })
 
+  it('explains a microphone held by another app', () => {
+    render(<ErrorIndicator error="MicInUse" />)
+
+    expect(screen.getByText('Microphone in use')).toBeInTheDocument()
+    expect(screen.getByText('Another app may be using it')).toBeInTheDocument()
+    expect(
+      screen.queryByText('Open System Preferences')
+    ).not.toBeInTheDocument()
+  })
+
   it('renders generic recording failed error', () => {
     render(
       <ErrorIndicator error={{ RecordingFailed: { reason: 'test error' } }} />
@@ -21,6 +21,14 @@ function parseError(error: CyranoError | null): {
     return { type: 'mic', message: 'Microphone access denied' }
   }
 
+  if (error === 'MicInUse') {
+    return {
+      type: 'other',
+      message: 'Microphone in use',
+      details: 'Another app may be using it',
+    }
+  }
+
   if (typeof error === 'object') {
     if ('ModelNotFound' in error) {
       return {
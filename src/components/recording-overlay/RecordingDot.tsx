@@ -0,0 +1,34 @@
+/**
+ * RecordingDot - Minimal indicator for the dot overlay style.
+ *
+ * Fills the 12px dot window with a single colored dot: pulsing red while
+ * recording, pulsing blue while transcribing, green when done and amber on
+ * error. The label is only exposed to assistive technologies.
+ */
+interface RecordingDotProps {
+  state: 'recording' | 'transcribing' | 'done' | 'error'
+}
+
+const dotStyles: Record<RecordingDotProps['state'], string> = {
+  recording: 'animate-pulse-recording bg-red-500',
+  transcribing: 'animate-pulse-recording bg-blue-500',
+  done: 'bg-green-500',
+  error: 'bg-amber-500',
+}
+
+const dotLabels: Record<RecordingDotProps['state'], string> = {
+  recording: 'Recording...',
+  transcribing: 'Transcribing...',
+  done: 'Done',
+  error: 'Error',
+}
+
+export function RecordingDot({ state }: RecordingDotProps) {
+  return (
+    <span
+      role="status"
+      aria-label={dotLabels[state]}
+      className={`h-3 w-3 rounded-full ${dotStyles[state]}`}
+    />
+  )
+}
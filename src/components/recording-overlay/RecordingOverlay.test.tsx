@@ -99,6 +99,16 @@ describe('RecordingOverlay', () => {
     expect(svg).toHaveClass('text-green-500')
   })
 
+  it('renders only a dot in the dot variant', () => {
+    useUIStore.setState({ recordingState: 'transcribing' })
+    render(<RecordingOverlay variant="dot" />)
+
+    const dot = screen.getByRole('status')
+    expect(dot).toHaveAttribute('aria-label', 'Transcribing...')
+    expect(dot).toHaveClass('bg-blue-500')
+    expect(screen.queryByText('Transcribing...')).not.toBeInTheDocument()
+  })
+
   it('calls dismissRecordingOverlay when clicking in done state', async () => {
     const { commands } = await import('@/lib/tauri-bindings')
     useUIStore.setState({ recordingState: 'done' })
@@ -4,6 +4,7 @@ import { logger } from '@/lib/logger'
 import { useUIStore } from '@/store/ui-store'
 import { RecordingIndicator } from './RecordingIndicator'
 import { ErrorIndicator } from './ErrorIndicator'
+import { RecordingDot } from './RecordingDot'
 import { SuccessIndicator } from './SuccessIndicator'
 import { TranscribingIndicator } from './TranscribingIndicator'
 
@@ -20,10 +21,18 @@ export const AUTO_DISMISS_ERROR_MS = 1800
  * behavior. Clicking anywhere on the overlay cancels the current recording
  * and dismisses the overlay. When an error occurs, displays the error state.
  * Terminal states (done/error) auto-dismiss after a short delay.
+ *
+ * The `dot` variant renders in the 12px dot window and only shows a colored
+ * dot for the state, with the same click and auto-dismiss behavior.
  */
-export function RecordingOverlay() {
+export function RecordingOverlay({
+  variant = 'panel',
+}: {
+  variant?: 'panel' | 'dot'
+}) {
   const recordingState = useUIStore(state => state.recordingState)
   const recordingError = useUIStore(state => state.recordingError)
+  const transcriptionPreview = useUIStore(state => state.transcriptionPreview)
   const isError = recordingState === 'error' || recordingError !== null
 
   // Ref to store timeout ID for cleanup and cancellation
@@ -116,6 +125,22 @@ export function RecordingOverlay() {
     }
   }
 
+  if (variant === 'dot') {
+    const dotState = isError
+      ? 'error'
+      : recordingState === 'done' || recordingState === 'transcribing'
+        ? recordingState
+        : 'recording'
+    return (
+      <div
+        onClick={handleClick}
+        className="flex h-screen w-screen cursor-pointer items-center justify-center"
+      >
+        <RecordingDot state={dotState} />
+      </div>
+    )
+  }
+
   const renderIndicator = () => {
     if (isError) {
       return <ErrorIndicator error={recordingError} />
@@ -124,7 +149,7 @@ export function RecordingOverlay() {
       return <SuccessIndicator />
     }
     if (recordingState === 'transcribing') {
-      return <TranscribingIndicator />
+      return <TranscribingIndicator preview={transcriptionPreview} />
     }
     return <RecordingIndicator />
   }
@@ -3,9 +3,13 @@ import { listen } from '@tauri-apps/api/event'
 import { getCurrentWindow } from '@tauri-apps/api/window'
 import { commands, type CyranoError } from '@/lib/tauri-bindings'
 import { logger } from '@/lib/logger'
+import { listenInWindow } from '@/lib/window-events'
 import { useUIStore } from '@/store/ui-store'
 import { RecordingOverlay } from './RecordingOverlay'
 
+/** Label of the dot overlay window, which loads this same page */
+const RECORDING_DOT_LABEL = 'recording-overlay-dot'
+
 /**
  * Apply theme from localStorage to document.
  * Follows the same pattern as QuickPaneApp.tsx for consistency.
@@ -33,7 +37,7 @@ function applyTheme() {
  * This component handles:
  * - Theme synchronization with the main window
  * - Re-applying theme when window gains focus
- * - Rendering the recording overlay content
+ * - Rendering the recording overlay content, as a dot in the dot window
  */
 export default function RecordingOverlayApp() {
   // Apply theme on mount and listen for theme changes from main window
@@ -83,12 +87,13 @@ export default function RecordingOverlayApp() {
 
   // Listen for recording events to update overlay state
   // Note: The overlay window has its own Zustand store instance,
-  // so we need to listen for events directly here
+  // so we need to listen for events directly here, scoped to this window so
+  // only the channels it subscribes to reach it
   useEffect(() => {
     const unlisteners: (() => void)[] = []
 
     // Listen for recording-failed to show error state
-    listen<{ error: CyranoError }>('recording-failed', event => {
+    listenInWindow<{ error: CyranoError }>('recording-failed', event => {
       logger.info('Recording overlay received recording-failed event', {
         error: event.payload.error,
       })
@@ -103,7 +108,7 @@ export default function RecordingOverlayApp() {
       })
 
     // Listen for recording-stopped to transition to transcribing state
-    listen<{ duration_ms: number; sample_count: number }>(
+    listenInWindow<{ duration_ms: number; sample_count: number }>(
       'recording-stopped',
       event => {
         logger.info('Recording overlay received recording-stopped event', {
@@ -122,12 +127,17 @@ export default function RecordingOverlayApp() {
       })
 
     // Listen for recording-started to reset to recording state
-    listen<{ timestamp: number }>('recording-started', event => {
+    listenInWindow<{ timestamp: number }>('recording-started', event => {
       logger.info('Recording overlay received recording-started event', {
         timestamp: event.payload.timestamp,
       })
-      const { setRecordingState, clearRecordingError } = useUIStore.getState()
+      const {
+        setRecordingState,
+        clearRecordingError,
+        clearTranscriptionPreview,
+      } = useUIStore.getState()
       clearRecordingError()
+      clearTranscriptionPreview()
       setRecordingState('recording')
     })
       .then(unlisten => unlisteners.push(unlisten))
@@ -138,7 +148,7 @@ export default function RecordingOverlayApp() {
       })
 
     // Listen for recording-state-changed to update state (legacy/generic)
-    listen<{ state: string }>('recording-state-changed', event => {
+    listenInWindow<{ state: string }>('recording-state-changed', event => {
       const { setRecordingState } = useUIStore.getState()
       setRecordingState(
         event.payload.state.toLowerCase() as
@@ -160,7 +170,7 @@ export default function RecordingOverlayApp() {
       })
 
     // Listen for transcription-started event
-    listen<{ timestamp: number }>('transcription-started', event => {
+    listenInWindow<{ timestamp: number }>('transcription-started', event => {
       logger.info('Recording overlay received transcription-started event', {
         timestamp: event.payload.timestamp,
       })
@@ -174,8 +184,24 @@ export default function RecordingOverlayApp() {
         )
       })
 
+    // Listen for transcription-segment to show the text as it is decoded
+    listenInWindow<{ index: number; text: string }>(
+      'transcription-segment',
+      event => {
+        const { appendTranscriptionSegment } = useUIStore.getState()
+        appendTranscriptionSegment(event.payload.text)
+      }
+    )
+      .then(unlisten => unlisteners.push(unlisten))
+      .catch(error => {
+        logger.error(
+          'Failed to setup transcription-segment listener in overlay',
+          { error }
+        )
+      })
+
     // Listen for transcription-complete event
-    listen<{ text: string; duration_ms: number }>(
+    listenInWindow<{ text: string; duration_ms: number }>(
       'transcription-complete',
       event => {
         logger.info('Recording overlay received transcription-complete event', {
@@ -197,7 +223,7 @@ export default function RecordingOverlayApp() {
       })
 
     // Listen for transcription-failed event
-    listen<{ error: CyranoError }>('transcription-failed', event => {
+    listenInWindow<{ error: CyranoError }>('transcription-failed', event => {
       logger.error('Recording overlay received transcription-failed event', {
         error: event.payload.error,
       })
@@ -213,7 +239,7 @@ export default function RecordingOverlayApp() {
       })
 
     // Listen for transcription-cancelled event
-    listen<{ timestamp: number }>('transcription-cancelled', event => {
+    listenInWindow<{ timestamp: number }>('transcription-cancelled', event => {
       logger.info('Recording overlay received transcription-cancelled event', {
         timestamp: event.payload.timestamp,
       })
@@ -239,7 +265,7 @@ export default function RecordingOverlayApp() {
       })
 
     // Listen for clipboard-copied event (informational)
-    listen<{ text_length: number }>('clipboard-copied', event => {
+    listenInWindow<{ text_length: number }>('clipboard-copied', event => {
       logger.info('Text copied to clipboard', {
         textLength: event.payload.text_length,
       })
@@ -250,7 +276,7 @@ export default function RecordingOverlayApp() {
       })
 
     // Listen for clipboard-failed event (non-fatal, log only)
-    listen<{ error: CyranoError }>('clipboard-failed', event => {
+    listenInWindow<{ error: CyranoError }>('clipboard-failed', event => {
       logger.warn('Clipboard copy failed (transcription still available)', {
         error: event.payload.error,
       })
@@ -265,5 +291,8 @@ export default function RecordingOverlayApp() {
     }
   }, [])
 
-  return <RecordingOverlay />
+  const variant =
+    getCurrentWindow().label === RECORDING_DOT_LABEL ? 'dot' : 'panel'
+
+  return <RecordingOverlay variant={variant} />
 }
@@ -24,4 +24,10 @@ describe('TranscribingIndicator', () => {
     const svg = document.querySelector('svg')
     expect(svg).toHaveClass('text-blue-500')
   })
+
+  it('renders the text decoded so far', () => {
+    render(<TranscribingIndicator preview=" Hello world" />)
+
+    expect(screen.getByText('Hello world')).toBeInTheDocument()
+  })
 })
@@ -4,15 +4,27 @@ import { Spinner } from '@/components/ui/spinner'
  * TranscribingIndicator - Visual indicator for transcription in progress.
  *
  * Displays a spinning loader with "Transcribing..." text to provide
- * clear visual feedback that audio is being processed.
+ * clear visual feedback that audio is being processed. The text decoded so
+ * far, if any, is shown below on a single truncated line.
  */
-export function TranscribingIndicator() {
+export function TranscribingIndicator({
+  preview,
+}: {
+  preview?: string | null
+}) {
   return (
-    <div className="flex items-center gap-3">
-      <Spinner className="size-5 text-blue-500" />
-      <span className="text-lg font-medium text-foreground">
-        Transcribing...
-      </span>
+    <div className="flex min-w-0 flex-col items-center gap-1">
+      <div className="flex items-center gap-3">
+        <Spinner className="size-5 text-blue-500" />
+        <span className="text-lg font-medium text-foreground">
+          Transcribing...
+        </span>
+      </div>
+      {preview && (
+        <span className="max-w-full truncate text-xs text-muted-foreground">
+          {preview.trim()}
+        </span>
+      )}
     </div>
   )
 }
@@ -1,5 +1,6 @@
 export { RecordingOverlay } from './RecordingOverlay'
 export { RecordingIndicator } from './RecordingIndicator'
+export { RecordingDot } from './RecordingDot'
 export { ErrorIndicator } from './ErrorIndicator'
 export { TranscribingIndicator } from './TranscribingIndicator'
 export { default as RecordingOverlayApp } from './RecordingOverlayApp'
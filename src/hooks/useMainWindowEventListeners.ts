@@ -17,6 +17,19 @@ interface RecordingStoppedPayload {
   sample_count: number
 }
 
+/** Payload for recording-interrupted event */
+interface RecordingInterruptedPayload {
+  state: string
+  interrupted_at: number
+  recording: { duration_ms: number; recorded_at: number } | null
+}
+
+/** Payload for startup-report event */
+interface StartupReportPayload {
+  degraded: boolean
+  entries: { name: string; status: string; detail: string }[]
+}
+
 /** Payload for recording-failed event */
 interface RecordingFailedPayload {
   error: CyranoError
@@ -65,6 +78,36 @@ export function useMainWindowEventListeners() {
     }
   }, [])
 
+  // Surface features that failed to initialize at startup
+  useEffect(() => {
+    let isMounted = true
+    let unlisten: (() => void) | null = null
+
+    listen<StartupReportPayload>('startup-report', event => {
+      if (!event.payload.degraded) return
+      logger.warn('Running in degraded mode', {
+        failed: event.payload.entries.filter(entry => entry.status !== 'Ok'),
+      })
+    })
+      .then(unlistenFn => {
+        if (!isMounted) {
+          unlistenFn()
+        } else {
+          unlisten = unlistenFn
+        }
+      })
+      .catch(error => {
+        logger.error('Failed to setup startup-report listener', { error })
+      })
+
+    return () => {
+      isMounted = false
+      if (unlisten) {
+        unlisten()
+      }
+    }
+  }, [])
+
   // Listen for recording overlay/state events (cross-window)
   useEffect(() => {
     let isMounted = true
@@ -155,6 +198,26 @@ export function useMainWindowEventListeners() {
         logger.error('Failed to setup recording-stopped listener', { error })
       })
 
+    // Listen for a dictation interrupted by the last quit: the backend
+    // reset it, so drop any state assumed from before the restart
+    listen<RecordingInterruptedPayload>('recording-interrupted', event => {
+      if (!isMounted) return
+      logger.warn('Previous dictation was interrupted', {
+        state: event.payload.state,
+        recoverableMs: event.payload.recording?.duration_ms ?? 0,
+      })
+      const { setRecordingOverlayVisible, setRecordingState } =
+        useUIStore.getState()
+      setRecordingOverlayVisible(false)
+      setRecordingState('idle')
+    })
+      .then(unlisten => unlistenFns.push(unlisten))
+      .catch(error => {
+        logger.error('Failed to setup recording-interrupted listener', {
+          error,
+        })
+      })
+
     // Listen for recording-failed event
     listen<RecordingFailedPayload>('recording-failed', event => {
       if (!isMounted) return
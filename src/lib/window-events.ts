@@ -0,0 +1,22 @@
+/**
+ * Window-scoped event listening.
+ *
+ * The backend routes events to the windows subscribed to their channel
+ * (recording, transcription, history...). A global `listen` still
+ * receives events routed to other windows, so secondary windows listen
+ * through this helper to only hear their own channels.
+ */
+
+import type { EventCallback, UnlistenFn } from '@tauri-apps/api/event'
+import { getCurrentWindow } from '@tauri-apps/api/window'
+
+/**
+ * Listens for an event sent to the current window.
+ * @returns a promise resolving to the function that removes the listener
+ */
+export function listenInWindow<T>(
+  event: string,
+  handler: EventCallback<T>
+): Promise<UnlistenFn> {
+  return getCurrentWindow().listen<T>(event, handler)
+}
@@ -22,6 +22,8 @@ interface UIState {
   recordingError: CyranoError | null
   /** Transcribed text result for clipboard operations (Story 2.3) */
   transcriptionResult: string | null
+  /** Text decoded so far while transcribing, shown before the result */
+  transcriptionPreview: string | null
 
   toggleLeftSidebar: () => void
   setLeftSidebarVisible: (visible: boolean) => void
@@ -38,6 +40,8 @@ interface UIState {
   clearRecordingError: () => void
   setTranscriptionResult: (result: string | null) => void
   clearTranscriptionResult: () => void
+  appendTranscriptionSegment: (text: string) => void
+  clearTranscriptionPreview: () => void
 }
 
 export const useUIStore = create<UIState>()(
@@ -52,6 +56,7 @@ export const useUIStore = create<UIState>()(
       recordingState: 'idle' as RecordingState,
       recordingError: null,
       transcriptionResult: null,
+      transcriptionPreview: null,
 
       toggleLeftSidebar: () =>
         set(
@@ -137,6 +142,22 @@ export const useUIStore = create<UIState>()(
           undefined,
           'clearTranscriptionResult'
         ),
+
+      appendTranscriptionSegment: text =>
+        set(
+          state => ({
+            transcriptionPreview: (state.transcriptionPreview ?? '') + text,
+          }),
+          undefined,
+          'appendTranscriptionSegment'
+        ),
+
+      clearTranscriptionPreview: () =>
+        set(
+          { transcriptionPreview: null },
+          undefined,
+          'clearTranscriptionPreview'
+        ),
     }),
     {
       name: 'ui-store',